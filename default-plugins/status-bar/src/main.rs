@@ -16,8 +16,8 @@ use zellij_tile_utils::{palette_match, style};
 use first_line::first_line;
 use second_line::{
     floating_panes_are_visible, fullscreen_panes_to_hide, keybinds,
-    locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide, system_clipboard_error,
-    text_copied_hint,
+    locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide, status_message,
+    system_clipboard_error, text_copied_hint,
 };
 use tip::utils::get_cached_tip_name;
 
@@ -34,6 +34,7 @@ struct State {
     mode_info: ModeInfo,
     text_copy_destination: Option<CopyDestination>,
     display_system_clipboard_failure: bool,
+    status_message: Option<String>,
 }
 
 register_plugin!(State);
@@ -179,6 +180,7 @@ impl ZellijPlugin for State {
             EventType::CopyToClipboard,
             EventType::InputReceived,
             EventType::SystemClipboardFailure,
+            EventType::StatusMessage,
         ]);
     }
 
@@ -196,9 +198,13 @@ impl ZellijPlugin for State {
             Event::SystemClipboardFailure => {
                 self.display_system_clipboard_failure = true;
             },
+            Event::StatusMessage(message) => {
+                self.status_message = Some(message);
+            },
             Event::InputReceived => {
                 self.text_copy_destination = None;
                 self.display_system_clipboard_failure = false;
+                self.status_message = None;
             },
             _ => {},
         }
@@ -242,6 +248,8 @@ impl State {
             text_copied_hint(&self.mode_info.style.colors, copy_destination)
         } else if self.display_system_clipboard_failure {
             system_clipboard_error(&self.mode_info.style.colors)
+        } else if let Some(message) = &self.status_message {
+            status_message(&self.mode_info.style.colors, message)
         } else if let Some(active_tab) = active_tab {
             if active_tab.is_fullscreen_active {
                 match self.mode_info.mode {
@@ -110,6 +110,7 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
     use Action as A;
     use InputMode as IM;
     use actions::Direction as Dir;
+    use actions::Fraction;
     use actions::ResizeDirection as RDir;
     use actions::SearchDirection as SDir;
     use actions::SearchOption as SOpt;
@@ -150,6 +151,7 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
                 &[A::MoveFocus(Dir::Up)], &[A::MoveFocus(Dir::Right)]])),
         (s("New"), s("New"), action_key(&km, &[A::NewPane(None, None), TO_NORMAL])),
         (s("Close"), s("Close"), action_key(&km, &[A::CloseFocus, TO_NORMAL])),
+        (s("Reopen closed"), s("Reopen"), action_key(&km, &[A::UndoClosePane, TO_NORMAL])),
         (s("Rename"), s("Rename"),
             action_key(&km, &[A::SwitchToMode(IM::RenamePane), A::PaneNameInput(vec![0])])),
         (s("Split down"), s("Down"), action_key(&km, &[A::NewPane(Some(Dir::Down), None), TO_NORMAL])),
@@ -159,6 +161,8 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
         (s("Floating toggle"), s("Floating"),
             action_key(&km, &[A::ToggleFloatingPanes, TO_NORMAL])),
         (s("Embed pane"), s("Embed"), action_key(&km, &[A::TogglePaneEmbedOrFloating, TO_NORMAL])),
+        (s("Pin pane"), s("Pin"), action_key(&km, &[A::TogglePanePinned, TO_NORMAL])),
+        (s("Send signal"), s("Signal"), action_key(&km, &[A::SwitchToMode(IM::Signal)])),
         (s("Next"), s("Next"), action_key(&km, &[A::SwitchFocus])),
         (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::Tab {
@@ -185,6 +189,9 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
         (s("Rename"), s("Rename"),
             action_key(&km, &[A::SwitchToMode(IM::RenameTab), A::TabNameInput(vec![0])])),
         (s("Sync"), s("Sync"), action_key(&km, &[A::ToggleActiveSyncTab, TO_NORMAL])),
+        (s("Break pane"), s("Break"), action_key(&km, &[A::MovePaneToNewTab, TO_NORMAL])),
+        (s("Move pane"), s("Move pane"), action_key_group(&km, &[
+            &[A::MovePaneToTab(Dir::Left), TO_NORMAL], &[A::MovePaneToTab(Dir::Right), TO_NORMAL]])),
         (s("Toggle"), s("Toggle"), action_key(&km, &[A::ToggleTab])),
         (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::Resize { vec![
@@ -193,6 +200,10 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
             &[A::Resize(RDir::Up)], &[A::Resize(RDir::Right)]])),
         (s("Increase/Decrease size"), s("Increase/Decrease"),
             action_key_group(&km, &[&[A::Resize(RDir::Increase)], &[A::Resize(RDir::Decrease)]])),
+        (s("Resize to 1/3, 1/2 or 2/3"), s("Presets"), action_key_group(&km, &[
+            &[A::ResizeToFraction(Fraction { numerator: 1, denominator: 3 })],
+            &[A::ResizeToFraction(Fraction { numerator: 1, denominator: 2 })],
+            &[A::ResizeToFraction(Fraction { numerator: 2, denominator: 3 })]])),
         (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::Move { vec![
         (s("Move"), s("Move"), action_key_group(&km, &[
@@ -208,6 +219,8 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
             action_key_group(&km, &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]])),
         (s("Edit scrollback in default editor"), s("Edit"),
             action_key(&km, &[Action::EditScrollback, TO_NORMAL])),
+        (s("Toggle following output"), s("Follow"),
+            action_key(&km, &[Action::ToggleFollowOutput])),
         (s("Enter search term"), s("Search"),
             action_key(&km, &[A::SwitchToMode(IM::EnterSearch), A::SearchInput(vec![0])])),
         (s("Select pane"), s("Select"), to_normal_key),
@@ -232,8 +245,16 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
             action_key(&km, &[A::SearchToggleOption(SOpt::Wrap)])),
         (s("Whole words"), s("Whole"),
             action_key(&km, &[A::SearchToggleOption(SOpt::WholeWord)])),
+    ]} else if mi.mode == IM::Signal { vec![
+        (s("Interrupt"), s("INT"), action_key(&km, &[A::SignalFocusedPane(s("SIGINT")), TO_NORMAL])),
+        (s("Terminate"), s("TERM"), action_key(&km, &[A::SignalFocusedPane(s("SIGTERM")), TO_NORMAL])),
+        (s("Kill"), s("KILL"), action_key(&km, &[A::SignalFocusedPane(s("SIGKILL")), TO_NORMAL])),
+        (s("Hang up"), s("HUP"), action_key(&km, &[A::SignalFocusedPane(s("SIGHUP")), TO_NORMAL])),
+        (s("Quit"), s("QUIT"), action_key(&km, &[A::SignalFocusedPane(s("SIGQUIT")), TO_NORMAL])),
+        (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::Session { vec![
         (s("Detach"), s("Detach"), action_key(&km, &[Action::Detach])),
+        (s("Keybindings"), s("Keys"), action_key(&km, &[A::SwitchToMode(IM::Help)])),
         (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::Tmux { vec![
         (s("Move focus"), s("Move"), action_key_group(&km, &[
@@ -362,6 +383,15 @@ pub fn system_clipboard_error(palette: &Palette) -> LinePart {
     }
 }
 
+pub fn status_message(palette: &Palette, message: &str) -> LinePart {
+    let hint = format!(" {}", message);
+    let red_color = palette_match!(palette.red);
+    LinePart {
+        len: hint.chars().count(),
+        part: Style::new().fg(red_color).bold().paint(hint).to_string(),
+    }
+}
+
 pub fn fullscreen_panes_to_hide(palette: &Palette, panes_to_hide: usize) -> LinePart {
     let text_color = palette_match!(match palette.theme_hue {
         ThemeHue::Dark => palette.white,
@@ -1,4 +1,5 @@
 use crate::install::populate_data_dir;
+use crate::sessions::detach_session as detach_session_impl;
 use crate::sessions::kill_session as kill_session_impl;
 use crate::sessions::{
     assert_session, assert_session_ne, get_active_session, get_sessions,
@@ -13,20 +14,20 @@ use zellij_client::old_config_converter::{
     config_yaml_to_config_kdl, convert_old_yaml_files, layout_yaml_to_layout_kdl,
 };
 use zellij_client::start_client as start_client_impl;
-use zellij_client::{os_input_output::get_client_os_input, ClientInfo};
+use zellij_client::{os_input_output::get_client_os_input, ClientInfo, StartClientError};
 use zellij_server::os_input_output::get_server_os_input;
 use zellij_server::start_server as start_server_impl;
 use zellij_utils::input::actions::Action;
 use zellij_utils::input::config::ConfigError;
-use zellij_utils::input::options::Options;
+use zellij_utils::input::options::{OnNestedSession, Options};
 use zellij_utils::nix;
 use zellij_utils::{
-    cli::{CliArgs, Command, SessionCommand, Sessions},
+    cli::{CliAction, CliArgs, Command, SessionCommand, Sessions},
     envs,
     setup::{get_default_data_dir, Setup},
 };
 
-use std::{fs::File, io::prelude::*};
+use std::{fs::File, io, io::prelude::*};
 
 pub(crate) use crate::sessions::list_sessions;
 
@@ -74,6 +75,23 @@ pub(crate) fn kill_session(target_session: &Option<String>) {
     }
 }
 
+pub(crate) fn detach_session(target_session: &Option<String>) {
+    let target_session = target_session
+        .clone()
+        .or_else(|| envs::get_session_name().ok());
+    match target_session {
+        Some(target_session) => {
+            assert_session(&target_session);
+            detach_session_impl(&target_session);
+            process::exit(0);
+        },
+        None => {
+            println!("Please specify the session name to detach.");
+            process::exit(1);
+        },
+    }
+}
+
 fn get_os_input<OsInputOutput>(
     fn_get_os_input: fn() -> Result<OsInputOutput, nix::Error>,
 ) -> OsInputOutput {
@@ -124,6 +142,8 @@ fn find_indexed_session(
 
 pub(crate) fn send_action_to_session(
     cli_action: zellij_utils::cli::CliAction,
+    pane_id: Option<String>,
+    tab_id: Option<u32>,
     requested_session_name: Option<String>,
 ) {
     match get_active_session() {
@@ -142,13 +162,13 @@ pub(crate) fn send_action_to_session(
                     std::process::exit(1);
                 }
             }
-            attach_with_cli_client(cli_action, &session_name);
+            attach_with_cli_client(cli_action, pane_id, tab_id, &session_name);
         },
         ActiveSession::Many => {
             let existing_sessions = get_sessions().unwrap();
             if let Some(session_name) = requested_session_name {
                 if existing_sessions.contains(&session_name) {
-                    attach_with_cli_client(cli_action, &session_name);
+                    attach_with_cli_client(cli_action, pane_id, tab_id, &session_name);
                 } else {
                     eprintln!(
                         "Session '{}' not found. The following sessions are active:",
@@ -158,7 +178,16 @@ pub(crate) fn send_action_to_session(
                     std::process::exit(1);
                 }
             } else if let Ok(session_name) = envs::get_session_name() {
-                attach_with_cli_client(cli_action, &session_name);
+                if existing_sessions.contains(&session_name) {
+                    attach_with_cli_client(cli_action, pane_id, tab_id, &session_name);
+                } else {
+                    eprintln!(
+                        "Current session '{}' not found. The following sessions are active:",
+                        session_name
+                    );
+                    print_sessions(existing_sessions);
+                    std::process::exit(1);
+                }
             } else {
                 eprintln!("Please specify the session name to send actions to. The following sessions are active:");
                 print_sessions(existing_sessions);
@@ -236,9 +265,23 @@ pub(crate) fn convert_old_theme_file(old_theme_file: PathBuf) {
     }
 }
 
-fn attach_with_cli_client(cli_action: zellij_utils::cli::CliAction, session_name: &str) {
+fn attach_with_cli_client(
+    cli_action: zellij_utils::cli::CliAction,
+    pane_id: Option<String>,
+    tab_id: Option<u32>,
+    session_name: &str,
+) {
     let os_input = get_os_input(zellij_client::os_input_output::get_client_os_input);
-    match Action::actions_from_cli(cli_action) {
+    let actions = Action::focus_actions_from_cli(pane_id, tab_id).and_then(|mut actions| {
+        // the focus is only moved for the action, not for whoever is using the session
+        let restores_focus = !actions.is_empty();
+        actions.append(&mut Action::actions_from_cli(cli_action)?);
+        if restores_focus {
+            actions.push(Action::RestoreFocus);
+        }
+        Ok(actions)
+    });
+    match actions {
         Ok(actions) => {
             zellij_client::cli_client::start_cli_client(Box::new(os_input), session_name, actions);
             std::process::exit(0);
@@ -276,29 +319,38 @@ fn attach_with_session_name(
     create: bool,
 ) -> ClientInfo {
     match &session_name {
-        Some(session) if create => {
-            if session_exists(session).unwrap() {
-                ClientInfo::Attach(session_name.unwrap(), config_options)
-            } else {
+        Some(session) if create => match session_exists(session) {
+            Ok(true) => ClientInfo::Attach(session_name.unwrap(), config_options),
+            Ok(false) => {
+                assert_session_ne(session);
                 ClientInfo::New(session_name.unwrap())
-            }
+            },
+            Err(e) => {
+                eprintln!("Error occurred: {:?}", e);
+                process::exit(1);
+            },
         },
-        Some(prefix) => match match_session_name(prefix).unwrap() {
-            SessionNameMatch::UniquePrefix(s) | SessionNameMatch::Exact(s) => {
+        Some(prefix) => match match_session_name(prefix) {
+            Ok(SessionNameMatch::UniquePrefix(s) | SessionNameMatch::Exact(s)) => {
                 ClientInfo::Attach(s, config_options)
             },
-            SessionNameMatch::AmbiguousPrefix(sessions) => {
-                println!(
+            Ok(SessionNameMatch::AmbiguousPrefix(sessions)) => {
+                // the matching sessions are listed on stdout, so that a script can pick one
+                eprintln!(
                     "Ambiguous selection: multiple sessions names start with '{}':",
                     prefix
                 );
                 print_sessions(sessions);
                 process::exit(1);
             },
-            SessionNameMatch::None => {
+            Ok(SessionNameMatch::None) => {
                 eprintln!("No session with the name '{}' found!", prefix);
                 process::exit(1);
             },
+            Err(e) => {
+                eprintln!("Error occurred: {:?}", e);
+                process::exit(1);
+            },
         },
         None => match get_active_session() {
             ActiveSession::None if create => create_new_client(),
@@ -316,28 +368,42 @@ fn attach_with_session_name(
     }
 }
 
-pub(crate) fn start_client(opts: CliArgs) {
+/// Prints why the client could not be started, pointing at the offending part of the
+/// configuration or layout file if there is one
+pub(crate) fn report_start_client_error(e: StartClientError) {
+    match e {
+        StartClientError::Config(ConfigError::KdlError(error)) => {
+            let report: Report = error.into();
+            eprintln!("{:?}", report);
+        },
+        e => eprintln!("{}", e),
+    }
+}
+
+pub(crate) fn start_client(opts: CliArgs) -> Result<(), StartClientError> {
     // look for old YAML config/layout/theme files and convert them to KDL
     convert_old_yaml_files(&opts);
-    let (config, layout, config_options) = match Setup::from_cli_args(&opts) {
-        Ok(results) => results,
-        Err(e) => {
-            if let ConfigError::KdlError(error) = e {
-                let report: Report = error.into();
-                eprintln!("{:?}", report);
-            } else {
-                eprintln!("{}", e);
-            }
-            process::exit(1);
-        },
-    };
+    let (config, layout, config_options) = Setup::from_cli_args(&opts)?;
     let os_input = get_os_input(get_client_os_input);
+    if let Some(recording_path) = &opts.record {
+        os_input.record_output_to(recording_path).map_err(|e| {
+            StartClientError::Terminal(io::Error::new(
+                e.kind(),
+                format!(
+                    "failed to start recording to {}: {}",
+                    recording_path.display(),
+                    e
+                ),
+            ))
+        })?;
+    }
 
     if let Some(Command::Sessions(Sessions::Attach {
         session_name,
         create,
         index,
         options,
+        ..
     })) = opts.command.clone()
     {
         let config_options = match options.as_deref() {
@@ -374,8 +440,11 @@ pub(crate) fn start_client(opts: CliArgs) {
             config_options,
             client,
             attach_layout,
-        );
+        )?;
     } else {
+        if let Ok(outer_session_name) = envs::get_session_name() {
+            handle_nested_session(&outer_session_name, &opts, &config_options);
+        }
         let start_client_plan = |session_name: std::string::String| {
             assert_session_ne(&session_name);
             install_default_assets(&opts);
@@ -390,7 +459,7 @@ pub(crate) fn start_client(opts: CliArgs) {
                 config_options,
                 ClientInfo::New(session_name),
                 Some(layout),
-            );
+            )?;
         } else {
             if let Some(session_name) = config_options.session_name.as_ref() {
                 if let Ok(val) = envs::get_session_name() {
@@ -424,7 +493,7 @@ pub(crate) fn start_client(opts: CliArgs) {
                             config_options,
                             client,
                             attach_layout,
-                        );
+                        )?;
                     },
                     _ => {
                         start_client_plan(session_name.clone());
@@ -435,7 +504,7 @@ pub(crate) fn start_client(opts: CliArgs) {
                             config_options.clone(),
                             ClientInfo::New(session_name.clone()),
                             Some(layout),
-                        );
+                        )?;
                     },
                 }
                 // after we detach, this happens and so we need to exit before the rest of the
@@ -453,7 +522,29 @@ pub(crate) fn start_client(opts: CliArgs) {
                 config_options,
                 ClientInfo::New(session_name),
                 Some(layout),
-            );
+            )?;
         }
     }
+    Ok(())
+}
+
+/// Called when a new session is started from inside a pane of the session `outer_session_name`.
+/// Only returns if the new session should be nested inside the pane.
+fn handle_nested_session(outer_session_name: &str, opts: &CliArgs, config_options: &Options) {
+    match config_options.on_nested_session.unwrap_or_default() {
+        OnNestedSession::Nest => {},
+        OnNestedSession::Refuse => {
+            eprintln!("You are already inside the zellij session \"{}\". To start a nested session anyway, set the on_nested_session option to \"nest\".", outer_session_name);
+            process::exit(1);
+        },
+        OnNestedSession::NewTab => {
+            let new_tab = CliAction::NewTab {
+                layout: opts.layout.clone(),
+                name: None,
+                cwd: None,
+            };
+            send_action_to_session(new_tab, Some(outer_session_name.to_owned()));
+            process::exit(0);
+        },
+    }
 }
@@ -7,16 +7,34 @@ mod tests;
 use zellij_utils::{
     clap::Parser,
     cli::{CliAction, CliArgs, Command, Sessions},
+    consts::{ZELLIJ_LOG_FILE_ENV, ZELLIJ_LOG_LEVEL_ENV},
     logging::*,
 };
 
 fn main() {
-    configure_logger();
-    let opts = CliArgs::parse();
+    let mut opts = CliArgs::parse();
+    if let Some(log_level) = &opts.log_level {
+        // the server is started as a separate process, this way it logs at the same level
+        std::env::set_var(ZELLIJ_LOG_LEVEL_ENV, log_level);
+    }
+    if let Some(log_file) = opts.log_file.take() {
+        // the server changes its working directory when it daemonizes
+        let log_file = std::env::current_dir()
+            .map(|current_dir| current_dir.join(&log_file))
+            .unwrap_or(log_file);
+        std::env::set_var(ZELLIJ_LOG_FILE_ENV, &log_file);
+        opts.log_file = Some(log_file);
+    }
+    configure_logger(opts.log_level.as_deref(), opts.log_file.as_deref());
 
     {
-        if let Some(Command::Sessions(Sessions::Action(cli_action))) = opts.command {
-            commands::send_action_to_session(cli_action, opts.session);
+        if let Some(Command::Sessions(Sessions::Action {
+            pane_id,
+            tab_id,
+            action,
+        })) = opts.command
+        {
+            commands::send_action_to_session(action, pane_id, tab_id, opts.session);
             std::process::exit(0);
         }
         if let Some(Command::Sessions(Sessions::Run {
@@ -36,7 +54,7 @@ fn main() {
                 name,
                 close_on_exit,
             };
-            commands::send_action_to_session(command_cli_action, opts.session);
+            commands::send_action_to_session(command_cli_action, None, None, opts.session);
             std::process::exit(0);
         }
         if let Some(Command::Sessions(Sessions::Edit {
@@ -52,7 +70,7 @@ fn main() {
                 line_number,
                 floating,
             };
-            commands::send_action_to_session(command_cli_action, opts.session);
+            commands::send_action_to_session(command_cli_action, None, None, opts.session);
             std::process::exit(0);
         }
         if let Some(Command::Sessions(Sessions::ConvertConfig { old_config_file })) = opts.command {
@@ -69,17 +87,22 @@ fn main() {
         }
     }
 
-    if let Some(Command::Sessions(Sessions::ListSessions)) = opts.command {
-        commands::list_sessions();
+    if let Some(Command::Sessions(Sessions::ListSessions { long })) = opts.command {
+        commands::list_sessions(long);
     } else if let Some(Command::Sessions(Sessions::KillAllSessions { yes })) = opts.command {
         commands::kill_all_sessions(yes);
     } else if let Some(Command::Sessions(Sessions::KillSession { ref target_session })) =
         opts.command
     {
         commands::kill_session(target_session);
+    } else if let Some(Command::Sessions(Sessions::DetachSession { ref target_session })) =
+        opts.command
+    {
+        commands::detach_session(target_session);
     } else if let Some(path) = opts.server {
         commands::start_server(path, opts.debug);
-    } else {
-        commands::start_client(opts);
+    } else if let Err(e) = commands::start_client(opts) {
+        commands::report_start_client_error(e);
+        std::process::exit(1);
     }
 }
@@ -1,12 +1,18 @@
 use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
 use std::time::SystemTime;
 use std::{fs, io, process};
 use suggest::Suggest;
 use zellij_utils::{
-    consts::ZELLIJ_SOCK_DIR,
+    consts::{BUILD_VERSION, ZELLIJ_SOCK_DIR},
+    data::ClientId,
     envs,
     interprocess::local_socket::LocalSocketStream,
     ipc::{ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg},
+    nix::{
+        sys::signal::{kill, Signal},
+        unistd::Pid,
+    },
 };
 
 pub(crate) fn get_sessions() -> Result<Vec<String>, io::ErrorKind> {
@@ -49,18 +55,39 @@ pub(crate) fn get_sessions_sorted_by_mtime() -> anyhow::Result<Vec<String>> {
     }
 }
 
+/// Connects to the session at `path` and exchanges versions with it. `Ok(None)` if the session
+/// was started by another version of zellij, which this one can't talk to.
+fn connect_to_session(
+    path: &Path,
+) -> io::Result<
+    Option<(
+        IpcSenderWithContext<ClientToServerMsg>,
+        IpcReceiverWithContext<ServerToClientMsg>,
+    )>,
+> {
+    let stream = LocalSocketStream::connect(path)?;
+    let mut sender = IpcSenderWithContext::new(stream);
+    let _ = sender.send_version();
+    let mut receiver: IpcReceiverWithContext<ServerToClientMsg> = sender.get_receiver();
+    if receiver.recv_version().as_deref() == Some(BUILD_VERSION.as_str()) {
+        Ok(Some((sender, receiver)))
+    } else {
+        Ok(None)
+    }
+}
+
 fn assert_socket(name: &str) -> bool {
     let path = &*ZELLIJ_SOCK_DIR.join(name);
-    match LocalSocketStream::connect(path) {
-        Ok(stream) => {
-            let mut sender = IpcSenderWithContext::new(stream);
+    match connect_to_session(path) {
+        Ok(Some((mut sender, mut receiver))) => {
             let _ = sender.send(ClientToServerMsg::ConnStatus);
-            let mut receiver: IpcReceiverWithContext<ServerToClientMsg> = sender.get_receiver();
             match receiver.recv() {
                 Some((ServerToClientMsg::Connected, _)) => true,
                 None | Some((_, _)) => false,
             }
         },
+        // the session is running, attaching to it will tell why it can't be used
+        Ok(None) => true,
         Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
             drop(fs::remove_file(path));
             false
@@ -113,10 +140,11 @@ pub(crate) fn get_active_session() -> ActiveSession {
 
 pub(crate) fn kill_session(name: &str) {
     let path = &*ZELLIJ_SOCK_DIR.join(name);
-    match LocalSocketStream::connect(path) {
-        Ok(stream) => {
-            let _ = IpcSenderWithContext::new(stream).send(ClientToServerMsg::KillSession);
+    match connect_to_session(path) {
+        Ok(Some((mut sender, _receiver))) => {
+            let _ = sender.send(ClientToServerMsg::KillSession);
         },
+        Ok(None) => kill_session_of_another_version(name, path),
         Err(e) => {
             eprintln!("Error occurred: {:?}", e);
             process::exit(1);
@@ -124,10 +152,112 @@ pub(crate) fn kill_session(name: &str) {
     };
 }
 
-pub(crate) fn list_sessions() {
+/// The server of a session started by another version of zellij can't be relied on to understand
+/// our messages, so it is sent a SIGTERM instead, which every version understands
+fn kill_session_of_another_version(name: &str, path: &Path) {
+    match session_server_pid(path) {
+        Some(pid) => {
+            if let Err(e) = kill(Pid::from_raw(pid), Signal::SIGTERM) {
+                eprintln!("Failed to kill session {:?}: {}", name, e);
+                process::exit(1);
+            }
+            // the server was not given the chance to clean up after itself
+            drop(fs::remove_file(path));
+        },
+        None => {
+            eprintln!(
+                "Session {:?} was started by another version of zellij, kill it with that version by running: zellij kill-session {}",
+                name, name
+            );
+            process::exit(1);
+        },
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn session_server_pid(path: &Path) -> Option<i32> {
+    use std::os::unix::io::AsRawFd;
+    use zellij_utils::nix::sys::socket::{getsockopt, sockopt::PeerCredentials};
+    let stream = LocalSocketStream::connect(path).ok()?;
+    getsockopt(stream.as_raw_fd(), PeerCredentials)
+        .ok()
+        .map(|credentials| credentials.pid())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn session_server_pid(_path: &Path) -> Option<i32> {
+    None
+}
+
+/// Returns the ids of the clients connected to the session, including the one we query it with,
+/// and its tab count. The server counts our client as connected until it is sent `ClientExited`
+/// through the sender.
+fn get_session_clients_and_tab_count(
+    name: &str,
+) -> Option<(
+    Vec<ClientId>,
+    usize,
+    IpcSenderWithContext<ClientToServerMsg>,
+)> {
+    let path = &*ZELLIJ_SOCK_DIR.join(name);
+    match connect_to_session(path) {
+        Ok(Some((mut sender, mut receiver))) => {
+            let _ = sender.send(ClientToServerMsg::ListClients);
+            match receiver.recv() {
+                Some((ServerToClientMsg::ActiveClients(client_ids, tab_count), _)) => {
+                    Some((client_ids, tab_count, sender))
+                },
+                None | Some((_, _)) => None,
+            }
+        },
+        Ok(None) | Err(_) => None,
+    }
+}
+
+pub(crate) fn detach_session(name: &str) {
+    match get_session_clients_and_tab_count(name) {
+        Some((client_ids, _tab_count, mut sender)) => {
+            // this also includes our own client id, which the server will simply drop
+            let _ = sender.send(ClientToServerMsg::DetachSession(client_ids));
+            let _ = sender.send(ClientToServerMsg::ClientExited);
+        },
+        None => {
+            eprintln!("Failed to query the clients of session {:?}", name);
+            process::exit(1);
+        },
+    };
+}
+
+pub(crate) fn print_sessions_with_client_and_tab_count(sessions: Vec<String>) {
+    let curr_session = envs::get_session_name().unwrap_or_else(|_| "".into());
+    for session in sessions.iter() {
+        let suffix = if curr_session == *session {
+            " (current)"
+        } else {
+            ""
+        };
+        // don't count the client we used to query the session
+        let (client_count, tab_count) = get_session_clients_and_tab_count(session)
+            .map(|(client_ids, tab_count, mut sender)| {
+                let _ = sender.send(ClientToServerMsg::ClientExited);
+                (
+                    client_ids.len().saturating_sub(1).to_string(),
+                    tab_count.to_string(),
+                )
+            })
+            .unwrap_or_else(|| ("?".into(), "?".into()));
+        println!("{}\t{}\t{}{}", session, client_count, tab_count, suffix);
+    }
+}
+
+pub(crate) fn list_sessions(long: bool) {
     let exit_code = match get_sessions() {
         Ok(sessions) if !sessions.is_empty() => {
-            print_sessions(sessions);
+            if long {
+                print_sessions_with_client_and_tab_count(sessions);
+            } else {
+                print_sessions(sessions);
+            }
             0
         },
         Ok(_) => {
@@ -198,6 +328,19 @@ pub(crate) fn assert_session(name: &str) {
 }
 
 pub(crate) fn assert_session_ne(name: &str) {
+    // the session name is used as the name of the server's ipc socket
+    let is_valid_name = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(std::path::MAIN_SEPARATOR)
+        && !name.contains('\0');
+    if !is_valid_name {
+        eprintln!(
+            "Invalid session name {:?}: session names must not be empty or contain path separators or null bytes.",
+            name
+        );
+        process::exit(1);
+    }
     match session_exists(name) {
         Ok(result) if !result => return,
         Ok(_) => println!("Session with name {:?} already exists. Use attach command to connect to it or specify a different name.", name),
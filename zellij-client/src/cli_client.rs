@@ -5,6 +5,7 @@ use std::{fs, path::PathBuf};
 
 use crate::os_input_output::ClientOsApi;
 use zellij_utils::{
+    consts::BUILD_VERSION,
     input::actions::Action,
     ipc::{ClientToServerMsg, ServerToClientMsg},
 };
@@ -17,7 +18,18 @@ pub fn start_cli_client(os_input: Box<dyn ClientOsApi>, session_name: &str, acti
         sock_dir.push(session_name);
         sock_dir
     };
-    os_input.connect_to_server(&*zellij_ipc_pipe);
+    if !zellij_ipc_pipe.exists() {
+        eprintln!("Session '{}' not found.", session_name);
+        process::exit(1);
+    }
+    let server_version = os_input.connect_to_server(&*zellij_ipc_pipe);
+    if server_version.as_deref() != Some(BUILD_VERSION.as_str()) {
+        eprintln!(
+            "Session '{}' was started by another version of zellij, which can't be sent actions by this one.",
+            session_name
+        );
+        process::exit(1);
+    }
     for action in actions {
         let msg = ClientToServerMsg::Action(action, None);
         os_input.send_to_server(msg);
@@ -28,6 +40,15 @@ pub fn start_cli_client(os_input: Box<dyn ClientOsApi>, session_name: &str, acti
                 os_input.send_to_server(ClientToServerMsg::ClientExited);
                 process::exit(0);
             },
+            Some((ServerToClientMsg::Log(lines), _)) => {
+                for line in lines {
+                    println!("{}", line);
+                }
+            },
+            None => {
+                eprintln!("Lost connection to session '{}'.", session_name);
+                process::exit(1);
+            },
             _ => {},
         }
     }
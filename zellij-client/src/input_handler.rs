@@ -1,7 +1,8 @@
 //! Main input logic.
 use crate::{
-    os_input_output::ClientOsApi, stdin_ansi_parser::AnsiStdinInstruction, ClientId,
-    ClientInstruction, CommandIsExecuting, InputInstruction,
+    os_input_output::{ClientOsApi, ENABLE_MOUSE_MOTION_REPORTING},
+    stdin_ansi_parser::AnsiStdinInstruction,
+    ClientId, ClientInstruction, CommandIsExecuting, InputInstruction,
 };
 use zellij_utils::{
     channels::{Receiver, SenderWithContext, OPENCALLS},
@@ -18,6 +19,8 @@ use zellij_utils::{
     termwiz::input::InputEvent,
 };
 
+use std::io::Write;
+
 #[derive(Debug, Clone, Copy)]
 enum HeldMouseButton {
     Left,
@@ -80,6 +83,7 @@ impl InputHandler {
         if self.options.mouse_mode.unwrap_or(true) {
             self.os_input.enable_mouse();
         }
+        self.enable_mouse_motion_reporting();
         loop {
             if self.should_exit {
                 break;
@@ -117,6 +121,7 @@ impl InputHandler {
                 },
                 Ok((InputInstruction::SwitchToMode(input_mode), _error_context)) => {
                     self.mode = input_mode;
+                    self.enable_mouse_motion_reporting();
                 },
                 Ok((
                     InputInstruction::AnsiStdinInstructions(ansi_stdin_instructions),
@@ -130,11 +135,31 @@ impl InputHandler {
             }
         }
     }
+    /// With focus_follows_mouse, the terminal is asked to also report the mouse moving while no
+    /// button is held. This is asked again whenever the mode changes, so that the reporting is
+    /// back on if the terminal reset it in the meantime.
+    fn enable_mouse_motion_reporting(&self) {
+        if self.options.mouse_mode.unwrap_or(true)
+            && self.options.focus_follows_mouse.unwrap_or(false)
+        {
+            let _ = self
+                .os_input
+                .get_stdout_writer()
+                .write_all(ENABLE_MOUSE_MOTION_REPORTING.as_bytes());
+        }
+    }
     fn handle_key(&mut self, key: &Key, raw_bytes: Vec<u8>) {
         let keybinds = &self.config.keybinds;
-        for action in
-            keybinds.get_actions_for_key_in_mode_or_default_action(&self.mode, key, raw_bytes)
-        {
+        let actions = match keybinds.get_actions_for_key_in_mode(&self.mode, key) {
+            Some(actions) => actions.clone(),
+            // with a prefix key tmux mode is only armed for a single keystroke, a key that is
+            // not bound after the prefix goes back to passing input through
+            None if self.mode == InputMode::Tmux && self.options.prefix_key.is_some() => {
+                vec![Action::SwitchToMode(InputMode::Normal)]
+            },
+            None => vec![keybinds.default_action_for_mode(&self.mode, raw_bytes)],
+        };
+        for action in actions {
             let should_exit = self.dispatch_action(action, None);
             if should_exit {
                 self.should_exit = true;
@@ -199,6 +224,14 @@ impl InputHandler {
                     self.holding_mouse = Some(HeldMouseButton::Middle);
                 },
             },
+            MouseEvent::Release(point)
+                if self.holding_mouse.is_none()
+                    && self.options.focus_follows_mouse.unwrap_or(false) =>
+            {
+                // with motion reporting on, the mouse moving without a button held is reported
+                // the same way as a button being released
+                self.dispatch_action(Action::MouseMove(point), None);
+            },
             MouseEvent::Release(point) => {
                 let button_released = self.holding_mouse.unwrap_or_default();
                 match button_released {
@@ -247,6 +280,11 @@ impl InputHandler {
 
         match action {
             Action::NoOp => {},
+            Action::Quit if self.options.confirm_quit.unwrap_or(false) => {
+                // the server either asks us to confirm first or tells us to exit
+                self.os_input
+                    .send_to_server(ClientToServerMsg::Action(action, client_id));
+            },
             Action::Quit => {
                 self.os_input
                     .send_to_server(ClientToServerMsg::Action(action, client_id));
@@ -272,8 +310,13 @@ impl InputHandler {
             | Action::ToggleFloatingPanes
             | Action::TogglePaneEmbedOrFloating
             | Action::NewTab(..)
+            | Action::NewTabWithLayout(_)
             | Action::GoToNextTab
             | Action::GoToPreviousTab
+            | Action::MoveTabLeft
+            | Action::MoveTabRight
+            | Action::MovePaneToNewTab
+            | Action::MovePaneToTab(_)
             | Action::CloseTab
             | Action::GoToTab(_)
             | Action::ToggleTab
@@ -4,12 +4,15 @@ pub mod cli_client;
 mod command_is_executing;
 mod input_handler;
 pub mod old_config_converter;
+mod recording;
 mod stdin_ansi_parser;
 mod stdin_handler;
 
 use log::info;
 use std::env::current_exe;
+use std::fmt;
 use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
 use std::process::Command;
 use std::sync::{Arc, Mutex};
@@ -22,15 +25,22 @@ use crate::{
 };
 use zellij_utils::{
     channels::{self, ChannelWithContext, SenderWithContext},
-    consts::ZELLIJ_IPC_PIPE,
+    consts::{BUILD_VERSION, ZELLIJ_IPC_PIPE},
     data::{ClientId, InputMode, Style},
     envs,
     errors::{ClientContext, ContextType, ErrorInstruction},
-    input::{config::Config, options::Options},
+    input::{
+        config::{Config, ConfigError},
+        options::Options,
+    },
     ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg},
+    shared::{color_depth, quantize_palette},
     termwiz::input::InputEvent,
 };
-use zellij_utils::{cli::CliArgs, input::layout::Layout};
+use zellij_utils::{
+    cli::{CliArgs, Command, Sessions},
+    input::layout::Layout,
+};
 
 /// Instructions related to the client-side application
 #[derive(Debug, Clone)]
@@ -42,6 +52,7 @@ pub(crate) enum ClientInstruction {
     SwitchToMode(InputMode),
     Connected,
     ActiveClients(Vec<ClientId>),
+    Log(Vec<String>),
 }
 
 impl From<ServerToClientMsg> for ClientInstruction {
@@ -54,7 +65,10 @@ impl From<ServerToClientMsg> for ClientInstruction {
                 ClientInstruction::SwitchToMode(input_mode)
             },
             ServerToClientMsg::Connected => ClientInstruction::Connected,
-            ServerToClientMsg::ActiveClients(clients) => ClientInstruction::ActiveClients(clients),
+            ServerToClientMsg::ActiveClients(clients, _tab_count) => {
+                ClientInstruction::ActiveClients(clients)
+            },
+            ServerToClientMsg::Log(lines) => ClientInstruction::Log(lines),
         }
     }
 }
@@ -69,6 +83,7 @@ impl From<&ClientInstruction> for ClientContext {
             ClientInstruction::SwitchToMode(_) => ClientContext::SwitchToMode,
             ClientInstruction::Connected => ClientContext::Connected,
             ClientInstruction::ActiveClients(_) => ClientContext::ActiveClients,
+            ClientInstruction::Log(_) => ClientContext::Log,
         }
     }
 }
@@ -79,6 +94,34 @@ impl ErrorInstruction for ClientInstruction {
     }
 }
 
+/// Spawns a client thread whose panic is reported to the main loop as a
+/// [`ClientInstruction::Error`], so that it restores the terminal before exiting
+fn spawn_client_thread<F>(
+    name: &str,
+    send_client_instructions: SenderWithContext<ClientInstruction>,
+    f: F,
+) -> io::Result<thread::JoinHandle<()>>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let thread_name = name.to_string();
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) {
+                let msg = payload
+                    .downcast_ref::<&str>()
+                    .map(|msg| msg.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "An unexpected error occurred!".to_string());
+                let _ = send_client_instructions.send(ClientInstruction::Error(format!(
+                    "Thread '{}' panicked: {}",
+                    thread_name, msg
+                )));
+            }
+        })
+}
+
 fn spawn_server(socket_path: &Path, debug: bool) -> io::Result<()> {
     let mut cmd = Command::new(current_exe()?);
     cmd.arg("--server");
@@ -115,6 +158,55 @@ impl ClientInfo {
     }
 }
 
+/// The ways starting a client can fail, left to whoever started it to report
+#[derive(Debug)]
+pub enum StartClientError {
+    /// The configuration or the layout could not be loaded
+    Config(ConfigError),
+    /// The server could not be started
+    Connection(io::Error),
+    /// The terminal the client runs in could not be set up
+    Terminal(io::Error),
+    /// The session was started by a build of zellij this one can't talk to, whose version it
+    /// holds if the session told it
+    VersionMismatch(Option<String>),
+}
+
+impl fmt::Display for StartClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StartClientError::Config(e) => write!(f, "{}", e),
+            StartClientError::Connection(e) => write!(f, "Failed to start the server: {}", e),
+            StartClientError::Terminal(e) => write!(f, "Failed to set up the terminal: {}", e),
+            StartClientError::VersionMismatch(server_version) => write!(
+                f,
+                "This session was started by zellij {}, which can't talk to this zellij ({}).\nPlease quit the old session and start a new one.",
+                server_version.as_deref().unwrap_or("of another version"),
+                *BUILD_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StartClientError {}
+
+impl From<ConfigError> for StartClientError {
+    fn from(e: ConfigError) -> Self {
+        StartClientError::Config(e)
+    }
+}
+
+const READ_ONLY_INDICATOR: &str = " READ ONLY ";
+
+/// Draws the read-only indicator in the top right corner, leaving the cursor where it was
+fn read_only_indicator(cols: usize) -> String {
+    let col = cols.saturating_sub(READ_ONLY_INDICATOR.len()) + 1;
+    format!(
+        "\u{1b}7\u{1b}[1;{}H\u{1b}[1;7m{}\u{1b}[m\u{1b}8",
+        col, READ_ONLY_INDICATOR
+    )
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum InputInstruction {
     KeyEvent(InputEvent, Vec<u8>),
@@ -129,36 +221,56 @@ pub fn start_client(
     config_options: Options,
     info: ClientInfo,
     layout: Option<Layout>,
-) {
+) -> Result<(), StartClientError> {
     info!("Starting Zellij client!");
     let clear_client_terminal_attributes = "\u{1b}[?1l\u{1b}=\u{1b}[r\u{1b}[?1000l\u{1b}[?1002l\u{1b}[?1003l\u{1b}[?1005l\u{1b}[?1006l\u{1b}[?12l";
-    let take_snapshot = "\u{1b}[?1049h";
+    let no_altscreen = opts.no_altscreen;
+    let quiet = opts.quiet;
+    let read_only = matches!(
+        opts.command,
+        Some(Command::Sessions(Sessions::Attach {
+            read_only: true,
+            ..
+        }))
+    );
+    let full_screen_ws = os_input.get_terminal_size_using_fd(0);
+    let (take_snapshot, restore_snapshot) = snapshot_sequences(no_altscreen, full_screen_ws.rows);
     let bracketed_paste = "\u{1b}[?2004h";
-    os_input.unset_raw_mode(0).unwrap();
+    os_input
+        .unset_raw_mode(0)
+        .map_err(|e| StartClientError::Terminal(e.into()))?;
 
-    let _ = os_input
+    os_input
         .get_stdout_writer()
-        .write(take_snapshot.as_bytes())
-        .unwrap();
-    let _ = os_input
+        .write_all(take_snapshot.as_bytes())
+        .map_err(StartClientError::Terminal)?;
+    os_input
         .get_stdout_writer()
-        .write(clear_client_terminal_attributes.as_bytes())
-        .unwrap();
+        .write_all(clear_client_terminal_attributes.as_bytes())
+        .map_err(StartClientError::Terminal)?;
     envs::set_zellij("0".to_string());
     config.env.set_vars();
 
+    let color_depth = color_depth(
+        envs::get_color_depth().ok().as_deref(),
+        config_options.color_depth,
+        std::env::var("TERM").ok().as_deref(),
+        std::env::var("COLORTERM").ok().as_deref(),
+    );
     let palette = config
         .theme_config(&config_options)
         .unwrap_or_else(|| os_input.load_palette());
+    let palette = quantize_palette(palette, color_depth);
 
-    let full_screen_ws = os_input.get_terminal_size_using_fd(0);
     let client_attributes = ClientAttributes {
         size: full_screen_ws,
         style: Style {
             colors: palette,
             rounded_corners: config.ui.pane_frames.rounded_corners,
+            frame_style: config.ui.pane_frames.style,
         },
         keybinds: config.keybinds.clone(),
+        read_only,
     };
 
     let first_msg = match info {
@@ -172,7 +284,7 @@ pub fn start_client(
             envs::set_session_name(name);
             envs::set_initial_environment_vars();
 
-            spawn_server(&*ZELLIJ_IPC_PIPE, opts.debug).unwrap();
+            spawn_server(&*ZELLIJ_IPC_PIPE, opts.debug).map_err(StartClientError::Connection)?;
 
             ClientToServerMsg::NewClient(
                 client_attributes,
@@ -184,16 +296,24 @@ pub fn start_client(
         },
     };
 
-    os_input.connect_to_server(&*ZELLIJ_IPC_PIPE);
+    let server_version = os_input.connect_to_server(&*ZELLIJ_IPC_PIPE);
+    if server_version.as_deref() != Some(BUILD_VERSION.as_str()) {
+        let _ = os_input
+            .get_stdout_writer()
+            .write_all(restore_snapshot.as_bytes());
+        return Err(StartClientError::VersionMismatch(server_version));
+    }
     os_input.send_to_server(first_msg);
 
     let mut command_is_executing = CommandIsExecuting::new();
 
     os_input.set_raw_mode(0);
-    let _ = os_input
-        .get_stdout_writer()
-        .write(bracketed_paste.as_bytes())
-        .unwrap();
+    if config_options.bracketed_paste.unwrap_or(true) {
+        let _ = os_input
+            .get_stdout_writer()
+            .write(bracketed_paste.as_bytes())
+            .unwrap();
+    }
 
     let (send_client_instructions, receive_client_instructions): ChannelWithContext<
         ClientInstruction,
@@ -210,47 +330,49 @@ pub fn start_client(
         let send_client_instructions = send_client_instructions.clone();
         let os_input = os_input.clone();
         Box::new(move |info| {
-            if let Ok(()) = os_input.unset_raw_mode(0) {
-                handle_panic(info, &send_client_instructions);
+            let _ = os_input.unset_raw_mode(0);
+            if thread::current().name() == Some("main") {
+                // the main loop is the one that would restore the terminal, so it won't get the
+                // chance - leave the alternate screen and show the cursor before reporting
+                os_input.disable_mouse();
+                let mut stdout = os_input.get_stdout_writer();
+                let _ = stdout.write_all(format!("{}\u{1b}[?25h", restore_snapshot).as_bytes());
+                let _ = stdout.flush();
             }
+            handle_panic(info, &send_client_instructions);
         })
     });
 
     let on_force_close = config_options.on_force_close.unwrap_or_default();
     let stdin_ansi_parser = Arc::new(Mutex::new(StdinAnsiParser::new()));
 
-    let _stdin_thread = thread::Builder::new()
-        .name("stdin_handler".to_string())
-        .spawn({
-            let os_input = os_input.clone();
-            let send_input_instructions = send_input_instructions.clone();
-            let stdin_ansi_parser = stdin_ansi_parser.clone();
-            move || stdin_loop(os_input, send_input_instructions, stdin_ansi_parser)
-        });
+    let _stdin_thread = spawn_client_thread("stdin_handler", send_client_instructions.clone(), {
+        let os_input = os_input.clone();
+        let send_input_instructions = send_input_instructions.clone();
+        let stdin_ansi_parser = stdin_ansi_parser.clone();
+        move || stdin_loop(os_input, send_input_instructions, stdin_ansi_parser)
+    });
 
-    let _input_thread = thread::Builder::new()
-        .name("input_handler".to_string())
-        .spawn({
-            let send_client_instructions = send_client_instructions.clone();
-            let command_is_executing = command_is_executing.clone();
-            let os_input = os_input.clone();
-            let default_mode = config_options.default_mode.unwrap_or_default();
-            move || {
-                input_loop(
-                    os_input,
-                    config,
-                    config_options,
-                    command_is_executing,
-                    send_client_instructions,
-                    default_mode,
-                    receive_input_instructions,
-                )
-            }
-        });
+    let _input_thread = spawn_client_thread("input_handler", send_client_instructions.clone(), {
+        let send_client_instructions = send_client_instructions.clone();
+        let command_is_executing = command_is_executing.clone();
+        let os_input = os_input.clone();
+        let default_mode = config_options.default_mode.unwrap_or_default();
+        move || {
+            input_loop(
+                os_input,
+                config,
+                config_options,
+                command_is_executing,
+                send_client_instructions,
+                default_mode,
+                receive_input_instructions,
+            )
+        }
+    });
 
-    let _signal_thread = thread::Builder::new()
-        .name("signal_listener".to_string())
-        .spawn({
+    let _signal_thread =
+        spawn_client_thread("signal_listener", send_client_instructions.clone(), {
             let os_input = os_input.clone();
             move || {
                 os_input.handle_signals(
@@ -286,48 +408,46 @@ pub fn start_client(
         })
         .unwrap();
 
-    let router_thread = thread::Builder::new()
-        .name("router".to_string())
-        .spawn({
-            let os_input = os_input.clone();
-            let mut should_break = false;
-            move || loop {
-                match os_input.recv_from_server() {
-                    Some((instruction, err_ctx)) => {
-                        err_ctx.update_thread_ctx();
-                        if let ServerToClientMsg::Exit(_) = instruction {
-                            should_break = true;
-                        }
-                        send_client_instructions.send(instruction.into()).unwrap();
-                        if should_break {
-                            break;
-                        }
-                    },
-                    None => {
-                        send_client_instructions
-                            .send(ClientInstruction::UnblockInputThread)
-                            .unwrap();
-                        log::error!("Received empty message from server");
-                        send_client_instructions
-                            .send(ClientInstruction::Error(
-                                "Received empty message from server".to_string(),
-                            ))
-                            .unwrap();
+    let router_thread = spawn_client_thread("router", send_client_instructions.clone(), {
+        let os_input = os_input.clone();
+        let mut should_break = false;
+        move || loop {
+            match os_input.recv_from_server() {
+                Some((instruction, err_ctx)) => {
+                    err_ctx.update_thread_ctx();
+                    if let ServerToClientMsg::Exit(_) = instruction {
+                        should_break = true;
+                    }
+                    send_client_instructions.send(instruction.into()).unwrap();
+                    if should_break {
                         break;
-                    },
-                }
+                    }
+                },
+                None => {
+                    send_client_instructions
+                        .send(ClientInstruction::UnblockInputThread)
+                        .unwrap();
+                    log::error!("Received empty message from server");
+                    send_client_instructions
+                        .send(ClientInstruction::Error(
+                            "Received empty message from server".to_string(),
+                        ))
+                        .unwrap();
+                    break;
+                },
             }
-        })
-        .unwrap();
+        }
+    })
+    .unwrap();
 
     let handle_error = |backtrace: String| {
         os_input.unset_raw_mode(0).unwrap();
         let goto_start_of_last_line = format!("\u{1b}[{};{}H", full_screen_ws.rows, 1);
-        let restore_snapshot = "\u{1b}[?1049l";
+        let show_cursor = "\u{1b}[?25h";
         os_input.disable_mouse();
         let error = format!(
-            "{}\n{}{}\n",
-            restore_snapshot, goto_start_of_last_line, backtrace
+            "{}{}\n{}{}\n",
+            restore_snapshot, show_cursor, goto_start_of_last_line, backtrace
         );
         let _ = os_input
             .get_stdout_writer()
@@ -337,12 +457,15 @@ pub fn start_client(
         std::process::exit(1);
     };
 
-    let exit_msg: String;
+    let exit_reason: ExitReason;
+    let mut pending_instruction = None;
 
     loop {
-        let (client_instruction, mut err_ctx) = receive_client_instructions
-            .recv()
-            .expect("failed to receive app instruction on channel");
+        let (client_instruction, mut err_ctx) = pending_instruction.take().unwrap_or_else(|| {
+            receive_client_instructions
+                .recv()
+                .expect("failed to receive app instruction on channel")
+        });
 
         err_ctx.add_call(ContextType::Client((&client_instruction).into()));
         match client_instruction {
@@ -352,7 +475,7 @@ pub fn start_client(
                 if let ExitReason::Error(_) = reason {
                     handle_error(reason.to_string());
                 }
-                exit_msg = reason.to_string();
+                exit_reason = reason;
                 break;
             },
             ClientInstruction::Error(backtrace) => {
@@ -363,6 +486,30 @@ pub fn start_client(
                 stdout
                     .write_all(output.as_bytes())
                     .expect("cannot write to stdout");
+                // write out any renders that queued up in the meantime before flushing once -
+                // they only contain what changed, so none of them can be skipped
+                while let Ok((next_instruction, next_err_ctx)) =
+                    receive_client_instructions.try_recv()
+                {
+                    match next_instruction {
+                        ClientInstruction::Render(output) => {
+                            stdout
+                                .write_all(output.as_bytes())
+                                .expect("cannot write to stdout");
+                        },
+                        next_instruction => {
+                            pending_instruction = Some((next_instruction, next_err_ctx));
+                            break;
+                        },
+                    }
+                }
+                if read_only {
+                    // drawn over every render, so that the session cannot paint over it
+                    let cols = os_input.get_terminal_size_using_fd(0).cols;
+                    stdout
+                        .write_all(read_only_indicator(cols).as_bytes())
+                        .expect("cannot write to stdout");
+                }
                 stdout.flush().expect("could not flush");
             },
             ClientInstruction::UnblockInputThread => {
@@ -373,6 +520,12 @@ pub fn start_client(
                     .send(InputInstruction::SwitchToMode(input_mode))
                     .unwrap();
             },
+            ClientInstruction::Log(lines) => {
+                // printing these would garble the screen of the session
+                for line in lines {
+                    log::info!("{}", line);
+                }
+            },
             _ => {},
         }
     }
@@ -380,23 +533,58 @@ pub fn start_client(
     router_thread.join().unwrap();
 
     // cleanup();
-    let reset_style = "\u{1b}[m";
-    let show_cursor = "\u{1b}[?25h";
-    let restore_snapshot = "\u{1b}[?1049l";
-    let goto_start_of_last_line = format!("\u{1b}[{};{}H", full_screen_ws.rows, 1);
-    let goodbye_message = format!(
-        "{}\n{}{}{}{}\n",
-        goto_start_of_last_line, restore_snapshot, reset_style, show_cursor, exit_msg
-    );
+    let goodbye_message =
+        goodbye_message(&exit_reason, quiet, restore_snapshot, full_screen_ws.rows);
 
     os_input.disable_mouse();
-    info!("{}", exit_msg);
+    info!("{}", exit_reason);
     os_input.unset_raw_mode(0).unwrap();
     let mut stdout = os_input.get_stdout_writer();
     let _ = stdout.write(goodbye_message.as_bytes()).unwrap();
     stdout.flush().unwrap();
+    Ok(())
+}
+
+/// The sequences written when the client starts and exits to keep what was on screen before it
+/// started. The alternate screen is used unless `no_altscreen` is set.
+fn snapshot_sequences(no_altscreen: bool, rows: usize) -> (String, &'static str) {
+    if no_altscreen {
+        // scroll whatever is on screen into the terminal's scrollback so we can draw over the
+        // visible rows without losing it
+        ("\n".repeat(rows), "")
+    } else {
+        ("\u{1b}[?1049h".to_owned(), "\u{1b}[?1049l")
+    }
+}
+
+/// What is written once the client exits: the sequences restoring the terminal, followed by the
+/// reason for exiting. Only the message of a normal exit is left out when quiet, errors and the
+/// reasons for being detached or not being able to attach are still printed.
+fn goodbye_message(
+    exit_reason: &ExitReason,
+    quiet: bool,
+    restore_snapshot: &str,
+    rows: usize,
+) -> String {
+    let reset_style = "\u{1b}[m";
+    let show_cursor = "\u{1b}[?25h";
+    let goto_start_of_last_line = format!("\u{1b}[{};{}H", rows, 1);
+    let exited_normally = matches!(exit_reason, ExitReason::Normal | ExitReason::NormalDetached);
+    let printed_exit_msg = if quiet && exited_normally {
+        String::new()
+    } else {
+        format!("{}\n", exit_reason)
+    };
+    format!(
+        "{}\n{}{}{}{}",
+        goto_start_of_last_line, restore_snapshot, reset_style, show_cursor, printed_exit_msg
+    )
 }
 
 #[cfg(test)]
 #[path = "./unit/stdin_tests.rs"]
 mod stdin_tests;
+
+#[cfg(test)]
+#[path = "./unit/start_client_tests.rs"]
+mod start_client_tests;
@@ -76,7 +76,8 @@ const COPY_COMMAND_DESCRIPTION: &'static str = "
 // Provide a command to execute when copying text. The text will be piped to
 // the stdin of the program to perform the copy. This can be used with
 // terminal emulators which do not support the OSC 52 ANSI control sequence
-// that will be used by default if this option is not set.
+// that will be used by default if this option is not set. If the command
+// cannot be run, the text is copied with OSC 52 instead.
 // Examples:
 //
 // copy_command \"xclip -selection clipboard\" // x11
@@ -87,7 +88,8 @@ const COPY_COMMAND_DESCRIPTION: &'static str = "
 const COPY_CLIPBOARD_DESCRIPTION: &'static str = "
 // Choose the destination for copied text
 // Allows using the primary selection buffer (on x11/wayland) instead of the system clipboard.
-// Does not apply when using copy_command.
+// When using copy_command, this only applies if the command cannot be run and the text is copied
+// with OSC 52 instead.
 // Options:
 //   - system (default)
 //   - primary
@@ -1,3 +1,4 @@
+use crate::recording::{Recording, TeeWriter};
 use zellij_utils::pane_size::Size;
 use zellij_utils::{interprocess, libc, nix, signal_hook};
 
@@ -18,10 +19,31 @@ use zellij_utils::{
     shared::default_palette,
 };
 
-const SIGWINCH_CB_THROTTLE_DURATION: time::Duration = time::Duration::from_millis(50);
+const SIGWINCH_CB_DEBOUNCE_DURATION: time::Duration = time::Duration::from_millis(30);
 
 const ENABLE_MOUSE_SUPPORT: &str = "\u{1b}[?1000h\u{1b}[?1002h\u{1b}[?1015h\u{1b}[?1006h";
-const DISABLE_MOUSE_SUPPORT: &str = "\u{1b}[?1006l\u{1b}[?1015l\u{1b}[?1002l\u{1b}[?1000l";
+const DISABLE_MOUSE_SUPPORT: &str =
+    "\u{1b}[?1006l\u{1b}[?1015l\u{1b}[?1003l\u{1b}[?1002l\u{1b}[?1000l";
+// also report the mouse moving while no button is held
+pub(crate) const ENABLE_MOUSE_MOTION_REPORTING: &str = "\u{1b}[?1003h";
+
+/// Wait until no more SIGWINCH signals arrive in between polls of `pending_signals`, returning
+/// whether a signal to quit arrived in the meantime.
+fn wait_for_resizes_to_settle(mut pending_signals: impl FnMut() -> Vec<i32>) -> bool {
+    loop {
+        thread::sleep(SIGWINCH_CB_DEBOUNCE_DURATION);
+        let mut resized_again = false;
+        for signal in pending_signals() {
+            match signal {
+                SIGWINCH => resized_again = true,
+                _ => return true,
+            }
+        }
+        if !resized_again {
+            return false;
+        }
+    }
+}
 
 fn into_raw_mode(pid: RawFd) {
     let mut tio = termios::tcgetattr(pid).expect("could not get terminal attribute");
@@ -77,6 +99,16 @@ pub struct ClientOsInputOutput {
     orig_termios: Arc<Mutex<termios::Termios>>,
     send_instructions_to_server: Arc<Mutex<Option<IpcSenderWithContext<ClientToServerMsg>>>>,
     receive_instructions_from_server: Arc<Mutex<Option<IpcReceiverWithContext<ServerToClientMsg>>>>,
+    recording: Arc<Mutex<Option<Recording>>>,
+}
+
+impl ClientOsInputOutput {
+    /// Starts recording everything written to stdout into an asciicast file at `path`.
+    pub fn record_output_to(&self, path: &Path) -> io::Result<()> {
+        let recording = Recording::new(path, get_terminal_size_using_fd(0))?;
+        *self.recording.lock().unwrap() = Some(recording);
+        Ok(())
+    }
 }
 
 /// The `ClientOsApi` trait represents an abstract interface to the features of an operating system that
@@ -103,8 +135,9 @@ pub trait ClientOsApi: Send + Sync {
     // This should be called from the client-side router thread only.
     fn recv_from_server(&self) -> Option<(ServerToClientMsg, ErrorContext)>;
     fn handle_signals(&self, sigwinch_cb: Box<dyn Fn()>, quit_cb: Box<dyn Fn()>);
-    /// Establish a connection with the server socket.
-    fn connect_to_server(&self, path: &Path);
+    /// Establish a connection with the server socket, returning the version the server was built
+    /// as (`None` if it didn't say).
+    fn connect_to_server(&self, path: &Path) -> Option<String>;
     fn load_palette(&self) -> Palette;
     fn enable_mouse(&self);
     fn disable_mouse(&self);
@@ -137,7 +170,7 @@ impl ClientOsApi for ClientOsInputOutput {
     }
     fn get_stdout_writer(&self) -> Box<dyn io::Write> {
         let stdout = ::std::io::stdout();
-        Box::new(stdout)
+        Box::new(TeeWriter::new(stdout, self.recording.clone()))
     }
     fn get_stdin_reader(&self) -> Box<dyn io::Read> {
         let stdin = ::std::io::stdin();
@@ -163,17 +196,19 @@ impl ClientOsApi for ClientOsInputOutput {
             .recv()
     }
     fn handle_signals(&self, sigwinch_cb: Box<dyn Fn()>, quit_cb: Box<dyn Fn()>) {
-        let mut sigwinch_cb_timestamp = time::Instant::now();
         let mut signals = Signals::new(&[SIGWINCH, SIGTERM, SIGINT, SIGQUIT, SIGHUP]).unwrap();
-        for signal in signals.forever() {
+        while let Some(signal) = signals.forever().next() {
             match signal {
                 SIGWINCH => {
-                    // throttle sigwinch_cb calls, reduce excessive renders while resizing
-                    if sigwinch_cb_timestamp.elapsed() < SIGWINCH_CB_THROTTLE_DURATION {
-                        thread::sleep(SIGWINCH_CB_THROTTLE_DURATION);
-                    }
-                    sigwinch_cb_timestamp = time::Instant::now();
+                    // resizing a window sends a burst of these, wait until they stop coming and
+                    // only then report the (final) size, to avoid a relayout for each of them
+                    let should_quit =
+                        wait_for_resizes_to_settle(|| signals.pending().collect::<Vec<_>>());
                     sigwinch_cb();
+                    if should_quit {
+                        quit_cb();
+                        break;
+                    }
                 },
                 SIGTERM | SIGINT | SIGQUIT | SIGHUP => {
                     quit_cb();
@@ -183,7 +218,7 @@ impl ClientOsApi for ClientOsInputOutput {
             }
         }
     }
-    fn connect_to_server(&self, path: &Path) {
+    fn connect_to_server(&self, path: &Path) -> Option<String> {
         let socket;
         loop {
             match LocalSocketStream::connect(path) {
@@ -196,10 +231,13 @@ impl ClientOsApi for ClientOsInputOutput {
                 },
             }
         }
-        let sender = IpcSenderWithContext::new(socket);
-        let receiver = sender.get_receiver();
+        let mut sender = IpcSenderWithContext::new(socket);
+        let _ = sender.send_version();
+        let mut receiver = sender.get_receiver();
+        let server_version = receiver.recv_version();
         *self.send_instructions_to_server.lock().unwrap() = Some(sender);
         *self.receive_instructions_from_server.lock().unwrap() = Some(receiver);
+        server_version
     }
     fn load_palette(&self) -> Palette {
         // this was removed because termbg doesn't release stdin in certain scenarios (we know of
@@ -247,6 +285,7 @@ pub fn get_client_os_input() -> Result<ClientOsInputOutput, nix::Error> {
         orig_termios,
         send_instructions_to_server: Arc::new(Mutex::new(None)),
         receive_instructions_from_server: Arc::new(Mutex::new(None)),
+        recording: Arc::new(Mutex::new(None)),
     })
 }
 
@@ -292,3 +331,7 @@ impl Default for StdinPoller {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/os_input_output_tests.rs"]
+mod os_input_output_tests;
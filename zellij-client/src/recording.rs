@@ -0,0 +1,99 @@
+//! Recording everything the client draws to the terminal, in the
+//! [asciicast v2](https://github.com/asciinema/asciinema/blob/develop/doc/asciicast-v2.md)
+//! format so that it can be played back with asciinema.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use zellij_utils::{pane_size::Size, serde_json};
+
+pub(crate) struct Recording {
+    file: BufWriter<File>,
+    started_at: Instant,
+    // bytes of a utf-8 character that was cut off at the end of the last recorded write
+    incomplete_character: Vec<u8>,
+}
+
+impl Recording {
+    /// Creates the recording file at `path` and writes the header describing a terminal of
+    /// `size` to it.
+    pub fn new(path: &Path, size: Size) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs())
+            .unwrap_or(0);
+        let header = serde_json::json!({
+            "version": 2,
+            "width": size.cols,
+            "height": size.rows,
+            "timestamp": timestamp,
+        });
+        writeln!(file, "{}", header)?;
+        Ok(Recording {
+            file,
+            started_at: Instant::now(),
+            incomplete_character: vec![],
+        })
+    }
+    /// Records `output` as having been written to the terminal just now.
+    pub fn record_output(&mut self, output: &[u8]) -> io::Result<()> {
+        let mut output = std::mem::take(&mut self.incomplete_character)
+            .into_iter()
+            .chain(output.iter().copied())
+            .collect::<Vec<u8>>();
+        if let Err(e) = std::str::from_utf8(&output) {
+            if e.error_len().is_none() {
+                // the rest of this character will come with the next write
+                self.incomplete_character = output.split_off(e.valid_up_to());
+            }
+        }
+        if output.is_empty() {
+            return Ok(());
+        }
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        let event = serde_json::json!([elapsed, "o", String::from_utf8_lossy(&output)]);
+        writeln!(self.file, "{}", event)
+    }
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Writes everything to `inner`, also adding it to the [`Recording`] if one is in progress.
+pub(crate) struct TeeWriter<W: Write> {
+    inner: W,
+    recording: Arc<Mutex<Option<Recording>>>,
+}
+
+impl<W: Write> TeeWriter<W> {
+    pub fn new(inner: W, recording: Arc<Mutex<Option<Recording>>>) -> Self {
+        TeeWriter { inner, recording }
+    }
+}
+
+impl<W: Write> Write for TeeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(recording) = self.recording.lock().unwrap().as_mut() {
+            if let Err(e) = recording.record_output(&buf[..written]) {
+                log::error!("Failed to record output: {}", e);
+            }
+        }
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(recording) = self.recording.lock().unwrap().as_mut() {
+            if let Err(e) = recording.flush() {
+                log::error!("Failed to flush recording: {}", e);
+            }
+        }
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/recording_tests.rs"]
+mod recording_tests;
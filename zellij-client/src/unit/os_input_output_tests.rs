@@ -0,0 +1,26 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+#[test]
+fn resizes_settle_once_no_more_sigwinch_arrives() {
+    let mut polls = VecDeque::from(vec![vec![SIGWINCH, SIGWINCH], vec![SIGWINCH], vec![]]);
+    let should_quit = wait_for_resizes_to_settle(|| polls.pop_front().unwrap());
+    assert!(!should_quit, "no signal to quit arrived");
+    assert!(
+        polls.is_empty(),
+        "signals are polled until a poll without a resize"
+    );
+}
+
+#[test]
+fn signal_to_quit_ends_the_wait_for_resizes_to_settle() {
+    let mut polls = VecDeque::from(vec![vec![SIGWINCH, SIGTERM], vec![SIGWINCH]]);
+    let should_quit = wait_for_resizes_to_settle(|| polls.pop_front().unwrap());
+    assert!(should_quit, "signal to quit is reported");
+    assert_eq!(
+        polls.len(),
+        1,
+        "signals are not polled again once asked to quit"
+    );
+}
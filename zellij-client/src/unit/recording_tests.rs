@@ -0,0 +1,45 @@
+use super::{Recording, TeeWriter};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use zellij_utils::{pane_size::Size, serde_json, tempfile};
+
+#[test]
+fn tee_writer_records_output_as_asciicast() {
+    let recording_file = tempfile::NamedTempFile::new().unwrap();
+    let size = Size { rows: 20, cols: 80 };
+    let recording = Recording::new(recording_file.path(), size).unwrap();
+    let mut output = vec![];
+    {
+        let mut writer = TeeWriter::new(&mut output, Arc::new(Mutex::new(Some(recording))));
+        writer.write_all(b"\x1b[?1049h").unwrap();
+        writer.write_all("hi ".as_bytes()).unwrap();
+        // a character split between two writes is recorded once it is complete
+        let character = "é".as_bytes();
+        writer.write_all(&character[..1]).unwrap();
+        writer.write_all(&character[1..]).unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(
+        output,
+        "\u{1b}[?1049hhi é".as_bytes(),
+        "output written through"
+    );
+
+    let recorded = std::fs::read_to_string(recording_file.path()).unwrap();
+    let lines: Vec<serde_json::Value> = recorded
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 4, "header and three output events");
+    assert_eq!(lines[0]["version"], 2);
+    assert_eq!(lines[0]["width"], 80);
+    assert_eq!(lines[0]["height"], 20);
+    let recorded_output: Vec<&str> = lines[1..]
+        .iter()
+        .map(|event| {
+            assert_eq!(event[1], "o", "output event");
+            event[2].as_str().unwrap()
+        })
+        .collect();
+    assert_eq!(recorded_output, vec!["\u{1b}[?1049h", "hi ", "é"]);
+}
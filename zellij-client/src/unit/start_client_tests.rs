@@ -0,0 +1,150 @@
+use super::{
+    goodbye_message, snapshot_sequences, spawn_client_thread, start_client, ClientInfo,
+    ClientInstruction, StartClientError,
+};
+use crate::os_input_output::{ClientOsApi, StdinPoller};
+use std::io;
+use std::os::unix::io::RawFd;
+use std::path::Path;
+use zellij_utils::channels::{self, ChannelWithContext, SenderWithContext};
+use zellij_utils::cli::CliArgs;
+use zellij_utils::data::Palette;
+use zellij_utils::errors::ErrorContext;
+use zellij_utils::input::config::Config;
+use zellij_utils::input::options::Options;
+use zellij_utils::ipc::{ClientToServerMsg, ExitReason, ServerToClientMsg};
+use zellij_utils::nix;
+use zellij_utils::pane_size::Size;
+
+/// A client os api whose standard input is not a terminal
+#[derive(Clone)]
+struct NoTerminalClientOsApi {}
+
+impl ClientOsApi for NoTerminalClientOsApi {
+    fn get_terminal_size_using_fd(&self, _fd: RawFd) -> Size {
+        Size { rows: 20, cols: 80 }
+    }
+    fn set_raw_mode(&mut self, _fd: RawFd) {
+        unimplemented!()
+    }
+    fn unset_raw_mode(&self, _fd: RawFd) -> Result<(), nix::Error> {
+        Err(nix::Error::ENOTTY)
+    }
+    fn get_stdout_writer(&self) -> Box<dyn io::Write> {
+        Box::new(io::sink())
+    }
+    fn get_stdin_reader(&self) -> Box<dyn io::Read> {
+        unimplemented!()
+    }
+    fn read_from_stdin(&mut self) -> Vec<u8> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ClientOsApi> {
+        Box::new(self.clone())
+    }
+    fn send_to_server(&self, _msg: ClientToServerMsg) {
+        unimplemented!()
+    }
+    fn recv_from_server(&self) -> Option<(ServerToClientMsg, ErrorContext)> {
+        unimplemented!()
+    }
+    fn handle_signals(&self, _sigwinch_cb: Box<dyn Fn()>, _quit_cb: Box<dyn Fn()>) {
+        unimplemented!()
+    }
+    fn connect_to_server(&self, _path: &Path) -> Option<String> {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        unimplemented!()
+    }
+    fn enable_mouse(&self) {}
+    fn disable_mouse(&self) {}
+    fn stdin_poller(&self) -> StdinPoller {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn start_client_returns_an_error_when_there_is_no_terminal() {
+    let result = start_client(
+        Box::new(NoTerminalClientOsApi {}),
+        CliArgs::default(),
+        Config::default(),
+        Options::default(),
+        ClientInfo::New("test-session".to_owned()),
+        None,
+    );
+    assert!(
+        matches!(result, Err(StartClientError::Terminal(_))),
+        "terminal setup failure is returned rather than exiting"
+    );
+}
+
+#[test]
+fn panic_in_a_client_thread_is_reported_to_the_main_loop() {
+    let (send_client_instructions, receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::unbounded();
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+    let thread = spawn_client_thread("test_thread", send_client_instructions, || {
+        panic!("something went wrong")
+    })
+    .unwrap();
+    assert!(
+        thread.join().is_ok(),
+        "the panic does not escape the thread"
+    );
+    match receive_client_instructions.try_recv() {
+        Ok((ClientInstruction::Error(error), _)) => {
+            assert_eq!(error, "Thread 'test_thread' panicked: something went wrong");
+        },
+        other => panic!("expected an error instruction, got {:?}", other),
+    }
+}
+
+#[test]
+fn goodbye_message_restores_the_terminal_and_is_only_left_out_of_a_normal_exit_when_quiet() {
+    let restore_snapshot = "\u{1b}[?1049l";
+    let restore_terminal = "\u{1b}[20;1H\n\u{1b}[?1049l\u{1b}[m\u{1b}[?25h";
+    let exit_reasons = [
+        ExitReason::Normal,
+        ExitReason::NormalDetached,
+        ExitReason::ForceDetached,
+        ExitReason::CannotAttach,
+        ExitReason::Error("server crashed".to_owned()),
+    ];
+    for exit_reason in exit_reasons.iter() {
+        let with_exit_message = format!("{}{}\n", restore_terminal, exit_reason);
+        assert_eq!(
+            goodbye_message(exit_reason, false, restore_snapshot, 20),
+            with_exit_message,
+            "message of {:?} printed after restoring the terminal",
+            exit_reason
+        );
+        let quiet_exit = if matches!(exit_reason, ExitReason::Normal | ExitReason::NormalDetached) {
+            restore_terminal.to_owned()
+        } else {
+            with_exit_message
+        };
+        assert_eq!(
+            goodbye_message(exit_reason, true, restore_snapshot, 20),
+            quiet_exit,
+            "terminal restored when quiet, message of {:?} only printed if not a normal exit",
+            exit_reason
+        );
+    }
+}
+
+#[test]
+fn screen_is_scrolled_into_the_scrollback_instead_of_using_the_alternate_screen_when_asked() {
+    assert_eq!(
+        snapshot_sequences(false, 3),
+        ("\u{1b}[?1049h".to_owned(), "\u{1b}[?1049l"),
+        "alternate screen is entered on start and left on exit"
+    );
+    assert_eq!(
+        snapshot_sequences(true, 3),
+        ("\n\n\n".to_owned(), ""),
+        "visible rows are scrolled away on start and the last drawn screen is left on exit"
+    );
+}
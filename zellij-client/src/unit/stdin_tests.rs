@@ -11,7 +11,7 @@ use zellij_utils::termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
 
 use crate::InputInstruction;
 use crate::{
-    os_input_output::{ClientOsApi, StdinPoller},
+    os_input_output::{ClientOsApi, StdinPoller, ENABLE_MOUSE_MOTION_REPORTING},
     ClientInstruction, CommandIsExecuting,
 };
 
@@ -175,7 +175,7 @@ impl ClientOsApi for FakeClientOsApi {
     fn handle_signals(&self, _sigwinch_cb: Box<dyn Fn()>, _quit_cb: Box<dyn Fn()>) {
         unimplemented!()
     }
-    fn connect_to_server(&self, _path: &Path) {
+    fn connect_to_server(&self, _path: &Path) -> Option<String> {
         unimplemented!()
     }
     fn load_palette(&self) -> Palette {
@@ -313,6 +313,61 @@ pub fn move_focus_left_in_normal_mode() {
     );
 }
 
+#[test]
+pub fn mouse_motion_reporting_is_enabled_whenever_the_mode_changes() {
+    let events_sent_to_server = Arc::new(Mutex::new(vec![]));
+    let command_is_executing = CommandIsExecuting::new();
+    let client_os_api =
+        FakeClientOsApi::new(events_sent_to_server.clone(), command_is_executing.clone());
+    let client_os_api_clone = client_os_api.clone();
+    let config = Config::from_default_assets().unwrap();
+    let options = Options {
+        focus_follows_mouse: Some(true),
+        ..Default::default()
+    };
+
+    let (send_client_instructions, _receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::bounded(50);
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+
+    let (send_input_instructions, receive_input_instructions): ChannelWithContext<
+        InputInstruction,
+    > = channels::bounded(50);
+    let send_input_instructions = SenderWithContext::new(send_input_instructions);
+    send_input_instructions
+        .send(InputInstruction::SwitchToMode(InputMode::Locked))
+        .unwrap();
+    send_input_instructions
+        .send(InputInstruction::SwitchToMode(InputMode::Normal))
+        .unwrap();
+    send_input_instructions
+        .send(InputInstruction::KeyEvent(
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::CTRL,
+            }),
+            commands::QUIT.to_vec(),
+        ))
+        .unwrap();
+
+    input_loop(
+        Box::new(client_os_api),
+        config,
+        options,
+        command_is_executing,
+        send_client_instructions,
+        InputMode::Normal,
+        receive_input_instructions,
+    );
+    let stdout = String::from_utf8(client_os_api_clone.stdout_buffer()).unwrap();
+    assert_eq!(
+        stdout.matches(ENABLE_MOUSE_MOTION_REPORTING).count(),
+        3,
+        "enabled once the input loop starts and after each of the two mode changes"
+    );
+}
+
 #[test]
 pub fn terminal_info_queried_from_terminal_emulator() {
     let events_sent_to_server = Arc::new(Mutex::new(vec![]));
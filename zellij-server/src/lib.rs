@@ -20,17 +20,22 @@ use std::{
     path::PathBuf,
     sync::{Arc, Mutex, RwLock},
     thread,
+    time::{Duration, Instant},
 };
 use zellij_utils::envs;
 use zellij_utils::nix::sys::stat::{umask, Mode};
 use zellij_utils::pane_size::Size;
+use zellij_utils::signal_hook::{
+    consts::signal::{SIGINT, SIGTERM},
+    iterator::Signals,
+};
 
 use wasmer::Store;
 
 use crate::{
     os_input_output::ServerOsApi,
-    pty::{pty_thread_main, Pty, PtyInstruction},
-    screen::{screen_thread_main, ScreenInstruction},
+    pty::{pty_thread_main, shell_from_env, split_shell_args, Pty, PtyInstruction},
+    screen::{screen_thread_main, MaxPanes, ScreenInstruction},
     thread_bus::{Bus, ThreadSenders},
     wasm_vm::{wasm_thread_main, PluginInstruction},
 };
@@ -38,18 +43,21 @@ use route::route_thread_main;
 use zellij_utils::{
     channels::{self, ChannelWithContext, SenderWithContext},
     cli::CliArgs,
-    consts::{DEFAULT_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE},
+    consts::{
+        DEFAULT_PENDING_OUTPUT_LIMIT, DEFAULT_PTY_READ_BUFFER_SIZE, DEFAULT_SCROLL_BUFFER_SIZE,
+        MAX_PTY_READ_BUFFER_SIZE, PENDING_OUTPUT_LIMIT, PTY_READ_BUFFER_SIZE, SCROLL_BUFFER_SIZE,
+    },
     data::{Event, PluginCapabilities},
     errors::{ContextType, ErrorInstruction, FatalError, ServerContext},
     input::{
         command::{RunCommand, TerminalAction},
         get_mode_info,
         layout::Layout,
-        options::Options,
+        options::{ClientSizePolicy, OnForceClose, Options},
         plugins::PluginsConfig,
     },
     ipc::{ClientAttributes, ExitReason, ServerToClientMsg},
-    setup::get_default_data_dir,
+    setup::{get_default_data_dir, get_layout_dir_from_cli},
 };
 
 pub type ClientId = u16;
@@ -74,7 +82,9 @@ pub enum ServerInstruction {
     DetachSession(Vec<ClientId>),
     AttachClient(ClientAttributes, Options, ClientId),
     ConnStatus(ClientId),
-    ActiveClients(ClientId),
+    ActiveClients(ClientId, usize), // the tab count is reported by the screen
+    SetClientSizePolicy(ClientSizePolicy),
+    Log(Vec<String>, ClientId),
 }
 
 impl From<&ServerInstruction> for ServerContext {
@@ -90,7 +100,9 @@ impl From<&ServerInstruction> for ServerContext {
             ServerInstruction::DetachSession(..) => ServerContext::DetachSession,
             ServerInstruction::AttachClient(..) => ServerContext::AttachClient,
             ServerInstruction::ConnStatus(..) => ServerContext::ConnStatus,
-            ServerInstruction::ActiveClients(_) => ServerContext::ActiveClients,
+            ServerInstruction::ActiveClients(..) => ServerContext::ActiveClients,
+            ServerInstruction::SetClientSizePolicy(_) => ServerContext::SetClientSizePolicy,
+            ServerInstruction::Log(..) => ServerContext::Log,
         }
     }
 }
@@ -153,14 +165,27 @@ macro_rules! send_to_client {
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct SessionState {
     clients: HashMap<ClientId, Option<Size>>,
+    size_policy: ClientSizePolicy,
+    latest_resized_client: Option<ClientId>,
+    last_input_at: Instant,
 }
 
 impl SessionState {
     pub fn new() -> Self {
         SessionState {
             clients: HashMap::new(),
+            size_policy: ClientSizePolicy::default(),
+            latest_resized_client: None,
+            last_input_at: Instant::now(),
         }
     }
+    /// Restarts the countdown to the idle timeout
+    pub fn register_input(&mut self) {
+        self.last_input_at = Instant::now();
+    }
+    pub fn idle_for(&self) -> Duration {
+        self.last_input_at.elapsed()
+    }
     pub fn new_client(&mut self) -> ClientId {
         let clients: HashSet<ClientId> = self.clients.keys().copied().collect();
         let mut next_client_id = 1;
@@ -176,9 +201,27 @@ impl SessionState {
     }
     pub fn remove_client(&mut self, client_id: ClientId) {
         self.clients.remove(&client_id);
+        if self.latest_resized_client == Some(client_id) {
+            self.latest_resized_client = None;
+        }
     }
     pub fn set_client_size(&mut self, client_id: ClientId, size: Size) {
         self.clients.insert(client_id, Some(size));
+        self.latest_resized_client = Some(client_id);
+    }
+    pub fn set_size_policy(&mut self, size_policy: ClientSizePolicy) {
+        self.size_policy = size_policy;
+    }
+    /// The size to render the session at, according to the size policy
+    pub fn session_size(&self) -> Option<Size> {
+        // None if there are no client sizes
+        match self.size_policy {
+            ClientSizePolicy::Smallest => self.min_client_terminal_size(),
+            ClientSizePolicy::Latest => self
+                .latest_resized_client
+                .and_then(|client_id| self.clients.get(&client_id).copied().flatten())
+                .or_else(|| self.min_client_terminal_size()),
+        }
     }
     pub fn min_client_terminal_size(&self) -> Option<Size> {
         // None if there are no client sizes
@@ -236,6 +279,13 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
         })
     });
 
+    let _ = thread::Builder::new()
+        .name("server_signals".to_string())
+        .spawn({
+            let to_server = to_server.clone();
+            move || handle_quit_signals(to_server)
+        });
+
     let thread_handles = Arc::new(Mutex::new(Vec::new()));
 
     let _ = thread::Builder::new()
@@ -313,17 +363,42 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     },
                 );
                 *session_data.write().unwrap() = Some(session);
+                session_state
+                    .write()
+                    .unwrap()
+                    .set_size_policy(config_options.client_size_policy.unwrap_or_default());
+                if let Some(idle_timeout) = config_options.idle_timeout.filter(|t| *t > 0) {
+                    let session_state = session_state.clone();
+                    let to_server = to_server.clone();
+                    let on_idle_timeout = config_options.on_idle_timeout.unwrap_or_default();
+                    let _ = thread::Builder::new()
+                        .name("idle_timeout".to_string())
+                        .spawn(move || {
+                            watch_idle_timeout(
+                                session_state,
+                                to_server,
+                                Duration::from_secs(idle_timeout as u64),
+                                on_idle_timeout,
+                            )
+                        });
+                }
                 session_state
                     .write()
                     .unwrap()
                     .set_client_size(client_id, client_attributes.size);
+                session_data
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .senders
+                    .send_to_screen(ScreenInstruction::ClientResize(
+                        client_id,
+                        client_attributes.size,
+                    ))
+                    .unwrap();
 
-                let default_shell = config_options.default_shell.map(|shell| {
-                    TerminalAction::RunCommand(RunCommand {
-                        command: shell,
-                        ..Default::default()
-                    })
-                });
+                let default_shell = default_shell_from_options(&config_options);
 
                 let spawn_tabs = |tab_layout, tab_name| {
                     session_data
@@ -378,14 +453,14 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .write()
                     .unwrap()
                     .set_client_size(client_id, attrs.size);
-                let min_size = session_state
-                    .read()
-                    .unwrap()
-                    .min_client_terminal_size()
+                let session_size = session_state.read().unwrap().session_size().unwrap();
+                session_data
+                    .senders
+                    .send_to_screen(ScreenInstruction::ClientResize(client_id, attrs.size))
                     .unwrap();
                 session_data
                     .senders
-                    .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                    .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                     .unwrap();
                 session_data
                     .senders
@@ -431,14 +506,14 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 let _ =
                     os_input.send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
                 remove_client!(client_id, os_input, session_state);
-                if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
+                if let Some(session_size) = session_state.read().unwrap().session_size() {
                     session_data
                         .write()
                         .unwrap()
                         .as_ref()
                         .unwrap()
                         .senders
-                        .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                        .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                         .unwrap();
                 }
                 session_data
@@ -464,14 +539,14 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
             },
             ServerInstruction::RemoveClient(client_id) => {
                 remove_client!(client_id, os_input, session_state);
-                if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
+                if let Some(session_size) = session_state.read().unwrap().session_size() {
                     session_data
                         .write()
                         .unwrap()
                         .as_ref()
                         .unwrap()
                         .senders
-                        .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                        .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                         .unwrap();
                 }
                 session_data
@@ -502,18 +577,19 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
             },
             ServerInstruction::DetachSession(client_ids) => {
                 for client_id in client_ids {
-                    let _ = os_input
-                        .send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
+                    let _ = os_input.send_to_client(
+                        client_id,
+                        ServerToClientMsg::Exit(ExitReason::NormalDetached),
+                    );
                     remove_client!(client_id, os_input, session_state);
-                    if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size()
-                    {
+                    if let Some(session_size) = session_state.read().unwrap().session_size() {
                         session_data
                             .write()
                             .unwrap()
                             .as_ref()
                             .unwrap()
                             .senders
-                            .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                            .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                             .unwrap();
                     }
                     session_data
@@ -567,11 +643,22 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 }
                 break;
             },
+            ServerInstruction::SetClientSizePolicy(size_policy) => {
+                session_state.write().unwrap().set_size_policy(size_policy);
+                if let Some(session_size) = session_state.read().unwrap().session_size() {
+                    if let Some(session_data) = session_data.read().unwrap().as_ref() {
+                        session_data
+                            .senders
+                            .send_to_screen(ScreenInstruction::TerminalResize(session_size))
+                            .unwrap();
+                    }
+                }
+            },
             ServerInstruction::ConnStatus(client_id) => {
                 let _ = os_input.send_to_client(client_id, ServerToClientMsg::Connected);
                 remove_client!(client_id, os_input, session_state);
             },
-            ServerInstruction::ActiveClients(client_id) => {
+            ServerInstruction::ActiveClients(client_id, tab_count) => {
                 let client_ids = session_state.read().unwrap().client_ids();
                 log::error!(
                     "Sending client_ids {:?} to client {}",
@@ -581,7 +668,15 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 send_to_client!(
                     client_id,
                     os_input,
-                    ServerToClientMsg::ActiveClients(client_ids),
+                    ServerToClientMsg::ActiveClients(client_ids, tab_count),
+                    session_state
+                );
+            },
+            ServerInstruction::Log(lines, client_id) => {
+                send_to_client!(
+                    client_id,
+                    os_input,
+                    ServerToClientMsg::Log(lines),
                     session_state
                 );
             },
@@ -606,6 +701,80 @@ pub struct SessionOptions {
     pub plugins: Option<PluginsConfig>,
 }
 
+/// The shell to start new panes with if the options (from the config or the layout) choose one or
+/// the arguments to start it with
+fn default_shell_from_options(options: &Options) -> Option<TerminalAction> {
+    if options.default_shell.is_none() && options.default_shell_args.is_none() {
+        return None;
+    }
+    Some(TerminalAction::RunCommand(RunCommand {
+        command: options.default_shell.clone().unwrap_or_else(shell_from_env),
+        args: options
+            .default_shell_args
+            .as_deref()
+            .map(split_shell_args)
+            .unwrap_or_default(),
+        hold_on_close: options.hold_shell_on_exit.unwrap_or(false),
+        ..Default::default()
+    }))
+}
+
+/// Detaches all clients or quits the session whenever no client sent any input for `idle_timeout`
+fn watch_idle_timeout(
+    session_state: Arc<RwLock<SessionState>>,
+    to_server: SenderWithContext<ServerInstruction>,
+    idle_timeout: Duration,
+    on_idle_timeout: OnForceClose,
+) {
+    loop {
+        let idle_for = session_state.read().unwrap().idle_for();
+        if idle_for < idle_timeout {
+            thread::sleep(idle_timeout - idle_for);
+            continue;
+        }
+        let client_ids = session_state.read().unwrap().client_ids();
+        let instruction = match on_idle_timeout {
+            OnForceClose::Quit => Some(ServerInstruction::KillSession),
+            // a detached session has no one left to detach
+            OnForceClose::Detach if client_ids.is_empty() => None,
+            OnForceClose::Detach => Some(ServerInstruction::DetachSession(client_ids)),
+        };
+        if let Some(instruction) = instruction {
+            log::info!(
+                "No input for {:?}, on idle timeout: {:?}",
+                idle_timeout,
+                on_idle_timeout
+            );
+            if to_server.send(instruction).is_err() {
+                break;
+            }
+        }
+        session_state.write().unwrap().register_input();
+    }
+}
+
+/// Kills the session once the server is asked to terminate (eg. on logout or shutdown), so that the
+/// panes are closed and the clients restore their terminals rather than being left behind with it
+fn handle_quit_signals(to_server: SenderWithContext<ServerInstruction>) {
+    // the signal handler itself only wakes up this thread, which does the rest
+    match Signals::new(&[SIGTERM, SIGINT]) {
+        Ok(signals) => kill_session_on_quit_signal(signals, &to_server),
+        Err(e) => log::error!("Failed to listen for quit signals: {}", e),
+    }
+}
+
+/// Waits for the first of `signals` and kills the session. Tearing the session down drops the
+/// pty, which hangs up on the process group of every pane the same way quitting does.
+fn kill_session_on_quit_signal(
+    mut signals: Signals,
+    to_server: &SenderWithContext<ServerInstruction>,
+) {
+    if let Some(signal) = signals.forever().next() {
+        log::info!("Received signal {}, killing the session", signal);
+        let _ = to_server.send(ServerInstruction::KillSession);
+    }
+}
+
 fn init_session(
     os_input: Box<dyn ServerOsApi>,
     to_server: SenderWithContext<ServerInstruction>,
@@ -626,6 +795,21 @@ fn init_session(
                 .unwrap_or(DEFAULT_SCROLL_BUFFER_SIZE),
         )
         .unwrap();
+    PTY_READ_BUFFER_SIZE
+        .set(
+            config_options
+                .pty_read_buffer_size
+                .unwrap_or(DEFAULT_PTY_READ_BUFFER_SIZE)
+                .clamp(1, MAX_PTY_READ_BUFFER_SIZE),
+        )
+        .unwrap();
+    PENDING_OUTPUT_LIMIT
+        .set(
+            config_options
+                .pending_output_limit
+                .unwrap_or(DEFAULT_PENDING_OUTPUT_LIMIT),
+        )
+        .unwrap();
 
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
     let to_screen = SenderWithContext::new(to_screen);
@@ -643,6 +827,12 @@ fn init_session(
         channels::unbounded();
     let to_pty_writer = SenderWithContext::new(to_pty_writer);
 
+    // the layout of new tabs is looked for by name in the same folder as that of the session
+    let layout_dir = config_options
+        .layout_dir
+        .clone()
+        .or_else(|| get_layout_dir_from_cli(&opts));
+
     // Determine and initialize the data directory
     let data_dir = opts.data_dir.unwrap_or_else(get_default_data_dir);
 
@@ -650,12 +840,7 @@ fn init_session(
         arrow_fonts: config_options.simplified_ui.unwrap_or_default(),
     };
 
-    let default_shell = config_options.default_shell.clone().map(|command| {
-        TerminalAction::RunCommand(RunCommand {
-            command,
-            ..Default::default()
-        })
-    });
+    let default_shell = default_shell_from_options(&config_options);
 
     let pty_thread = thread::Builder::new()
         .name("pty".to_string())
@@ -672,6 +857,11 @@ fn init_session(
                 ),
                 opts.debug,
                 config_options.scrollback_editor.clone(),
+                config_options.hold_shell_on_exit.unwrap_or(false),
+                config_options.max_command_restarts,
+                config_options.command_restart_window,
+                config_options.new_tab_layout.clone(),
+                layout_dir,
             );
 
             move || pty_thread_main(pty, layout).fatal()
@@ -690,7 +880,9 @@ fn init_session(
                 Some(&to_pty_writer),
                 Some(os_input.clone()),
             );
-            let max_panes = opts.max_panes;
+            let max_panes = opts
+                .max_panes
+                .map(|max_panes| MaxPanes::new(max_panes, opts.max_panes_evict));
 
             let client_attributes_clone = client_attributes.clone();
             move || {
@@ -759,3 +951,11 @@ fn init_session(
         pty_writer_thread: Some(pty_writer_thread),
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/session_state_tests.rs"]
+mod session_state_tests;
+
+#[cfg(test)]
+#[path = "./unit/quit_signals_tests.rs"]
+mod quit_signals_tests;
@@ -1,15 +1,19 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
-use std::{fs::File, io::Write};
+use std::collections::{BTreeMap, HashMap};
+use std::{
+    fs::File,
+    io::{self, Write},
+};
 
 use crate::panes::PaneId;
 use zellij_utils::tempfile::tempfile;
 
 use std::env;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
-use std::process::{Child, Command};
+use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use zellij_utils::{async_std, interprocess, libc, nix, signal_hook};
 
@@ -19,8 +23,10 @@ use interprocess::local_socket::LocalSocketStream;
 
 use sysinfo::{ProcessExt, ProcessRefreshKind, System, SystemExt};
 
+use nix::fcntl::{fcntl, FcntlArg, OFlag};
+use nix::poll::{poll, PollFd, PollFlags};
 use nix::pty::{openpty, OpenptyResult, Winsize};
-use nix::sys::signal::{kill, Signal};
+use nix::sys::signal::{kill, killpg, Signal};
 use nix::sys::termios;
 
 use nix::unistd;
@@ -166,12 +172,27 @@ fn handle_openpty(
         };
 
         let child_id = child.id();
+        log::debug!(
+            "event=spawn_terminal terminal_id={} pid={} command={:?}",
+            terminal_id,
+            child_id,
+            cmd.command
+        );
         std::thread::spawn(move || {
             child.wait().unwrap();
             let exit_status = handle_command_exit(child);
             let _ = nix::unistd::close(pid_secondary);
             quit_cb(PaneId::Terminal(terminal_id), exit_status, cmd);
         });
+        // writing to a program that does not read its input must not block the pty writer,
+        // reading waits for the pty to be readable instead (see `RawFdAsyncReader`)
+        if let Err(e) = set_nonblocking(pid_primary) {
+            log::error!(
+                "Failed to make terminal {} non-blocking: {}",
+                terminal_id,
+                e
+            );
+        }
 
         Ok((pid_primary, child_id as RawFd))
     } else {
@@ -179,6 +200,12 @@ fn handle_openpty(
     }
 }
 
+fn set_nonblocking(fd: RawFd) -> Result<(), nix::Error> {
+    let flags = OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL)?);
+    fcntl(fd, FcntlArg::F_SETFL(flags | OFlag::O_NONBLOCK))?;
+    Ok(())
+}
+
 /// Spawns a new terminal from the parent terminal with [`termios`](termios::Termios)
 /// `orig_termios`.
 ///
@@ -280,6 +307,8 @@ fn spawn_terminal(
                 args,
                 cwd: None,
                 hold_on_close: false,
+                restart_on_exit: false,
+                start_suspended: false,
             }
         },
         TerminalAction::RunCommand(command) => command,
@@ -333,11 +362,11 @@ impl std::fmt::Display for SpawnTerminalError {
 pub struct ServerOsInputOutput {
     orig_termios: Arc<Mutex<termios::Termios>>,
     client_senders: Arc<Mutex<HashMap<ClientId, IpcSenderWithContext<ServerToClientMsg>>>>,
-    terminal_id_to_raw_fd: Arc<Mutex<BTreeMap<u32, Option<RawFd>>>>, // A value of None means the
-                                                                     // terminal_id exists but is
-                                                                     // not connected to an fd (eg.
-                                                                     // a command pane with a
-                                                                     // non-existing command)
+    /// A value of None means the terminal_id exists but is not connected to an fd (eg. a command
+    /// pane with a non-existing command)
+    terminal_id_to_raw_fd: Arc<Mutex<BTreeMap<u32, Option<RawFd>>>>,
+    /// Terminal ids are never reused, so that they keep addressing the same pane
+    next_terminal_id: Arc<Mutex<u32>>,
 }
 
 // async fn in traits is not supported by rust, so dtolnay's excellent async_trait macro is being
@@ -361,10 +390,29 @@ impl RawFdAsyncReader {
     }
 }
 
+// how long a read waits for a non-blocking pty to be readable before trying again, so that a read
+// that timed out does not leave a thread waiting on an idle pane behind for long
+const PTY_READABLE_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
 #[async_trait]
 impl AsyncReader for RawFdAsyncReader {
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
-        self.fd.read(buf).await
+        loop {
+            match self.fd.read(buf).await {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    let fd = self.fd.as_raw_fd();
+                    async_std::task::spawn_blocking(move || {
+                        let mut poll_fds = [PollFd::new(fd, PollFlags::POLLIN)];
+                        poll(
+                            &mut poll_fds,
+                            PTY_READABLE_POLL_TIMEOUT.as_millis() as libc::c_int,
+                        )
+                    })
+                    .await?;
+                },
+                read => return read,
+            }
+        }
     }
 }
 
@@ -381,6 +429,10 @@ pub trait ServerOsApi: Send + Sync {
         quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
         default_editor: Option<PathBuf>,
     ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError>;
+    /// Take the next terminal id without spawning anything in it yet, so that a command can be
+    /// run in it later with [`ServerOsApi::re_run_command_in_terminal`]. Returns `None` once the
+    /// ids have run out.
+    fn reserve_terminal_id(&self) -> Option<u32>;
     /// Read bytes from the standard output of the virtual terminal referred to by `fd`.
     fn read_from_tty_stdout(&self, fd: RawFd, buf: &mut [u8]) -> Result<usize, nix::Error>;
     /// Creates an `AsyncReader` that can be used to read from `fd` in an async context
@@ -393,6 +445,9 @@ pub trait ServerOsApi: Send + Sync {
     fn kill(&self, pid: Pid) -> Result<(), nix::Error>;
     /// Terminate the process with process ID `pid`. (SIGKILL)
     fn force_kill(&self, pid: Pid) -> Result<(), nix::Error>;
+    /// Hang up on the process group led by `pid` (SIGHUP), which the process a terminal was
+    /// started with leads along with whatever it runs without job control
+    fn hang_up_process_group(&self, pid: Pid) -> Result<(), nix::Error>;
     /// Returns a [`Box`] pointer to this [`ServerOsApi`] struct.
     fn box_clone(&self) -> Box<dyn ServerOsApi>;
     fn send_to_client(
@@ -419,6 +474,19 @@ pub trait ServerOsApi: Send + Sync {
         quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
     ) -> Result<(RawFd, RawFd), SpawnTerminalError>;
     fn clear_terminal_id(&self, terminal_id: u32);
+    /// Returns whether the terminal is running a process other than the one it was started with
+    /// (eg. a program launched from its shell) in the foreground
+    fn foreground_process_is_running(&self, terminal_id: u32) -> bool;
+    /// Send `signal` to the foreground process group of the terminal, which is the job running in
+    /// it rather than necessarily the process it was started with
+    fn signal_foreground_process_group(
+        &self,
+        terminal_id: u32,
+        signal: Signal,
+    ) -> Result<(), nix::Error>;
+    /// Runs `command` (a program followed by its arguments) with `buf` written to its stdin,
+    /// leaving its output alone
+    fn pipe_to_command(&self, buf: String, command: &[String]) -> io::Result<()>;
 }
 
 impl ServerOsApi for ServerOsInputOutput {
@@ -426,6 +494,12 @@ impl ServerOsApi for ServerOsInputOutput {
         match self.terminal_id_to_raw_fd.lock().unwrap().get(&id) {
             Some(Some(fd)) => {
                 if cols > 0 && rows > 0 {
+                    log::debug!(
+                        "event=resize_terminal terminal_id={} cols={} rows={}",
+                        id,
+                        cols,
+                        rows
+                    );
                     set_terminal_size_using_fd(*fd, cols, rows);
                 }
             },
@@ -441,29 +515,8 @@ impl ServerOsApi for ServerOsInputOutput {
         default_editor: Option<PathBuf>,
     ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
         let orig_termios = self.orig_termios.lock().unwrap();
-        let mut terminal_id = None;
-        {
-            let current_ids: HashSet<u32> = self
-                .terminal_id_to_raw_fd
-                .lock()
-                .unwrap()
-                .keys()
-                .copied()
-                .collect();
-            for i in 0..u32::MAX {
-                let i = i as u32;
-                if !current_ids.contains(&i) {
-                    terminal_id = Some(i);
-                    break;
-                }
-            }
-        }
-        match terminal_id {
+        match self.reserve_terminal_id() {
             Some(terminal_id) => {
-                self.terminal_id_to_raw_fd
-                    .lock()
-                    .unwrap()
-                    .insert(terminal_id, None);
                 match spawn_terminal(
                     terminal_action,
                     orig_termios.clone(),
@@ -484,6 +537,23 @@ impl ServerOsApi for ServerOsInputOutput {
             None => Err(SpawnTerminalError::NoMoreTerminalIds),
         }
     }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        let terminal_id = {
+            let mut next_terminal_id = self.next_terminal_id.lock().unwrap();
+            let terminal_id = *next_terminal_id;
+            next_terminal_id.checked_add(1).map(|next| {
+                *next_terminal_id = next;
+                terminal_id
+            })
+        };
+        if let Some(terminal_id) = terminal_id {
+            self.terminal_id_to_raw_fd
+                .lock()
+                .unwrap()
+                .insert(terminal_id, None);
+        }
+        terminal_id
+    }
     fn read_from_tty_stdout(&self, fd: RawFd, buf: &mut [u8]) -> Result<usize, nix::Error> {
         unistd::read(fd, buf)
     }
@@ -521,6 +591,9 @@ impl ServerOsApi for ServerOsInputOutput {
         let _ = kill(pid, Some(Signal::SIGKILL));
         Ok(())
     }
+    fn hang_up_process_group(&self, pid: Pid) -> Result<(), nix::Error> {
+        killpg(pid, Signal::SIGHUP)
+    }
     fn send_to_client(
         &self,
         client_id: ClientId,
@@ -605,6 +678,57 @@ impl ServerOsApi for ServerOsInputOutput {
             .unwrap()
             .remove(&terminal_id);
     }
+    fn foreground_process_is_running(&self, terminal_id: u32) -> bool {
+        match self.terminal_id_to_raw_fd.lock().unwrap().get(&terminal_id) {
+            Some(Some(fd)) => {
+                // the process the terminal was started with is the leader of its session, so
+                // any other foreground process group belongs to something it launched
+                let session_leader = unsafe { libc::tcgetsid(*fd) };
+                match unistd::tcgetpgrp(*fd) {
+                    Ok(foreground_process_group) => {
+                        session_leader > 0 && foreground_process_group.as_raw() != session_leader
+                    },
+                    Err(_) => false,
+                }
+            },
+            _ => false,
+        }
+    }
+    fn signal_foreground_process_group(
+        &self,
+        terminal_id: u32,
+        signal: Signal,
+    ) -> Result<(), nix::Error> {
+        match self.terminal_id_to_raw_fd.lock().unwrap().get(&terminal_id) {
+            Some(Some(fd)) => killpg(unistd::tcgetpgrp(*fd)?, signal),
+            _ => Err(nix::Error::EBADF),
+        }
+    }
+    fn pipe_to_command(&self, buf: String, command: &[String]) -> io::Result<()> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no command given"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin of the command was piped");
+        // the command may take its time to read all of it, which the caller shouldn't wait for
+        std::thread::Builder::new()
+            .name("pipe_to_command".to_string())
+            .spawn(move || {
+                if let Err(e) = stdin.write_all(buf.as_bytes()) {
+                    log::error!("Failed to write to the stdin of the command: {}", e);
+                }
+                drop(stdin);
+                if let Err(e) = child.wait() {
+                    log::error!("Failed to wait for the command: {}", e);
+                }
+            })?;
+        Ok(())
+    }
 }
 
 impl Clone for Box<dyn ServerOsApi> {
@@ -620,6 +744,7 @@ pub fn get_server_os_input() -> Result<ServerOsInputOutput, nix::Error> {
         orig_termios,
         client_senders: Arc::new(Mutex::new(HashMap::new())),
         terminal_id_to_raw_fd: Arc::new(Mutex::new(BTreeMap::new())),
+        next_terminal_id: Arc::new(Mutex::new(0)),
     })
 }
 
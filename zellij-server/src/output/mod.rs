@@ -18,7 +18,7 @@ use std::{
 };
 use zellij_utils::errors::prelude::*;
 use zellij_utils::pane_size::PaneGeom;
-use zellij_utils::pane_size::SizeInPixels;
+use zellij_utils::pane_size::{Size, SizeInPixels};
 
 fn vte_goto_instruction(x_coords: usize, y_coords: usize, vte_output: &mut String) -> Result<()> {
     write!(
@@ -112,7 +112,7 @@ fn serialize_chunks(
             )
             .with_context(err_context)?;
             chunk_width += t_character.width;
-            vte_output.push(t_character.character);
+            t_character.push_to(&mut vte_output);
         }
         character_styles.clear();
     }
@@ -207,6 +207,8 @@ pub struct Output {
     sixel_image_store: Rc<RefCell<SixelImageStore>>,
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     floating_panes_stack: Option<FloatingPanesStack>,
+    /// Clients whose terminal is smaller than the screen, with their terminal size
+    client_sizes: HashMap<ClientId, Size>,
 }
 
 impl Output {
@@ -232,6 +234,12 @@ impl Output {
             self.client_character_chunks.insert(*client_id, vec![]);
         }
     }
+    /// Leaves out the characters that do not fit `size` from what is rendered for the client, so
+    /// that a client smaller than the screen does not have them drawn over the edges of its
+    /// terminal.
+    pub fn clip_client_to(&mut self, client_id: ClientId, size: Size) {
+        self.client_sizes.insert(client_id, size);
+    }
     pub fn add_character_chunks_to_client(
         &mut self,
         client_id: ClientId,
@@ -360,8 +368,16 @@ impl Output {
 
         let mut serialized_render_instructions = HashMap::new();
 
-        for (client_id, client_character_chunks) in self.client_character_chunks.drain() {
+        for (client_id, mut client_character_chunks) in self.client_character_chunks.drain() {
             let mut client_serialized_render_instructions = String::new();
+            let mut client_sixel_chunks = self.sixel_chunks.remove(&client_id);
+            if let Some(size) = self.client_sizes.get(&client_id) {
+                client_character_chunks = clip_character_chunks(client_character_chunks, *size);
+                if let Some(sixel_chunks) = client_sixel_chunks.as_mut() {
+                    sixel_chunks
+                        .retain(|chunk| chunk.cell_x < size.cols && chunk.cell_y < size.rows);
+                }
+            }
 
             // append pre-vte instructions for this client
             if let Some(pre_vte_instructions_for_client) =
@@ -376,7 +392,7 @@ impl Output {
             client_serialized_render_instructions.push_str(
                 &serialize_chunks(
                     client_character_chunks,
-                    self.sixel_chunks.get(&client_id),
+                    client_sixel_chunks.as_ref(),
                     self.link_handler.as_mut(),
                     &mut self.sixel_image_store.borrow_mut(),
                 )
@@ -398,6 +414,17 @@ impl Output {
     }
 }
 
+fn clip_character_chunks(character_chunks: Vec<CharacterChunk>, size: Size) -> Vec<CharacterChunk> {
+    character_chunks
+        .into_iter()
+        .filter(|chunk| chunk.y < size.rows && chunk.x < size.cols)
+        .map(|mut chunk| {
+            chunk.retain_by_width(size.cols - chunk.x);
+            chunk
+        })
+        .collect()
+}
+
 // this struct represents the geometry of a group of floating panes
 // we use it to filter out CharacterChunks who are behind these geometries
 // and so would not be visible. If a chunk is partially covered, it is adjusted
@@ -859,14 +886,19 @@ impl OutputBuffer {
         viewport: &[Row],
         viewport_width: usize,
         viewport_height: usize,
+        horizontal_offset: usize,
         x_offset: usize,
         y_offset: usize,
     ) -> Vec<CharacterChunk> {
         if self.should_update_all_lines {
             let mut changed_chunks = Vec::with_capacity(viewport.len());
             for line_index in 0..viewport_height {
-                let terminal_characters =
-                    self.extract_line_from_viewport(line_index, viewport, viewport_width);
+                let terminal_characters = self.extract_line_from_viewport(
+                    line_index,
+                    viewport,
+                    viewport_width,
+                    horizontal_offset,
+                );
 
                 let x = x_offset; // right now we only buffer full lines as this doesn't seem to have a huge impact on performance, but the infra is here if we want to change this
                 let y = line_index + y_offset;
@@ -879,8 +911,12 @@ impl OutputBuffer {
             line_changes.dedup();
             let mut changed_chunks = Vec::with_capacity(line_changes.len());
             for line_index in line_changes {
-                let terminal_characters =
-                    self.extract_line_from_viewport(line_index, viewport, viewport_width);
+                let terminal_characters = self.extract_line_from_viewport(
+                    line_index,
+                    viewport,
+                    viewport_width,
+                    horizontal_offset,
+                );
                 let x = x_offset;
                 let y = line_index + y_offset;
                 changed_chunks.push(CharacterChunk::new(terminal_characters, x, y));
@@ -892,25 +928,63 @@ impl OutputBuffer {
         &self,
         row: &Row,
         viewport_width: usize,
+        horizontal_offset: usize,
     ) -> Vec<TerminalCharacter> {
+        let row_width = row.width();
+        if horizontal_offset > 0 || row_width > viewport_width {
+            return self.extract_visible_characters_from_row(
+                row,
+                viewport_width,
+                horizontal_offset,
+            );
+        }
         let mut terminal_characters: Vec<TerminalCharacter> = row.columns.iter().copied().collect();
         // pad row
-        let row_width = row.width();
         if row_width < viewport_width {
             let mut padding = vec![EMPTY_TERMINAL_CHARACTER; viewport_width - row_width];
             terminal_characters.append(&mut padding);
         }
         terminal_characters
     }
+    // the part of a row wider than the viewport (eg. with linewrap disabled) that is scrolled into
+    // view, `horizontal_offset` columns from its start
+    fn extract_visible_characters_from_row(
+        &self,
+        row: &Row,
+        viewport_width: usize,
+        horizontal_offset: usize,
+    ) -> Vec<TerminalCharacter> {
+        let mut terminal_characters = Vec::with_capacity(viewport_width);
+        let mut column = 0;
+        let mut visible_width = 0;
+        for character in row.columns.iter() {
+            let character_start = column;
+            column += character.width;
+            if character_start < horizontal_offset {
+                continue;
+            }
+            if visible_width + character.width > viewport_width {
+                break;
+            }
+            visible_width += character.width;
+            terminal_characters.push(*character);
+        }
+        terminal_characters.append(&mut vec![
+            EMPTY_TERMINAL_CHARACTER;
+            viewport_width - visible_width
+        ]);
+        terminal_characters
+    }
     fn extract_line_from_viewport(
         &self,
         line_index: usize,
         viewport: &[Row],
         viewport_width: usize,
+        horizontal_offset: usize,
     ) -> Vec<TerminalCharacter> {
         match viewport.get(line_index) {
             // TODO: iterator?
-            Some(row) => self.extract_characters_from_row(row, viewport_width),
+            Some(row) => self.extract_characters_from_row(row, viewport_width, horizontal_offset),
             None => {
                 vec![EMPTY_TERMINAL_CHARACTER; viewport_width]
             },
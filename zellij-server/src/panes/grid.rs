@@ -4,7 +4,6 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use unicode_width::UnicodeWidthChar;
-use zellij_utils::regex::Regex;
 
 use std::{
     cmp::Ordering,
@@ -33,8 +32,8 @@ use crate::panes::link_handler::LinkHandler;
 use crate::panes::search::SearchResult;
 use crate::panes::selection::Selection;
 use crate::panes::terminal_character::{
-    AnsiCode, CharacterStyles, CharsetIndex, Cursor, CursorShape, StandardCharset,
-    TerminalCharacter, EMPTY_TERMINAL_CHARACTER,
+    AnsiCode, CharacterStyles, CharsetIndex, CombiningMarks, Cursor, CursorShape, StandardCharset,
+    TerminalCharacter, EMPTY_TERMINAL_CHARACTER, VARIATION_SELECTOR_16,
 };
 
 fn get_top_non_canonical_rows(rows: &mut Vec<Row>) -> Vec<Row> {
@@ -288,11 +287,13 @@ macro_rules! dump_screen {
             if line.is_canonical && !is_first {
                 buf.push_str("\n");
             }
-            let s: String = (&line.columns).into_iter().map(|x| x.character).collect();
-            // Replace the spaces at the end of the line. Sometimes, the lines are
-            // collected with spaces until the end of the panel.
-            let re = Regex::new("([^ ])[ ]*$").unwrap();
-            buf.push_str(&(re.replace(&s, "${1}")));
+            let mut s = String::new();
+            for terminal_character in &line.columns {
+                terminal_character.push_to(&mut s);
+            }
+            // Remove the spaces at the end of the line (including lines that are only spaces).
+            // Sometimes, the lines are collected with spaces until the end of the panel.
+            buf.push_str(s.trim_end_matches(' '));
             is_first = false;
         }
         buf
@@ -351,8 +352,10 @@ pub struct Grid {
     pub erasure_mode: bool,         // ERM
     pub sixel_scrolling: bool,      // DECSDM
     pub insert_mode: bool,
-    pub disable_linewrap: bool,
-    pub new_line_mode: bool, // Automatic newline LNM
+    pub disable_linewrap: bool,   // DECAWM reset by the program
+    linewrap_toggled_off: bool,   // by the user, regardless of what the program sets
+    pub horizontal_offset: usize, // columns scrolled to the right, only while linewrap is disabled
+    pub new_line_mode: bool,      // Automatic newline LNM
     pub clear_viewport_before_rendering: bool,
     pub width: usize,
     pub height: usize,
@@ -424,6 +427,7 @@ impl Debug for Grid {
                         character: sixel_indication_character(x),
                         width: 1,
                         styles: Default::default(),
+                        combining_marks: CombiningMarks::new(),
                     };
                     row.add_character_at(fake_sixel_terminal_character, x);
                 }
@@ -479,6 +483,8 @@ impl Grid {
             sixel_scrolling: false,
             insert_mode: false,
             disable_linewrap: false,
+            linewrap_toggled_off: false,
+            horizontal_offset: 0,
             new_line_mode: false,
             alternate_screen_state: None,
             clear_viewport_before_rendering: false,
@@ -615,13 +621,26 @@ impl Grid {
                 cursor_canonical_line_index = i;
             }
             if i == self.cursor.y {
-                let line_wrap_position_in_line = self.cursor.y - cursor_canonical_line_index;
-                cursor_index_in_canonical_line = line_wrap_position_in_line + self.cursor.x;
+                let rows_wrapped_before_cursor = &self.viewport[cursor_canonical_line_index..i];
+                cursor_index_in_canonical_line = rows_wrapped_before_cursor
+                    .iter()
+                    .map(|row| row.width())
+                    .sum::<usize>()
+                    + self.cursor.x;
                 break;
             }
         }
         cursor_index_in_canonical_line
     }
+    // the rows of the viewport the canonical line starting at `y` is wrapped into
+    fn canonical_line_row_count(&self, y: usize) -> usize {
+        1 + self
+            .viewport
+            .iter()
+            .skip(y + 1)
+            .take_while(|row| !row.is_canonical)
+            .count()
+    }
     fn canonical_line_y_coordinates(&self, canonical_line_index: usize) -> usize {
         let mut canonical_lines_traversed = 0;
         let mut y_coordinates = 0;
@@ -727,133 +746,172 @@ impl Grid {
         self.change_size(intermediate_rows, intermediate_columns);
         self.change_size(new_rows, new_columns);
     }
-    pub fn change_size(&mut self, new_rows: usize, new_columns: usize) {
-        // Do nothing if this pane hasn't been given a proper size yet
-        if new_columns == 0 || new_rows == 0 {
-            return;
-        }
-        self.selection.reset();
-        self.sixel_grid.character_cell_size_possibly_changed();
-        if new_columns != self.width && self.alternate_screen_state.is_none() {
-            self.horizontal_tabstops = create_horizontal_tabstops(new_columns);
-            let mut cursor_canonical_line_index = self.cursor_canonical_line_index();
-            let cursor_index_in_canonical_line = self.cursor_index_in_canonical_line();
-            let mut viewport_canonical_lines = vec![];
-            for mut row in self.viewport.drain(..) {
-                if !row.is_canonical
-                    && viewport_canonical_lines.is_empty()
-                    && !self.lines_above.is_empty()
-                {
-                    let mut first_line_above = self.lines_above.pop_back().unwrap();
-                    first_line_above.append(&mut row.columns);
-                    viewport_canonical_lines.push(first_line_above);
-                    cursor_canonical_line_index += 1;
-                } else if row.is_canonical {
-                    viewport_canonical_lines.push(row);
-                } else {
-                    match viewport_canonical_lines.last_mut() {
-                        Some(last_line) => {
-                            last_line.append(&mut row.columns);
-                        },
-                        None => {
-                            // the state is corrupted somehow
-                            // this is a bug and I'm not yet sure why it happens
-                            // usually it fixes itself and is a result of some race
-                            // TODO: investigate why this happens and solve it
-                            return;
-                        },
-                    }
+    /// Wraps the lines of the viewport to `new_columns`, joining the rows they were wrapped into
+    /// before and moving the cursor along with the character it was on. Returns false if the rows
+    /// could not be joined back into lines.
+    fn rewrap_lines(&mut self, new_columns: usize) -> bool {
+        let mut cursor_canonical_line_index = self.cursor_canonical_line_index();
+        let cursor_index_in_canonical_line = self.cursor_index_in_canonical_line();
+        let mut viewport_canonical_lines = vec![];
+        for mut row in self.viewport.drain(..) {
+            if !row.is_canonical
+                && viewport_canonical_lines.is_empty()
+                && !self.lines_above.is_empty()
+            {
+                let mut first_line_above = self.lines_above.pop_back().unwrap();
+                first_line_above.append(&mut row.columns);
+                viewport_canonical_lines.push(first_line_above);
+                cursor_canonical_line_index += 1;
+            } else if row.is_canonical {
+                viewport_canonical_lines.push(row);
+            } else {
+                match viewport_canonical_lines.last_mut() {
+                    Some(last_line) => {
+                        last_line.append(&mut row.columns);
+                    },
+                    None => {
+                        // the state is corrupted somehow
+                        // this is a bug and I'm not yet sure why it happens
+                        // usually it fixes itself and is a result of some race
+                        // TODO: investigate why this happens and solve it
+                        return false;
+                    },
                 }
             }
+        }
 
-            // trim lines after the last empty space that has no following character, because
-            // terminals don't trim empty lines
-            for line in &mut viewport_canonical_lines {
-                let mut trim_at = None;
-                for (index, character) in line.columns.iter().enumerate() {
-                    if character.character != EMPTY_TERMINAL_CHARACTER.character {
-                        trim_at = None;
-                    } else if trim_at.is_none() {
-                        trim_at = Some(index);
-                    }
-                }
-                if let Some(trim_at) = trim_at {
-                    let excess_width_until_trim_at = line.excess_width_until(trim_at);
-                    line.truncate(trim_at + excess_width_until_trim_at);
+        // trim lines after the last empty space that has no following character, because
+        // terminals don't trim empty lines
+        for line in &mut viewport_canonical_lines {
+            let mut trim_at = None;
+            for (index, character) in line.columns.iter().enumerate() {
+                if character.character != EMPTY_TERMINAL_CHARACTER.character {
+                    trim_at = None;
+                } else if trim_at.is_none() {
+                    trim_at = Some(index);
                 }
             }
+            if let Some(trim_at) = trim_at {
+                let excess_width_until_trim_at = line.excess_width_until(trim_at);
+                line.truncate(trim_at + excess_width_until_trim_at);
+            }
+        }
 
-            let mut new_viewport_rows = vec![];
-            for mut canonical_line in viewport_canonical_lines {
-                let mut canonical_line_parts: Vec<Row> = vec![];
-                if canonical_line.columns.is_empty() {
-                    canonical_line_parts.push(Row::new(new_columns).canonical());
-                }
-                while !canonical_line.columns.is_empty() {
-                    let next_wrap = canonical_line.drain_until(new_columns);
-                    // If the next character is wider than the grid (i.e. there is nothing in
-                    // `next_wrap`, then just abort the resizing
-                    if next_wrap.is_empty() {
-                        break;
-                    }
-                    let row = Row::from_columns(next_wrap);
-                    // if there are no more parts, this row is canonical as long as it originally
-                    // was canonical (it might not have been for example if it's the first row in
-                    // the viewport, and the actual canonical row is above it in the scrollback)
-                    let row = if canonical_line_parts.is_empty() && canonical_line.is_canonical {
-                        row.canonical()
-                    } else {
-                        row
-                    };
-                    canonical_line_parts.push(row);
+        let mut new_viewport_rows = vec![];
+        for mut canonical_line in viewport_canonical_lines {
+            let mut canonical_line_parts: Vec<Row> = vec![];
+            if canonical_line.columns.is_empty() {
+                canonical_line_parts.push(Row::new(new_columns).canonical());
+            }
+            while !canonical_line.columns.is_empty() {
+                let next_wrap = canonical_line.drain_until(new_columns);
+                // If the next character is wider than the grid (i.e. there is nothing in
+                // `next_wrap`, then just abort the resizing
+                if next_wrap.is_empty() {
+                    break;
                 }
-                new_viewport_rows.append(&mut canonical_line_parts);
+                let row = Row::from_columns(next_wrap);
+                // if there are no more parts, this row is canonical as long as it originally
+                // was canonical (it might not have been for example if it's the first row in
+                // the viewport, and the actual canonical row is above it in the scrollback)
+                let row = if canonical_line_parts.is_empty() && canonical_line.is_canonical {
+                    row.canonical()
+                } else {
+                    row
+                };
+                canonical_line_parts.push(row);
             }
+            new_viewport_rows.append(&mut canonical_line_parts);
+        }
 
-            self.viewport = new_viewport_rows;
-
-            let mut new_cursor_y = self.canonical_line_y_coordinates(cursor_canonical_line_index);
+        self.viewport = new_viewport_rows;
 
-            let new_cursor_x = (cursor_index_in_canonical_line / new_columns)
-                + (cursor_index_in_canonical_line % new_columns);
-            let current_viewport_row_count = self.viewport.len();
-            match current_viewport_row_count.cmp(&self.height) {
-                Ordering::Less => {
-                    let row_count_to_transfer = self.height - current_viewport_row_count;
-
-                    transfer_rows_from_lines_above_to_viewport(
-                        &mut self.lines_above,
-                        &mut self.viewport,
-                        &mut self.sixel_grid,
-                        row_count_to_transfer,
-                        new_columns,
-                    );
-                    let rows_pulled = self.viewport.len() - current_viewport_row_count;
-                    new_cursor_y += rows_pulled;
-                },
-                Ordering::Greater => {
-                    let row_count_to_transfer = current_viewport_row_count - self.height;
-                    if row_count_to_transfer > new_cursor_y {
-                        new_cursor_y = 0;
-                    } else {
-                        new_cursor_y -= row_count_to_transfer;
-                    }
-                    transfer_rows_from_viewport_to_lines_above(
-                        &mut self.viewport,
-                        &mut self.lines_above,
-                        &mut self.sixel_grid,
-                        row_count_to_transfer,
-                        new_columns,
-                    );
-                },
-                Ordering::Equal => {},
+        // the cursor stays after the same character of its line, on whichever row that character
+        // was wrapped to
+        let canonical_line_y = self.canonical_line_y_coordinates(cursor_canonical_line_index);
+        let last_wrapped_row = self.canonical_line_row_count(canonical_line_y) - 1;
+        let wrapped_rows = std::cmp::min(
+            cursor_index_in_canonical_line / new_columns,
+            last_wrapped_row,
+        );
+        let mut new_cursor_y = canonical_line_y + wrapped_rows;
+        let new_cursor_x = std::cmp::min(
+            cursor_index_in_canonical_line - wrapped_rows * new_columns,
+            new_columns,
+        );
+        let current_viewport_row_count = self.viewport.len();
+        match current_viewport_row_count.cmp(&self.height) {
+            Ordering::Less => {
+                let row_count_to_transfer = self.height - current_viewport_row_count;
+
+                transfer_rows_from_lines_above_to_viewport(
+                    &mut self.lines_above,
+                    &mut self.viewport,
+                    &mut self.sixel_grid,
+                    row_count_to_transfer,
+                    new_columns,
+                );
+                let rows_pulled = self.viewport.len() - current_viewport_row_count;
+                new_cursor_y += rows_pulled;
+            },
+            Ordering::Greater => {
+                let row_count_to_transfer = current_viewport_row_count - self.height;
+                if row_count_to_transfer > new_cursor_y {
+                    new_cursor_y = 0;
+                } else {
+                    new_cursor_y -= row_count_to_transfer;
+                }
+                transfer_rows_from_viewport_to_lines_above(
+                    &mut self.viewport,
+                    &mut self.lines_above,
+                    &mut self.sixel_grid,
+                    row_count_to_transfer,
+                    new_columns,
+                );
+            },
+            Ordering::Equal => {},
+        }
+        self.cursor.y = new_cursor_y;
+        self.cursor.x = new_cursor_x;
+        if let Some(saved_cursor_position) = self.saved_cursor_position.as_mut() {
+            saved_cursor_position.y = new_cursor_y;
+            saved_cursor_position.x = new_cursor_x;
+        };
+        true
+    }
+    pub fn change_size(&mut self, new_rows: usize, new_columns: usize) {
+        // Do nothing if this pane hasn't been given a proper size yet
+        if new_columns == 0 || new_rows == 0 {
+            return;
+        }
+        self.selection.reset();
+        self.sixel_grid.character_cell_size_possibly_changed();
+        if new_columns != self.width
+            && self.alternate_screen_state.is_none()
+            && !self.linewrap_is_disabled()
+        {
+            self.horizontal_tabstops = create_horizontal_tabstops(new_columns);
+            if !self.rewrap_lines(new_columns) {
+                return;
             }
-            self.cursor.y = new_cursor_y;
-            self.cursor.x = new_cursor_x;
+        } else if new_columns != self.width && self.alternate_screen_state.is_none() {
+            // without linewrap lines are not rewrapped, what no longer fits is kept past the
+            // right edge to be scrolled to horizontally
+            self.horizontal_tabstops = create_horizontal_tabstops(new_columns);
+            self.cursor.x = std::cmp::min(self.cursor.x, new_columns);
             if let Some(saved_cursor_position) = self.saved_cursor_position.as_mut() {
-                saved_cursor_position.y = new_cursor_y;
-                saved_cursor_position.x = new_cursor_x;
-            };
+                saved_cursor_position.x = std::cmp::min(saved_cursor_position.x, new_columns);
+            }
+            let widest_line = self
+                .viewport
+                .iter()
+                .map(|row| row.width())
+                .max()
+                .unwrap_or(0);
+            self.horizontal_offset = std::cmp::min(
+                self.horizontal_offset,
+                widest_line.saturating_sub(new_columns),
+            );
         } else if new_columns != self.width && self.alternate_screen_state.is_some() {
             // in alternate screen just truncate exceeding width
             for row in &mut self.viewport {
@@ -957,6 +1015,7 @@ impl Grid {
             &self.viewport,
             self.width,
             self.height,
+            self.horizontal_offset,
             x_offset,
             y_offset,
         );
@@ -978,7 +1037,8 @@ impl Grid {
         (changed_character_chunks, changed_sixel_image_chunks)
     }
     pub fn cursor_coordinates(&self) -> Option<(usize, usize)> {
-        if self.cursor_is_hidden {
+        // when scrolled back, the cursor's line is not the one displayed at its position
+        if self.cursor_is_hidden || self.is_scrolled || self.horizontal_offset > 0 {
             None
         } else {
             Some((self.cursor.x, self.cursor.y))
@@ -1018,6 +1078,61 @@ impl Grid {
         }
         self.output_buffer.update_all_lines();
     }
+    pub fn scroll_left(&mut self, count: usize) {
+        self.horizontal_offset = self.horizontal_offset.saturating_sub(count);
+        self.output_buffer.update_all_lines();
+    }
+    /// Returns whether the grid actually scrolled
+    pub fn scroll_right(&mut self, count: usize) -> bool {
+        // lines are only wider than the grid while linewrap is disabled
+        if !self.linewrap_is_disabled() {
+            return false;
+        }
+        let widest_line = self
+            .viewport
+            .iter()
+            .map(|row| row.width())
+            .max()
+            .unwrap_or(0);
+        let max_offset = widest_line.saturating_sub(self.width);
+        let horizontal_offset = std::cmp::min(self.horizontal_offset + count, max_offset);
+        if horizontal_offset == self.horizontal_offset {
+            return false;
+        }
+        self.horizontal_offset = horizontal_offset;
+        self.output_buffer.update_all_lines();
+        true
+    }
+    /// Whether lines are cut off at the right edge rather than wrapped, because either the program
+    /// reset DECAWM or the user toggled linewrap off
+    pub fn linewrap_is_disabled(&self) -> bool {
+        self.disable_linewrap || self.linewrap_toggled_off
+    }
+    /// Toggles linewrap for the user, wrapping what was written past the right edge while it was
+    /// disabled to the width of the grid once it is enabled again. This is kept apart from DECAWM
+    /// so that the program setting it does not undo the toggle.
+    pub fn toggle_linewrap(&mut self) {
+        self.linewrap_toggled_off = !self.linewrap_toggled_off;
+        if !self.linewrap_is_disabled() {
+            self.reset_horizontal_offset();
+            if self.alternate_screen_state.is_none() {
+                // the cursor was left at the right edge while the line went on past it
+                if let Some(row) = self.viewport.get(self.cursor.y) {
+                    if self.cursor.x == self.width {
+                        self.cursor.x = std::cmp::max(row.width(), self.width);
+                    }
+                }
+                self.rewrap_lines(self.width);
+            }
+            self.output_buffer.update_all_lines();
+        }
+    }
+    pub fn reset_horizontal_offset(&mut self) {
+        if self.horizontal_offset > 0 {
+            self.horizontal_offset = 0;
+            self.output_buffer.update_all_lines();
+        }
+    }
     pub fn rotate_scroll_region_up(&mut self, count: usize) {
         if let Some((scroll_region_top, scroll_region_bottom)) = self
             .scroll_region
@@ -1193,21 +1308,57 @@ impl Grid {
     }
     pub fn add_character(&mut self, terminal_character: TerminalCharacter) {
         let character_width = terminal_character.width;
-        // Drop zero-width Unicode/UTF-8 codepoints, like for example Variation Selectors.
-        // This breaks unicode grapheme segmentation, and is the reason why some characters
-        // aren't displayed correctly. Refer to this issue for more information:
-        //     https://github.com/zellij-org/zellij/issues/1538
         if character_width == 0 {
+            // zero-width codepoints (eg. combining accents or Variation Selectors) are drawn on
+            // the cell of the character before them rather than taking up a cell of their own,
+            // see: https://github.com/zellij-org/zellij/issues/1538
+            // a narrow character can only be widened if the line has room for its second column
+            let can_widen = self.cursor.x < self.width;
+            if let Some(row) = self.viewport.get_mut(self.cursor.y) {
+                let widened = row.add_combining_mark_before(
+                    terminal_character.character,
+                    self.cursor.x,
+                    can_widen,
+                );
+                if widened {
+                    self.cursor.x += 1;
+                }
+            }
+            self.output_buffer.update_line(self.cursor.y);
             return;
         }
         if self.cursor.x + character_width > self.width {
-            if self.disable_linewrap {
+            if self.linewrap_is_disabled() && self.cursor.x == self.width {
+                // characters written past the right edge are kept after the end of the line so
+                // that they can be scrolled to horizontally
+                if let Some(row) = self.viewport.get_mut(self.cursor.y) {
+                    row.push(terminal_character);
+                }
+                self.output_buffer.update_line(self.cursor.y);
+                return;
+            }
+            if self.linewrap_is_disabled() {
+                // a wide character that would straddle the right edge is replaced with a space
+                // so that the line still fills the width of the pane
+                if self.cursor.x < self.width {
+                    let mut space = terminal_character;
+                    space.character = ' ';
+                    space.width = 1;
+                    self.add_character_at_cursor_position(space, false);
+                    self.move_cursor_forward_until_edge(1);
+                }
                 return;
             }
             self.line_wrap();
         }
         self.add_character_at_cursor_position(terminal_character, false);
         self.move_cursor_forward_until_edge(character_width);
+        if self.linewrap_is_disabled() && self.cursor.x == self.width {
+            // what was kept past the right edge of this line was overwritten along with it
+            if let Some(row) = self.viewport.get_mut(self.cursor.y) {
+                row.truncate(self.width);
+            }
+        }
     }
     pub fn get_character_under_cursor(&self) -> Option<TerminalCharacter> {
         let absolute_x_in_line = self.get_absolute_character_index(self.cursor.x, self.cursor.y);
@@ -1524,6 +1675,7 @@ impl Grid {
         self.active_charset = Default::default();
         self.erasure_mode = false;
         self.disable_linewrap = false;
+        self.horizontal_offset = 0;
         self.new_line_mode = false;
         self.cursor.change_shape(CursorShape::Initial);
         self.output_buffer.update_all_lines();
@@ -1597,7 +1749,7 @@ impl Grid {
                 Row::from_columns(VecDeque::from(vec![EMPTY_TERMINAL_CHARACTER; self.width]));
 
             // get the row from lines_above, viewport, or lines below depending on index
-            let row = if l < 0 && self.lines_above.len() > l.abs() as usize {
+            let row = if l < 0 && self.lines_above.len() >= l.abs() as usize {
                 let offset_from_end = l.abs();
                 &self.lines_above[self
                     .lines_above
@@ -1619,7 +1771,7 @@ impl Grid {
             let mut terminal_col = 0;
             for terminal_character in &row.columns {
                 if (start_column..end_column).contains(&terminal_col) {
-                    line_selection.push(terminal_character.character);
+                    terminal_character.push_to(&mut line_selection);
                 }
 
                 terminal_col += terminal_character.width;
@@ -1941,6 +2093,59 @@ impl Grid {
     pub fn is_alternate_mode_active(&self) -> bool {
         self.alternate_screen_state.is_some()
     }
+    fn enter_alternate_screen(&mut self) {
+        if self.alternate_screen_state.is_some() {
+            // entering again would replace the saved primary screen with the alternate one
+            return;
+        }
+        // bring back the lines below the viewport so they are saved with the primary screen
+        // rather than scrolled into the alternate one
+        self.reset_viewport();
+        let current_lines_above = std::mem::replace(
+            &mut self.lines_above,
+            VecDeque::with_capacity(*SCROLL_BUFFER_SIZE.get().unwrap()),
+        );
+        let current_viewport =
+            std::mem::replace(&mut self.viewport, vec![Row::new(self.width).canonical()]);
+        let current_cursor = std::mem::replace(&mut self.cursor, Cursor::new(0, 0));
+        let sixel_image_store = self.sixel_grid.sixel_image_store.clone();
+        let alternate_sixelgrid = std::mem::replace(
+            &mut self.sixel_grid,
+            SixelGrid::new(self.character_cell_size.clone(), sixel_image_store),
+        );
+        self.alternate_screen_state = Some(AlternateScreenState::new(
+            current_lines_above,
+            current_viewport,
+            current_cursor,
+            alternate_sixelgrid,
+        ));
+        self.clear_viewport_before_rendering = true;
+        self.scrollback_buffer_lines = self.recalculate_scrollback_buffer_count();
+        self.output_buffer.update_all_lines(); // make sure the screen gets cleared in the next render
+    }
+    fn leave_alternate_screen(&mut self) {
+        if let Some(mut alternate_screen_state) = self.alternate_screen_state.take() {
+            if let Some(image_ids_to_reap) = self.sixel_grid.clear() {
+                // reap images before dropping the alternate_screen_state contents
+                // - we can't implement a drop method for this because the store is
+                // outside of the alternate_screen_state struct
+                self.sixel_grid.reap_images(image_ids_to_reap);
+            }
+            // anything scrolled into view in the alternate screen goes away with it
+            self.lines_below.clear();
+            self.is_scrolled = false;
+            alternate_screen_state.apply_contents_to(
+                &mut self.lines_above,
+                &mut self.viewport,
+                &mut self.cursor,
+                &mut self.sixel_grid,
+            );
+            self.clear_viewport_before_rendering = true;
+            self.force_change_size(self.height, self.width); // the alternative_viewport might have been of a different size...
+            self.scrollback_buffer_lines = self.recalculate_scrollback_buffer_count();
+            self.mark_for_rerender();
+        }
+    }
 }
 
 impl Perform for Grid {
@@ -1953,6 +2158,7 @@ impl Perform for Grid {
             character: c,
             width: c.width().unwrap_or(0),
             styles: self.cursor.pending_styles,
+            combining_marks: CombiningMarks::new(),
         };
         self.set_preceding_character(terminal_character);
         self.add_character(terminal_character);
@@ -2037,8 +2243,8 @@ impl Perform for Grid {
         }
 
         match params[0] {
-            // Set window title.
-            b"0" | b"2" => {
+            // Set window title (or icon name, which we show as the title too).
+            b"0" | b"1" | b"2" => {
                 if params.len() >= 2 {
                     let title = params[1..]
                         .iter()
@@ -2301,27 +2507,8 @@ impl Perform for Grid {
                         2004 => {
                             self.bracketed_paste_mode = false;
                         },
-                        1049 => {
-                            if let Some(mut alternate_screen_state) =
-                                self.alternate_screen_state.take()
-                            {
-                                if let Some(image_ids_to_reap) = self.sixel_grid.clear() {
-                                    // reap images before dropping the alternate_screen_state contents
-                                    // - we can't implement a drop method for this because the store is
-                                    // outside of the alternate_screen_state struct
-                                    self.sixel_grid.reap_images(image_ids_to_reap);
-                                }
-                                alternate_screen_state.apply_contents_to(
-                                    &mut self.lines_above,
-                                    &mut self.viewport,
-                                    &mut self.cursor,
-                                    &mut self.sixel_grid,
-                                );
-                            }
-                            self.alternate_screen_state = None;
-                            self.clear_viewport_before_rendering = true;
-                            self.force_change_size(self.height, self.width); // the alternative_viewport might have been of a different size...
-                            self.mark_for_rerender();
+                        47 | 1047 | 1049 => {
+                            self.leave_alternate_screen();
                         },
                         25 => {
                             self.hide_cursor();
@@ -2393,33 +2580,8 @@ impl Perform for Grid {
                         2004 => {
                             self.bracketed_paste_mode = true;
                         },
-                        1049 => {
-                            // enter alternate buffer
-                            let current_lines_above = std::mem::replace(
-                                &mut self.lines_above,
-                                VecDeque::with_capacity(*SCROLL_BUFFER_SIZE.get().unwrap()),
-                            );
-                            let current_viewport = std::mem::replace(
-                                &mut self.viewport,
-                                vec![Row::new(self.width).canonical()],
-                            );
-                            let current_cursor =
-                                std::mem::replace(&mut self.cursor, Cursor::new(0, 0));
-                            let sixel_image_store = self.sixel_grid.sixel_image_store.clone();
-                            let alternate_sixelgrid = std::mem::replace(
-                                &mut self.sixel_grid,
-                                SixelGrid::new(self.character_cell_size.clone(), sixel_image_store),
-                            );
-                            self.alternate_screen_state = Some(AlternateScreenState::new(
-                                current_lines_above,
-                                current_viewport,
-                                current_cursor,
-                                alternate_sixelgrid,
-                            ));
-                            self.clear_viewport_before_rendering = true;
-                            self.scrollback_buffer_lines =
-                                self.recalculate_scrollback_buffer_count();
-                            self.output_buffer.update_all_lines(); // make sure the screen gets cleared in the next render
+                        47 | 1047 | 1049 => {
+                            self.enter_alternate_screen();
                         },
                         1 => {
                             self.cursor_key_mode = true;
@@ -2436,6 +2598,9 @@ impl Perform for Grid {
                         },
                         7 => {
                             self.disable_linewrap = false;
+                            if !self.linewrap_is_disabled() {
+                                self.reset_horizontal_offset();
+                            }
                         },
                         80 => {
                             self.sixel_scrolling = true;
@@ -2975,6 +3140,37 @@ impl Row {
         }
         (absolute_index, position_inside_character)
     }
+    /// Returns true if the mark widened the character before it (eg. VS16 turning a narrow
+    /// character into a wide emoji), in which case it now also takes up the column at x
+    pub fn add_combining_mark_before(&mut self, mark: char, x: usize, can_widen: bool) -> bool {
+        // the mark goes on the character that ends at x, if there is one on this line
+        if x == 0 || x > self.width() {
+            return false;
+        }
+        let (index, _) = self.absolute_character_index_and_position_in_char(x - 1);
+        let widened = match self.columns.get_mut(index) {
+            Some(terminal_character) => {
+                terminal_character.combining_marks.push(mark);
+                let widened =
+                    can_widen && mark == VARIATION_SELECTOR_16 && terminal_character.width == 1;
+                if widened {
+                    terminal_character.width = 2;
+                }
+                widened
+            },
+            None => false,
+        };
+        if widened {
+            // the character's second column covers whatever was drawn at x
+            if let Some(covered_character) = self.columns.remove(index + 1) {
+                for _ in 1..covered_character.width {
+                    self.columns.insert(index + 1, EMPTY_TERMINAL_CHARACTER);
+                }
+            }
+            self.width = None;
+        }
+        widened
+    }
     pub fn add_character_at(&mut self, terminal_character: TerminalCharacter, x: usize) {
         match self.width_cached().cmp(&x) {
             Ordering::Equal => {
@@ -1,6 +1,7 @@
 pub mod alacritty_functions;
 pub mod grid;
 pub mod link_handler;
+pub(crate) mod persisted_scrollback;
 pub mod selection;
 pub mod sixel;
 pub mod terminal_character;
@@ -10,6 +11,7 @@ mod plugin_pane;
 mod search;
 mod terminal_pane;
 mod tiled_panes;
+mod utf8_decoder;
 
 pub use alacritty_functions::*;
 pub use floating_panes::*;
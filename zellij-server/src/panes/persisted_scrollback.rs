@@ -0,0 +1,219 @@
+//! Keeps the scrollback of the terminal panes of a session in a file, so that a session started
+//! again under the same name after a crash gets back the history of its panes.
+//!
+//! The file starts with a header line, followed by one record per pane:
+//! `<terminal id> <length in bytes>\n<scrollback as text>\n`
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use zellij_utils::consts::ZELLIJ_CACHE_DIR;
+use zellij_utils::errors::prelude::*;
+
+const HEADER: &[u8] = b"zellij-scrollback 1\n";
+// room left for the line introducing each record when splitting the size between the panes
+const RECORD_OVERHEAD: usize = 32;
+
+/// The directory the scrollback of all sessions is kept in.
+pub(crate) fn scrollback_dir() -> PathBuf {
+    ZELLIJ_CACHE_DIR.join("scrollback")
+}
+
+/// What the thread writing the persisted scrollback is asked to do, by session name.
+#[derive(Debug)]
+pub(crate) enum ScrollbackWrite {
+    Save(String, Vec<(u32, String)>),
+    Remove(String),
+}
+
+/// Starts the thread that writes the persisted scrollback to `dir`, so that the screen doesn't
+/// wait on the disk. The writes are done in the order they are sent, and the thread stops once
+/// the returned sender is dropped.
+pub(crate) fn spawn_writer(
+    dir: PathBuf,
+    max_size: usize,
+) -> (Sender<ScrollbackWrite>, JoinHandle<()>) {
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::Builder::new()
+        .name("scrollback_writer".to_string())
+        .spawn(move || {
+            for write in receiver {
+                match write {
+                    ScrollbackWrite::Save(session_name, scrollbacks) => {
+                        save(&dir, &session_name, &scrollbacks, max_size).non_fatal();
+                    },
+                    ScrollbackWrite::Remove(session_name) => remove(&dir, &session_name),
+                }
+            }
+        })
+        .expect("failed to spawn the scrollback writer thread");
+    (sender, handle)
+}
+
+/// Writes the scrollback of each terminal of the session, dropping the oldest lines of each so
+/// that the scrollback files in `dir` take at most `max_size` bytes, removing the files of other
+/// sessions from the oldest if needed.
+pub(crate) fn save(
+    dir: &Path,
+    session_name: &str,
+    scrollbacks: &[(u32, String)],
+    max_size: usize,
+) -> Result<()> {
+    let err_context = || format!("failed to persist scrollback of session {session_name}");
+
+    fs::create_dir_all(dir).with_context(err_context)?;
+    let path = dir.join(session_name);
+    let bytes = serialize(scrollbacks, max_size);
+    // a crash while writing leaves the previous file in place rather than half of the new one
+    let temp_path = dir.join(format!(".{}.tmp", session_name));
+    // the scrollback can hold anything that was shown in the panes, so only the user can read it,
+    // and a temp file left behind by a crash is replaced rather than reused with its permissions
+    let _ = fs::remove_file(&temp_path);
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&temp_path)
+        .and_then(|mut file| file.write_all(&bytes))
+        .with_context(err_context)?;
+    fs::rename(&temp_path, &path).with_context(err_context)?;
+    remove_oldest_files_over_size(dir, &path, max_size.saturating_sub(bytes.len()));
+    Ok(())
+}
+
+/// Reads the scrollback persisted for the session, by terminal id. Anything after the first
+/// record that cannot be read is ignored, as is a file that is not a scrollback file at all.
+pub(crate) fn load(dir: &Path, session_name: &str) -> HashMap<u32, String> {
+    match fs::read(dir.join(session_name)) {
+        Ok(bytes) => deserialize(&bytes),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Removes the scrollback persisted for the session, once it is no longer needed.
+pub(crate) fn remove(dir: &Path, session_name: &str) {
+    let path = dir.join(session_name);
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            log::error!(
+                "Failed to remove persisted scrollback {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+fn serialize(scrollbacks: &[(u32, String)], max_size: usize) -> Vec<u8> {
+    let mut bytes = HEADER.to_vec();
+    if scrollbacks.is_empty() {
+        return bytes;
+    }
+    let size_per_scrollback =
+        (max_size.saturating_sub(HEADER.len()) / scrollbacks.len()).saturating_sub(RECORD_OVERHEAD);
+    for (terminal_id, scrollback) in scrollbacks {
+        let scrollback = newest_lines(scrollback, size_per_scrollback);
+        bytes.extend(format!("{} {}\n", terminal_id, scrollback.len()).as_bytes());
+        bytes.extend(scrollback.as_bytes());
+        bytes.push(b'\n');
+    }
+    bytes
+}
+
+fn deserialize(bytes: &[u8]) -> HashMap<u32, String> {
+    let mut scrollbacks = HashMap::new();
+    let mut rest = match bytes.strip_prefix(HEADER) {
+        Some(rest) => rest,
+        None => return scrollbacks,
+    };
+    while !rest.is_empty() {
+        match next_record(rest) {
+            Some((terminal_id, scrollback, next)) => {
+                scrollbacks.insert(terminal_id, scrollback);
+                rest = next;
+            },
+            None => {
+                log::warn!("Ignoring the rest of a persisted scrollback that could not be read");
+                break;
+            },
+        }
+    }
+    scrollbacks
+}
+
+fn next_record(bytes: &[u8]) -> Option<(u32, String, &[u8])> {
+    let line_end = bytes.iter().position(|b| *b == b'\n')?;
+    let line = std::str::from_utf8(&bytes[..line_end]).ok()?;
+    let (terminal_id, length) = line.split_once(' ')?;
+    let terminal_id = terminal_id.parse().ok()?;
+    let length: usize = length.parse().ok()?;
+    let scrollback_start = line_end + 1;
+    let scrollback_end = scrollback_start.checked_add(length)?;
+    if bytes.get(scrollback_end) != Some(&b'\n') {
+        return None;
+    }
+    let scrollback = std::str::from_utf8(&bytes[scrollback_start..scrollback_end]).ok()?;
+    Some((
+        terminal_id,
+        scrollback.to_owned(),
+        &bytes[scrollback_end + 1..],
+    ))
+}
+
+/// The whole lines at the end of the scrollback that fit in `max_size` bytes
+fn newest_lines(scrollback: &str, max_size: usize) -> &str {
+    if scrollback.len() <= max_size {
+        return scrollback;
+    }
+    let cut = scrollback.len() - max_size;
+    match scrollback.as_bytes()[cut..]
+        .iter()
+        .position(|b| *b == b'\n')
+    {
+        Some(line_end) => &scrollback[cut + line_end + 1..],
+        None => "",
+    }
+}
+
+fn remove_oldest_files_over_size(dir: &Path, file_to_keep: &Path, max_size: usize) {
+    let mut files: Vec<_> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path() != file_to_keep)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((
+                    metadata.modified().ok()?,
+                    metadata.len() as usize,
+                    entry.path(),
+                ))
+            })
+            .collect(),
+        Err(_) => return,
+    };
+    files.sort();
+    let mut total_size: usize = files.iter().map(|(_modified, size, _path)| size).sum();
+    for (_modified, size, path) in files {
+        if total_size <= max_size {
+            break;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => total_size -= size,
+            Err(e) => log::error!(
+                "Failed to remove persisted scrollback {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/persisted_scrollback_tests.rs"]
+mod persisted_scrollback_tests;
@@ -278,6 +278,13 @@ impl Pane for PluginPane {
         };
         make_terminal_title(pane_title)
     }
+    fn current_title(&self) -> String {
+        if self.pane_name.is_empty() {
+            self.pane_title.to_owned()
+        } else {
+            self.pane_name.to_owned()
+        }
+    }
     fn update_name(&mut self, name: &str) {
         match name {
             "\0" => {
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
 use std::convert::From;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::num::NonZeroU32;
 use std::ops::{Index, IndexMut};
+use std::sync::Mutex;
 use unicode_width::UnicodeWidthChar;
 
-use zellij_utils::{data::PaletteColor, vte::ParamsIter};
+use zellij_utils::{data::PaletteColor, lazy_static::lazy_static, vte::ParamsIter};
 
 use crate::panes::alacritty_functions::parse_sgr_color;
 
@@ -11,8 +14,14 @@ pub const EMPTY_TERMINAL_CHARACTER: TerminalCharacter = TerminalCharacter {
     character: ' ',
     width: 1,
     styles: RESET_STYLES,
+    combining_marks: CombiningMarks::new(),
 };
 
+pub const MAX_COMBINING_MARKS: usize = 4;
+
+/// Emoji presentation selector, turns the (narrow) character before it into a wide emoji
+pub const VARIATION_SELECTOR_16: char = '\u{fe0f}';
+
 pub const RESET_STYLES: CharacterStyles = CharacterStyles {
     foreground: Some(AnsiCode::Reset),
     background: Some(AnsiCode::Reset),
@@ -67,6 +76,30 @@ pub enum NamedColor {
 }
 
 impl NamedColor {
+    /// The 16 first indexed colors are the named ones, which terminals limited to 16 colors
+    /// only understand through their own SGR codes
+    fn from_color_index(color_index: u8) -> Option<Self> {
+        let named_color = match color_index {
+            0 => NamedColor::Black,
+            1 => NamedColor::Red,
+            2 => NamedColor::Green,
+            3 => NamedColor::Yellow,
+            4 => NamedColor::Blue,
+            5 => NamedColor::Magenta,
+            6 => NamedColor::Cyan,
+            7 => NamedColor::White,
+            8 => NamedColor::BrightBlack,
+            9 => NamedColor::BrightRed,
+            10 => NamedColor::BrightGreen,
+            11 => NamedColor::BrightYellow,
+            12 => NamedColor::BrightBlue,
+            13 => NamedColor::BrightMagenta,
+            14 => NamedColor::BrightCyan,
+            15 => NamedColor::BrightWhite,
+            _ => return None,
+        };
+        Some(named_color)
+    }
     fn to_foreground_ansi_code(self) -> String {
         let v = match self {
             NamedColor::Black => 30,
@@ -352,6 +385,13 @@ impl CharacterStyles {
                     }
                 },
                 [49] => *self = self.background(Some(AnsiCode::Reset)),
+                [58] => {
+                    // underline colors are not rendered, but their color parameters still need
+                    // to be consumed so that they are not mistaken for styles of their own
+                    let mut iter = params.map(|param| param[0]);
+                    let _ = parse_sgr_color(&mut iter);
+                },
+                [58, ..] | [59] => {},
                 [90] => {
                     *self = self.foreground(Some(AnsiCode::NamedColor(NamedColor::BrightBlack)))
                 },
@@ -424,7 +464,14 @@ impl Display for CharacterStyles {
                     write!(f, "\u{1b}[38;2;{};{};{}m", r, g, b)?;
                 },
                 AnsiCode::ColorIndex(color_index) => {
-                    write!(f, "\u{1b}[38;5;{}m", color_index)?;
+                    match NamedColor::from_color_index(color_index) {
+                        Some(named_color) => {
+                            write!(f, "\u{1b}[{}m", named_color.to_foreground_ansi_code())?;
+                        },
+                        None => {
+                            write!(f, "\u{1b}[38;5;{}m", color_index)?;
+                        },
+                    }
                 },
                 AnsiCode::Reset => {
                     write!(f, "\u{1b}[39m")?;
@@ -441,7 +488,14 @@ impl Display for CharacterStyles {
                     write!(f, "\u{1b}[48;2;{};{};{}m", r, g, b)?;
                 },
                 AnsiCode::ColorIndex(color_index) => {
-                    write!(f, "\u{1b}[48;5;{}m", color_index)?;
+                    match NamedColor::from_color_index(color_index) {
+                        Some(named_color) => {
+                            write!(f, "\u{1b}[{}m", named_color.to_background_ansi_code())?;
+                        },
+                        None => {
+                            write!(f, "\u{1b}[48;5;{}m", color_index)?;
+                        },
+                    }
                 },
                 AnsiCode::Reset => {
                     write!(f, "\u{1b}[49m")?;
@@ -714,11 +768,85 @@ impl Cursor {
     }
 }
 
+#[derive(Default)]
+struct CombiningMarksStore {
+    sequences: Vec<String>,
+    ids: HashMap<String, NonZeroU32>,
+}
+
+impl CombiningMarksStore {
+    pub fn intern(&mut self, sequence: String) -> NonZeroU32 {
+        if let Some(id) = self.ids.get(&sequence) {
+            return *id;
+        }
+        self.sequences.push(sequence.clone());
+        // ids start at 1 so that an empty CombiningMarks is free to use the niche
+        let id = NonZeroU32::new(self.sequences.len() as u32).unwrap();
+        self.ids.insert(sequence, id);
+        id
+    }
+    pub fn get(&self, id: NonZeroU32) -> &str {
+        &self.sequences[id.get() as usize - 1]
+    }
+}
+
+lazy_static! {
+    // the same few sequences are used over and over again, so they are interned once and shared
+    // by every character that has them
+    static ref COMBINING_MARKS_STORE: Mutex<CombiningMarksStore> = Default::default();
+}
+
+/// Zero-width characters (eg. combining accents or variation selectors) drawn in the same cell
+/// as the character they follow. The marks themselves are kept out of line so that characters
+/// stay small and `Copy`, marks past [`MAX_COMBINING_MARKS`] are dropped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CombiningMarks {
+    id: Option<NonZeroU32>,
+}
+
+impl CombiningMarks {
+    pub const fn new() -> Self {
+        CombiningMarks { id: None }
+    }
+    pub fn push(&mut self, mark: char) {
+        let mut store = COMBINING_MARKS_STORE.lock().unwrap();
+        let mut sequence = match self.id {
+            Some(id) => store.get(id).to_owned(),
+            None => String::new(),
+        };
+        if sequence.chars().count() < MAX_COMBINING_MARKS {
+            sequence.push(mark);
+            self.id = Some(store.intern(sequence));
+        }
+    }
+    pub fn push_to(&self, output: &mut String) {
+        if let Some(id) = self.id {
+            output.push_str(COMBINING_MARKS_STORE.lock().unwrap().get(id));
+        }
+    }
+}
+
+impl Display for CombiningMarks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.id {
+            Some(id) => write!(f, "{}", COMBINING_MARKS_STORE.lock().unwrap().get(id)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for CombiningMarks {
+    fn default() -> Self {
+        CombiningMarks::new()
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub struct TerminalCharacter {
     pub character: char,
     pub styles: CharacterStyles,
     pub width: usize,
+    pub combining_marks: CombiningMarks,
 }
 
 impl TerminalCharacter {
@@ -727,12 +855,22 @@ impl TerminalCharacter {
             character,
             styles: CharacterStyles::default(),
             width: character.width().unwrap_or(0),
+            combining_marks: CombiningMarks::new(),
         }
     }
+    /// The character along with its combining marks, as they should be written to the terminal
+    pub fn push_to(&self, output: &mut String) {
+        output.push(self.character);
+        self.combining_marks.push_to(output);
+    }
 }
 
 impl ::std::fmt::Debug for TerminalCharacter {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.character)
+        write!(f, "{}{}", self.character, self.combining_marks)
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/terminal_character_tests.rs"]
+mod terminal_character_tests;
@@ -1,5 +1,6 @@
 use crate::output::{CharacterChunk, SixelImageChunk};
 use crate::panes::sixel::SixelImageStore;
+use crate::panes::utf8_decoder::Utf8Decoder;
 use crate::panes::{
     grid::Grid,
     terminal_character::{TerminalCharacter, EMPTY_TERMINAL_CHARACTER},
@@ -10,7 +11,7 @@ use crate::tab::{AdjustedInput, Pane};
 use crate::ClientId;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::rc::Rc;
 use std::time::{self, Instant};
 use zellij_utils::input::command::RunCommand;
@@ -28,6 +29,8 @@ use zellij_utils::{
 use crate::ui::pane_boundaries_frame::{FrameParams, PaneFrame};
 
 pub const SELECTION_SCROLL_INTERVAL_MS: u64 = 10;
+// how long the frame of a pane stays highlighted after the program running in it rang the bell
+pub const BELL_FLASH_DURATION: time::Duration = time::Duration::from_millis(200);
 
 // Some keys in different formats but are used in the code
 const LEFT_ARROW: &[u8] = &[27, 91, 68];
@@ -36,8 +39,8 @@ const UP_ARROW: &[u8] = &[27, 91, 65];
 const DOWN_ARROW: &[u8] = &[27, 91, 66];
 const HOME_KEY: &[u8] = &[27, 91, 72];
 const END_KEY: &[u8] = &[27, 91, 70];
-const BRACKETED_PASTE_BEGIN: &[u8] = &[27, 91, 50, 48, 48, 126];
-const BRACKETED_PASTE_END: &[u8] = &[27, 91, 50, 48, 49, 126];
+pub(crate) const BRACKETED_PASTE_BEGIN: &[u8] = &[27, 91, 50, 48, 48, 126];
+pub(crate) const BRACKETED_PASTE_END: &[u8] = &[27, 91, 50, 48, 49, 126];
 const ENTER_NEWLINE: &[u8] = &[10];
 const ENTER_CARRIAGE_RETURN: &[u8] = &[13];
 const SPACE: &[u8] = &[32];
@@ -83,6 +86,16 @@ pub enum PaneId {
     Plugin(u32), // FIXME: Drop the trait object, make this a wrapper for the struct?
 }
 
+// the way panes are addressed by `zellij action --pane-id`
+impl fmt::Display for PaneId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaneId::Terminal(id) => write!(f, "terminal_{}", id),
+            PaneId::Plugin(id) => write!(f, "plugin_{}", id),
+        }
+    }
+}
+
 // FIXME: This should hold an os_api handle so that terminal panes can set their own size via FD in
 // their `reflow_lines()` method. Drop a Box<dyn ServerOsApi> in here somewhere.
 #[allow(clippy::too_many_arguments)]
@@ -95,6 +108,7 @@ pub struct TerminalPane {
     pub active_at: Instant,
     pub style: Style,
     vte_parser: vte::Parser,
+    utf8_decoder: Utf8Decoder,
     selection_scrolled_at: time::Instant,
     content_offset: Offset,
     pane_title: String,
@@ -104,6 +118,8 @@ pub struct TerminalPane {
     borderless: bool,
     fake_cursor_locations: HashSet<(usize, usize)>, // (x, y) - these hold a record of previous fake cursors which we need to clear on render
     search_term: String,
+    follow_output: bool, // when false, new output is kept off-screen until the user catches up
+    bell_flash_until: Option<Instant>,
     is_held: Option<(Option<i32>, RunCommand)>, // a "held" pane means that its command has exited and its waiting for a
                                                 // possible user instruction to be re-run
 }
@@ -154,6 +170,7 @@ impl Pane for TerminalPane {
     }
     fn handle_pty_bytes(&mut self, bytes: VteBytes) {
         self.set_should_render(true);
+        let bytes = self.utf8_decoder.decode(bytes);
         for &byte in &bytes {
             self.vte_parser.advance(&mut self.grid, byte);
         }
@@ -173,7 +190,11 @@ impl Pane for TerminalPane {
         if let Some((_exit_status, run_command)) = &self.is_held {
             match input_bytes.as_slice() {
                 ENTER_CARRIAGE_RETURN | ENTER_NEWLINE | SPACE => {
-                    let run_command = run_command.clone();
+                    // once started, the command is re-run the same way after it exits
+                    let run_command = RunCommand {
+                        start_suspended: false,
+                        ..run_command.clone()
+                    };
                     self.is_held = None;
                     self.grid.reset_terminal_state();
                     self.set_should_render(true);
@@ -275,7 +296,6 @@ impl Pane for TerminalPane {
         _client_id: Option<ClientId>,
     ) -> Result<Option<(Vec<CharacterChunk>, Option<String>, Vec<SixelImageChunk>)>> {
         if self.should_render() {
-            let mut raw_vte_output = String::new();
             let content_x = self.get_content_x();
             let content_y = self.get_content_y();
 
@@ -327,17 +347,8 @@ impl Pane for TerminalPane {
                     }
                 }
             }
-            if self.grid.ring_bell {
-                let ring_bell = '\u{7}';
-                raw_vte_output.push(ring_bell);
-                self.grid.ring_bell = false;
-            }
             self.set_should_render(false);
-            Ok(Some((
-                character_chunks,
-                Some(raw_vte_output),
-                sixel_image_chunks,
-            )))
+            Ok(Some((character_chunks, None, sixel_image_chunks)))
         } else {
             Ok(None)
         }
@@ -395,8 +406,12 @@ impl Pane for TerminalPane {
             pane_title,
             frame_params,
         );
-        if let Some((exit_status, _run_command)) = &self.is_held {
-            frame.add_exit_status(exit_status.as_ref().copied());
+        if let Some((exit_status, run_command)) = &self.is_held {
+            if run_command.start_suspended {
+                frame.add_suspended_status();
+            } else {
+                frame.add_exit_status(exit_status.as_ref().copied());
+            }
         }
 
         let res = match self.frame.get(&client_id) {
@@ -447,7 +462,7 @@ impl Pane for TerminalPane {
                 self.get_content_x() + cursor_x + 1,
                 &character_under_cursor.styles,
             );
-            fake_cursor.push(character_under_cursor.character);
+            character_under_cursor.push_to(&mut fake_cursor);
             vte_output = Some(fake_cursor);
         }
         vte_output
@@ -462,6 +477,17 @@ impl Pane for TerminalPane {
         };
         make_terminal_title(pane_title)
     }
+    fn current_title(&self) -> String {
+        if self.pane_name.is_empty() {
+            self.grid
+                .title
+                .as_deref()
+                .unwrap_or(&self.pane_title)
+                .to_owned()
+        } else {
+            self.pane_name.to_owned()
+        }
+    }
     fn update_name(&mut self, name: &str) {
         match name {
             TERMINATING_STRING => {
@@ -524,19 +550,59 @@ impl Pane for TerminalPane {
     }
     fn scroll_up(&mut self, count: usize, _client_id: ClientId) {
         self.grid.move_viewport_up(count);
+        if self.grid.is_scrolled {
+            self.follow_output = false;
+        }
         self.set_should_render(true);
     }
     fn scroll_down(&mut self, count: usize, _client_id: ClientId) {
         self.grid.move_viewport_down(count);
+        if !self.grid.is_scrolled {
+            self.follow_output = true;
+        }
         self.set_should_render(true);
     }
     fn clear_scroll(&mut self) {
         self.grid.reset_viewport();
+        self.follow_output = true;
         self.set_should_render(true);
     }
     fn is_scrolled(&self) -> bool {
         self.grid.is_scrolled
     }
+    fn scroll_left(&mut self, count: usize) {
+        self.grid.scroll_left(count);
+        self.set_should_render(true);
+    }
+    fn scroll_right(&mut self, count: usize) -> bool {
+        let scrolled = self.grid.scroll_right(count);
+        self.set_should_render(true);
+        scrolled
+    }
+    fn clear_horizontal_scroll(&mut self) {
+        self.grid.reset_horizontal_offset();
+        self.set_should_render(true);
+    }
+    fn toggle_line_wrap(&mut self) {
+        self.grid.toggle_linewrap();
+        self.set_should_render(true);
+    }
+    fn follows_output(&self) -> bool {
+        self.follow_output
+    }
+    fn set_follow_output(&mut self, follow_output: bool) {
+        self.follow_output = follow_output;
+    }
+    fn scrollback_position_and_length(&mut self) -> (usize, usize) {
+        self.grid.scrollback_position_and_length()
+    }
+    fn restore_scrollback_position(&mut self, lines_from_top: usize) {
+        let (position, length) = self.grid.scrollback_position_and_length();
+        let lines_from_bottom = length.saturating_sub(position + lines_from_top);
+        self.grid.move_viewport_up(lines_from_bottom);
+        self.follow_output = false;
+        self.set_should_render(true);
+    }
 
     fn active_at(&self) -> Instant {
         self.active_at
@@ -545,6 +611,22 @@ impl Pane for TerminalPane {
     fn set_active_at(&mut self, time: Instant) {
         self.active_at = time;
     }
+    fn take_bell(&mut self) -> bool {
+        std::mem::replace(&mut self.grid.ring_bell, false)
+    }
+    fn flash_bell(&mut self) -> bool {
+        if self.is_flashing_bell() {
+            return false;
+        }
+        self.bell_flash_until = Some(Instant::now() + BELL_FLASH_DURATION);
+        self.set_should_render(true);
+        true
+    }
+    fn is_flashing_bell(&self) -> bool {
+        self.bell_flash_until
+            .map(|flash_until| Instant::now() < flash_until)
+            .unwrap_or(false)
+    }
     fn cursor_shape_csi(&self) -> String {
         self.grid.cursor_shape().get_csi_str().to_string()
     }
@@ -683,6 +765,9 @@ impl Pane for TerminalPane {
         self.grid.search_up();
         self.set_should_render(true);
     }
+    fn search_found_nothing(&self) -> bool {
+        !self.search_term.is_empty() && self.grid.search_results.selections.is_empty()
+    }
     fn toggle_search_case_sensitivity(&mut self) {
         self.grid.toggle_search_case_sensitivity();
         self.set_should_render(true);
@@ -742,6 +827,7 @@ impl TerminalPane {
             geom: position_and_size,
             geom_override: None,
             vte_parser: vte::Parser::new(),
+            utf8_decoder: Utf8Decoder::default(),
             active_at: Instant::now(),
             style,
             selection_scrolled_at: time::Instant::now(),
@@ -752,6 +838,8 @@ impl TerminalPane {
             fake_cursor_locations: HashSet::new(),
             search_term: String::new(),
             is_held: None,
+            follow_output: true,
+            bell_flash_until: None,
         }
     }
     pub fn get_x(&self) -> usize {
@@ -15,9 +15,11 @@ use std::time::Instant;
 use zellij_utils::errors::prelude::*;
 use zellij_utils::{
     data::{ModeInfo, Style},
+    input::actions::ResizeDirection,
     input::command::RunCommand,
-    input::layout::SplitDirection,
-    pane_size::{Offset, PaneGeom, Size, SizeInPixels, Viewport},
+    input::layout::{PaneLayout, SplitDirection, SplitSize},
+    input::options::AutoLayout,
+    pane_size::{Dimension, Offset, PaneGeom, Size, SizeInPixels, Viewport},
 };
 
 macro_rules! resize_pty {
@@ -39,17 +41,18 @@ fn pane_content_offset(position_and_size: &PaneGeom, viewport: &Viewport) -> (us
     // if the pane is not on the bottom or right edge on the screen, we need to reserve one space
     // from its content to leave room for the boundary between it and the next pane (if it doesn't
     // draw its own frame)
-    let columns_offset = if position_and_size.x + position_and_size.cols.as_usize() < viewport.cols
-    {
-        1
-    } else {
-        0
-    };
-    let rows_offset = if position_and_size.y + position_and_size.rows.as_usize() < viewport.rows {
-        1
-    } else {
-        0
-    };
+    let columns_offset =
+        if position_and_size.x + position_and_size.cols.as_usize() < viewport.x + viewport.cols {
+            1
+        } else {
+            0
+        };
+    let rows_offset =
+        if position_and_size.y + position_and_size.rows.as_usize() < viewport.y + viewport.rows {
+            1
+        } else {
+            0
+        };
     (columns_offset, rows_offset)
 }
 
@@ -69,6 +72,12 @@ pub struct TiledPanes {
     panes_to_hide: HashSet<PaneId>,
     fullscreen_is_active: bool,
     os_api: Box<dyn ServerOsApi>,
+    resize_percent: f64,
+    auto_layout: AutoLayout,
+    arranges_panes_automatically: bool, // turned off when a pane is resized by hand
+    min_pane_width: Option<usize>,
+    min_pane_height: Option<usize>,
+    is_too_small: bool, // the display area can't fit all the panes at their minimum size
 }
 
 impl TiledPanes {
@@ -85,6 +94,8 @@ impl TiledPanes {
         default_mode_info: ModeInfo,
         style: Style,
         os_api: Box<dyn ServerOsApi>,
+        resize_percent: f64,
+        auto_layout: AutoLayout,
     ) -> Self {
         TiledPanes {
             panes: BTreeMap::new(),
@@ -102,6 +113,30 @@ impl TiledPanes {
             panes_to_hide: HashSet::new(),
             fullscreen_is_active: false,
             os_api,
+            resize_percent,
+            auto_layout,
+            arranges_panes_automatically: auto_layout != AutoLayout::Manual,
+            min_pane_width: None,
+            min_pane_height: None,
+            is_too_small: false,
+        }
+    }
+    pub fn set_min_pane_size(
+        &mut self,
+        min_pane_width: Option<usize>,
+        min_pane_height: Option<usize>,
+    ) {
+        self.min_pane_width = min_pane_width;
+        self.min_pane_height = min_pane_height;
+    }
+    pub fn is_too_small(&self) -> bool {
+        self.is_too_small
+    }
+    // the smallest size panes may be laid out with when the display area is resized
+    fn min_pane_size(&self) -> Size {
+        Size {
+            rows: self.min_pane_height.unwrap_or(1),
+            cols: self.min_pane_width.unwrap_or(1),
         }
     }
     pub fn add_pane_with_existing_geom(&mut self, pane_id: PaneId, mut pane: Box<dyn Pane>) {
@@ -175,6 +210,7 @@ impl TiledPanes {
                 pane.set_geom(second_geom);
                 self.panes.insert(pane_id, pane);
                 self.relayout(!split_direction);
+                self.arrange_panes_automatically();
             }
         }
     }
@@ -203,6 +239,51 @@ impl TiledPanes {
             })
             .collect()
     }
+    /// Describes the arrangement of the panes as nested splits, with `describe_pane` providing
+    /// the layout of each individual pane
+    pub fn to_layout(&self, describe_pane: impl Fn(PaneId, &dyn Pane) -> PaneLayout) -> PaneLayout {
+        // hidden (eg. fullscreen) panes keep their place in the arrangement
+        let panes: Vec<(PaneId, PaneGeom)> = self
+            .panes
+            .iter()
+            .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+            .collect();
+        let describe_pane_with_id = |pane_id: PaneId| -> PaneLayout {
+            let pane = self.panes.get(&pane_id).unwrap();
+            describe_pane(pane_id, pane.as_ref())
+        };
+        layout_of_panes(&panes, (100.0, 100.0), &describe_pane_with_id)
+    }
+    pub fn pane_geoms(&self) -> HashMap<PaneId, PaneGeom> {
+        self.panes
+            .iter()
+            .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+            .collect()
+    }
+    /// Adds the pane back in the place of a closed pane, giving the panes that took over its
+    /// space their geometry from before it was closed. The pane is returned instead if the panes
+    /// were moved or resized since, as its place is then lost.
+    pub fn reinstate_pane(
+        &mut self,
+        pane_id: PaneId,
+        mut pane: Box<dyn Pane>,
+        geom: PaneGeom,
+        geoms_before_closing: &HashMap<PaneId, PaneGeom>,
+        geoms_after_closing: &HashMap<PaneId, PaneGeom>,
+    ) -> Option<Box<dyn Pane>> {
+        if &self.pane_geoms() != geoms_after_closing {
+            return Some(pane);
+        }
+        for (other_pane_id, other_pane_geom) in geoms_before_closing {
+            if let Some(other_pane) = self.panes.get_mut(other_pane_id) {
+                other_pane.set_geom(*other_pane_geom);
+            }
+        }
+        pane.set_geom(geom);
+        self.panes.insert(pane_id, pane);
+        self.set_pane_frames(self.draw_pane_frames);
+        None
+    }
     pub fn first_selectable_pane_id(&self) -> Option<PaneId> {
         self.panes
             .iter()
@@ -214,12 +295,14 @@ impl TiledPanes {
         self.panes.keys()
     }
     pub fn relayout(&mut self, direction: SplitDirection) {
+        let min_pane_size = self.min_pane_size();
         let mut pane_grid = TiledPaneGrid::new(
             &mut self.panes,
             &self.panes_to_hide,
             *self.display_area.borrow(),
             *self.viewport.borrow(),
-        );
+        )
+        .with_min_pane_size(min_pane_size);
         let result = match direction {
             SplitDirection::Horizontal => {
                 pane_grid.layout(direction, (*self.display_area.borrow()).cols)
@@ -270,7 +353,8 @@ impl TiledPanes {
         if let Some(active_pane_id) = &self.active_panes.get(&client_id) {
             if let Some(active_pane) = self.panes.get_mut(active_pane_id) {
                 let full_pane_size = active_pane.position_and_size();
-                if full_pane_size.rows.as_usize() < MIN_TERMINAL_HEIGHT * 2 {
+                let min_pane_height = self.min_pane_height.unwrap_or(MIN_TERMINAL_HEIGHT);
+                if full_pane_size.rows.as_usize() < min_pane_height * 2 {
                     return false;
                 } else {
                     return split(SplitDirection::Horizontal, &full_pane_size).is_some();
@@ -283,7 +367,8 @@ impl TiledPanes {
         if let Some(active_pane_id) = &self.active_panes.get(&client_id) {
             if let Some(active_pane) = self.panes.get_mut(active_pane_id) {
                 let full_pane_size = active_pane.position_and_size();
-                if full_pane_size.cols.as_usize() < MIN_TERMINAL_WIDTH * 2 {
+                let min_pane_width = self.min_pane_width.unwrap_or(MIN_TERMINAL_WIDTH);
+                if full_pane_size.cols.as_usize() < min_pane_width * 2 {
                     return false;
                 }
                 return split(SplitDirection::Vertical, &full_pane_size).is_some();
@@ -307,6 +392,7 @@ impl TiledPanes {
             new_pane.set_geom(bottom_winsize);
             self.panes.insert(pid, new_pane);
             self.relayout(SplitDirection::Vertical);
+            self.arrange_panes_automatically();
         }
     }
     pub fn split_pane_vertically(
@@ -325,6 +411,7 @@ impl TiledPanes {
             new_pane.set_geom(right_winsize);
             self.panes.insert(pid, new_pane);
             self.relayout(SplitDirection::Horizontal);
+            self.arrange_panes_automatically();
         }
     }
     pub fn focus_pane(&mut self, pane_id: PaneId, client_id: ClientId) {
@@ -363,6 +450,20 @@ impl TiledPanes {
     pub fn get_active_pane_id(&self, client_id: ClientId) -> Option<PaneId> {
         self.active_panes.get(&client_id).copied()
     }
+    /// The selectable pane right next to this one, eg. to focus once it is closed
+    pub fn neighbouring_pane_id(&mut self, pane_id: PaneId) -> Option<PaneId> {
+        let pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        pane_grid
+            .next_selectable_pane_id_to_the_left(&pane_id)
+            .or_else(|| pane_grid.next_selectable_pane_id_to_the_right(&pane_id))
+            .or_else(|| pane_grid.next_selectable_pane_id_above(&pane_id))
+            .or_else(|| pane_grid.next_selectable_pane_id_below(&pane_id))
+    }
     pub fn panes_contain(&self, pane_id: &PaneId) -> bool {
         self.panes.contains_key(pane_id)
     }
@@ -454,7 +555,9 @@ impl TiledPanes {
             output
                 .add_character_chunks_to_client(
                     *client_id,
-                    boundaries.render().with_context(err_context)?,
+                    boundaries
+                        .render(self.style.frame_style)
+                        .with_context(err_context)?,
                     None,
                 )
                 .with_context(err_context)?;
@@ -465,6 +568,7 @@ impl TiledPanes {
         self.panes.iter()
     }
     pub fn resize(&mut self, new_screen_size: Size) {
+        let min_pane_size = self.min_pane_size();
         // this is blocked out to appease the borrow checker
         {
             let mut display_area = self.display_area.borrow_mut();
@@ -475,7 +579,9 @@ impl TiledPanes {
                 &self.panes_to_hide,
                 *display_area,
                 *viewport,
-            );
+            )
+            .with_min_pane_size(min_pane_size);
+            self.is_too_small = false;
             match pane_grid.layout(SplitDirection::Horizontal, cols) {
                 Ok(_) => {
                     let column_difference = cols as isize - display_area.cols as isize;
@@ -485,6 +591,7 @@ impl TiledPanes {
                 },
                 Err(e) => {
                     log::error!("Failed to horizontally resize the tab: {:?}", e);
+                    self.is_too_small = true;
                 },
             };
             if pane_grid.layout(SplitDirection::Vertical, rows).is_ok() {
@@ -493,92 +600,164 @@ impl TiledPanes {
                 display_area.rows = rows;
             } else {
                 log::error!("Failed to vertically resize the tab!!!");
+                self.is_too_small = true;
             }
         }
         self.set_pane_frames(self.draw_pane_frames);
     }
-    pub fn resize_active_pane_left(&mut self, client_id: ClientId) {
+    /// Resizes the active pane by hand, after which the panes are no longer arranged automatically
+    fn resize_active_pane(
+        &mut self,
+        client_id: ClientId,
+        resize: impl FnOnce(&mut TiledPaneGrid, &PaneId),
+    ) {
+        self.resize_active_pane_by(client_id, self.resize_percent, resize);
+    }
+    fn resize_active_pane_by(
+        &mut self,
+        client_id: ClientId,
+        resize_percent: f64,
+        resize: impl FnOnce(&mut TiledPaneGrid, &PaneId),
+    ) {
+        self.arranges_panes_automatically = false;
         if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
             let mut pane_grid = TiledPaneGrid::new(
                 &mut self.panes,
                 &self.panes_to_hide,
                 *self.display_area.borrow(),
                 *self.viewport.borrow(),
-            );
-            pane_grid.resize_pane_left(&active_pane_id);
+            )
+            .with_resize_percent(resize_percent);
+            resize(&mut pane_grid, &active_pane_id);
             for pane in self.panes.values_mut() {
                 resize_pty!(pane, self.os_api);
             }
         }
     }
+    pub fn resize_active_pane_left(&mut self, client_id: ClientId) {
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_pane_left(pane_id)
+        });
+    }
     pub fn resize_active_pane_right(&mut self, client_id: ClientId) {
-        if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
-            let mut pane_grid = TiledPaneGrid::new(
-                &mut self.panes,
-                &self.panes_to_hide,
-                *self.display_area.borrow(),
-                *self.viewport.borrow(),
-            );
-            pane_grid.resize_pane_right(&active_pane_id);
-            for pane in self.panes.values_mut() {
-                resize_pty!(pane, self.os_api);
-            }
-        }
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_pane_right(pane_id)
+        });
     }
     pub fn resize_active_pane_up(&mut self, client_id: ClientId) {
-        if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
-            let mut pane_grid = TiledPaneGrid::new(
-                &mut self.panes,
-                &self.panes_to_hide,
-                *self.display_area.borrow(),
-                *self.viewport.borrow(),
-            );
-            pane_grid.resize_pane_up(&active_pane_id);
-            for pane in self.panes.values_mut() {
-                resize_pty!(pane, self.os_api);
-            }
-        }
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_pane_up(pane_id)
+        });
     }
     pub fn resize_active_pane_down(&mut self, client_id: ClientId) {
-        if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
-            let mut pane_grid = TiledPaneGrid::new(
-                &mut self.panes,
-                &self.panes_to_hide,
-                *self.display_area.borrow(),
-                *self.viewport.borrow(),
-            );
-            pane_grid.resize_pane_down(&active_pane_id);
-            for pane in self.panes.values_mut() {
-                resize_pty!(pane, self.os_api);
-            }
-        }
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_pane_down(pane_id)
+        });
     }
     pub fn resize_active_pane_increase(&mut self, client_id: ClientId) {
-        if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
-            let mut pane_grid = TiledPaneGrid::new(
-                &mut self.panes,
-                &self.panes_to_hide,
-                *self.display_area.borrow(),
-                *self.viewport.borrow(),
-            );
-            pane_grid.resize_increase(&active_pane_id);
-            for pane in self.panes.values_mut() {
-                resize_pty!(pane, self.os_api);
-            }
-        }
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_increase(pane_id)
+        });
     }
     pub fn resize_active_pane_decrease(&mut self, client_id: ClientId) {
-        if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
-            let mut pane_grid = TiledPaneGrid::new(
-                &mut self.panes,
-                &self.panes_to_hide,
-                *self.display_area.borrow(),
-                *self.viewport.borrow(),
-            );
-            pane_grid.resize_decrease(&active_pane_id);
-            for pane in self.panes.values_mut() {
-                resize_pty!(pane, self.os_api);
-            }
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_decrease(pane_id)
+        });
+    }
+    /// Resizes the active pane by a single cell of the display area rather than by the configured
+    /// resize percentage
+    pub fn resize_active_pane_by_one_cell(
+        &mut self,
+        direction: ResizeDirection,
+        client_id: ClientId,
+    ) {
+        let display_area = *self.display_area.borrow();
+        let one_column = 100.0 / std::cmp::max(display_area.cols, 1) as f64;
+        let one_row = 100.0 / std::cmp::max(display_area.rows, 1) as f64;
+        let resize_percent = match direction {
+            ResizeDirection::Left | ResizeDirection::Right => one_column,
+            ResizeDirection::Up | ResizeDirection::Down => one_row,
+            // a cell in both directions
+            ResizeDirection::Increase | ResizeDirection::Decrease => one_column.max(one_row),
+        };
+        self.resize_active_pane_by(
+            client_id,
+            resize_percent,
+            |pane_grid, pane_id| match direction {
+                ResizeDirection::Left => pane_grid.resize_pane_left(pane_id),
+                ResizeDirection::Right => pane_grid.resize_pane_right(pane_id),
+                ResizeDirection::Up => pane_grid.resize_pane_up(pane_id),
+                ResizeDirection::Down => pane_grid.resize_pane_down(pane_id),
+                ResizeDirection::Increase => pane_grid.resize_increase(pane_id),
+                ResizeDirection::Decrease => pane_grid.resize_decrease(pane_id),
+            },
+        );
+    }
+    pub fn resize_active_pane_to_fraction(&mut self, fraction: f64, client_id: ClientId) {
+        self.resize_active_pane(client_id, |pane_grid, pane_id| {
+            pane_grid.resize_pane_to_fraction(pane_id, fraction)
+        });
+    }
+    pub fn equalize(&mut self) {
+        let mut pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        pane_grid.equalize();
+        for pane in self.panes.values_mut() {
+            resize_pty!(pane, self.os_api);
+        }
+        self.set_pane_frames(self.draw_pane_frames);
+    }
+    /// Arranges the panes with the configured auto-layout (or in an even grid if there is none)
+    /// and goes back to doing so whenever panes are added or closed
+    pub fn apply_auto_layout(&mut self) {
+        let auto_layout = match self.auto_layout {
+            AutoLayout::Manual => AutoLayout::EvenGrid,
+            auto_layout => auto_layout,
+        };
+        self.arranges_panes_automatically = self.auto_layout != AutoLayout::Manual;
+        self.arrange_panes(auto_layout);
+    }
+    fn arrange_panes_automatically(&mut self) {
+        if self.arranges_panes_automatically {
+            self.arrange_panes(self.auto_layout);
+        }
+    }
+    /// Arranges the panes inside the viewport according to `auto_layout`, keeping their order
+    /// from left to right and top to bottom. The panes are left as they are if some of them have
+    /// a fixed size or if they would not fit.
+    fn arrange_panes(&mut self, auto_layout: AutoLayout) {
+        if self.fullscreen_is_active {
+            return;
+        }
+        let viewport = *self.viewport.borrow();
+        let mut panes: Vec<(PaneId, PaneGeom)> = self
+            .panes
+            .iter()
+            .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+            .filter(|(_pane_id, geom)| pane_geom_is_inside_viewport(&viewport, geom))
+            .collect();
+        if panes
+            .iter()
+            .any(|(_pane_id, geom)| geom.cols.is_fixed() || geom.rows.is_fixed())
+        {
+            log::info!("Not arranging the panes as some of them have a fixed size");
+            return;
+        }
+        panes.sort_by_key(|(_pane_id, geom)| (geom.x, geom.y));
+        match auto_layout_geoms(auto_layout, &viewport, panes.len()) {
+            Some(geoms) => {
+                for ((pane_id, _previous_geom), geom) in panes.iter().zip(geoms) {
+                    if let Some(pane) = self.panes.get_mut(pane_id) {
+                        pane.set_geom(geom);
+                    }
+                }
+                self.set_pane_frames(self.draw_pane_frames);
+            },
+            None => log::info!("Not arranging the panes as they would not fit"),
         }
     }
     pub fn focus_next_pane(&mut self, client_id: ClientId) {
@@ -789,7 +968,10 @@ impl TiledPanes {
         }
     }
     pub fn move_active_pane(&mut self, client_id: ClientId) {
-        let active_pane_id = self.get_active_pane_id(client_id).unwrap();
+        let active_pane_id = match self.get_active_pane_id(client_id) {
+            Some(active_pane_id) => active_pane_id,
+            None => return, // no pane to move, eg. this client has not focused a pane yet
+        };
         let pane_grid = TiledPaneGrid::new(
             &mut self.panes,
             &self.panes_to_hide,
@@ -997,6 +1179,7 @@ impl TiledPanes {
             let closed_pane = self.panes.remove(&pane_id);
             self.move_clients_out_of_pane(pane_id);
             self.set_pane_frames(self.draw_pane_frames); // recalculate pane frames and update size
+            self.arrange_panes_automatically();
             closed_pane
         } else {
             self.panes.remove(&pane_id);
@@ -1166,3 +1349,155 @@ pub fn pane_geom_is_inside_viewport(viewport: &Viewport, geom: &PaneGeom) -> boo
         && geom.x >= viewport.x
         && geom.x + geom.cols.as_usize() <= viewport.x + viewport.cols
 }
+
+/// Splits the panes filling a part of the screen into the columns (Vertical) or rows (Horizontal)
+/// that can be separated without cutting through any pane
+fn split_panes_along(
+    panes: &[(PaneId, PaneGeom)],
+    direction: SplitDirection,
+) -> Vec<Vec<(PaneId, PaneGeom)>> {
+    let span = |geom: &PaneGeom| match direction {
+        SplitDirection::Vertical => (geom.x, geom.x + geom.cols.as_usize()),
+        SplitDirection::Horizontal => (geom.y, geom.y + geom.rows.as_usize()),
+    };
+    let mut panes = panes.to_vec();
+    panes.sort_by_key(|(_pane_id, geom)| span(geom));
+    let mut parts: Vec<Vec<(PaneId, PaneGeom)>> = vec![];
+    let mut part_end = 0;
+    for (pane_id, geom) in panes {
+        let (start, end) = span(&geom);
+        match parts.last_mut() {
+            Some(part) if start < part_end => part.push((pane_id, geom)),
+            _ => parts.push(vec![(pane_id, geom)]),
+        }
+        part_end = part_end.max(end);
+    }
+    parts
+}
+
+fn layout_of_panes(
+    panes: &[(PaneId, PaneGeom)],
+    (cols_percent, rows_percent): (f64, f64),
+    describe_pane: &dyn Fn(PaneId) -> PaneLayout,
+) -> PaneLayout {
+    if let [(pane_id, _geom)] = panes {
+        return describe_pane(*pane_id);
+    }
+    for direction in [SplitDirection::Vertical, SplitDirection::Horizontal] {
+        let parts = split_panes_along(panes, direction);
+        if parts.len() < 2 {
+            continue;
+        }
+        let part_size = |part: &[(PaneId, PaneGeom)]| -> (usize, bool) {
+            // (size, is_fixed)
+            let dimensions = part.iter().map(|(_pane_id, geom)| match direction {
+                SplitDirection::Vertical => (geom.x, geom.x + geom.cols.as_usize(), geom.cols),
+                SplitDirection::Horizontal => (geom.y, geom.y + geom.rows.as_usize(), geom.rows),
+            });
+            let start = dimensions
+                .clone()
+                .map(|(start, _, _)| start)
+                .min()
+                .unwrap_or(0);
+            let end = dimensions.clone().map(|(_, end, _)| end).max().unwrap_or(0);
+            let is_fixed = dimensions
+                .clone()
+                .all(|(_, _, dimension)| dimension.is_fixed());
+            (end - start, is_fixed)
+        };
+        let parent_percent = match direction {
+            SplitDirection::Vertical => cols_percent,
+            SplitDirection::Horizontal => rows_percent,
+        };
+        let flexible_size: usize = parts
+            .iter()
+            .map(|part| part_size(part))
+            .filter(|(_size, is_fixed)| !is_fixed)
+            .map(|(size, _is_fixed)| size)
+            .sum();
+        // the last flexible part takes up whatever is left, absorbing rounding errors
+        let last_flexible_part = parts.iter().rposition(|part| !part_size(part).1);
+        let children = parts
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let (size, is_fixed) = part_size(part);
+                // layout percentages are relative to the whole tab, like those of the panes
+                let percent = parent_percent * size as f64 / flexible_size.max(1) as f64;
+                let child_percent = match direction {
+                    SplitDirection::Vertical => (percent, rows_percent),
+                    SplitDirection::Horizontal => (cols_percent, percent),
+                };
+                let mut child = layout_of_panes(part, child_percent, describe_pane);
+                child.split_size = if is_fixed {
+                    Some(SplitSize::Fixed(size))
+                } else if Some(index) == last_flexible_part {
+                    None
+                } else {
+                    Some(SplitSize::Percent(percent.round() as usize))
+                };
+                child
+            })
+            .collect();
+        return PaneLayout {
+            children_split_direction: direction,
+            children,
+            ..Default::default()
+        };
+    }
+    // not arranged in splits, this should not happen
+    PaneLayout {
+        children: panes
+            .iter()
+            .map(|(pane_id, _geom)| describe_pane(*pane_id))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// The geometries of `pane_count` panes arranged in the viewport according to `auto_layout`,
+/// column by column, or None if they do not fit
+fn auto_layout_geoms(
+    auto_layout: AutoLayout,
+    viewport: &Viewport,
+    pane_count: usize,
+) -> Option<Vec<PaneGeom>> {
+    if pane_count == 0 {
+        return None;
+    }
+    let panes_per_column: Vec<usize> = match auto_layout {
+        AutoLayout::Manual => return None,
+        AutoLayout::EvenGrid => {
+            let column_count = (pane_count as f64).sqrt().ceil() as usize;
+            (0..column_count)
+                .map(|i| pane_count / column_count + usize::from(i < pane_count % column_count))
+                .collect()
+        },
+        AutoLayout::MainAndStack if pane_count > 1 => vec![1, pane_count - 1],
+        AutoLayout::MainAndStack => vec![1],
+    };
+    let columns = split_evenly(viewport.x, viewport.cols, panes_per_column.len());
+    let mut geoms = vec![];
+    for ((x, cols), panes_in_column) in columns.into_iter().zip(panes_per_column) {
+        for (y, rows) in split_evenly(viewport.y, viewport.rows, panes_in_column) {
+            if cols.as_usize() < MIN_TERMINAL_WIDTH || rows.as_usize() < MIN_TERMINAL_HEIGHT {
+                return None;
+            }
+            geoms.push(PaneGeom { x, y, rows, cols });
+        }
+    }
+    Some(geoms)
+}
+
+/// Splits `space` starting at `position` into `parts` consecutive parts of (nearly) equal size
+fn split_evenly(mut position: usize, space: usize, parts: usize) -> Vec<(usize, Dimension)> {
+    let mut split = vec![];
+    for i in 0..parts {
+        let size = space / parts + usize::from(i < space % parts);
+        let mut dimension = Dimension::percent(100.0 / parts as f64);
+        dimension.set_inner(size);
+        split.push((position, dimension));
+        position += size;
+    }
+    split
+}
@@ -16,6 +16,7 @@ pub struct PaneResizer<'a> {
     panes: Rc<RefCell<HashMap<PaneId, &'a mut Box<dyn Pane>>>>,
     vars: HashMap<PaneId, Variable>,
     solver: Solver,
+    min_size: usize,
 }
 
 // FIXME: Just hold a mutable Pane reference instead of the PaneId, fixed, pos, and size?
@@ -41,9 +42,17 @@ impl<'a> PaneResizer<'a> {
             panes,
             vars,
             solver: Solver::new(),
+            min_size: 1,
         }
     }
 
+    /// Sets the smallest size a pane that isn't fixed-size may be laid out with, laying out the
+    /// panes in less space than that fails.
+    pub fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
     pub fn layout(&mut self, direction: SplitDirection, space: usize) -> Result<(), String> {
         self.solver.reset();
         let grid = self.solve(direction, space)?;
@@ -111,7 +120,7 @@ impl<'a> PaneResizer<'a> {
             for span in spans {
                 span.pos = offset;
                 let sz = rounded_sizes[&span.size_var];
-                if sz < 1 {
+                if sz < 1 || (!span.size.is_fixed() && sz < self.min_size as isize) {
                     return Err("Ran out of room for spans".into());
                 }
                 span.size.set_inner(sz as usize);
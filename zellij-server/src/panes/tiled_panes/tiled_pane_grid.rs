@@ -11,6 +11,7 @@ use zellij_utils::{
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Instant;
 
 const RESIZE_PERCENT: f64 = 5.0;
 const DEFAULT_CURSOR_HEIGHT_WIDTH_RATIO: usize = 4;
@@ -21,6 +22,8 @@ pub struct TiledPaneGrid<'a> {
     panes: Rc<RefCell<HashMap<PaneId, &'a mut Box<dyn Pane>>>>,
     display_area: Size, // includes all panes (including eg. the status bar and tab bar in the default layout)
     viewport: Viewport, // includes all non-UI panes
+    resize_percent: f64,
+    min_pane_size: Size,
 }
 
 impl<'a> TiledPaneGrid<'a> {
@@ -39,40 +42,58 @@ impl<'a> TiledPaneGrid<'a> {
             panes: Rc::new(RefCell::new(panes)),
             display_area,
             viewport,
+            resize_percent: RESIZE_PERCENT,
+            min_pane_size: Size { rows: 1, cols: 1 },
         }
     }
 
+    /// Sets the percentage of the screen by which a single resize grows or shrinks a pane.
+    pub fn with_resize_percent(mut self, resize_percent: f64) -> Self {
+        self.resize_percent = resize_percent;
+        self
+    }
+
+    /// Sets the smallest size panes may be laid out with, rather than a single row or column.
+    pub fn with_min_pane_size(mut self, min_pane_size: Size) -> Self {
+        self.min_pane_size = min_pane_size;
+        self
+    }
+
     pub fn layout(&mut self, direction: SplitDirection, space: usize) -> Result<(), String> {
-        let mut pane_resizer = PaneResizer::new(self.panes.clone());
+        let min_size = match direction {
+            SplitDirection::Horizontal => self.min_pane_size.cols,
+            SplitDirection::Vertical => self.min_pane_size.rows,
+        };
+        let mut pane_resizer = PaneResizer::new(self.panes.clone()).with_min_size(min_size);
         pane_resizer.layout(direction, space)
     }
     pub fn resize_pane_left(&mut self, pane_id: &PaneId) {
         // TODO: find out by how much we actually reduced and only reduce by that much
-        if self.try_increase_pane_and_surroundings_left(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_left(pane_id, self.resize_percent) {
             return;
         }
-        self.try_reduce_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
+        self.try_reduce_pane_and_surroundings_left(pane_id, self.resize_percent);
     }
     pub fn resize_pane_right(&mut self, pane_id: &PaneId) {
         // TODO: find out by how much we actually reduced and only reduce by that much
-        if self.try_increase_pane_and_surroundings_right(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_right(pane_id, self.resize_percent) {
             return;
         }
-        self.try_reduce_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
+        self.try_reduce_pane_and_surroundings_right(pane_id, self.resize_percent);
     }
     pub fn resize_pane_down(&mut self, pane_id: &PaneId) {
         // TODO: find out by how much we actually reduced and only reduce by that much
-        if self.try_increase_pane_and_surroundings_down(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_down(pane_id, self.resize_percent) {
             return;
         }
-        self.try_reduce_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+        self.try_reduce_pane_and_surroundings_down(pane_id, self.resize_percent);
     }
     pub fn resize_pane_up(&mut self, pane_id: &PaneId) {
         // TODO: find out by how much we actually reduced and only reduce by that much
-        if self.try_increase_pane_and_surroundings_up(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_up(pane_id, self.resize_percent) {
             return;
         }
-        self.try_reduce_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+        self.try_reduce_pane_and_surroundings_up(pane_id, self.resize_percent);
     }
     pub fn resize_increase(&mut self, pane_id: &PaneId) {
         if self.try_increase_pane_and_surroundings_right_and_down(pane_id) {
@@ -88,16 +109,16 @@ impl<'a> TiledPaneGrid<'a> {
             return;
         }
 
-        if self.try_increase_pane_and_surroundings_right(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_right(pane_id, self.resize_percent) {
             return;
         }
-        if self.try_increase_pane_and_surroundings_down(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_down(pane_id, self.resize_percent) {
             return;
         }
-        if self.try_increase_pane_and_surroundings_left(pane_id, RESIZE_PERCENT) {
+        if self.try_increase_pane_and_surroundings_left(pane_id, self.resize_percent) {
             return;
         }
-        self.try_increase_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+        self.try_increase_pane_and_surroundings_up(pane_id, self.resize_percent);
     }
     pub fn resize_decrease(&mut self, pane_id: &PaneId) {
         if self.try_reduce_pane_and_surroundings_left_and_up(pane_id) {
@@ -112,16 +133,16 @@ impl<'a> TiledPaneGrid<'a> {
         if self.try_reduce_pane_and_surroundings_left_and_down(pane_id) {
             return;
         }
-        if self.try_reduce_pane_and_surroundings_left(pane_id, RESIZE_PERCENT) {
+        if self.try_reduce_pane_and_surroundings_left(pane_id, self.resize_percent) {
             return;
         }
-        if self.try_reduce_pane_and_surroundings_right(pane_id, RESIZE_PERCENT) {
+        if self.try_reduce_pane_and_surroundings_right(pane_id, self.resize_percent) {
             return;
         }
-        if self.try_reduce_pane_and_surroundings_up(pane_id, RESIZE_PERCENT) {
+        if self.try_reduce_pane_and_surroundings_up(pane_id, self.resize_percent) {
             return;
         }
-        self.try_reduce_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+        self.try_reduce_pane_and_surroundings_down(pane_id, self.resize_percent);
     }
     fn can_increase_pane_and_surroundings_right(&self, pane_id: &PaneId, increase_by: f64) -> bool {
         if let Some(panes_to_the_right) = self.pane_ids_directly_right_of(pane_id) {
@@ -1009,9 +1030,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_increase_pane_and_surroundings_right_and_up(&mut self, pane_id: &PaneId) -> bool {
         let can_increase_pane_right =
-            self.can_increase_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_right(pane_id, self.resize_percent);
         let can_increase_pane_up =
-            self.can_increase_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_up(pane_id, self.resize_percent);
         if can_increase_pane_right && can_increase_pane_up {
             let pane_above_with_right_aligned_border = self
                 .viewport_pane_ids_directly_above(pane_id)
@@ -1023,13 +1044,13 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() + active_pane.cols() == pane.x()
                 });
-            self.try_increase_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
-            self.try_increase_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.try_increase_pane_and_surroundings_right(pane_id, self.resize_percent);
+            self.try_increase_pane_and_surroundings_up(pane_id, self.resize_percent);
             if let Some(pane_above_with_right_aligned_border) = pane_above_with_right_aligned_border
             {
                 self.try_reduce_pane_and_surroundings_right(
                     &pane_above_with_right_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1039,9 +1060,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_increase_pane_and_surroundings_left_and_up(&mut self, pane_id: &PaneId) -> bool {
         let can_increase_pane_left =
-            self.can_increase_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_left(pane_id, self.resize_percent);
         let can_increase_pane_up =
-            self.can_increase_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_up(pane_id, self.resize_percent);
         if can_increase_pane_left && can_increase_pane_up {
             let pane_above_with_left_aligned_border = self
                 .viewport_pane_ids_directly_above(pane_id)
@@ -1053,12 +1074,12 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() == pane.x() + pane.cols()
                 });
-            self.try_increase_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
-            self.try_increase_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.try_increase_pane_and_surroundings_left(pane_id, self.resize_percent);
+            self.try_increase_pane_and_surroundings_up(pane_id, self.resize_percent);
             if let Some(pane_above_with_left_aligned_border) = pane_above_with_left_aligned_border {
                 self.try_reduce_pane_and_surroundings_left(
                     &pane_above_with_left_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1068,9 +1089,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_increase_pane_and_surroundings_right_and_down(&mut self, pane_id: &PaneId) -> bool {
         let can_increase_pane_right =
-            self.can_increase_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_right(pane_id, self.resize_percent);
         let can_increase_pane_down =
-            self.can_increase_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_down(pane_id, self.resize_percent);
         if can_increase_pane_right && can_increase_pane_down {
             let pane_below_with_right_aligned_border = self
                 .viewport_pane_ids_directly_below(pane_id)
@@ -1082,13 +1103,13 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() + active_pane.cols() == pane.x()
                 });
-            self.try_increase_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
-            self.try_increase_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.try_increase_pane_and_surroundings_right(pane_id, self.resize_percent);
+            self.try_increase_pane_and_surroundings_down(pane_id, self.resize_percent);
             if let Some(pane_below_with_right_aligned_border) = pane_below_with_right_aligned_border
             {
                 self.try_reduce_pane_and_surroundings_right(
                     &pane_below_with_right_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1098,9 +1119,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_increase_pane_and_surroundings_left_and_down(&mut self, pane_id: &PaneId) -> bool {
         let can_increase_pane_left =
-            self.can_increase_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_left(pane_id, self.resize_percent);
         let can_increase_pane_down =
-            self.can_increase_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.can_increase_pane_and_surroundings_down(pane_id, self.resize_percent);
         if can_increase_pane_left && can_increase_pane_down {
             let pane_below_with_left_aligned_border = self
                 .viewport_pane_ids_directly_below(pane_id)
@@ -1112,12 +1133,12 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() == pane.x() + pane.cols()
                 });
-            self.try_increase_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
-            self.try_increase_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.try_increase_pane_and_surroundings_left(pane_id, self.resize_percent);
+            self.try_increase_pane_and_surroundings_down(pane_id, self.resize_percent);
             if let Some(pane_below_with_left_aligned_border) = pane_below_with_left_aligned_border {
                 self.try_reduce_pane_and_surroundings_left(
                     &pane_below_with_left_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1127,8 +1148,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_reduce_pane_and_surroundings_right_and_up(&mut self, pane_id: &PaneId) -> bool {
         let can_reduce_pane_right =
-            self.can_reduce_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
-        let can_reduce_pane_up = self.can_reduce_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.can_reduce_pane_and_surroundings_right(pane_id, self.resize_percent);
+        let can_reduce_pane_up =
+            self.can_reduce_pane_and_surroundings_up(pane_id, self.resize_percent);
         if can_reduce_pane_right && can_reduce_pane_up {
             let pane_below_with_left_aligned_border = self
                 .viewport_pane_ids_directly_below(pane_id)
@@ -1140,12 +1162,12 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() == pane.x() + pane.cols()
                 });
-            self.try_reduce_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
-            self.try_reduce_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.try_reduce_pane_and_surroundings_right(pane_id, self.resize_percent);
+            self.try_reduce_pane_and_surroundings_up(pane_id, self.resize_percent);
             if let Some(pane_below_with_left_aligned_border) = pane_below_with_left_aligned_border {
                 self.try_increase_pane_and_surroundings_right(
                     &pane_below_with_left_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1155,8 +1177,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_reduce_pane_and_surroundings_left_and_up(&mut self, pane_id: &PaneId) -> bool {
         let can_reduce_pane_left =
-            self.can_reduce_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
-        let can_reduce_pane_up = self.can_reduce_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.can_reduce_pane_and_surroundings_left(pane_id, self.resize_percent);
+        let can_reduce_pane_up =
+            self.can_reduce_pane_and_surroundings_up(pane_id, self.resize_percent);
         if can_reduce_pane_left && can_reduce_pane_up {
             let pane_below_with_right_aligned_border = self
                 .viewport_pane_ids_directly_below(pane_id)
@@ -1168,13 +1191,13 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() + active_pane.cols() == pane.x()
                 });
-            self.try_reduce_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
-            self.try_reduce_pane_and_surroundings_up(pane_id, RESIZE_PERCENT);
+            self.try_reduce_pane_and_surroundings_left(pane_id, self.resize_percent);
+            self.try_reduce_pane_and_surroundings_up(pane_id, self.resize_percent);
             if let Some(pane_below_with_right_aligned_border) = pane_below_with_right_aligned_border
             {
                 self.try_increase_pane_and_surroundings_left(
                     &pane_below_with_right_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1184,9 +1207,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_reduce_pane_and_surroundings_right_and_down(&mut self, pane_id: &PaneId) -> bool {
         let can_reduce_pane_right =
-            self.can_reduce_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
+            self.can_reduce_pane_and_surroundings_right(pane_id, self.resize_percent);
         let can_reduce_pane_down =
-            self.can_reduce_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.can_reduce_pane_and_surroundings_down(pane_id, self.resize_percent);
         if can_reduce_pane_right && can_reduce_pane_down {
             let pane_above_with_left_aligned_border = self
                 .viewport_pane_ids_directly_above(pane_id)
@@ -1198,12 +1221,12 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() == pane.x() + pane.cols()
                 });
-            self.try_reduce_pane_and_surroundings_right(pane_id, RESIZE_PERCENT);
-            self.try_reduce_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.try_reduce_pane_and_surroundings_right(pane_id, self.resize_percent);
+            self.try_reduce_pane_and_surroundings_down(pane_id, self.resize_percent);
             if let Some(pane_above_with_left_aligned_border) = pane_above_with_left_aligned_border {
                 self.try_increase_pane_and_surroundings_right(
                     &pane_above_with_left_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1213,9 +1236,9 @@ impl<'a> TiledPaneGrid<'a> {
     }
     fn try_reduce_pane_and_surroundings_left_and_down(&mut self, pane_id: &PaneId) -> bool {
         let can_reduce_pane_left =
-            self.can_reduce_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
+            self.can_reduce_pane_and_surroundings_left(pane_id, self.resize_percent);
         let can_reduce_pane_down =
-            self.can_reduce_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.can_reduce_pane_and_surroundings_down(pane_id, self.resize_percent);
         if can_reduce_pane_left && can_reduce_pane_down {
             let pane_above_with_right_aligned_border = self
                 .viewport_pane_ids_directly_above(pane_id)
@@ -1227,13 +1250,13 @@ impl<'a> TiledPaneGrid<'a> {
                     let active_pane = panes.get(pane_id).unwrap();
                     active_pane.x() + active_pane.cols() == pane.x()
                 });
-            self.try_reduce_pane_and_surroundings_left(pane_id, RESIZE_PERCENT);
-            self.try_reduce_pane_and_surroundings_down(pane_id, RESIZE_PERCENT);
+            self.try_reduce_pane_and_surroundings_left(pane_id, self.resize_percent);
+            self.try_reduce_pane_and_surroundings_down(pane_id, self.resize_percent);
             if let Some(pane_above_with_right_aligned_border) = pane_above_with_right_aligned_border
             {
                 self.try_increase_pane_and_surroundings_left(
                     &pane_above_with_right_aligned_border,
-                    RESIZE_PERCENT,
+                    self.resize_percent,
                 );
             }
             true
@@ -1344,82 +1367,70 @@ impl<'a> TiledPaneGrid<'a> {
     pub fn next_selectable_pane_id_to_the_left(&self, current_pane_id: &PaneId) -> Option<PaneId> {
         let panes = self.panes.borrow();
         let current_pane = panes.get(current_pane_id)?;
-        let panes: Vec<(PaneId, &&mut Box<dyn Pane>)> = panes
+        let candidates: Vec<(PaneId, usize, Instant)> = panes
             .iter()
             .filter(|(_, p)| p.selectable())
-            .map(|(p_id, p)| (*p_id, p))
-            .collect();
-        let next_index = panes
-            .iter()
-            .enumerate()
-            .filter(|(_, (_, c))| {
+            .filter(|(_, c)| {
                 c.is_directly_left_of(Box::as_ref(current_pane))
                     && c.horizontally_overlaps_with(Box::as_ref(current_pane))
             })
-            .max_by_key(|(_, (_, c))| c.active_at())
-            .map(|(_, (pid, _))| pid)
-            .copied();
-        next_index
+            .map(|(p_id, c)| {
+                let overlap = c.get_horizontal_overlap_with(Box::as_ref(current_pane));
+                (*p_id, overlap, c.active_at())
+            })
+            .collect();
+        pick_neighbouring_pane(&panes, current_pane_id, candidates)
     }
     pub fn next_selectable_pane_id_below(&self, current_pane_id: &PaneId) -> Option<PaneId> {
         let panes = self.panes.borrow();
         let current_pane = panes.get(current_pane_id)?;
-        let panes: Vec<(PaneId, &&mut Box<dyn Pane>)> = panes
+        let candidates: Vec<(PaneId, usize, Instant)> = panes
             .iter()
             .filter(|(_, p)| p.selectable())
-            .map(|(p_id, p)| (*p_id, p))
-            .collect();
-        let next_index = panes
-            .iter()
-            .enumerate()
-            .filter(|(_, (_, c))| {
+            .filter(|(_, c)| {
                 c.is_directly_below(Box::as_ref(current_pane))
                     && c.vertically_overlaps_with(Box::as_ref(current_pane))
             })
-            .max_by_key(|(_, (_, c))| c.active_at())
-            .map(|(_, (pid, _))| pid)
-            .copied();
-        next_index
+            .map(|(p_id, c)| {
+                let overlap = c.get_vertical_overlap_with(Box::as_ref(current_pane));
+                (*p_id, overlap, c.active_at())
+            })
+            .collect();
+        pick_neighbouring_pane(&panes, current_pane_id, candidates)
     }
     pub fn next_selectable_pane_id_above(&self, current_pane_id: &PaneId) -> Option<PaneId> {
         let panes = self.panes.borrow();
         let current_pane = panes.get(current_pane_id)?;
-        let panes: Vec<(PaneId, &&mut Box<dyn Pane>)> = panes
+        let candidates: Vec<(PaneId, usize, Instant)> = panes
             .iter()
             .filter(|(_, p)| p.selectable())
-            .map(|(p_id, p)| (*p_id, p))
-            .collect();
-        let next_index = panes
-            .iter()
-            .enumerate()
-            .filter(|(_, (_, c))| {
+            .filter(|(_, c)| {
                 c.is_directly_above(Box::as_ref(current_pane))
                     && c.vertically_overlaps_with(Box::as_ref(current_pane))
             })
-            .max_by_key(|(_, (_, c))| c.active_at())
-            .map(|(_, (pid, _))| pid)
-            .copied();
-        next_index
+            .map(|(p_id, c)| {
+                let overlap = c.get_vertical_overlap_with(Box::as_ref(current_pane));
+                (*p_id, overlap, c.active_at())
+            })
+            .collect();
+        pick_neighbouring_pane(&panes, current_pane_id, candidates)
     }
     pub fn next_selectable_pane_id_to_the_right(&self, current_pane_id: &PaneId) -> Option<PaneId> {
         let panes = self.panes.borrow();
         let current_pane = panes.get(current_pane_id)?;
-        let panes: Vec<(PaneId, &&mut Box<dyn Pane>)> = panes
+        let candidates: Vec<(PaneId, usize, Instant)> = panes
             .iter()
             .filter(|(_, p)| p.selectable())
-            .map(|(p_id, p)| (*p_id, p))
-            .collect();
-        let next_index = panes
-            .iter()
-            .enumerate()
-            .filter(|(_, (_, c))| {
+            .filter(|(_, c)| {
                 c.is_directly_right_of(Box::as_ref(current_pane))
                     && c.horizontally_overlaps_with(Box::as_ref(current_pane))
             })
-            .max_by_key(|(_, (_, c))| c.active_at())
-            .map(|(_, (pid, _))| pid)
-            .copied();
-        next_index
+            .map(|(p_id, c)| {
+                let overlap = c.get_horizontal_overlap_with(Box::as_ref(current_pane));
+                (*p_id, overlap, c.active_at())
+            })
+            .collect();
+        pick_neighbouring_pane(&panes, current_pane_id, candidates)
     }
     fn horizontal_borders(&self, pane_ids: &[PaneId]) -> HashSet<usize> {
         pane_ids.iter().fold(HashSet::new(), |mut borders, p| {
@@ -1655,6 +1666,271 @@ impl<'a> TiledPaneGrid<'a> {
             direction.map(|direction| (*t_id_to_split, direction))
         })
     }
+    /// Gives every pane in the viewport an equal share of the split it is in. Nested splits are
+    /// equalized on their own, so panes only ever share space with their siblings.
+    pub fn equalize(&mut self) {
+        let pane_ids: Vec<PaneId> = self
+            .panes
+            .borrow()
+            .keys()
+            .copied()
+            .filter(|id| self.is_inside_viewport(id))
+            .collect();
+        let mut new_sizes = HashMap::new();
+        self.equalize_split(pane_ids, 100.0, 100.0, &mut new_sizes);
+        {
+            let mut panes = self.panes.borrow_mut();
+            for (pane_id, (cols, rows)) in new_sizes {
+                if let Some(pane) = panes.get_mut(&pane_id) {
+                    let mut geom = pane.current_geom();
+                    if geom.cols.as_percent().is_some() {
+                        geom.cols = Dimension::percent(cols);
+                    }
+                    if geom.rows.as_percent().is_some() {
+                        geom.rows = Dimension::percent(rows);
+                    }
+                    pane.set_geom(geom);
+                }
+            }
+        }
+        let mut pane_resizer = PaneResizer::new(self.panes.clone());
+        if let Err(e) = pane_resizer.layout(SplitDirection::Horizontal, self.display_area.cols) {
+            log::error!("Failed to horizontally equalize panes: {}", e);
+        }
+        if let Err(e) = pane_resizer.layout(SplitDirection::Vertical, self.display_area.rows) {
+            log::error!("Failed to vertically equalize panes: {}", e);
+        }
+    }
+    /// Resizes the pane to the given fraction of the split it is directly in, along that split's
+    /// axis. The other panes in the split share what is left in proportion to their current sizes,
+    /// and the fraction is clamped so that none of them is shrunk below the minimum pane size.
+    pub fn resize_pane_to_fraction(&mut self, pane_id: &PaneId, fraction: f64) {
+        let pane_ids: Vec<PaneId> = self
+            .panes
+            .borrow()
+            .keys()
+            .copied()
+            .filter(|id| self.is_inside_viewport(id))
+            .collect();
+        let (siblings, direction) = match self.split_containing_pane(pane_ids, pane_id) {
+            Some(split) => split,
+            None => {
+                log::info!("Pane {:?} is not in a split, not resizing it", pane_id);
+                return;
+            },
+        };
+        let new_sizes = {
+            let panes = self.panes.borrow();
+            let span = |id: &PaneId| {
+                let pane = panes.get(id).unwrap();
+                match direction {
+                    SplitDirection::Vertical => (pane.x(), pane.x() + pane.cols()),
+                    SplitDirection::Horizontal => (pane.y(), pane.y() + pane.rows()),
+                }
+            };
+            let percent = |id: &PaneId| {
+                let geom = panes.get(id).unwrap().current_geom();
+                match direction {
+                    SplitDirection::Vertical => geom.cols.as_percent(),
+                    SplitDirection::Horizontal => geom.rows.as_percent(),
+                }
+            };
+            let min_size = match direction {
+                SplitDirection::Vertical => MIN_TERMINAL_WIDTH,
+                SplitDirection::Horizontal => MIN_TERMINAL_HEIGHT,
+            } as f64;
+            let split_pane_ids: Vec<PaneId> = siblings.iter().flatten().copied().collect();
+            if split_pane_ids.iter().any(|id| percent(id).is_none()) {
+                log::info!(
+                    "Split of pane {:?} has fixed size panes, not resizing it",
+                    pane_id
+                );
+                return;
+            }
+            let split_size: usize = siblings
+                .iter()
+                .map(|sibling| {
+                    let start = sibling.iter().map(|id| span(id).0).min().unwrap_or(0);
+                    let end = sibling.iter().map(|id| span(id).1).max().unwrap_or(0);
+                    end - start
+                })
+                .sum();
+            let (pane_start, pane_end) = span(pane_id);
+            let pane_size = (pane_end - pane_start) as f64;
+            let split_size = split_size as f64;
+            let others_size = split_size - pane_size;
+            if pane_size == 0.0 || others_size <= 0.0 {
+                return;
+            }
+            // the most the other panes may shrink by without any of them getting too small
+            let min_shrink_factor = split_pane_ids
+                .iter()
+                .filter(|id| *id != pane_id)
+                .map(|id| {
+                    let (start, end) = span(id);
+                    min_size / (end - start).max(1) as f64
+                })
+                .fold(0.0, f64::max);
+            let new_pane_size = (fraction * split_size)
+                .min(split_size - others_size * min_shrink_factor)
+                .max(min_size);
+            // the percentages of the panes are relative to the display, so the split's share of
+            // the display is given out anew rather than the pane's current percentage scaled
+            let split_percent: f64 = siblings
+                .iter()
+                .filter_map(|sibling| sibling.first().and_then(&percent))
+                .sum();
+            let pane_percent = percent(pane_id).unwrap();
+            let new_pane_percent = split_percent * new_pane_size / split_size;
+            let shrink_factor = (split_percent - new_pane_percent) / (split_percent - pane_percent);
+            let mut new_sizes = HashMap::new();
+            for id in split_pane_ids {
+                let new_size = if id == *pane_id {
+                    new_pane_percent
+                } else {
+                    percent(&id).unwrap() * shrink_factor
+                };
+                new_sizes.insert(id, new_size);
+            }
+            new_sizes
+        };
+        {
+            let mut panes = self.panes.borrow_mut();
+            for (pane_id, new_size) in new_sizes {
+                if let Some(pane) = panes.get_mut(&pane_id) {
+                    let mut geom = pane.current_geom();
+                    match direction {
+                        SplitDirection::Vertical => geom.cols = Dimension::percent(new_size),
+                        SplitDirection::Horizontal => geom.rows = Dimension::percent(new_size),
+                    }
+                    pane.set_geom(geom);
+                }
+            }
+        }
+        let mut pane_resizer = PaneResizer::new(self.panes.clone());
+        let (layout_direction, space) = match direction {
+            SplitDirection::Vertical => (SplitDirection::Horizontal, self.display_area.cols),
+            SplitDirection::Horizontal => (SplitDirection::Vertical, self.display_area.rows),
+        };
+        if let Err(e) = pane_resizer.layout(layout_direction, space) {
+            log::error!("Failed to resize pane to fraction: {}", e);
+        }
+    }
+    /// Finds the split the pane is directly in, returning the panes on either side of every line of
+    /// the split and the split's direction.
+    fn split_containing_pane(
+        &self,
+        pane_ids: Vec<PaneId>,
+        pane_id: &PaneId,
+    ) -> Option<(Vec<Vec<PaneId>>, SplitDirection)> {
+        for direction in [SplitDirection::Vertical, SplitDirection::Horizontal] {
+            let siblings = self.split_siblings(&pane_ids, direction);
+            if siblings.len() > 1 {
+                let sibling = siblings.iter().find(|sibling| sibling.contains(pane_id))?;
+                if sibling.len() == 1 {
+                    return Some((siblings, direction));
+                }
+                let sibling = sibling.clone();
+                return self.split_containing_pane(sibling, pane_id);
+            }
+        }
+        None
+    }
+    fn equalize_split(
+        &self,
+        pane_ids: Vec<PaneId>,
+        cols_percent: f64,
+        rows_percent: f64,
+        new_sizes: &mut HashMap<PaneId, (f64, f64)>,
+    ) {
+        if let [pane_id] = pane_ids.as_slice() {
+            new_sizes.insert(*pane_id, (cols_percent, rows_percent));
+            return;
+        }
+        for direction in [SplitDirection::Vertical, SplitDirection::Horizontal] {
+            let siblings = self.split_siblings(&pane_ids, direction);
+            if siblings.len() > 1 {
+                let sibling_count = siblings.len() as f64;
+                for sibling in siblings {
+                    match direction {
+                        SplitDirection::Vertical => self.equalize_split(
+                            sibling,
+                            cols_percent / sibling_count,
+                            rows_percent,
+                            new_sizes,
+                        ),
+                        SplitDirection::Horizontal => self.equalize_split(
+                            sibling,
+                            cols_percent,
+                            rows_percent / sibling_count,
+                            new_sizes,
+                        ),
+                    }
+                }
+                return;
+            }
+        }
+        // these panes can't be divided by a straight line, so they keep their current sizes
+    }
+    /// Splits the given panes into the groups on either side of every line (vertical for a
+    /// vertical split, horizontal for a horizontal one) that does not cross any of them.
+    fn split_siblings(&self, pane_ids: &[PaneId], direction: SplitDirection) -> Vec<Vec<PaneId>> {
+        let panes = self.panes.borrow();
+        let mut spans: Vec<(usize, usize, PaneId)> = pane_ids
+            .iter()
+            .map(|pane_id| {
+                let pane = panes.get(pane_id).unwrap();
+                match direction {
+                    SplitDirection::Vertical => (pane.x(), pane.x() + pane.cols(), *pane_id),
+                    SplitDirection::Horizontal => (pane.y(), pane.y() + pane.rows(), *pane_id),
+                }
+            })
+            .collect();
+        spans.sort_unstable();
+        let mut siblings: Vec<Vec<PaneId>> = vec![];
+        let mut sibling_end = 0;
+        for (start, end, pane_id) in spans {
+            match siblings.last_mut() {
+                Some(sibling) if start < sibling_end => {
+                    sibling.push(pane_id);
+                    sibling_end = sibling_end.max(end);
+                },
+                _ => {
+                    siblings.push(vec![pane_id]);
+                    sibling_end = end;
+                },
+            }
+        }
+        siblings
+    }
+}
+
+/// Picks which of the `candidates` (pane id, overlap with the current pane and when it was last
+/// active) directly next to the current pane should be focused. Going back to the pane that was
+/// focused before the current one takes precedence, so that moving back and forth is predictable,
+/// otherwise the pane sharing the longest edge with the current pane wins.
+fn pick_neighbouring_pane(
+    panes: &HashMap<PaneId, &mut Box<dyn Pane>>,
+    current_pane_id: &PaneId,
+    candidates: Vec<(PaneId, usize, Instant)>,
+) -> Option<PaneId> {
+    let previously_active_pane_id = panes
+        .iter()
+        .filter(|(p_id, p)| *p_id != current_pane_id && p.selectable())
+        .max_by_key(|(_, p)| p.active_at())
+        .map(|(p_id, _)| *p_id);
+    if let Some(previously_active_pane_id) = previously_active_pane_id {
+        if candidates
+            .iter()
+            .any(|(p_id, _, _)| *p_id == previously_active_pane_id)
+        {
+            return Some(previously_active_pane_id);
+        }
+    }
+    candidates
+        .into_iter()
+        .max_by_key(|(_, overlap, active_at)| (*overlap, *active_at))
+        .map(|(p_id, _, _)| p_id)
 }
 
 pub fn split(direction: SplitDirection, rect: &PaneGeom) -> Option<(PaneGeom, PaneGeom)> {
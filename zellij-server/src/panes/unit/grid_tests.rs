@@ -1,6 +1,7 @@
 use super::super::Grid;
 use crate::panes::grid::SixelImageStore;
 use crate::panes::link_handler::LinkHandler;
+use crate::panes::terminal_character::AnsiCode;
 use ::insta::assert_snapshot;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -767,6 +768,197 @@ fn wide_characters_line_wrap() {
     assert_snapshot!(format!("{:?}", grid));
 }
 
+fn row_text(grid: &Grid, row: usize) -> String {
+    let mut text = String::new();
+    for terminal_character in &grid.viewport[row].columns {
+        terminal_character.push_to(&mut text);
+    }
+    text
+}
+
+#[test]
+fn mixed_width_characters_keep_columns_aligned() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    // "e" with a combining acute accent, two CJK characters and an emoji
+    for byte in "ae\u{301}\u{4f60}\u{597d}\u{1f600}b".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "ae\u{301}\u{4f60}\u{597d}\u{1f600}b");
+    assert_eq!(
+        grid.viewport[0].columns[1].combining_marks.to_string(),
+        "\u{301}",
+        "combining mark is drawn on the character before it"
+    );
+    assert_eq!(
+        grid.viewport[0].width(),
+        9,
+        "wide characters take two columns"
+    );
+    assert_eq!(
+        grid.cursor.x, 9,
+        "combining marks do not advance the cursor"
+    );
+}
+
+#[test]
+fn combining_marks_follow_wide_characters_and_are_dropped_at_line_start() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    // a variation selector after a wide character, then a combining mark with nothing before it
+    for byte in "\u{2764}\u{fe0f}\u{4f60}\u{fe0f}\r\n\u{301}a".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "\u{2764}\u{fe0f}\u{4f60}\u{fe0f}");
+    assert_eq!(
+        row_text(&grid, 1),
+        "a",
+        "mark with no character before it is dropped"
+    );
+}
+
+#[test]
+fn variation_selector_16_widens_the_character_before_it() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    // a text-presentation heart turned into an emoji, then a character after it
+    for byte in "a\u{2764}\u{fe0f}b".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "a\u{2764}\u{fe0f}b");
+    assert_eq!(
+        grid.viewport[0].columns[1].width, 2,
+        "emoji presentation takes two columns"
+    );
+    assert_eq!(grid.viewport[0].width(), 4);
+    assert_eq!(grid.cursor.x, 4, "cursor moves past the widened character");
+}
+
+#[test]
+fn variation_selector_16_covers_the_character_after_it_when_overwriting() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    // write over an existing line, the widened heart covers the "c" after it
+    for byte in "abcd\r\u{2764}\u{fe0f}".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "\u{2764}\u{fe0f}cd");
+    assert_eq!(grid.viewport[0].width(), 4, "line keeps its width");
+    assert_eq!(grid.cursor.x, 2);
+}
+
+#[test]
+fn variation_selector_16_does_not_widen_a_character_in_the_last_column() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        5,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for byte in "abcd\u{2764}\u{fe0f}".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "abcd\u{2764}\u{fe0f}");
+    assert_eq!(
+        grid.viewport[0].width(),
+        5,
+        "line does not grow past the pane"
+    );
+}
+
+#[test]
+fn wide_character_straddling_right_edge_wraps_to_next_line() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        5,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for byte in "abcd\u{4f60}".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "abcd");
+    assert_eq!(row_text(&grid, 1), "\u{4f60}");
+}
+
+#[test]
+fn wide_character_straddling_right_edge_without_linewrap_is_replaced_with_space() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        5,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for byte in "\u{1b}[?7labcd\u{4f60}".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(row_text(&grid, 0), "abcd ");
+    assert_eq!(grid.viewport[0].width(), 5);
+    assert_eq!(
+        grid.viewport.len(),
+        1,
+        "nothing was wrapped to the next line"
+    );
+}
+
 #[test]
 fn insert_character_in_line_with_wide_character() {
     let mut vte_parser = vte::Parser::new();
@@ -1167,6 +1359,32 @@ fn copy_selected_text_from_lines_above() {
     );
 }
 
+#[test]
+fn copy_selected_text_from_first_line_above() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    let content = "line1\r\nline2\r\nline3\r\nline4\r\nline5".as_bytes();
+    for byte in content {
+        vte_parser.advance(&mut grid, *byte);
+    }
+
+    // the oldest line in the scrollback is two lines above the viewport
+    grid.start_selection(&Position::new(-2, 0));
+    grid.end_selection(&Position::new(-1, 5));
+    let text = grid.get_selected_text();
+    assert_eq!(text.unwrap(), "line1\nline2");
+}
+
 #[test]
 fn copy_selected_text_from_lines_below() {
     let mut vte_parser = vte::Parser::new();
@@ -2564,3 +2782,379 @@ pub fn cursor_hide_persists_through_alternate_screen() {
         "Cursor still shown away from alternate screen"
     );
 }
+
+#[test]
+pub fn cursor_is_hidden_while_scrolled_back() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        10,
+        50,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    let content = read_fixture("scrolling");
+    for byte in content {
+        vte_parser.advance(&mut grid, byte);
+    }
+    assert!(grid.cursor_coordinates().is_some(), "Cursor shown");
+
+    grid.scroll_up_one_line();
+    assert_eq!(
+        grid.cursor_coordinates(),
+        None,
+        "Cursor hidden while scrolled back"
+    );
+
+    grid.scroll_down_one_line();
+    assert!(
+        grid.cursor_coordinates().is_some(),
+        "Cursor shown again at the bottom of the scrollback"
+    );
+}
+
+#[test]
+pub fn truecolor_underline_color_does_not_swallow_other_styles() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let character_cell_size = Rc::new(RefCell::new(None));
+    let mut grid = Grid::new(
+        30,
+        112,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        character_cell_size,
+        sixel_image_store,
+    );
+    let content = "\u{1b}[58;2;255;0;0;1;38;2;10;20;30mx";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let styles = grid.viewport[0].columns[0].styles;
+    assert_eq!(styles.bold, Some(AnsiCode::On), "Bold applied");
+    assert_eq!(
+        styles.foreground,
+        Some(AnsiCode::RgbCode((10, 20, 30))),
+        "Truecolor foreground applied"
+    );
+}
+
+#[test]
+fn dump_screen_trims_trailing_whitespace() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        10,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    let content = "foo   \r\n     \r\nbar";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.dump_screen(false), "foo\n\nbar");
+}
+
+#[test]
+fn osc_0_1_and_2_set_the_title() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        10,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for (content, expected_title) in [
+        ("\u{1b}]0;zero\u{07}", "zero"),
+        ("\u{1b}]1;one\u{1b}\\", "one"),
+        ("\u{1b}]2;two;three\u{07}", "two;three"),
+    ] {
+        for byte in content.as_bytes() {
+            vte_parser.advance(&mut grid, *byte);
+        }
+        assert_eq!(grid.title.as_deref(), Some(expected_title));
+    }
+}
+
+#[test]
+fn alternate_screen_content_does_not_leak_into_the_primary_screen() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        10,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    let content = "primary\u{1b}[?1049halternate\u{1b}[?1049halternate again\u{1b}[?1049l";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.is_alternate_mode_active());
+    assert_eq!(
+        grid.dump_screen(true),
+        "primary",
+        "entering the alternate screen twice keeps the primary screen"
+    );
+    for (enter, leave) in [
+        ("\u{1b}[?47h", "\u{1b}[?47l"),
+        ("\u{1b}[?1047h", "\u{1b}[?1047l"),
+    ] {
+        for byte in enter.as_bytes() {
+            vte_parser.advance(&mut grid, *byte);
+        }
+        assert!(grid.is_alternate_mode_active());
+        assert_eq!(grid.dump_screen(true), "");
+        for byte in leave.as_bytes() {
+            vte_parser.advance(&mut grid, *byte);
+        }
+        assert!(!grid.is_alternate_mode_active());
+        assert_eq!(grid.dump_screen(true), "primary");
+    }
+}
+
+#[test]
+fn entering_the_alternate_screen_while_scrolled_up_keeps_the_lines_below() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        5,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    let lines: Vec<String> = (1..=20).map(|i| format!("line {}", i)).collect();
+    for byte in lines.join("\r\n").as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    grid.move_viewport_up(10);
+    for byte in "\u{1b}[?1049halternate".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.is_scrolled, "the alternate screen is not scrolled");
+    assert_eq!(
+        grid.dump_screen(true),
+        "alternate",
+        "no primary lines scrolled into the alternate screen"
+    );
+    for byte in "\u{1b}[?1049l".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.dump_screen(true), lines.join("\n"));
+}
+
+#[test]
+fn lines_wider_than_the_grid_can_be_scrolled_to_while_linewrap_is_disabled() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for byte in "\u{1b}[?7l0123456789abcdef\r\nshort".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let visible_lines = |grid: &mut Grid| -> Vec<String> {
+        let (character_chunks, _sixel_chunks) = grid.read_changes(0, 0);
+        character_chunks
+            .iter()
+            .map(|chunk| {
+                chunk
+                    .terminal_characters
+                    .iter()
+                    .map(|character| character.character)
+                    .collect()
+            })
+            .collect()
+    };
+    assert_eq!(
+        visible_lines(&mut grid)[..2],
+        ["0123456789", "short     "],
+        "line cut off at the right edge"
+    );
+    assert!(grid.scroll_right(4));
+    assert_eq!(
+        visible_lines(&mut grid)[..2],
+        ["456789abcd", "t         "],
+        "line scrolled to the right"
+    );
+    assert!(grid.scroll_right(10));
+    assert_eq!(
+        visible_lines(&mut grid)[0],
+        "6789abcdef",
+        "scrolled no further than the end of the widest line"
+    );
+    assert!(
+        !grid.scroll_right(1),
+        "nothing is scrolled once at the end of the widest line"
+    );
+    assert_eq!(grid.horizontal_offset, 6);
+    grid.reset_horizontal_offset();
+    assert_eq!(visible_lines(&mut grid)[0], "0123456789");
+}
+
+#[test]
+fn grid_is_not_scrolled_to_the_right_while_linewrap_is_enabled() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for byte in "0123456789abcdef".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.scroll_right(4), "wrapped lines fit in the grid");
+    assert_eq!(grid.horizontal_offset, 0);
+}
+
+#[test]
+fn lines_rewrap_and_keep_the_cursor_on_its_character_when_shrinking_and_growing() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        5,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    for byte in "first line\r\n0123456789abcdef".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    grid.change_size(5, 10);
+    assert_eq!(grid.viewport.len(), 3, "long line wrapped into two rows");
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((6, 2)),
+        "cursor on the row its character was wrapped to"
+    );
+    grid.change_size(5, 20);
+    assert_eq!(grid.viewport.len(), 2, "long line unwrapped");
+    assert_eq!(grid.cursor_coordinates(), Some((16, 1)));
+    assert_eq!(grid.dump_screen(false), "first line\n0123456789abcdef");
+}
+
+#[test]
+fn lines_written_with_linewrap_toggled_off_are_wrapped_when_it_is_toggled_on() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        5,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    grid.toggle_linewrap();
+    for byte in "0123456789abcdef".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.viewport.len(), 1, "line not wrapped");
+    grid.toggle_linewrap();
+    assert_eq!(grid.viewport.len(), 2, "line wrapped once linewrap is on");
+    assert_eq!(grid.cursor_coordinates(), Some((6, 1)));
+}
+
+#[test]
+fn lines_are_not_rewrapped_when_resizing_with_linewrap_disabled() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        5,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    grid.toggle_linewrap();
+    for byte in "0123456789abcdef".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    grid.change_size(5, 10);
+    assert_eq!(grid.viewport.len(), 1, "line not rewrapped");
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((10, 0)),
+        "cursor at the right edge"
+    );
+    grid.scroll_right(6);
+    assert_eq!(
+        grid.horizontal_offset, 6,
+        "line kept past the right edge can be scrolled to"
+    );
+    grid.change_size(5, 20);
+    assert_eq!(grid.viewport.len(), 1);
+    assert_eq!(grid.horizontal_offset, 0, "nothing left to scroll to");
+    assert_eq!(grid.dump_screen(false), "0123456789abcdef");
+}
+
+#[test]
+fn linewrap_toggled_off_by_the_user_stays_off_when_the_program_enables_it() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut grid = Grid::new(
+        5,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+    );
+    grid.toggle_linewrap();
+    for byte in "\u{1b}[?7h0123456789abcdef".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(grid.linewrap_is_disabled());
+    assert_eq!(grid.viewport.len(), 1, "line not wrapped");
+    grid.toggle_linewrap();
+    assert!(!grid.linewrap_is_disabled());
+    assert_eq!(grid.viewport.len(), 2, "line wrapped once toggled back on");
+}
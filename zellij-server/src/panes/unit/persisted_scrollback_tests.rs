@@ -0,0 +1,113 @@
+use super::*;
+use zellij_utils::tempfile::tempdir;
+
+#[test]
+fn saved_scrollback_is_loaded_back() {
+    let dir = tempdir().unwrap();
+    let scrollbacks = vec![
+        (0, String::from("$ ls\nfoo bar\n$ ")),
+        (3, String::from("ünïcode\n\nlines")),
+    ];
+    save(dir.path(), "my-session", &scrollbacks, 1024).unwrap();
+    let loaded = load(dir.path(), "my-session");
+    assert_eq!(loaded.len(), 2, "scrollback of both terminals loaded");
+    assert_eq!(loaded.get(&0).unwrap(), "$ ls\nfoo bar\n$ ");
+    assert_eq!(loaded.get(&3).unwrap(), "ünïcode\n\nlines");
+}
+
+#[test]
+fn partially_written_scrollback_keeps_the_records_before_it() {
+    let mut bytes = serialize(
+        &[(0, String::from("first")), (1, String::from("second"))],
+        1024,
+    );
+    bytes.truncate(bytes.len() - 3);
+    let loaded = deserialize(&bytes);
+    assert_eq!(loaded.len(), 1, "only the whole record is loaded");
+    assert_eq!(loaded.get(&0).unwrap(), "first");
+    assert!(
+        deserialize(b"not a scrollback file\n0 5\nfirst\n").is_empty(),
+        "a file without the header is ignored"
+    );
+}
+
+#[test]
+fn oldest_lines_are_dropped_to_stay_under_the_max_size() {
+    let scrollback: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+    let bytes = serialize(&[(0, scrollback)], 200);
+    assert!(bytes.len() <= 200, "scrollback kept under the max size");
+    let loaded = deserialize(&bytes);
+    let lines: Vec<&str> = loaded.get(&0).unwrap().lines().collect();
+    assert_eq!(lines.last(), Some(&"line 99"), "newest line kept");
+    assert!(
+        lines.first().unwrap().starts_with("line "),
+        "only whole lines kept"
+    );
+}
+
+#[test]
+fn scrollback_of_other_sessions_is_removed_once_over_the_max_size() {
+    let dir = tempdir().unwrap();
+    let scrollback = vec![(0, "a".repeat(100))];
+    save(dir.path(), "old-session", &scrollback, 1024).unwrap();
+    save(dir.path(), "new-session", &scrollback, 200).unwrap();
+    assert!(
+        load(dir.path(), "old-session").is_empty(),
+        "scrollback of the other session removed"
+    );
+    assert_eq!(
+        load(dir.path(), "new-session").get(&0).unwrap(),
+        &"a".repeat(100),
+        "scrollback of this session kept"
+    );
+}
+
+#[test]
+fn saved_scrollback_can_only_be_read_by_the_user() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempdir().unwrap();
+    save(
+        dir.path(),
+        "my-session",
+        &[(0, String::from("secret"))],
+        1024,
+    )
+    .unwrap();
+    let mode = fs::metadata(dir.path().join("my-session"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600, "only the user can read the file");
+}
+
+#[test]
+fn writer_thread_saves_and_removes_scrollback_in_order() {
+    let dir = tempdir().unwrap();
+    let (writer, handle) = spawn_writer(dir.path().to_path_buf(), 1024);
+    writer
+        .send(ScrollbackWrite::Save(
+            String::from("my-session"),
+            vec![(0, String::from("first"))],
+        ))
+        .unwrap();
+    writer
+        .send(ScrollbackWrite::Save(
+            String::from("other-session"),
+            vec![(0, String::from("second"))],
+        ))
+        .unwrap();
+    writer
+        .send(ScrollbackWrite::Remove(String::from("my-session")))
+        .unwrap();
+    drop(writer);
+    handle.join().unwrap();
+    assert!(
+        load(dir.path(), "my-session").is_empty(),
+        "scrollback removed after it was saved"
+    );
+    assert_eq!(
+        load(dir.path(), "other-session").get(&0).unwrap(),
+        "second",
+        "scrollback of the other session saved"
+    );
+}
@@ -412,3 +412,19 @@ pub fn searching_whole_word_case_insensitive() {
         format!("{:?}", terminal_pane.grid)
     );
 }
+
+#[test]
+pub fn searching_without_matches_leaves_viewport_unchanged() {
+    let mut terminal_pane = create_pane();
+    let viewport_before_search = format!("{:?}", terminal_pane.grid);
+    terminal_pane.update_search_term("no such text in the fixture");
+    assert!(terminal_pane.search_found_nothing());
+    terminal_pane.search_up();
+    terminal_pane.search_down();
+    assert!(terminal_pane.search_found_nothing());
+    assert_eq!(format!("{:?}", terminal_pane.grid), viewport_before_search);
+
+    terminal_pane.update_search_term("\0"); // clears the search term
+    terminal_pane.update_search_term("tortor");
+    assert!(!terminal_pane.search_found_nothing());
+}
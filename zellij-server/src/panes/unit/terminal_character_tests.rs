@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn sixteen_color_indices_are_rendered_with_their_named_sgr_codes() {
+    let styles = CharacterStyles::new()
+        .foreground(Some(AnsiCode::ColorIndex(3)))
+        .background(Some(AnsiCode::ColorIndex(12)));
+    assert_eq!(styles.to_string(), "\u{1b}[33m\u{1b}[104m");
+    let styles = CharacterStyles::new()
+        .foreground(Some(AnsiCode::ColorIndex(9)))
+        .background(Some(AnsiCode::ColorIndex(0)));
+    assert_eq!(styles.to_string(), "\u{1b}[91m\u{1b}[40m");
+}
+
+#[test]
+fn other_color_indices_are_rendered_as_256_colors() {
+    let styles = CharacterStyles::new()
+        .foreground(Some(AnsiCode::ColorIndex(16)))
+        .background(Some(AnsiCode::ColorIndex(238)));
+    assert_eq!(styles.to_string(), "\u{1b}[38;5;16m\u{1b}[48;5;238m");
+}
+
+#[test]
+fn quantized_palette_colors_are_rendered_with_named_sgr_codes() {
+    use zellij_utils::shared::{quantize_color, ColorDepth};
+    let color = quantize_color(PaletteColor::Rgb((0, 255, 0)), ColorDepth::Ansi16);
+    let styles = CharacterStyles::new()
+        .foreground(Some(color.into()))
+        .background(Some(color.into()));
+    let rendered = styles.to_string();
+    assert!(!rendered.contains("38;5;"), "{:?}", rendered);
+    assert!(!rendered.contains("48;5;"), "{:?}", rendered);
+}
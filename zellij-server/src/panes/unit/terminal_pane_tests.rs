@@ -1,13 +1,14 @@
 use super::super::TerminalPane;
 use crate::panes::sixel::SixelImageStore;
 use crate::panes::LinkHandler;
-use crate::tab::Pane;
+use crate::tab::{AdjustedInput, Pane};
 use ::insta::assert_snapshot;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
+    input::command::RunCommand,
     pane_size::{Offset, PaneGeom, SizeInPixels},
     position::Position,
 };
@@ -656,3 +657,146 @@ pub fn frameless_pane_position_is_on_frame() {
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 130)));
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 131)));
 }
+
+#[test]
+pub fn scrolling_up_stops_following_output_until_scrolled_to_bottom() {
+    let fake_client_id = 1;
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(121);
+    fake_win_size.rows.set_inner(20);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+    ); // 0 is the pane index
+    let mut text_to_fill_pane = String::new();
+    for i in 0..30 {
+        writeln!(&mut text_to_fill_pane, "\rline {}", i + 1).unwrap();
+    }
+    terminal_pane.handle_pty_bytes(text_to_fill_pane.into_bytes());
+    assert!(
+        terminal_pane.follows_output(),
+        "panes follow output by default"
+    );
+    terminal_pane.scroll_up(10, fake_client_id);
+    assert!(
+        !terminal_pane.follows_output(),
+        "scrolling up stops following"
+    );
+    terminal_pane.scroll_down(3, fake_client_id);
+    assert!(!terminal_pane.follows_output(), "still scrolled up");
+    terminal_pane.scroll_down(7, fake_client_id);
+    assert!(
+        terminal_pane.follows_output(),
+        "following again at the bottom"
+    );
+}
+
+#[test]
+pub fn restoring_scrollback_position_keeps_lines_in_view() {
+    let fake_client_id = 1;
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(121);
+    fake_win_size.rows.set_inner(20);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+    ); // 0 is the pane index
+    let mut text_to_fill_pane = String::new();
+    for i in 0..30 {
+        writeln!(&mut text_to_fill_pane, "\rline {}", i + 1).unwrap();
+    }
+    terminal_pane.handle_pty_bytes(text_to_fill_pane.into_bytes());
+    terminal_pane.scroll_up(5, fake_client_id);
+    let (position, length) = terminal_pane.scrollback_position_and_length();
+    let lines_from_top = length - position;
+    let scrolled_to = format!("{:?}", terminal_pane.grid);
+
+    terminal_pane.clear_scroll();
+    let mut more_text = String::new();
+    for i in 30..40 {
+        writeln!(&mut more_text, "\rline {}", i + 1).unwrap();
+    }
+    terminal_pane.handle_pty_bytes(more_text.into_bytes());
+    terminal_pane.restore_scrollback_position(lines_from_top);
+
+    assert_eq!(format!("{:?}", terminal_pane.grid), scrolled_to);
+    assert!(!terminal_pane.follows_output());
+}
+
+#[test]
+pub fn suspended_pane_runs_its_command_when_enter_is_pressed() {
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(121);
+    fake_win_size.rows.set_inner(20);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+    ); // 0 is the pane index
+    let run_command = RunCommand {
+        command: "htop".into(),
+        start_suspended: true,
+        ..Default::default()
+    };
+    terminal_pane.hold(None, run_command);
+    assert!(
+        terminal_pane
+            .adjust_input_to_terminal(b"a".to_vec())
+            .is_none(),
+        "other keys are ignored while suspended"
+    );
+    match terminal_pane.adjust_input_to_terminal(b"\r".to_vec()) {
+        Some(AdjustedInput::ReRunCommandInThisPane(run_command)) => {
+            assert_eq!(run_command.command, std::path::PathBuf::from("htop"));
+            assert!(
+                !run_command.start_suspended,
+                "the command is held rather than suspended once it exits"
+            );
+        },
+        _ => panic!("expected the command to be run"),
+    }
+}
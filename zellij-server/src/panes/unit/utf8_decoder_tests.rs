@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn valid_utf8_is_left_as_is() {
+    let mut decoder = Utf8Decoder::default();
+    let bytes = "ascii, ünïcode and 🦀\r\n".as_bytes().to_vec();
+    assert_eq!(decoder.decode(bytes.clone()), bytes);
+}
+
+#[test]
+fn multibyte_characters_split_between_reads_are_decoded_whole() {
+    let mut decoder = Utf8Decoder::default();
+    let bytes = "a🦀b".as_bytes();
+    assert_eq!(decoder.decode(bytes[..3].to_vec()), b"a");
+    assert_eq!(decoder.decode(bytes[3..4].to_vec()), b"");
+    assert_eq!(decoder.decode(bytes[4..].to_vec()), "🦀b".as_bytes());
+}
+
+#[test]
+fn invalid_bytes_are_replaced_without_dropping_the_characters_after_them() {
+    let mut decoder = Utf8Decoder::default();
+    assert_eq!(
+        decoder.decode(b"a\xffb\xe2\x82c\xc3".to_vec()),
+        "a\u{fffd}b\u{fffd}c".as_bytes()
+    );
+    assert_eq!(
+        decoder.decode(b"\x1b[m".to_vec()),
+        "\u{fffd}\u{1b}[m".as_bytes(),
+        "a character cut off at the end of a read and never finished is replaced"
+    );
+}
@@ -0,0 +1,54 @@
+//! Decodes the output of the program in a terminal pane as UTF-8 before it is parsed, so that
+//! invalid bytes (eg. from `cat`ing a binary file) show up as U+FFFD rather than being dropped
+//! along with the characters around them.
+
+use std::mem;
+
+const REPLACEMENT_CHARACTER: &[u8] = "\u{fffd}".as_bytes();
+
+#[derive(Debug, Default)]
+pub(crate) struct Utf8Decoder {
+    // the start of a multibyte character cut off at the end of the last read
+    incomplete_character: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    /// Returns `bytes` as valid UTF-8, with each invalid sequence replaced by U+FFFD. A multibyte
+    /// character cut off at the end of `bytes` is kept until the rest of it is decoded.
+    pub fn decode(&mut self, bytes: Vec<u8>) -> Vec<u8> {
+        if self.incomplete_character.is_empty() && std::str::from_utf8(&bytes).is_ok() {
+            return bytes;
+        }
+        let mut input = mem::take(&mut self.incomplete_character);
+        input.extend(bytes);
+        let mut decoded = Vec::with_capacity(input.len());
+        let mut rest = &input[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    decoded.extend(valid.as_bytes());
+                    break;
+                },
+                Err(e) => {
+                    let (valid, invalid) = rest.split_at(e.valid_up_to());
+                    decoded.extend(valid);
+                    match e.error_len() {
+                        Some(invalid_len) => {
+                            decoded.extend(REPLACEMENT_CHARACTER);
+                            rest = &invalid[invalid_len..];
+                        },
+                        None => {
+                            self.incomplete_character = invalid.to_vec();
+                            break;
+                        },
+                    }
+                },
+            }
+        }
+        decoded
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/utf8_decoder_tests.rs"]
+mod utf8_decoder_tests;
@@ -3,15 +3,23 @@ use crate::terminal_bytes::TerminalBytes;
 use crate::{
     panes::PaneId,
     screen::ScreenInstruction,
+    tab::ClosedPane,
     thread_bus::{Bus, ThreadSenders},
     wasm_vm::PluginInstruction,
     ClientId, ServerInstruction,
 };
 use async_std::task::{self, JoinHandle};
-use std::{collections::HashMap, env, os::unix::io::RawFd, path::PathBuf};
-use zellij_utils::nix::unistd::Pid;
+use std::{
+    collections::HashMap,
+    env,
+    os::unix::io::RawFd,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use zellij_utils::nix::{sys::signal::Signal, unistd::Pid};
 use zellij_utils::{
     async_std,
+    data::Event,
     errors::prelude::*,
     errors::{ContextType, PtyContext},
     input::{
@@ -23,6 +31,14 @@ use zellij_utils::{
 pub type VteBytes = Vec<u8>;
 pub type TabIndex = u32;
 
+// the restarts of a command that count towards its max_command_restarts, unless the config
+// chooses another window
+const DEFAULT_COMMAND_RESTART_WINDOW: Duration = Duration::from_secs(60);
+// how long we wait before restarting a command, doubled for every recent restart
+const COMMAND_RESTART_DELAY: Duration = Duration::from_millis(500);
+const MAX_COMMAND_RESTART_DELAY: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_COMMAND_RESTARTS: usize = 5;
+
 #[derive(Clone, Copy, Debug)]
 pub enum ClientOrTabIndex {
     ClientId(ClientId),
@@ -54,9 +70,12 @@ pub(crate) enum PtyInstruction {
         Option<String>,
         ClientId,
     ), // the String is the tab name
+    NewTabWithLayout(Option<TerminalAction>, PathBuf, ClientId), // PathBuf is a layout path or name
     ClosePane(PaneId),
     CloseTab(Vec<PaneId>),
     ReRunCommandInPane(PaneId, RunCommand),
+    CommandExited(PaneId, Option<i32>, RunCommand), // Option<i32> is the exit status
+    ReopenClosedPane(Option<TerminalAction>, ClosedPane, ClientId),
     Exit,
 }
 
@@ -72,7 +91,10 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::ClosePane(_) => PtyContext::ClosePane,
             PtyInstruction::CloseTab(_) => PtyContext::CloseTab,
             PtyInstruction::NewTab(..) => PtyContext::NewTab,
+            PtyInstruction::NewTabWithLayout(..) => PtyContext::NewTabWithLayout,
             PtyInstruction::ReRunCommandInPane(..) => PtyContext::ReRunCommandInPane,
+            PtyInstruction::CommandExited(..) => PtyContext::CommandExited,
+            PtyInstruction::ReopenClosedPane(..) => PtyContext::ReopenClosedPane,
             PtyInstruction::Exit => PtyContext::Exit,
         }
     }
@@ -85,6 +107,14 @@ pub(crate) struct Pty {
     debug_to_file: bool,
     task_handles: HashMap<u32, JoinHandle<()>>, // terminal_id to join-handle
     default_editor: Option<PathBuf>,
+    /// Whether the default shell's pane stays open showing its exit status once the shell exits.
+    hold_shell_on_exit: bool,
+    max_command_restarts: usize,
+    command_restart_window: Duration,
+    /// terminal_id => when its command was last restarted within the restart window
+    command_restarts: HashMap<u32, Vec<Instant>>,
+    new_tab_layout: Option<PathBuf>,
+    layout_dir: Option<PathBuf>,
 }
 
 pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
@@ -99,14 +129,13 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                 name,
                 client_or_tab_index,
             ) => {
-                let (hold_on_close, run_command, pane_title) = match &terminal_action {
-                    Some(TerminalAction::RunCommand(run_command)) => (
-                        run_command.hold_on_close,
-                        Some(run_command.clone()),
-                        Some(name.unwrap_or_else(|| run_command.to_string())),
-                    ),
-                    _ => (false, None, name),
+                let pane_title = match &terminal_action {
+                    Some(TerminalAction::RunCommand(run_command)) => {
+                        Some(name.unwrap_or_else(|| run_command.to_string()))
+                    },
+                    _ => name,
                 };
+                let command_to_run = pty.command_to_run(&terminal_action);
                 match pty.spawn_terminal(terminal_action, client_or_tab_index) {
                     Ok(pid) => {
                         pty.bus
@@ -119,8 +148,8 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                             ))
                             .with_context(err_context)?;
                     },
-                    Err(SpawnTerminalError::CommandNotFound(pid)) => {
-                        if hold_on_close {
+                    Err(SpawnTerminalError::CommandNotFound(pid)) => match command_to_run {
+                        Some(run_command) => {
                             pty.bus
                                 .senders
                                 .send_to_screen(ScreenInstruction::NewPane(
@@ -130,19 +159,18 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                                     client_or_tab_index,
                                 ))
                                 .with_context(err_context)?;
-                            if let Some(run_command) = run_command {
-                                send_command_not_found_to_screen(
-                                    pty.bus.senders.clone(),
-                                    pid,
-                                    run_command.clone(),
-                                )
-                                .with_context(err_context)?;
-                            }
-                        } else {
+                            send_command_not_found_to_screen(
+                                pty.bus.senders.clone(),
+                                pid,
+                                run_command,
+                            )
+                            .with_context(err_context)?;
+                        },
+                        None => {
                             log::error!("Failed to spawn terminal: command not found");
                             pty.close_pane(PaneId::Terminal(pid))
                                 .with_context(err_context)?;
-                        }
+                        },
                     },
                     Err(e) => {
                         log::error!("Failed to spawn terminal: {}", e);
@@ -169,14 +197,13 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                 }
             },
             PtyInstruction::SpawnTerminalVertically(terminal_action, name, client_id) => {
-                let (hold_on_close, run_command, pane_title) = match &terminal_action {
-                    Some(TerminalAction::RunCommand(run_command)) => (
-                        run_command.hold_on_close,
-                        Some(run_command.clone()),
-                        Some(name.unwrap_or_else(|| run_command.to_string())),
-                    ),
-                    _ => (false, None, name),
+                let pane_title = match &terminal_action {
+                    Some(TerminalAction::RunCommand(run_command)) => {
+                        Some(name.unwrap_or_else(|| run_command.to_string()))
+                    },
+                    _ => name,
                 };
+                let command_to_run = pty.command_to_run(&terminal_action);
                 match pty.spawn_terminal(terminal_action, ClientOrTabIndex::ClientId(client_id)) {
                     Ok(pid) => {
                         pty.bus
@@ -188,8 +215,8 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                             ))
                             .with_context(err_context)?;
                     },
-                    Err(SpawnTerminalError::CommandNotFound(pid)) => {
-                        if hold_on_close {
+                    Err(SpawnTerminalError::CommandNotFound(pid)) => match command_to_run {
+                        Some(run_command) => {
                             pty.bus
                                 .senders
                                 .send_to_screen(ScreenInstruction::VerticalSplit(
@@ -198,30 +225,18 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                                     client_id,
                                 ))
                                 .with_context(err_context)?;
-                            if let Some(run_command) = run_command {
-                                pty.bus
-                                    .senders
-                                    .send_to_screen(ScreenInstruction::PtyBytes(
-                                        pid,
-                                        format!(
-                                            "Command not found: {}",
-                                            run_command.command.display()
-                                        )
-                                        .as_bytes()
-                                        .to_vec(),
-                                    ))
-                                    .with_context(err_context)?;
-                                pty.bus
-                                    .senders
-                                    .send_to_screen(ScreenInstruction::HoldPane(
-                                        PaneId::Terminal(pid),
-                                        Some(2), // exit status
-                                        run_command,
-                                        None,
-                                    ))
-                                    .with_context(err_context)?;
-                            }
-                        }
+                            send_command_not_found_to_screen(
+                                pty.bus.senders.clone(),
+                                pid,
+                                run_command,
+                            )
+                            .with_context(err_context)?;
+                        },
+                        None => {
+                            log::error!("Failed to spawn terminal: command not found");
+                            pty.close_pane(PaneId::Terminal(pid))
+                                .with_context(err_context)?;
+                        },
                     },
                     Err(e) => {
                         log::error!("Failed to spawn terminal: {}", e);
@@ -229,14 +244,13 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                 }
             },
             PtyInstruction::SpawnTerminalHorizontally(terminal_action, name, client_id) => {
-                let (hold_on_close, run_command, pane_title) = match &terminal_action {
-                    Some(TerminalAction::RunCommand(run_command)) => (
-                        run_command.hold_on_close,
-                        Some(run_command.clone()),
-                        Some(name.unwrap_or_else(|| run_command.to_string())),
-                    ),
-                    _ => (false, None, name),
+                let pane_title = match &terminal_action {
+                    Some(TerminalAction::RunCommand(run_command)) => {
+                        Some(name.unwrap_or_else(|| run_command.to_string()))
+                    },
+                    _ => name,
                 };
+                let command_to_run = pty.command_to_run(&terminal_action);
                 match pty.spawn_terminal(terminal_action, ClientOrTabIndex::ClientId(client_id)) {
                     Ok(pid) => {
                         pty.bus
@@ -248,8 +262,8 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                             ))
                             .with_context(err_context)?;
                     },
-                    Err(SpawnTerminalError::CommandNotFound(pid)) => {
-                        if hold_on_close {
+                    Err(SpawnTerminalError::CommandNotFound(pid)) => match command_to_run {
+                        Some(run_command) => {
                             pty.bus
                                 .senders
                                 .send_to_screen(ScreenInstruction::HorizontalSplit(
@@ -258,30 +272,18 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                                     client_id,
                                 ))
                                 .with_context(err_context)?;
-                            if let Some(run_command) = run_command {
-                                pty.bus
-                                    .senders
-                                    .send_to_screen(ScreenInstruction::PtyBytes(
-                                        pid,
-                                        format!(
-                                            "Command not found: {}",
-                                            run_command.command.display()
-                                        )
-                                        .as_bytes()
-                                        .to_vec(),
-                                    ))
-                                    .with_context(err_context)?;
-                                pty.bus
-                                    .senders
-                                    .send_to_screen(ScreenInstruction::HoldPane(
-                                        PaneId::Terminal(pid),
-                                        Some(2), // exit status
-                                        run_command,
-                                        None,
-                                    ))
-                                    .with_context(err_context)?;
-                            }
-                        }
+                            send_command_not_found_to_screen(
+                                pty.bus.senders.clone(),
+                                pid,
+                                run_command,
+                            )
+                            .with_context(err_context)?;
+                        },
+                        None => {
+                            log::error!("Failed to spawn terminal: command not found");
+                            pty.close_pane(PaneId::Terminal(pid))
+                                .with_context(err_context)?;
+                        },
                     },
                     Err(e) => {
                         log::error!("Failed to spawn terminal: {}", e);
@@ -298,27 +300,18 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     .with_context(err_context)?;
             },
             PtyInstruction::NewTab(terminal_action, tab_layout, tab_name, client_id) => {
-                pty.spawn_terminals_for_layout(
-                    tab_layout.unwrap_or_else(|| layout.new_tab()),
-                    terminal_action.clone(),
-                    client_id,
-                )
-                .with_context(err_context)?;
-
-                if let Some(tab_name) = tab_name {
-                    // clear current name at first
-                    pty.bus
-                        .senders
-                        .send_to_screen(ScreenInstruction::UpdateTabName(vec![0], client_id))
-                        .with_context(err_context)?;
-                    pty.bus
-                        .senders
-                        .send_to_screen(ScreenInstruction::UpdateTabName(
-                            tab_name.into_bytes(),
-                            client_id,
-                        ))
-                        .with_context(err_context)?;
-                }
+                let (tab_layout, tab_name) = match tab_layout {
+                    Some(tab_layout) => (tab_layout, tab_name),
+                    None => pty.new_tab_layout(None, &layout, tab_name, client_id),
+                };
+                pty.new_tab(terminal_action, tab_layout, tab_name, client_id)
+                    .with_context(err_context)?;
+            },
+            PtyInstruction::NewTabWithLayout(terminal_action, layout_name, client_id) => {
+                let (tab_layout, tab_name) =
+                    pty.new_tab_layout(Some(&layout_name), &layout, None, client_id);
+                pty.new_tab(terminal_action, tab_layout, tab_name, client_id)
+                    .with_context(err_context)?;
             },
             PtyInstruction::ClosePane(id) => {
                 pty.close_pane(id).with_context(err_context)?;
@@ -338,29 +331,56 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                 match pty.rerun_command_in_pane(pane_id, run_command.clone()) {
                     Ok(..) => {},
                     Err(SpawnTerminalError::CommandNotFound(pid)) => {
-                        if run_command.hold_on_close {
-                            pty.bus
-                                .senders
-                                .send_to_screen(ScreenInstruction::PtyBytes(
-                                    pid,
-                                    format!("Command not found: {}", run_command.command.display())
-                                        .as_bytes()
-                                        .to_vec(),
-                                ))
-                                .with_context(err_context)?;
+                        send_command_not_found_to_screen(pty.bus.senders.clone(), pid, run_command)
+                            .with_context(err_context)?;
+                    },
+                    Err(e) => {
+                        log::error!("Failed to spawn terminal: {}", e);
+                    },
+                }
+            },
+            PtyInstruction::CommandExited(pane_id, exit_status, run_command) => {
+                pty.command_exited(pane_id, exit_status, run_command, Instant::now())
+                    .with_context(err_context)?;
+            },
+            PtyInstruction::ReopenClosedPane(terminal_action, closed_pane, client_id) => {
+                let command_to_run = pty.command_to_run(&terminal_action);
+                match pty.spawn_terminal(terminal_action, ClientOrTabIndex::ClientId(client_id)) {
+                    Ok(pid) => {
+                        pty.bus
+                            .senders
+                            .send_to_screen(ScreenInstruction::ReopenClosedPane(
+                                PaneId::Terminal(pid),
+                                closed_pane,
+                                client_id,
+                            ))
+                            .with_context(err_context)?;
+                    },
+                    Err(SpawnTerminalError::CommandNotFound(pid)) => match command_to_run {
+                        Some(run_command) => {
                             pty.bus
                                 .senders
-                                .send_to_screen(ScreenInstruction::HoldPane(
+                                .send_to_screen(ScreenInstruction::ReopenClosedPane(
                                     PaneId::Terminal(pid),
-                                    Some(2), // exit status
-                                    run_command,
-                                    None,
+                                    closed_pane,
+                                    client_id,
                                 ))
                                 .with_context(err_context)?;
-                        }
+                            send_command_not_found_to_screen(
+                                pty.bus.senders.clone(),
+                                pid,
+                                run_command,
+                            )
+                            .with_context(err_context)?;
+                        },
+                        None => {
+                            log::error!("Failed to reopen closed pane: command not found");
+                            pty.close_pane(PaneId::Terminal(pid))
+                                .with_context(err_context)?;
+                        },
                     },
                     Err(e) => {
-                        log::error!("Failed to spawn terminal: {}", e);
+                        log::error!("Failed to reopen closed pane: {}", e);
                     },
                 }
             },
@@ -375,6 +395,11 @@ impl Pty {
         bus: Bus<PtyInstruction>,
         debug_to_file: bool,
         default_editor: Option<PathBuf>,
+        hold_shell_on_exit: bool,
+        max_command_restarts: Option<usize>,
+        command_restart_window: Option<usize>,
+        new_tab_layout: Option<PathBuf>,
+        layout_dir: Option<PathBuf>,
     ) -> Self {
         Pty {
             active_panes: HashMap::new(),
@@ -383,16 +408,37 @@ impl Pty {
             debug_to_file,
             task_handles: HashMap::new(),
             default_editor,
+            hold_shell_on_exit,
+            max_command_restarts: max_command_restarts.unwrap_or(DEFAULT_MAX_COMMAND_RESTARTS),
+            command_restart_window: command_restart_window
+                .map(|seconds| Duration::from_secs(seconds as u64))
+                .unwrap_or(DEFAULT_COMMAND_RESTART_WINDOW),
+            command_restarts: HashMap::new(),
+            new_tab_layout,
+            layout_dir,
         }
     }
     pub fn get_default_terminal(&self, cwd: Option<PathBuf>) -> TerminalAction {
         TerminalAction::RunCommand(RunCommand {
             args: vec![],
-            command: PathBuf::from(env::var("SHELL").expect("Could not find the SHELL variable")),
+            command: shell_from_env(),
             cwd, // note: this might also be filled by the calling function, eg. spawn_terminal
-            hold_on_close: false,
+            hold_on_close: self.hold_shell_on_exit,
+            restart_on_exit: false,
+            start_suspended: false,
         })
     }
+    /// The command a new pane runs for `terminal_action`, the default shell if it has none (`None`
+    /// if the pane opens a file instead)
+    fn command_to_run(&self, terminal_action: &Option<TerminalAction>) -> Option<RunCommand> {
+        match terminal_action
+            .clone()
+            .unwrap_or_else(|| self.get_default_terminal(None))
+        {
+            TerminalAction::RunCommand(run_command) => Some(run_command),
+            TerminalAction::OpenFile(..) => None,
+        }
+    }
     fn fill_cwd(&self, terminal_action: &mut TerminalAction, client_id: ClientId) {
         if let TerminalAction::RunCommand(run_command) = terminal_action {
             if run_command.cwd.is_none() {
@@ -479,6 +525,65 @@ impl Pty {
         self.id_to_child_pid.insert(terminal_id, child_fd);
         Ok(terminal_id)
     }
+    fn new_tab(
+        &mut self,
+        terminal_action: Option<TerminalAction>,
+        tab_layout: PaneLayout,
+        tab_name: Option<String>,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to open new tab for client {client_id}");
+        self.spawn_terminals_for_layout(tab_layout, terminal_action, client_id)
+            .with_context(err_context)?;
+
+        if let Some(tab_name) = tab_name {
+            // clear current name at first
+            self.bus
+                .senders
+                .send_to_screen(ScreenInstruction::UpdateTabName(vec![0], client_id))
+                .with_context(err_context)?;
+            self.bus
+                .senders
+                .send_to_screen(ScreenInstruction::UpdateTabName(
+                    tab_name.into_bytes(),
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
+    /// The layout of a new tab (and its name, if the layout gives it one): the given layout, or
+    /// the configured `new_tab_layout` if none is given, or the default tab of the session's
+    /// layout if neither is there. A layout that cannot be loaded opens a single pane instead.
+    fn new_tab_layout(
+        &self,
+        layout_name: Option<&PathBuf>,
+        session_layout: &Layout,
+        tab_name: Option<String>,
+        client_id: ClientId,
+    ) -> (PaneLayout, Option<String>) {
+        let layout_name = match layout_name.or(self.new_tab_layout.as_ref()) {
+            Some(layout_name) => layout_name,
+            None => return (session_layout.new_tab(), tab_name),
+        };
+        match load_tab_layout(layout_name, self.layout_dir.clone()) {
+            Ok((layout_tab_name, tab_layout)) => (tab_layout, tab_name.or(layout_tab_name)),
+            Err(e) => {
+                let message = format!(
+                    "Failed to load layout {}, opening a single pane instead: {}",
+                    layout_name.display(),
+                    e
+                );
+                log::warn!("{}", message);
+                let _ = self.bus.senders.send_to_plugin(PluginInstruction::Update(
+                    None,
+                    Some(client_id),
+                    Event::StatusMessage(message),
+                ));
+                (PaneLayout::default(), tab_name)
+            },
+        }
+    }
     pub fn spawn_terminals_for_layout(
         &mut self,
         layout: PaneLayout,
@@ -489,10 +594,8 @@ impl Pty {
         let mut default_shell = default_shell.unwrap_or_else(|| self.get_default_terminal(None));
         self.fill_cwd(&mut default_shell, client_id);
         let extracted_run_instructions = layout.extract_run_instructions();
-        let mut new_pane_pids: Vec<(u32, Option<RunCommand>, Result<RawFd, SpawnTerminalError>)> =
-            vec![]; // (terminal_id,
-                    // run_command
-                    // file_descriptor)
+        // (terminal_id, run_command, file_descriptor - None if nothing was started in the terminal)
+        let mut new_pane_pids: Vec<(u32, Option<RunCommand>, Option<RawFd>)> = vec![];
         for run_instruction in extracted_run_instructions {
             let quit_cb = Box::new({
                 let senders = self.bus.senders.clone();
@@ -502,11 +605,36 @@ impl Pty {
             });
             match run_instruction {
                 Some(Run::Command(command)) => {
+                    if command.start_suspended {
+                        // the command is run in this terminal once the user presses Enter
+                        match self
+                            .bus
+                            .os_input
+                            .as_ref()
+                            .with_context(err_context)?
+                            .reserve_terminal_id()
+                        {
+                            Some(terminal_id) => {
+                                new_pane_pids.push((terminal_id, Some(command.clone()), None));
+                            },
+                            None => log::error!(
+                                "Failed to spawn terminal: {}",
+                                SpawnTerminalError::NoMoreTerminalIds
+                            ),
+                        }
+                        continue;
+                    }
                     let hold_on_close = command.hold_on_close;
                     let quit_cb = Box::new({
                         let senders = self.bus.senders.clone();
-                        move |pane_id, exit_status, command| {
-                            if hold_on_close {
+                        move |pane_id, exit_status, command: RunCommand| {
+                            if command.restart_on_exit {
+                                let _ = senders.send_to_pty(PtyInstruction::CommandExited(
+                                    pane_id,
+                                    exit_status,
+                                    command,
+                                ));
+                            } else if hold_on_close {
                                 let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
                                     pane_id,
                                     exit_status,
@@ -532,15 +660,11 @@ impl Pty {
                             new_pane_pids.push((
                                 terminal_id,
                                 Some(command.clone()),
-                                Ok(pid_primary),
+                                Some(pid_primary),
                             ));
                         },
                         Err(SpawnTerminalError::CommandNotFound(terminal_id)) => {
-                            new_pane_pids.push((
-                                terminal_id,
-                                Some(command.clone()),
-                                Err(SpawnTerminalError::CommandNotFound(terminal_id)),
-                            ));
+                            new_pane_pids.push((terminal_id, Some(command.clone()), None));
                         },
                         Err(e) => {
                             log::error!("Failed to spawn terminal: {}", e);
@@ -554,18 +678,14 @@ impl Pty {
                         .os_input
                         .as_mut()
                         .with_context(err_context)?
-                        .spawn_terminal(shell, quit_cb, self.default_editor.clone())
+                        .spawn_terminal(shell.clone(), quit_cb, self.default_editor.clone())
                     {
                         Ok((terminal_id, pid_primary, child_fd)) => {
                             self.id_to_child_pid.insert(terminal_id, child_fd);
-                            new_pane_pids.push((terminal_id, None, Ok(pid_primary)));
+                            new_pane_pids.push((terminal_id, None, Some(pid_primary)));
                         },
                         Err(SpawnTerminalError::CommandNotFound(terminal_id)) => {
-                            new_pane_pids.push((
-                                terminal_id,
-                                None,
-                                Err(SpawnTerminalError::CommandNotFound(terminal_id)),
-                            ));
+                            new_pane_pids.push((terminal_id, held_shell_command(&shell), None));
                         },
                         Err(e) => {
                             log::error!("Failed to spawn terminal: {}", e);
@@ -585,14 +705,10 @@ impl Pty {
                         ) {
                         Ok((terminal_id, pid_primary, child_fd)) => {
                             self.id_to_child_pid.insert(terminal_id, child_fd);
-                            new_pane_pids.push((terminal_id, None, Ok(pid_primary)));
+                            new_pane_pids.push((terminal_id, None, Some(pid_primary)));
                         },
                         Err(SpawnTerminalError::CommandNotFound(terminal_id)) => {
-                            new_pane_pids.push((
-                                terminal_id,
-                                None,
-                                Err(SpawnTerminalError::CommandNotFound(terminal_id)),
-                            ));
+                            new_pane_pids.push((terminal_id, None, None));
                         },
                         Err(e) => {
                             log::error!("Failed to spawn terminal: {}", e);
@@ -609,13 +725,13 @@ impl Pty {
                     {
                         Ok((terminal_id, pid_primary, child_fd)) => {
                             self.id_to_child_pid.insert(terminal_id, child_fd);
-                            new_pane_pids.push((terminal_id, None, Ok(pid_primary)));
+                            new_pane_pids.push((terminal_id, None, Some(pid_primary)));
                         },
                         Err(SpawnTerminalError::CommandNotFound(terminal_id)) => {
                             new_pane_pids.push((
                                 terminal_id,
+                                held_shell_command(&default_shell),
                                 None,
-                                Err(SpawnTerminalError::CommandNotFound(terminal_id)),
                             ));
                         },
                         Err(e) => {
@@ -641,7 +757,7 @@ impl Pty {
             .with_context(err_context)?;
         for (terminal_id, run_command, pid_primary) in new_pane_pids {
             match pid_primary {
-                Ok(pid_primary) => {
+                Some(pid_primary) => {
                     let terminal_bytes = task::spawn({
                         let senders = self.bus.senders.clone();
                         let os_input = self
@@ -667,7 +783,15 @@ impl Pty {
                     });
                     self.task_handles.insert(terminal_id, terminal_bytes);
                 },
-                _ => match run_command {
+                None => match run_command {
+                    Some(run_command) if run_command.start_suspended => {
+                        send_command_suspended_to_screen(
+                            self.bus.senders.clone(),
+                            terminal_id,
+                            run_command,
+                        )
+                        .with_context(err_context)?;
+                    },
                     Some(run_command) => {
                         if run_command.hold_on_close {
                             send_command_not_found_to_screen(
@@ -695,7 +819,14 @@ impl Pty {
         match id {
             PaneId::Terminal(id) => {
                 self.task_handles.remove(&id);
+                self.command_restarts.remove(&id);
                 if let Some(child_fd) = self.id_to_child_pid.remove(&id) {
+                    let os_input = self.bus.os_input.as_ref().with_context(err_context)?;
+                    if os_input.foreground_process_is_running(id) {
+                        // give whatever the pane's shell is running a chance to clean up too,
+                        // it would only be hung up on once the terminal itself goes away
+                        let _ = os_input.signal_foreground_process_group(id, Signal::SIGHUP);
+                    }
                     task::block_on(async {
                         let err_context = || format!("failed to run async task for pane {id}");
                         self.bus
@@ -722,6 +853,17 @@ impl Pty {
         }
         Ok(())
     }
+    /// Hangs up on the process group of every pane, so that the jobs their shells run go away
+    /// along with the session rather than being left behind
+    pub fn hang_up_panes(&self) {
+        if let Some(os_input) = self.bus.os_input.as_ref() {
+            for (id, child_pid) in &self.id_to_child_pid {
+                if let Err(e) = os_input.hang_up_process_group(Pid::from_raw(*child_pid)) {
+                    log::warn!("Failed to hang up on the processes of pane {}: {}", id, e);
+                }
+            }
+        }
+    }
     pub fn close_tab(&mut self, ids: Vec<PaneId>) -> Result<()> {
         for id in ids {
             self.close_pane(id)
@@ -734,6 +876,88 @@ impl Pty {
             self.active_panes.insert(client_id, pane_id);
         }
     }
+    /// Restarts the command of a pane with `restart_on_exit` after a delay that grows with each of
+    /// its recent restarts, or holds the pane once the command restarted too often
+    pub fn command_exited(
+        &mut self,
+        pane_id: PaneId,
+        exit_status: Option<i32>,
+        run_command: RunCommand,
+        now: Instant,
+    ) -> Result<()> {
+        let err_context = || format!("failed to handle exited command in pane {pane_id:?}");
+        let terminal_id = match pane_id {
+            PaneId::Terminal(terminal_id) => terminal_id,
+            PaneId::Plugin(_) => return Ok(()),
+        };
+        let exit_status_description = exit_status
+            .map(|exit_status| exit_status.to_string())
+            .unwrap_or_else(|| String::from("unknown"));
+        let command_restart_window = self.command_restart_window;
+        let restarts = self
+            .command_restarts
+            .entry(terminal_id)
+            .or_insert_with(Vec::new);
+        restarts.retain(|restarted_at| now.duration_since(*restarted_at) < command_restart_window);
+        if restarts.len() >= self.max_command_restarts {
+            log::warn!(
+                "Command {} in pane {:?} restarted {} times within {:?}, not restarting it again",
+                run_command,
+                pane_id,
+                restarts.len(),
+                command_restart_window
+            );
+            self.command_restarts.remove(&terminal_id);
+            let failure_line = format!(
+                "\r\n\u{1b}[31mCommand exited with status {} too often, not restarting it\u{1b}[m\r\n",
+                exit_status_description
+            );
+            self.bus
+                .senders
+                .send_to_screen(ScreenInstruction::PtyBytes(
+                    terminal_id,
+                    failure_line.into_bytes(),
+                ))
+                .with_context(err_context)?;
+            self.bus
+                .senders
+                .send_to_screen(ScreenInstruction::HoldPane(
+                    pane_id,
+                    exit_status,
+                    run_command,
+                    None,
+                ))
+                .with_context(err_context)?;
+            return Ok(());
+        }
+        let delay = std::cmp::min(
+            COMMAND_RESTART_DELAY * 2u32.saturating_pow(restarts.len() as u32),
+            MAX_COMMAND_RESTART_DELAY,
+        );
+        restarts.push(now);
+        let restart_line = format!(
+            "\r\n\u{1b}[33mCommand exited with status {}, restarting it in {:.1}s\u{1b}[m\r\n",
+            exit_status_description,
+            delay.as_secs_f32()
+        );
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::PtyBytes(
+                terminal_id,
+                restart_line.into_bytes(),
+            ))
+            .with_context(err_context)?;
+        let senders = self.bus.senders.clone();
+        task::spawn(async move {
+            task::sleep(delay).await;
+            // the screen knows whether the pane is still around to be restarted
+            let _ = senders.send_to_screen(ScreenInstruction::RestartCommandInPane(
+                pane_id,
+                run_command,
+            ));
+        });
+        Ok(())
+    }
     pub fn rerun_command_in_pane(
         &mut self,
         pane_id: PaneId,
@@ -746,14 +970,22 @@ impl Pty {
 
                 let quit_cb = Box::new({
                     let senders = self.bus.senders.clone();
-                    move |pane_id, exit_status, command| {
-                        // we only re-run held panes, so we'll never close them from Pty
-                        let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
-                            pane_id,
-                            exit_status,
-                            command,
-                            None,
-                        ));
+                    move |pane_id, exit_status, command: RunCommand| {
+                        if command.restart_on_exit {
+                            let _ = senders.send_to_pty(PtyInstruction::CommandExited(
+                                pane_id,
+                                exit_status,
+                                command,
+                            ));
+                        } else {
+                            // we only re-run held panes, so we'll never close them from Pty
+                            let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
+                                pane_id,
+                                exit_status,
+                                command,
+                                None,
+                            ));
+                        }
                     }
                 });
                 let (pid_primary, child_fd): (RawFd, RawFd) = self
@@ -796,6 +1028,7 @@ impl Pty {
 
 impl Drop for Pty {
     fn drop(&mut self) {
+        self.hang_up_panes();
         let child_ids: Vec<u32> = self.id_to_child_pid.keys().copied().collect();
         for id in child_ids {
             self.close_pane(PaneId::Terminal(id))
@@ -805,6 +1038,80 @@ impl Drop for Pty {
     }
 }
 
+/// Loads the layout of a single tab (and its name, if it has one) by path or by name from the
+/// layout dir
+fn load_tab_layout(
+    layout_name: &PathBuf,
+    layout_dir: Option<PathBuf>,
+) -> Result<(Option<String>, PaneLayout)> {
+    // ConfigError is not Send, so it is only kept as its message
+    let (path_to_raw_layout, raw_layout) =
+        Layout::stringified_from_path_or_default(Some(layout_name), layout_dir)
+            .map_err(|e| anyhow!("{}", e))?;
+    let layout =
+        Layout::from_str(&raw_layout, path_to_raw_layout, None).map_err(|e| anyhow!("{}", e))?;
+    let mut tabs = layout.tabs();
+    if tabs.len() > 1 {
+        bail!("a tab layout cannot itself have tabs");
+    }
+    Ok(tabs
+        .drain(..)
+        .next()
+        .unwrap_or_else(|| (None, layout.new_tab())))
+}
+
+/// The shell to start when the config doesn't choose one: `$SHELL`, or `/bin/sh` if that isn't set
+pub(crate) fn shell_from_env() -> PathBuf {
+    env::var("SHELL").map(PathBuf::from).unwrap_or_else(|_| {
+        log::warn!("Could not find the SHELL variable, falling back to /bin/sh");
+        PathBuf::from("/bin/sh")
+    })
+}
+
+/// Splits the arguments of the default shell the way a shell would, so quoted arguments and
+/// backslash escapes (eg. `-c "echo hi"`) are kept whole
+pub(crate) fn split_shell_args(args: &str) -> Vec<String> {
+    let mut split_args = vec![];
+    let mut current_arg: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = args.chars();
+    while let Some(character) = chars.next() {
+        match (quote, character) {
+            (Some(quote_character), _) if character == quote_character => quote = None,
+            (None, '\\') | (Some('"'), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current_arg.get_or_insert_with(String::new).push(escaped);
+                }
+            },
+            (Some(_), _) => current_arg.get_or_insert_with(String::new).push(character),
+            (None, '"') | (None, '\'') => {
+                quote = Some(character);
+                current_arg.get_or_insert_with(String::new);
+            },
+            (None, _) if character.is_whitespace() => {
+                if let Some(arg) = current_arg.take() {
+                    split_args.push(arg);
+                }
+            },
+            (None, _) => current_arg.get_or_insert_with(String::new).push(character),
+        }
+    }
+    split_args.extend(current_arg);
+    split_args
+}
+
+/// The default shell as a command to hold its pane open with, so the pane can say why the shell
+/// didn't start
+fn held_shell_command(shell: &TerminalAction) -> Option<RunCommand> {
+    match shell {
+        TerminalAction::RunCommand(run_command) => Some(RunCommand {
+            hold_on_close: true,
+            ..run_command.clone()
+        }),
+        TerminalAction::OpenFile(..) => None,
+    }
+}
+
 fn send_command_not_found_to_screen(
     senders: ThreadSenders,
     terminal_id: u32,
@@ -829,3 +1136,32 @@ fn send_command_not_found_to_screen(
         .with_context(err_context)?;
     Ok(())
 }
+
+fn send_command_suspended_to_screen(
+    senders: ThreadSenders,
+    terminal_id: u32,
+    run_command: RunCommand,
+) -> Result<()> {
+    let err_context = || format!("failed to send suspended command for terminal {terminal_id}");
+    senders
+        .send_to_screen(ScreenInstruction::PtyBytes(
+            terminal_id,
+            format!("[press Enter to run: {}]", run_command)
+                .as_bytes()
+                .to_vec(),
+        ))
+        .with_context(err_context)?;
+    senders
+        .send_to_screen(ScreenInstruction::HoldPane(
+            PaneId::Terminal(terminal_id),
+            None,
+            run_command,
+            None,
+        ))
+        .with_context(err_context)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./unit/pty_tests.rs"]
+mod pty_tests;
@@ -1,6 +1,17 @@
 use zellij_utils::errors::{prelude::*, ContextType, PtyWriteContext};
 
+use crate::os_input_output::ServerOsApi;
 use crate::thread_bus::Bus;
+use crate::wasm_vm::PluginInstruction;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+use zellij_utils::{data::Event, nix};
+
+// how long we wait for new instructions before trying to write the queued bytes again
+const WRITE_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+// a queue this long means the program running in the pane does not keep up with its input
+const QUEUED_BYTES_WARNING_THRESHOLD: usize = 1024 * 1024;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum PtyWriteInstruction {
@@ -17,31 +28,109 @@ impl From<&PtyWriteInstruction> for PtyWriteContext {
     }
 }
 
+/// The bytes still to be written to the pty of each terminal, in the order they were sent to us
+#[derive(Default)]
+struct WriteQueues {
+    queues: BTreeMap<u32, VecDeque<u8>>,
+    // terminals we warned about since their queue was last emptied
+    warned_about: Vec<u32>,
+}
+
+impl WriteQueues {
+    fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+    /// Queues `bytes`, returning a warning to show the user if this made the queue of the terminal
+    /// grow too long
+    fn push(&mut self, terminal_id: u32, bytes: Vec<u8>) -> Option<String> {
+        let queue = self.queues.entry(terminal_id).or_insert_with(VecDeque::new);
+        queue.extend(bytes);
+        if queue.len() > QUEUED_BYTES_WARNING_THRESHOLD && !self.warned_about.contains(&terminal_id)
+        {
+            let warning = format!(
+                "Pane {} is not reading its input, {} bytes are waiting to be written to it",
+                terminal_id,
+                queue.len()
+            );
+            log::warn!("{}", warning);
+            self.warned_about.push(terminal_id);
+            return Some(warning);
+        }
+        None
+    }
+    /// Writes as much of each queue as its pty takes without blocking, the ptys being
+    /// non-blocking
+    fn write_to_ptys(&mut self, os_input: &dyn ServerOsApi) {
+        for (terminal_id, queue) in self.queues.iter_mut() {
+            while !queue.is_empty() {
+                let bytes = queue.make_contiguous();
+                match os_input.write_to_tty_stdin(*terminal_id, bytes) {
+                    Ok(0) => {
+                        // the terminal is gone, there is no one left to read the queued bytes
+                        queue.clear();
+                    },
+                    Ok(written) => {
+                        // a partial write leaves the rest of the bytes at the front of the queue
+                        let pty_is_full = written < bytes.len();
+                        queue.drain(..written);
+                        if pty_is_full {
+                            break;
+                        }
+                    },
+                    // the program running in the pane did not read what is already there
+                    Err(nix::Error::EAGAIN) => break,
+                    Err(e) => {
+                        log::error!("Failed to write to terminal {}: {}", terminal_id, e);
+                        queue.clear();
+                    },
+                }
+            }
+        }
+        self.queues.retain(|_terminal_id, queue| !queue.is_empty());
+        let queues = &self.queues;
+        self.warned_about
+            .retain(|terminal_id| queues.contains_key(terminal_id));
+    }
+}
+
 pub(crate) fn pty_writer_main(bus: Bus<PtyWriteInstruction>) -> Result<()> {
     let err_context = || "failed to write to pty".to_string();
 
+    let mut write_queues = WriteQueues::default();
     loop {
-        let (event, mut err_ctx) = bus.recv().with_context(err_context)?;
-        err_ctx.add_call(ContextType::PtyWrite((&event).into()));
+        // block until there is something to write, otherwise only wait until we retry writing
+        let received = if write_queues.is_empty() {
+            Some(bus.recv().with_context(err_context)?)
+        } else {
+            bus.recv_timeout(WRITE_RETRY_INTERVAL)
+                .with_context(err_context)?
+        };
+        if let Some((event, mut err_ctx)) = received {
+            err_ctx.add_call(ContextType::PtyWrite((&event).into()));
+            match event {
+                PtyWriteInstruction::Write(bytes, terminal_id) => {
+                    if let Some(warning) = write_queues.push(terminal_id, bytes) {
+                        let _ = bus.senders.send_to_plugin(PluginInstruction::Update(
+                            None,
+                            None,
+                            Event::StatusMessage(warning),
+                        ));
+                    }
+                },
+                PtyWriteInstruction::Exit => {
+                    return Ok(());
+                },
+            }
+        }
         let os_input = bus
             .os_input
-            .clone()
+            .as_ref()
             .context("no OS input API found")
             .with_context(err_context)?;
-        match event {
-            PtyWriteInstruction::Write(bytes, terminal_id) => {
-                os_input
-                    .write_to_tty_stdin(terminal_id, &bytes)
-                    .with_context(err_context)
-                    .non_fatal();
-                os_input
-                    .tcdrain(terminal_id)
-                    .with_context(err_context)
-                    .non_fatal();
-            },
-            PtyWriteInstruction::Exit => {
-                return Ok(());
-            },
-        }
+        write_queues.write_to_ptys(os_input.as_ref());
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/pty_writer_tests.rs"]
+mod pty_writer_tests;
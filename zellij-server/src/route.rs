@@ -1,7 +1,9 @@
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
 use crate::{
     os_input_output::ServerOsApi,
+    panes::PaneId,
     pty::{ClientOrTabIndex, PtyInstruction},
     screen::ScreenInstruction,
     wasm_vm::PluginInstruction,
@@ -9,14 +11,17 @@ use crate::{
 };
 use zellij_utils::{
     channels::SenderWithContext,
+    consts::BUILD_VERSION,
     data::Event,
     errors::prelude::*,
     input::{
         actions::{Action, Direction, ResizeDirection, SearchDirection, SearchOption},
         command::TerminalAction,
         get_mode_info,
+        options::OnForceClose,
     },
     ipc::{ClientToServerMsg, ExitReason, IpcReceiverWithContext, ServerToClientMsg},
+    nix::sys::signal::Signal,
 };
 
 use crate::ClientId;
@@ -35,7 +40,7 @@ pub(crate) fn route_action(
     // this is a bit of a hack around the unfortunate architecture we use with plugins
     // this will change as soon as we refactor
     match action {
-        Action::MouseHoldLeft(..) | Action::MouseHoldRight(..) => {},
+        Action::MouseHoldLeft(..) | Action::MouseHoldRight(..) | Action::MouseMove(..) => {},
         _ => {
             session
                 .senders
@@ -116,6 +121,30 @@ pub(crate) fn route_action(
                 .send_to_screen(screen_instr)
                 .with_context(err_context)?;
         },
+        Action::EqualizePanes => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::EqualizePanes(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ApplyAutoLayout => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ApplyAutoLayout(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ResizeFine(direction) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ResizeFine(direction, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ResizeToFraction(fraction) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ResizeToFraction(fraction, client_id))
+                .with_context(err_context)?;
+        },
         Action::SwitchFocus => {
             session
                 .senders
@@ -177,6 +206,23 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::DumpScreen(val, client_id, full))
                 .with_context(err_context)?;
         },
+        Action::PipePane(command, full) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::PipePane(command, client_id, full))
+                .with_context(err_context)?;
+        },
+        Action::SetClientSizePolicy(policy) => {
+            to_server
+                .send(ServerInstruction::SetClientSizePolicy(policy))
+                .with_context(err_context)?;
+        },
+        Action::DumpLayout(path) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::DumpLayout(path, client_id))
+                .with_context(err_context)?;
+        },
         Action::EditScrollback => {
             session
                 .senders
@@ -213,6 +259,12 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::ScrollToBottom(client_id))
                 .with_context(err_context)?;
         },
+        Action::ToggleFollowOutput => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ToggleFollowOutput(client_id))
+                .with_context(err_context)?;
+        },
         Action::PageScrollUp => {
             session
                 .senders
@@ -237,6 +289,24 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::HalfPageScrollDown(client_id))
                 .with_context(err_context)?;
         },
+        Action::ScrollLeft => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ScrollLeft(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ScrollRight => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ScrollRight(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ToggleLineWrap => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ToggleLineWrap(client_id))
+                .with_context(err_context)?;
+        },
         Action::ToggleFocusFullscreen => {
             session
                 .senders
@@ -381,6 +451,12 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::TogglePaneEmbedOrFloating(client_id))
                 .with_context(err_context)?;
         },
+        Action::TogglePanePinned => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::TogglePanePinned(client_id))
+                .with_context(err_context)?;
+        },
         Action::ToggleFloatingPanes => {
             session
                 .senders
@@ -436,6 +512,21 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::CloseFocusedPane(client_id))
                 .with_context(err_context)?;
         },
+        Action::UndoClosePane => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::UndoClosePane(client_id))
+                .with_context(err_context)?;
+        },
+        Action::SignalFocusedPane(signal) => match Signal::from_str(&signal) {
+            Ok(signal) => {
+                session
+                    .senders
+                    .send_to_screen(ScreenInstruction::SignalFocusedPane(signal, client_id))
+                    .with_context(err_context)?;
+            },
+            Err(_) => log::error!("Unknown signal: {}", signal),
+        },
         Action::NewTab(tab_layout, tab_name) => {
             let shell = session.default_shell.clone();
             session
@@ -445,6 +536,17 @@ pub(crate) fn route_action(
                 ))
                 .with_context(err_context)?;
         },
+        Action::NewTabWithLayout(layout_name) => {
+            let shell = session.default_shell.clone();
+            session
+                .senders
+                .send_to_pty(PtyInstruction::NewTabWithLayout(
+                    shell,
+                    layout_name,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
         Action::GoToNextTab => {
             session
                 .senders
@@ -457,6 +559,30 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::SwitchTabPrev(client_id))
                 .with_context(err_context)?;
         },
+        Action::MoveTabLeft => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MoveTabLeft(client_id))
+                .with_context(err_context)?;
+        },
+        Action::MoveTabRight => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MoveTabRight(client_id))
+                .with_context(err_context)?;
+        },
+        Action::MovePaneToNewTab => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MovePaneToNewTab(client_id))
+                .with_context(err_context)?;
+        },
+        Action::MovePaneToTab(direction) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MovePaneToTab(direction, client_id))
+                .with_context(err_context)?;
+        },
         Action::ToggleActiveSyncTab => {
             session
                 .senders
@@ -475,6 +601,42 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::GoToTab(i, Some(client_id)))
                 .with_context(err_context)?;
         },
+        Action::GoToTabWithId(tab_id) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::GoToTabWithId(tab_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::FocusTerminalPaneWithId(id) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::FocusPaneWithId(
+                    PaneId::Terminal(id),
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::FocusPluginPaneWithId(id) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::FocusPaneWithId(
+                    PaneId::Plugin(id),
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::RestoreFocus => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::RestoreFocus(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ListPanes => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::ListPanes(client_id))
+                .with_context(err_context)?;
+        },
         Action::TabNameInput(c) => {
             session
                 .senders
@@ -553,6 +715,12 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::MouseHoldMiddle(point, client_id))
                 .with_context(err_context)?;
         },
+        Action::MouseMove(point) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MouseMove(point, client_id))
+                .with_context(err_context)?;
+        },
         Action::Copy => {
             session
                 .senders
@@ -615,6 +783,27 @@ pub(crate) fn route_action(
     Ok(should_break)
 }
 
+/// A read-only client can only move between its own input modes to get to detaching
+fn is_allowed_for_read_only_clients(action: &Action) -> bool {
+    matches!(action, Action::SwitchToMode(_) | Action::Detach)
+}
+
+/// Whether an action coming from a client should be carried out. The actions a read-only client
+/// isn't allowed to take are dropped, and since the client might be blocking its input until they
+/// are done, it is told to carry on.
+fn should_route_action(
+    action: &Action,
+    read_only: bool,
+    os_input: &dyn ServerOsApi,
+    client_id: ClientId,
+) -> bool {
+    if read_only && !is_allowed_for_read_only_clients(action) {
+        let _ = os_input.send_to_client(client_id, ServerToClientMsg::UnblockInputThread);
+        return false;
+    }
+    true
+}
+
 // this should only be used for one-off startup instructions
 macro_rules! send_to_screen_or_retry_queue {
     ($rlocked_sessions:expr, $message:expr, $instruction: expr, $retry_queue:expr) => {{
@@ -635,25 +824,45 @@ macro_rules! send_to_screen_or_retry_queue {
 pub(crate) fn route_thread_main(
     session_data: Arc<RwLock<Option<SessionMetaData>>>,
     session_state: Arc<RwLock<SessionState>>,
-    os_input: Box<dyn ServerOsApi>,
+    mut os_input: Box<dyn ServerOsApi>,
     to_server: SenderWithContext<ServerInstruction>,
     mut receiver: IpcReceiverWithContext<ClientToServerMsg>,
     client_id: ClientId,
 ) -> Result<()> {
+    if !handshake_with_client(&mut receiver, &mut os_input, &session_state, client_id) {
+        if session_data.read().unwrap().is_none() {
+            // this server was spawned for this client alone, so it has nothing left to do
+            let _ = to_server.send(ServerInstruction::KillSession);
+        }
+        return Ok(());
+    }
     let mut retry_queue = vec![];
+    // what to do if the client goes away without telling us, set from the client's options once
+    // it joins the session - a connection that only queries the session is just dropped
+    let mut on_force_close: Option<OnForceClose> = None;
+    // whether quitting has to be confirmed, in which case the client waits to be told to exit
+    let mut confirm_quit = false;
+    // whether the client only watches the session, set from its attributes
+    let mut read_only = false;
     let err_context = || format!("failed to handle instruction for client {client_id}");
     'route_loop: loop {
         match receiver.recv() {
             Some((instruction, err_ctx)) => {
                 err_ctx.update_thread_ctx();
                 let rlocked_sessions = session_data.read().unwrap();
-                let handle_instruction = |instruction: ClientToServerMsg,
-                                          mut retry_queue: Option<&mut Vec<ClientToServerMsg>>|
+                let mut handle_instruction = |instruction: ClientToServerMsg,
+                                              mut retry_queue: Option<
+                    &mut Vec<ClientToServerMsg>,
+                >|
                  -> Result<bool> {
                     let mut should_break = false;
                     match instruction {
                         ClientToServerMsg::Action(action, maybe_client_id) => {
                             let client_id = maybe_client_id.unwrap_or(client_id);
+                            if !should_route_action(&action, read_only, &*os_input, client_id) {
+                                return Ok(should_break);
+                            }
+                            session_state.write().unwrap().register_input();
                             if let Some(rlocked_sessions) = rlocked_sessions.as_ref() {
                                 if let Action::SwitchToMode(input_mode) = action {
                                     let send_res = os_input.send_to_client(
@@ -666,7 +875,12 @@ pub(crate) fn route_thread_main(
                                         return Ok(true);
                                     }
                                 }
-                                if route_action(
+                                if confirm_quit && matches!(action, Action::Quit) {
+                                    rlocked_sessions
+                                        .senders
+                                        .send_to_screen(ScreenInstruction::ConfirmQuit(client_id))
+                                        .with_context(err_context)?;
+                                } else if route_action(
                                     action,
                                     rlocked_sessions,
                                     &*os_input,
@@ -682,16 +896,24 @@ pub(crate) fn route_thread_main(
                                 .write()
                                 .unwrap()
                                 .set_client_size(client_id, new_size);
-                            let min_size = session_state
+                            let session_size = session_state
                                 .read()
                                 .unwrap()
-                                .min_client_terminal_size()
+                                .session_size()
                                 .with_context(err_context)?;
                             rlocked_sessions
                                 .as_ref()
                                 .unwrap()
                                 .senders
-                                .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                                .send_to_screen(ScreenInstruction::ClientResize(
+                                    client_id, new_size,
+                                ))
+                                .with_context(err_context)?;
+                            rlocked_sessions
+                                .as_ref()
+                                .unwrap()
+                                .senders
+                                .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                                 .with_context(err_context)?;
                         },
                         ClientToServerMsg::TerminalPixelDimensions(pixel_dimensions) => {
@@ -737,6 +959,9 @@ pub(crate) fn route_thread_main(
                             layout,
                             plugin_config,
                         ) => {
+                            on_force_close = Some(opts.on_force_close.unwrap_or_default());
+                            confirm_quit = opts.confirm_quit.unwrap_or(false);
+                            read_only = client_attributes.read_only;
                             let new_client_instruction = ServerInstruction::NewClient(
                                 client_attributes,
                                 cli_args,
@@ -750,6 +975,11 @@ pub(crate) fn route_thread_main(
                                 .with_context(err_context)?;
                         },
                         ClientToServerMsg::AttachClient(client_attributes, opts) => {
+                            on_force_close = Some(opts.on_force_close.unwrap_or_default());
+                            confirm_quit = opts.confirm_quit.unwrap_or(false);
+                            read_only = client_attributes.read_only;
+                            // a client attaching to an idle session gets the whole idle timeout
+                            session_state.write().unwrap().register_input();
                             let attach_client_instruction =
                                 ServerInstruction::AttachClient(client_attributes, opts, client_id);
                             to_server
@@ -776,7 +1006,13 @@ pub(crate) fn route_thread_main(
                             should_break = true;
                         },
                         ClientToServerMsg::ListClients => {
-                            let _ = to_server.send(ServerInstruction::ActiveClients(client_id));
+                            // the screen knows the tab count, it replies through the server thread
+                            send_to_screen_or_retry_queue!(
+                                rlocked_sessions,
+                                ScreenInstruction::ReportTabCount(client_id),
+                                instruction,
+                                retry_queue
+                            );
                         },
                     }
                     Ok(should_break)
@@ -801,9 +1037,49 @@ pub(crate) fn route_thread_main(
                         "Received empty message".to_string(),
                     )),
                 );
+                // the connection to the client is gone, treat it as if its terminal was closed
+                let _ = match on_force_close {
+                    Some(OnForceClose::Quit) => {
+                        to_server.send(ServerInstruction::ClientExit(client_id))
+                    },
+                    Some(OnForceClose::Detach) => {
+                        to_server.send(ServerInstruction::DetachSession(vec![client_id]))
+                    },
+                    None => Ok(()),
+                };
                 break;
             },
         }
     }
     Ok(())
 }
+
+/// Exchanges versions with a client that just connected. Returns false (after removing the
+/// client) if it is of a different build than this server, as the two can't be relied on to
+/// understand each other's messages.
+fn handshake_with_client(
+    receiver: &mut IpcReceiverWithContext<ClientToServerMsg>,
+    os_input: &mut Box<dyn ServerOsApi>,
+    session_state: &RwLock<SessionState>,
+    client_id: ClientId,
+) -> bool {
+    let client_version = receiver.recv_version();
+    // the client is sent our version either way, so it can tell the user which one is running
+    let _ = receiver.get_sender::<ServerToClientMsg>().send_version();
+    if client_version.as_deref() == Some(BUILD_VERSION.as_str()) {
+        return true;
+    }
+    log::error!(
+        "Refusing client {} of version {}, this server is version {}",
+        client_id,
+        client_version.as_deref().unwrap_or("unknown"),
+        *BUILD_VERSION
+    );
+    os_input.remove_client(client_id);
+    session_state.write().unwrap().remove_client(client_id);
+    false
+}
+
+#[cfg(test)]
+#[path = "./unit/route_tests.rs"]
+mod route_tests;
@@ -4,12 +4,21 @@ use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 use std::str;
+use std::sync::mpsc::Sender;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use zellij_utils::errors::prelude::*;
+use zellij_utils::input::actions::{Fraction, ResizeDirection};
 use zellij_utils::input::command::RunCommand;
-use zellij_utils::input::options::Clipboard;
+use zellij_utils::input::options::{AutoLayout, BellStyle, Clipboard};
+use zellij_utils::nix::sys::signal::Signal;
 use zellij_utils::pane_size::{Size, SizeInPixels};
-use zellij_utils::{input::command::TerminalAction, input::layout::PaneLayout, position::Position};
+use zellij_utils::{
+    input::command::TerminalAction,
+    input::layout::{Layout, PaneLayout},
+    position::Position,
+};
 
 use crate::panes::alacritty_functions::xparse_color;
 use crate::panes::terminal_character::AnsiCode;
@@ -17,21 +26,35 @@ use crate::panes::terminal_character::AnsiCode;
 use crate::{
     output::Output,
     panes::sixel::SixelImageStore,
-    panes::PaneId,
+    panes::{
+        persisted_scrollback::{self, ScrollbackWrite},
+        PaneId, BELL_FLASH_DURATION,
+    },
     pty::{ClientOrTabIndex, PtyInstruction, VteBytes},
-    tab::Tab,
+    tab::{ClosedPane, Tab},
     thread_bus::Bus,
-    ui::overlay::{Overlay, OverlayWindow, Overlayable},
+    ui::overlay::{
+        help::generate_help, prompt::Prompt, Overlay, OverlayType, OverlayWindow, Overlayable,
+    },
     wasm_vm::PluginInstruction,
     ClientId, ServerInstruction,
 };
 use zellij_utils::{
     data::{Event, InputMode, ModeInfo, Palette, PaletteColor, PluginCapabilities, Style, TabInfo},
     errors::{ContextType, ScreenContext},
-    input::{get_mode_info, options::Options},
+    input::{actions::Direction, get_mode_info, options::Options},
     ipc::{ClientAttributes, PixelDimensions, ServerToClientMsg},
+    status::{status_line, SessionStatus, StatusLine},
 };
 
+// how long the mouse has to rest on a pane for the focus to follow it, so that moving the mouse
+// across several panes does not focus each of them in turn
+const FOCUS_FOLLOWS_MOUSE_DEBOUNCE: Duration = Duration::from_millis(100);
+// how often the scrollback of the panes is saved to disk when the user asked for it
+const PERSIST_SCROLLBACK_INTERVAL: Duration = Duration::from_secs(30);
+// in megabytes
+const DEFAULT_PERSIST_SCROLLBACK_MAX_SIZE: usize = 50;
+
 /// Get the active tab and call a closure on it
 ///
 /// If no active tab can be found, an error is logged instead.
@@ -122,6 +145,7 @@ pub enum ScreenInstruction {
     // should_float
     OpenInPlaceEditor(PaneId, ClientId),
     TogglePaneEmbedOrFloating(ClientId),
+    TogglePanePinned(ClientId),
     ToggleFloatingPanes(ClientId, Option<TerminalAction>),
     HorizontalSplit(PaneId, Option<String>, ClientId), // String is initial title
     VerticalSplit(PaneId, Option<String>, ClientId),   // String is initial title
@@ -132,6 +156,10 @@ pub enum ScreenInstruction {
     ResizeUp(ClientId),
     ResizeIncrease(ClientId),
     ResizeDecrease(ClientId),
+    ResizeFine(ResizeDirection, ClientId),
+    EqualizePanes(ClientId),
+    ApplyAutoLayout(ClientId),
+    ResizeToFraction(Fraction, ClientId),
     SwitchFocus(ClientId),
     FocusNextPane(ClientId),
     FocusPreviousPane(ClientId),
@@ -148,35 +176,56 @@ pub enum ScreenInstruction {
     MovePaneLeft(ClientId),
     Exit,
     DumpScreen(String, ClientId, bool),
+    PipePane(Vec<String>, ClientId, bool),
+    DumpLayout(String, ClientId),
     EditScrollback(ClientId),
     ScrollUp(ClientId),
     ScrollUpAt(Position, ClientId),
     ScrollDown(ClientId),
     ScrollDownAt(Position, ClientId),
     ScrollToBottom(ClientId),
+    ToggleFollowOutput(ClientId),
     PageScrollUp(ClientId),
     PageScrollDown(ClientId),
     HalfPageScrollUp(ClientId),
     HalfPageScrollDown(ClientId),
+    ScrollLeft(ClientId),
+    ScrollRight(ClientId),
+    ToggleLineWrap(ClientId),
     ClearScroll(ClientId),
     CloseFocusedPane(ClientId),
+    UndoClosePane(ClientId),
+    ReopenClosedPane(PaneId, ClosedPane, ClientId),
+    SignalFocusedPane(Signal, ClientId),
     ToggleActiveTerminalFullscreen(ClientId),
     TogglePaneFrames,
     SetSelectable(PaneId, bool, usize),
     ClosePane(PaneId, Option<ClientId>),
     HoldPane(PaneId, Option<i32>, RunCommand, Option<ClientId>), // Option<i32> is the exit status
+    RestartCommandInPane(PaneId, RunCommand),
+    PersistScrollback,
     UpdatePaneName(Vec<u8>, ClientId),
     UndoRenamePane(ClientId),
     NewTab(PaneLayout, Vec<u32>, ClientId),
     SwitchTabNext(ClientId),
     SwitchTabPrev(ClientId),
+    MoveTabLeft(ClientId),
+    MoveTabRight(ClientId),
+    MovePaneToNewTab(ClientId),
+    MovePaneToTab(Direction, ClientId),
     ToggleActiveSyncTab(ClientId),
     CloseTab(ClientId),
     GoToTab(u32, Option<ClientId>), // this Option is a hacky workaround, please do not copy this behaviour
+    GoToTabWithId(u32, ClientId),
+    FocusPaneWithId(PaneId, ClientId),
+    RestoreFocus(ClientId),
+    ListPanes(ClientId),
+    ReportTabCount(ClientId),
     ToggleTab(ClientId),
     UpdateTabName(Vec<u8>, ClientId),
     UndoRenameTab(ClientId),
     TerminalResize(Size),
+    ClientResize(ClientId, Size),
     TerminalPixelDimensions(PixelDimensions),
     TerminalBackgroundColor(String),
     TerminalForegroundColor(String),
@@ -192,12 +241,15 @@ pub enum ScreenInstruction {
     MouseHoldLeft(Position, ClientId),
     MouseHoldRight(Position, ClientId),
     MouseHoldMiddle(Position, ClientId),
+    MouseMove(Position, ClientId),
+    FocusFollowsMouse(ClientId),
     Copy(ClientId),
     AddClient(ClientId),
     RemoveClient(ClientId),
     AddOverlay(Overlay, ClientId),
     RemoveOverlay(ClientId),
     ConfirmPrompt(ClientId),
+    ConfirmQuit(ClientId),
     DenyPrompt(ClientId),
     UpdateSearch(Vec<u8>, ClientId),
     SearchDown(ClientId),
@@ -217,6 +269,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::TogglePaneEmbedOrFloating(..) => {
                 ScreenContext::TogglePaneEmbedOrFloating
             },
+            ScreenInstruction::TogglePanePinned(..) => ScreenContext::TogglePanePinned,
             ScreenInstruction::ToggleFloatingPanes(..) => ScreenContext::ToggleFloatingPanes,
             ScreenInstruction::HorizontalSplit(..) => ScreenContext::HorizontalSplit,
             ScreenInstruction::VerticalSplit(..) => ScreenContext::VerticalSplit,
@@ -227,6 +280,10 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::ResizeUp(..) => ScreenContext::ResizeUp,
             ScreenInstruction::ResizeIncrease(..) => ScreenContext::ResizeIncrease,
             ScreenInstruction::ResizeDecrease(..) => ScreenContext::ResizeDecrease,
+            ScreenInstruction::ResizeFine(..) => ScreenContext::ResizeFine,
+            ScreenInstruction::EqualizePanes(..) => ScreenContext::EqualizePanes,
+            ScreenInstruction::ApplyAutoLayout(..) => ScreenContext::ApplyAutoLayout,
+            ScreenInstruction::ResizeToFraction(..) => ScreenContext::ResizeToFraction,
             ScreenInstruction::SwitchFocus(..) => ScreenContext::SwitchFocus,
             ScreenInstruction::FocusNextPane(..) => ScreenContext::FocusNextPane,
             ScreenInstruction::FocusPreviousPane(..) => ScreenContext::FocusPreviousPane,
@@ -247,16 +304,25 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::MovePaneLeft(..) => ScreenContext::MovePaneLeft,
             ScreenInstruction::Exit => ScreenContext::Exit,
             ScreenInstruction::DumpScreen(..) => ScreenContext::DumpScreen,
+            ScreenInstruction::PipePane(..) => ScreenContext::PipePane,
+            ScreenInstruction::DumpLayout(..) => ScreenContext::DumpLayout,
             ScreenInstruction::EditScrollback(..) => ScreenContext::EditScrollback,
             ScreenInstruction::ScrollUp(..) => ScreenContext::ScrollUp,
             ScreenInstruction::ScrollDown(..) => ScreenContext::ScrollDown,
             ScreenInstruction::ScrollToBottom(..) => ScreenContext::ScrollToBottom,
+            ScreenInstruction::ToggleFollowOutput(..) => ScreenContext::ToggleFollowOutput,
             ScreenInstruction::PageScrollUp(..) => ScreenContext::PageScrollUp,
             ScreenInstruction::PageScrollDown(..) => ScreenContext::PageScrollDown,
             ScreenInstruction::HalfPageScrollUp(..) => ScreenContext::HalfPageScrollUp,
             ScreenInstruction::HalfPageScrollDown(..) => ScreenContext::HalfPageScrollDown,
+            ScreenInstruction::ScrollLeft(..) => ScreenContext::ScrollLeft,
+            ScreenInstruction::ScrollRight(..) => ScreenContext::ScrollRight,
+            ScreenInstruction::ToggleLineWrap(..) => ScreenContext::ToggleLineWrap,
             ScreenInstruction::ClearScroll(..) => ScreenContext::ClearScroll,
             ScreenInstruction::CloseFocusedPane(..) => ScreenContext::CloseFocusedPane,
+            ScreenInstruction::UndoClosePane(..) => ScreenContext::UndoClosePane,
+            ScreenInstruction::ReopenClosedPane(..) => ScreenContext::ReopenClosedPane,
+            ScreenInstruction::SignalFocusedPane(..) => ScreenContext::SignalFocusedPane,
             ScreenInstruction::ToggleActiveTerminalFullscreen(..) => {
                 ScreenContext::ToggleActiveTerminalFullscreen
             },
@@ -264,16 +330,28 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::SetSelectable(..) => ScreenContext::SetSelectable,
             ScreenInstruction::ClosePane(..) => ScreenContext::ClosePane,
             ScreenInstruction::HoldPane(..) => ScreenContext::HoldPane,
+            ScreenInstruction::RestartCommandInPane(..) => ScreenContext::RestartCommandInPane,
+            ScreenInstruction::PersistScrollback => ScreenContext::PersistScrollback,
             ScreenInstruction::UpdatePaneName(..) => ScreenContext::UpdatePaneName,
             ScreenInstruction::UndoRenamePane(..) => ScreenContext::UndoRenamePane,
             ScreenInstruction::NewTab(..) => ScreenContext::NewTab,
             ScreenInstruction::SwitchTabNext(..) => ScreenContext::SwitchTabNext,
             ScreenInstruction::SwitchTabPrev(..) => ScreenContext::SwitchTabPrev,
+            ScreenInstruction::MoveTabLeft(..) => ScreenContext::MoveTabLeft,
+            ScreenInstruction::MoveTabRight(..) => ScreenContext::MoveTabRight,
+            ScreenInstruction::MovePaneToNewTab(..) => ScreenContext::MovePaneToNewTab,
+            ScreenInstruction::MovePaneToTab(..) => ScreenContext::MovePaneToTab,
             ScreenInstruction::CloseTab(..) => ScreenContext::CloseTab,
             ScreenInstruction::GoToTab(..) => ScreenContext::GoToTab,
+            ScreenInstruction::GoToTabWithId(..) => ScreenContext::GoToTabWithId,
+            ScreenInstruction::FocusPaneWithId(..) => ScreenContext::FocusPaneWithId,
+            ScreenInstruction::RestoreFocus(..) => ScreenContext::RestoreFocus,
+            ScreenInstruction::ListPanes(..) => ScreenContext::ListPanes,
+            ScreenInstruction::ReportTabCount(..) => ScreenContext::ReportTabCount,
             ScreenInstruction::UpdateTabName(..) => ScreenContext::UpdateTabName,
             ScreenInstruction::UndoRenameTab(..) => ScreenContext::UndoRenameTab,
             ScreenInstruction::TerminalResize(..) => ScreenContext::TerminalResize,
+            ScreenInstruction::ClientResize(..) => ScreenContext::ClientResize,
             ScreenInstruction::TerminalPixelDimensions(..) => {
                 ScreenContext::TerminalPixelDimensions
             },
@@ -300,6 +378,8 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::MouseHoldLeft(..) => ScreenContext::MouseHoldLeft,
             ScreenInstruction::MouseHoldRight(..) => ScreenContext::MouseHoldRight,
             ScreenInstruction::MouseHoldMiddle(..) => ScreenContext::MouseHoldMiddle,
+            ScreenInstruction::MouseMove(..) => ScreenContext::MouseMove,
+            ScreenInstruction::FocusFollowsMouse(..) => ScreenContext::FocusFollowsMouse,
             ScreenInstruction::Copy(..) => ScreenContext::Copy,
             ScreenInstruction::ToggleTab(..) => ScreenContext::ToggleTab,
             ScreenInstruction::AddClient(..) => ScreenContext::AddClient,
@@ -307,6 +387,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::AddOverlay(..) => ScreenContext::AddOverlay,
             ScreenInstruction::RemoveOverlay(..) => ScreenContext::RemoveOverlay,
             ScreenInstruction::ConfirmPrompt(..) => ScreenContext::ConfirmPrompt,
+            ScreenInstruction::ConfirmQuit(..) => ScreenContext::ConfirmQuit,
             ScreenInstruction::DenyPrompt(..) => ScreenContext::DenyPrompt,
             ScreenInstruction::UpdateSearch(..) => ScreenContext::UpdateSearch,
             ScreenInstruction::SearchDown(..) => ScreenContext::SearchDown,
@@ -320,11 +401,28 @@ impl From<&ScreenInstruction> for ScreenContext {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MaxPanes {
+    pub count: usize,
+    /// Close old panes to make room for new ones instead of refusing to open them
+    pub evict: bool,
+}
+
+impl MaxPanes {
+    pub(crate) fn new(count: usize, evict: bool) -> Self {
+        Self { count, evict }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct CopyOptions {
     pub command: Option<String>,
     pub clipboard: Clipboard,
     pub copy_on_select: bool,
+    pub clipboard_from_panes: bool,
+    pub middle_click_paste: bool,
+    /// The most recently copied text, shared by all tabs so that it can be pasted in any of them
+    pub copied_text: Rc<RefCell<Option<String>>>,
 }
 
 impl CopyOptions {
@@ -332,11 +430,16 @@ impl CopyOptions {
         copy_command: Option<String>,
         copy_clipboard: Clipboard,
         copy_on_select: bool,
+        clipboard_from_panes: bool,
+        middle_click_paste: bool,
     ) -> Self {
         Self {
             command: copy_command,
             clipboard: copy_clipboard,
             copy_on_select,
+            clipboard_from_panes,
+            middle_click_paste,
+            copied_text: Rc::new(RefCell::new(None)),
         }
     }
 
@@ -346,6 +449,9 @@ impl CopyOptions {
             command: None,
             clipboard: Clipboard::default(),
             copy_on_select: true,
+            clipboard_from_panes: true,
+            middle_click_paste: true,
+            copied_text: Rc::new(RefCell::new(None)),
         }
     }
 }
@@ -356,9 +462,11 @@ pub(crate) struct Screen {
     /// A Bus for sending and receiving messages with the other threads.
     pub bus: Bus<ScreenInstruction>,
     /// An optional maximal amount of panes allowed per [`Tab`] in this [`Screen`] instance.
-    max_panes: Option<usize>,
+    max_panes: Option<MaxPanes>,
     /// A map between this [`Screen`]'s tabs and their ID/key.
     tabs: BTreeMap<usize, Tab>,
+    /// The index of the next [`Tab`] to be created, tab indices are never reused
+    next_tab_index: usize,
     /// The full size of this [`Screen`].
     size: Size,
     pixel_dimensions: PixelDimensions,
@@ -378,18 +486,82 @@ pub(crate) struct Screen {
     draw_pane_frames: bool,
     session_is_mirrored: bool,
     copy_options: CopyOptions,
+    /// Whether every render should repaint all panes rather than only what changed.
+    full_redraw: bool,
+    /// Whether moving a tab past either end of the tab list wraps it around to the other end.
+    move_tab_wraparound: bool,
+    /// Whether closing a pane that is running a program other than its shell needs confirming.
+    confirm_pane_close: bool,
+    /// The client and pane of a pane close that is waiting to be confirmed.
+    pane_close_to_confirm: Option<(ClientId, PaneId)>,
+    /// The percentage of the screen a pane grows or shrinks by on each resize.
+    resize_percent: f64,
+    /// Whether to show a status line in each client's terminal title.
+    status_in_title: bool,
+    status_line: Box<dyn StatusLine>,
+    /// The status line last shown in each client's terminal title.
+    status_lines_in_title: HashMap<ClientId, String>,
+    /// The terminal size of each client, to clip what is rendered for clients smaller than the
+    /// screen (eg. when the session is sized to the latest client).
+    client_sizes: HashMap<ClientId, Size>,
+    /// Floating panes that are moved along to whichever [`Tab`] becomes active for the client
+    /// that pinned them.
+    pinned_panes: HashMap<PaneId, ClientId>,
+    /// The number of recently closed panes of each tab that can be reopened.
+    undo_close_depth: usize,
+    /// How the panes of each tab are arranged as panes are added or closed.
+    auto_layout: AutoLayout,
+    /// How a bell rung by the program running in a pane is shown.
+    bell_style: BellStyle,
+    /// The clients whose terminal bell is rung with the next render.
+    clients_to_ring: HashSet<ClientId>,
+    /// When to render again to return the frames of panes flashing a visual bell to their color.
+    bell_flash_ends_at: Option<Instant>,
+    /// Where the mouse last moved to and when, for each client whose focus is yet to follow it.
+    pending_mouse_moves: HashMap<ClientId, (Position, Instant)>,
+    /// The most bytes the persisted scrollback of all sessions takes, if it is persisted at all.
+    persist_scrollback_max_size: Option<usize>,
+    /// Scrollback persisted by an earlier session with the same name, by terminal id, shown in
+    /// each terminal before its first output.
+    restored_scrollback: HashMap<u32, String>,
+    /// The tab index and pane the first client had focused before a cli action was targeted at
+    /// another tab or pane, by the id of the cli client, to go back to once the action is done.
+    focus_before_cli_action: HashMap<ClientId, (usize, Option<PaneId>)>,
+    /// The thread that writes the persisted scrollback, if it is persisted.
+    scrollback_writer: Option<(Sender<ScrollbackWrite>, JoinHandle<()>)>,
+    /// Whether going to a tab past the last one opens a new tab.
+    go_to_tab_creates_tab: bool,
+    /// The number of lines the mouse wheel scrolls a pane by per tick.
+    scroll_wheel_lines: usize,
+    /// The fewest columns and rows a tiled pane may have, if not the defaults.
+    min_pane_width: Option<usize>,
+    min_pane_height: Option<usize>,
 }
 
 impl Screen {
     /// Creates and returns a new [`Screen`].
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         bus: Bus<ScreenInstruction>,
         client_attributes: &ClientAttributes,
-        max_panes: Option<usize>,
+        max_panes: Option<MaxPanes>,
         mode_info: ModeInfo,
         draw_pane_frames: bool,
         session_is_mirrored: bool,
         copy_options: CopyOptions,
+        full_redraw: bool,
+        move_tab_wraparound: bool,
+        confirm_pane_close: bool,
+        resize_percent: f64,
+        status_in_title: bool,
+        undo_close_depth: usize,
+        auto_layout: AutoLayout,
+        bell_style: BellStyle,
+        persist_scrollback_max_size: Option<usize>,
+        go_to_tab_creates_tab: bool,
+        scroll_wheel_lines: usize,
+        min_pane_width: Option<usize>,
+        min_pane_height: Option<usize>,
     ) -> Self {
         Screen {
             bus,
@@ -402,6 +574,7 @@ impl Screen {
             connected_clients: Rc::new(RefCell::new(HashSet::new())),
             active_tab_indices: BTreeMap::new(),
             tabs: BTreeMap::new(),
+            next_tab_index: 0,
             overlay: OverlayWindow::default(),
             terminal_emulator_colors: Rc::new(RefCell::new(Palette::default())),
             terminal_emulator_color_codes: Rc::new(RefCell::new(HashMap::new())),
@@ -411,18 +584,40 @@ impl Screen {
             draw_pane_frames,
             session_is_mirrored,
             copy_options,
+            full_redraw,
+            move_tab_wraparound,
+            confirm_pane_close,
+            pane_close_to_confirm: None,
+            resize_percent,
+            status_in_title,
+            status_line: status_line(),
+            status_lines_in_title: HashMap::new(),
+            client_sizes: HashMap::new(),
+            pinned_panes: HashMap::new(),
+            undo_close_depth,
+            auto_layout,
+            bell_style,
+            clients_to_ring: HashSet::new(),
+            bell_flash_ends_at: None,
+            pending_mouse_moves: HashMap::new(),
+            persist_scrollback_max_size,
+            restored_scrollback: HashMap::new(),
+            focus_before_cli_action: HashMap::new(),
+            scrollback_writer: None,
+            go_to_tab_creates_tab,
+            scroll_wheel_lines,
+            min_pane_width,
+            min_pane_height,
         }
     }
 
     /// Returns the index where a new [`Tab`] should be created in this [`Screen`].
-    /// Currently, this is right after the last currently existing tab, or `0` if
-    /// no tabs exist in this screen yet.
-    fn get_new_tab_index(&self) -> usize {
-        if let Some(index) = self.tabs.keys().last() {
-            *index + 1
-        } else {
-            0
-        }
+    /// Currently, this is right after the last tab ever created, or `0` if no tabs
+    /// were created in this screen yet, so that the index of a closed tab is never reused.
+    fn get_new_tab_index(&mut self) -> usize {
+        let index = self.next_tab_index;
+        self.next_tab_index += 1;
+        index
     }
 
     fn move_clients_from_closed_tab(
@@ -500,6 +695,9 @@ impl Screen {
     }
 
     fn update_client_tab_focus(&mut self, client_id: ClientId, new_tab_index: usize) {
+        if let Some(new_tab) = self.tabs.get_mut(&new_tab_index) {
+            new_tab.has_unseen_bell = false;
+        }
         match self.active_tab_indices.remove(&client_id) {
             Some(old_active_index) => {
                 self.active_tab_indices.insert(client_id, new_tab_index);
@@ -547,6 +745,8 @@ impl Screen {
                     .with_context(err_context)?;
                     self.update_client_tab_focus(client_id, new_tab_index);
                 }
+                self.move_pinned_panes(current_tab_index, new_tab_index, client_id)
+                    .with_context(err_context)?;
 
                 if let Some(current_tab) = self.get_indexed_tab_mut(current_tab_index) {
                     if current_tab.has_no_connected_clients() {
@@ -610,7 +810,183 @@ impl Screen {
         Ok(())
     }
 
+    /// Moves this [`Screen`]'s active [`Tab`] one position to the left.
+    pub fn move_active_tab_left(&mut self, client_id: ClientId) -> Result<()> {
+        self.move_active_tab(client_id, true)
+            .with_context(|| format!("failed to move active tab left for client {client_id}"))
+    }
+
+    /// Moves this [`Screen`]'s active [`Tab`] one position to the right.
+    pub fn move_active_tab_right(&mut self, client_id: ClientId) -> Result<()> {
+        self.move_active_tab(client_id, false)
+            .with_context(|| format!("failed to move active tab right for client {client_id}"))
+    }
+
+    fn move_active_tab(&mut self, client_id: ClientId, move_left: bool) -> Result<()> {
+        let client_id = if self.get_active_tab(client_id).is_some() {
+            Some(client_id)
+        } else {
+            self.get_first_client_id()
+        };
+        let active_tab_index = match client_id.and_then(|c| self.active_tab_indices.get(&c)) {
+            Some(active_tab_index) => *active_tab_index,
+            None => {
+                log::error!("Active tab not found for client id: {client_id:?}");
+                return Ok(());
+            },
+        };
+        let last_position = self.tabs.len().saturating_sub(1);
+        let active_tab_position = self
+            .tabs
+            .get(&active_tab_index)
+            .map(|tab| tab.position)
+            .context("failed to get active tab")?;
+        let at_edge = if move_left {
+            active_tab_position == 0
+        } else {
+            active_tab_position == last_position
+        };
+        if at_edge && !self.move_tab_wraparound {
+            return Ok(());
+        }
+        for tab in self.tabs.values_mut() {
+            if tab.index == active_tab_index {
+                tab.position = match (move_left, at_edge) {
+                    (true, false) => active_tab_position - 1,
+                    (false, false) => active_tab_position + 1,
+                    (true, true) => last_position,
+                    (false, true) => 0,
+                };
+            } else if at_edge {
+                // wrapping around shifts all the other tabs over by one
+                if move_left {
+                    tab.position -= 1;
+                } else {
+                    tab.position += 1;
+                }
+            } else if move_left && tab.position + 1 == active_tab_position {
+                tab.position += 1;
+            } else if !move_left && tab.position == active_tab_position + 1 {
+                tab.position -= 1;
+            }
+        }
+        self.update_tabs()
+    }
+
+    /// Switches to the tab with the given id, which is its index rather than its position
+    pub fn go_to_tab_with_id(&mut self, tab_id: usize, client_id: ClientId) -> Result<()> {
+        let cli_client_id = client_id;
+        let client_id = self.client_id_or_first_client_id(client_id);
+        self.remember_focus_before_cli_action(cli_client_id, client_id);
+        match self.tabs.get(&tab_id) {
+            Some(tab) => {
+                let tab_position = tab.position;
+                self.switch_active_tab(tab_position, client_id)
+            },
+            None => {
+                log::error!("No tab with id {}", tab_id);
+                Ok(())
+            },
+        }
+    }
+
+    /// Focuses the pane with the given id, switching to its tab first
+    pub fn focus_pane_with_id(&mut self, pane_id: PaneId, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to focus pane {pane_id:?} for client {client_id}");
+
+        let cli_client_id = client_id;
+        let client_id = self.client_id_or_first_client_id(client_id);
+        let (tab_index, tab_position) = match self
+            .tabs
+            .values()
+            .find(|tab| tab.get_all_pane_ids().contains(&pane_id))
+        {
+            Some(tab) => (tab.index, tab.position),
+            None => {
+                log::error!("No pane with id {}", pane_id);
+                return Ok(());
+            },
+        };
+        self.remember_focus_before_cli_action(cli_client_id, client_id);
+        self.switch_active_tab(tab_position, client_id)
+            .with_context(err_context)?;
+        if let Some(tab) = self.tabs.get_mut(&tab_index) {
+            tab.focus_pane_with_id(pane_id, client_id);
+        }
+        Ok(())
+    }
+
+    /// Remembers the tab and pane the client had focused before a cli action moved its focus,
+    /// unless the action already did so earlier on
+    fn remember_focus_before_cli_action(&mut self, cli_client_id: ClientId, client_id: ClientId) {
+        if cli_client_id == client_id || self.focus_before_cli_action.contains_key(&cli_client_id) {
+            return;
+        }
+        if let Some(tab) = self.get_active_tab(client_id) {
+            let focus = (tab.index, tab.get_active_pane_id(client_id));
+            self.focus_before_cli_action.insert(cli_client_id, focus);
+        }
+    }
+
+    /// Focuses the tab and pane again that the client had focused before the cli action of
+    /// `cli_client_id` was targeted at another tab or pane
+    pub fn restore_focus_after_cli_action(&mut self, cli_client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to restore focus after cli action {cli_client_id}");
+
+        let (tab_index, pane_id) = match self.focus_before_cli_action.remove(&cli_client_id) {
+            Some(focus) => focus,
+            None => return Ok(()),
+        };
+        let client_id = self.client_id_or_first_client_id(cli_client_id);
+        // the tab or pane might have been closed by the action
+        if let Some(tab_position) = self.tabs.get(&tab_index).map(|tab| tab.position) {
+            self.switch_active_tab(tab_position, client_id)
+                .with_context(err_context)?;
+            if let (Some(pane_id), Some(tab)) = (pane_id, self.tabs.get_mut(&tab_index)) {
+                tab.focus_pane_with_id(pane_id, client_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a line describing each pane of each tab to the client
+    pub fn list_panes(&mut self, client_id: ClientId) -> Result<()> {
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        let lines = tabs.iter().flat_map(|tab| tab.list_panes()).collect();
+        self.bus
+            .senders
+            .send_to_server(ServerInstruction::Log(lines, client_id))
+            .with_context(|| format!("failed to list panes for client {client_id}"))
+    }
+
+    /// Tells the server how many tabs there are, so that it can send them along with the list of
+    /// connected clients
+    pub fn report_tab_count(&self, client_id: ClientId) -> Result<()> {
+        self.bus
+            .senders
+            .send_to_server(ServerInstruction::ActiveClients(client_id, self.tabs.len()))
+            .with_context(|| format!("failed to report the tab count to client {client_id}"))
+    }
+
+    /// The client itself if it is attached, otherwise (eg. for a cli action) the first attached
+    /// client, whose tabs and panes the action then applies to
+    fn client_id_or_first_client_id(&self, client_id: ClientId) -> ClientId {
+        if self.active_tab_indices.contains_key(&client_id) {
+            client_id
+        } else {
+            self.get_first_client_id().unwrap_or(client_id)
+        }
+    }
+
     pub fn go_to_tab(&mut self, tab_index: usize, client_id: ClientId) -> Result<()> {
+        if tab_index > self.tabs.len() && self.go_to_tab_creates_tab {
+            return self
+                .bus
+                .senders
+                .send_to_pty(PtyInstruction::NewTab(None, None, None, client_id))
+                .with_context(|| format!("failed to create tab for client {client_id}"));
+        }
         self.switch_active_tab(tab_index.saturating_sub(1), client_id)
     }
 
@@ -674,6 +1050,11 @@ impl Screen {
     }
 
     pub fn resize_to_screen(&mut self, new_screen_size: Size) -> Result<()> {
+        log::debug!(
+            "event=resize_screen cols={} rows={}",
+            new_screen_size.cols,
+            new_screen_size.rows
+        );
         self.size = new_screen_size;
         for tab in self.tabs.values_mut() {
             tab.resize_whole_tab(new_screen_size);
@@ -734,8 +1115,12 @@ impl Screen {
         let mut tabs_to_close = vec![];
         let size = self.size;
         let overlay = self.overlay.clone();
+        let full_redraw = self.full_redraw;
         for (tab_index, tab) in &mut self.tabs {
             if tab.has_selectable_tiled_panes() {
+                if full_redraw {
+                    tab.set_force_render();
+                }
                 let vte_overlay = overlay.generate_overlay(size).context(err_context)?;
                 tab.render(&mut output, Some(vte_overlay))
                     .context(err_context)?;
@@ -746,6 +1131,15 @@ impl Screen {
         for tab_index in tabs_to_close {
             self.close_tab_at_index(tab_index).context(err_context)?;
         }
+        for (client_id, client_size) in &self.client_sizes {
+            if client_size.rows < size.rows || client_size.cols < size.cols {
+                output.clip_client_to(*client_id, *client_size);
+            }
+        }
+        if self.status_in_title {
+            self.render_status_lines_in_title(&mut output);
+        }
+        output.add_post_vte_instruction_to_multiple_clients(self.clients_to_ring.drain(), "\u{7}");
         let serialized_output = output.serialize().context(err_context)?;
         self.bus
             .senders
@@ -753,6 +1147,188 @@ impl Screen {
             .context(err_context)
     }
 
+    /// Remembers where the mouse moved to, for the focus to follow it once it rests there. Returns
+    /// true if the mouse just started moving, in which case `focus_follows_mouse_after` has to be
+    /// called to have the focus follow it.
+    pub fn mouse_moved(&mut self, position: Position, client_id: ClientId, now: Instant) -> bool {
+        self.pending_mouse_moves
+            .insert(client_id, (position, now))
+            .is_none()
+    }
+
+    /// Checks back on the mouse of the client once `delay` has passed
+    pub fn focus_follows_mouse_after(&self, delay: Duration, client_id: ClientId) {
+        let senders = self.bus.senders.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            let _ = senders.send_to_screen(ScreenInstruction::FocusFollowsMouse(client_id));
+        });
+    }
+
+    /// Focuses the pane the mouse of the client rests on, rendering if this changed the focus.
+    /// Returns how much longer to wait instead if the mouse moved too recently.
+    pub fn mouse_rested(&mut self, client_id: ClientId, now: Instant) -> Result<Option<Duration>> {
+        let err_context = || format!("failed to follow mouse of client {client_id}");
+
+        let (position, moved_at) = match self.pending_mouse_moves.get(&client_id) {
+            Some(pending_mouse_move) => *pending_mouse_move,
+            None => return Ok(None),
+        };
+        let resting_for = now.saturating_duration_since(moved_at);
+        if resting_for < FOCUS_FOLLOWS_MOUSE_DEBOUNCE {
+            return Ok(Some(FOCUS_FOLLOWS_MOUSE_DEBOUNCE - resting_for));
+        }
+        self.pending_mouse_moves.remove(&client_id);
+        let focus_changed = match self.get_active_tab_mut(client_id) {
+            Some(tab) => tab
+                .handle_mouse_move(&position, client_id)
+                .with_context(err_context)?,
+            None => false,
+        };
+        if focus_changed {
+            self.render().with_context(err_context)?;
+        }
+        Ok(None)
+    }
+
+    /// Shows a bell rung by the program running in the given terminal of the given tab, and marks
+    /// the tab in the tab bar if none of the clients is looking at it.
+    fn ring_bell(&mut self, tab_index: usize, pid: u32) -> Result<()> {
+        let err_context = || format!("failed to ring bell of terminal {pid}");
+
+        let clients_in_tab: Vec<ClientId> = self
+            .active_tab_indices
+            .iter()
+            .filter(|(_client_id, active_tab_index)| **active_tab_index == tab_index)
+            .map(|(client_id, _active_tab_index)| *client_id)
+            .collect();
+        match self.bell_style {
+            BellStyle::None => return Ok(()),
+            BellStyle::Audible => self.clients_to_ring.extend(clients_in_tab.iter()),
+            BellStyle::Visual => {
+                let started_flashing = self
+                    .tabs
+                    .get_mut(&tab_index)
+                    .map(|tab| tab.flash_bell(pid))
+                    .unwrap_or(false);
+                if started_flashing {
+                    // the screen thread renders once more when the flash is over, the frames of
+                    // all flashing panes are returned to their color together
+                    self.bell_flash_ends_at = Some(Instant::now() + BELL_FLASH_DURATION);
+                }
+            },
+        }
+        if clients_in_tab.is_empty() {
+            if let Some(tab) = self.tabs.get_mut(&tab_index) {
+                if !tab.has_unseen_bell {
+                    tab.has_unseen_bell = true;
+                    self.update_tabs().with_context(err_context)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the scrollback persisted by an earlier session with the same name, if any.
+    fn restore_persisted_scrollback(&mut self) {
+        if let Some(session_name) = &self.default_mode_info.session_name {
+            self.restored_scrollback =
+                persisted_scrollback::load(&persisted_scrollback::scrollback_dir(), session_name);
+        }
+    }
+
+    /// Shows the scrollback restored for the terminal, if it was not shown yet.
+    fn show_restored_scrollback(&mut self, pid: u32) -> Result<()> {
+        if let Some(scrollback) = self.restored_scrollback.remove(&pid) {
+            let mut vte_bytes = scrollback.replace('\n', "\r\n");
+            vte_bytes.push_str("\r\n");
+            for tab in self.tabs.values_mut() {
+                if tab.has_terminal_pid(pid) {
+                    tab.handle_pty_bytes(pid, vte_bytes.into_bytes())
+                        .with_context(|| {
+                            format!("failed to restore scrollback of terminal {pid}")
+                        })?;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Has the scrollback of every terminal of the session saved, if the user asked for it.
+    fn persist_scrollback(&mut self) -> Result<()> {
+        let session_name = match (
+            self.scrollback_writer.is_some(),
+            self.default_mode_info.session_name.clone(),
+        ) {
+            (true, Some(session_name)) => session_name,
+            _ => return Ok(()),
+        };
+        let mut scrollbacks = vec![];
+        for tab in self.tabs.values_mut() {
+            scrollbacks.append(&mut tab.scrollbacks_to_persist());
+        }
+        if let Some((writer, _)) = &self.scrollback_writer {
+            writer
+                .send(ScrollbackWrite::Save(session_name, scrollbacks))
+                .context("failed to send scrollback to its writer thread")?;
+        }
+        Ok(())
+    }
+
+    /// Shows the status line in the terminal title of each client whose status changed since it
+    /// was last shown.
+    fn render_status_lines_in_title(&mut self, output: &mut Output) {
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        let tab_names: Vec<String> = tabs
+            .iter()
+            .map(|tab| {
+                if tab.name.is_empty() {
+                    format!("Tab #{}", tab.position + 1)
+                } else {
+                    tab.name.clone()
+                }
+            })
+            .collect();
+        for (client_id, active_tab) in self.active_tab_indices.iter() {
+            let active_tab = match self.tabs.get(active_tab) {
+                Some(active_tab) => active_tab,
+                None => continue,
+            };
+            let mode_info = self
+                .mode_info
+                .get(client_id)
+                .unwrap_or(&self.default_mode_info);
+            let status = SessionStatus {
+                session_name: mode_info.session_name.clone(),
+                tab_names: tab_names.clone(),
+                active_tab: active_tab.position,
+                mode: mode_info.mode,
+                pane_count: active_tab
+                    .get_all_pane_ids()
+                    .iter()
+                    .filter(|pane_id| matches!(pane_id, PaneId::Terminal(_)))
+                    .count(),
+            };
+            // names from layouts and custom status lines can hold control characters, which
+            // would end the title sequence early and be interpreted by the client's terminal
+            let status_line: String = self
+                .status_line
+                .render(&status, self.size.cols)
+                .chars()
+                .filter(|c| !c.is_control())
+                .collect();
+            if self.status_lines_in_title.get(client_id) != Some(&status_line) {
+                output.add_post_vte_instruction_to_client(
+                    *client_id,
+                    &format!("\u{1b}]2;{}\u{7}", status_line),
+                );
+                self.status_lines_in_title.insert(*client_id, status_line);
+            }
+        }
+    }
+
     /// Returns a mutable reference to this [`Screen`]'s tabs.
     pub fn get_tabs_mut(&mut self) -> &mut BTreeMap<usize, Tab> {
         &mut self.tabs
@@ -801,6 +1377,172 @@ impl Screen {
         &mut self.overlay.overlay_stack
     }
 
+    /// Writes the arrangement of the tabs and their tiled panes to `file`, as a layout that can be
+    /// loaded again to recreate them
+    pub fn dump_layout(&mut self, file: String, client_id: ClientId) {
+        let client_id = if self.active_tab_indices.contains_key(&client_id) {
+            client_id
+        } else {
+            match self.get_first_client_id() {
+                Some(client_id) => client_id,
+                None => {
+                    log::error!("No client ids in screen found");
+                    return;
+                },
+            }
+        };
+        let active_tab_index = self.active_tab_indices.get(&client_id).copied();
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        let layout = Layout {
+            tabs: tabs
+                .iter()
+                .map(|tab| (Some(tab.name.clone()), tab.current_layout(client_id)))
+                .collect(),
+            focused_tab_index: tabs
+                .iter()
+                .position(|tab| Some(tab.index) == active_tab_index),
+            template: None,
+        };
+        if let Some(active_tab) = self.get_active_tab_mut(client_id) {
+            active_tab.write_to_file(layout.to_kdl(), Some(file));
+        }
+    }
+
+    /// Asks the client to confirm closing its focused pane if that pane is running a program
+    /// other than its shell. Returns whether the client was asked.
+    pub fn prompt_before_closing_focused_pane(&mut self, client_id: ClientId) -> Result<bool> {
+        if !self.confirm_pane_close {
+            return Ok(false);
+        }
+        let pane_id = match self
+            .get_active_tab(client_id)
+            .and_then(|tab| tab.focused_pane_with_foreground_process(client_id))
+        {
+            Some(pane_id) => pane_id,
+            None => return Ok(false),
+        };
+        let err_context =
+            || format!("failed to ask client {client_id} to confirm closing pane {pane_id:?}");
+
+        let prompt = Prompt::new(
+            " This pane is running a program, close it anyway? [Y]es / [N]o".to_string(),
+            None,
+            None,
+        );
+        self.pane_close_to_confirm = Some((client_id, pane_id));
+        self.show_prompt(prompt, client_id)
+            .with_context(err_context)?;
+        Ok(true)
+    }
+
+    /// Quits the client, first asking it to confirm if it would leave more than one tab behind.
+    pub fn confirm_quit(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to quit client {client_id}");
+
+        let quit = ServerInstruction::ClientExit(client_id);
+        if self.tabs.len() > 1 {
+            let prompt = Prompt::new(
+                format!(
+                    " There are {} tabs open, quit anyway? [Y]es / [N]o",
+                    self.tabs.len()
+                ),
+                Some(Box::new(quit)),
+                None,
+            );
+            self.show_prompt(prompt, client_id)
+                .with_context(err_context)
+        } else {
+            self.bus
+                .senders
+                .send_to_server(quit)
+                .with_context(err_context)
+        }
+    }
+
+    fn show_prompt(&mut self, prompt: Prompt, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to show prompt to client {client_id}");
+
+        self.get_active_overlays_mut().push(Overlay {
+            overlay_type: OverlayType::Prompt(prompt),
+        });
+        let mut mode_info = self
+            .mode_info
+            .get(&client_id)
+            .unwrap_or(&self.default_mode_info)
+            .clone();
+        mode_info.mode = InputMode::Prompt;
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::Update(
+                None,
+                Some(client_id),
+                Event::ModeUpdate(mode_info.clone()),
+            ))
+            .with_context(err_context)?;
+        self.change_mode(mode_info, client_id)
+            .with_context(err_context)?;
+        if let Some(os_input) = &mut self.bus.os_input {
+            let _ = os_input.send_to_client(
+                client_id,
+                ServerToClientMsg::SwitchToMode(InputMode::Prompt),
+            );
+        }
+        Ok(())
+    }
+
+    /// Removes the keybinding help if it is the topmost overlay, redrawing the panes it covered
+    fn hide_help(&mut self) {
+        if self
+            .get_active_overlays_mut()
+            .last()
+            .map(|overlay| overlay.is_help())
+            .unwrap_or(false)
+        {
+            self.get_active_overlays_mut().pop();
+            for tab in self.tabs.values_mut() {
+                tab.set_force_render();
+            }
+        }
+    }
+
+    /// Scrolls the keybinding help by `lines` (up if negative) if it is the topmost overlay,
+    /// returns whether it was
+    fn scroll_help(&mut self, lines: isize) -> bool {
+        let size = self.size;
+        match self
+            .get_active_overlays_mut()
+            .last_mut()
+            .and_then(|overlay| overlay.help_mut())
+        {
+            Some(help) => {
+                help.scroll(lines, size);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Closes the pane whose closing was waiting to be confirmed, if any.
+    pub fn close_pane_to_confirm(&mut self) -> Result<()> {
+        if let Some((client_id, pane_id)) = self.pane_close_to_confirm.take() {
+            let err_context =
+                || format!("failed to close pane {pane_id:?} after client {client_id} confirmed");
+            for tab in self.tabs.values_mut() {
+                if tab.get_all_pane_ids().contains(&pane_id) {
+                    tab.close_pane(pane_id, false);
+                    self.bus
+                        .senders
+                        .send_to_pty(PtyInstruction::ClosePane(pane_id))
+                        .with_context(err_context)?;
+                    break;
+                }
+            }
+            self.update_tabs().with_context(err_context)?;
+        }
+        Ok(())
+    }
+
     /// Returns a mutable reference to this [`Screen`]'s indexed [`Tab`].
     pub fn get_indexed_tab_mut(&mut self, tab_index: usize) -> Option<&mut Tab> {
         self.get_tabs_mut().get_mut(&tab_index)
@@ -814,6 +1556,201 @@ impl Screen {
         new_ids: Vec<u32>,
         client_id: ClientId,
     ) -> Result<()> {
+        self.new_tab_with(client_id, |tab, tab_index, client_id| {
+            tab.apply_layout(layout, new_ids, tab_index, client_id)
+        })
+    }
+
+    /// Moves the pane focused by this client out of its [`Tab`] and into a new [`Tab`] of its
+    /// own, switching to it. Does nothing if the pane is already alone in its tab.
+    pub fn move_focused_pane_to_new_tab(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context =
+            || format!("failed to move focused pane to a new tab for client {client_id}");
+        let extracted_pane = self
+            .get_active_tab_mut(client_id)
+            .and_then(|active_tab| active_tab.extract_focused_pane(client_id));
+        match extracted_pane {
+            Some(pane) => self
+                .new_tab_with(client_id, |tab, _tab_index, client_id| {
+                    tab.add_tiled_pane(pane, client_id)
+                })
+                .with_context(err_context),
+            None => {
+                log::info!("Focused pane can not be moved to a new tab, leaving it in place");
+                Ok(())
+            },
+        }
+    }
+
+    /// Moves the pane focused by this client into the neighbouring [`Tab`] in the given direction
+    /// (`Left` and `Up` being the previous tab, `Right` and `Down` the next one), and switches to
+    /// that tab so the pane stays focused.
+    pub fn move_focused_pane_to_adjacent_tab(
+        &mut self,
+        direction: Direction,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || {
+            format!(
+                "failed to move focused pane {direction:?} to another tab for client {client_id}"
+            )
+        };
+        let active_tab_position = match self.get_active_tab(client_id) {
+            Some(active_tab) => active_tab.position,
+            None => {
+                log::error!("Active tab not found for client id: {client_id:?}");
+                return Ok(());
+            },
+        };
+        let target_tab_position = match direction {
+            Direction::Left | Direction::Up => active_tab_position.checked_sub(1),
+            Direction::Right | Direction::Down => Some(active_tab_position + 1),
+        };
+        let target_tab = target_tab_position.and_then(|target_tab_position| {
+            self.tabs
+                .values_mut()
+                .find(|tab| tab.position == target_tab_position)
+        });
+        let target_tab = match target_tab {
+            Some(target_tab) => target_tab,
+            None => {
+                log::info!("No tab to the {direction:?} to move the focused pane to");
+                return Ok(());
+            },
+        };
+        let (target_tab_index, target_tab_position) = (target_tab.index, target_tab.position);
+        if target_tab.is_at_max_panes() {
+            let message = format!(
+                "Tab #{} has reached the maximum number of panes, pane not moved",
+                target_tab_position + 1
+            );
+            log::error!("{}", message);
+            return self
+                .bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(
+                    None,
+                    Some(client_id),
+                    Event::StatusMessage(message),
+                ))
+                .with_context(err_context);
+        }
+        if !target_tab.has_room_for_new_tiled_pane() {
+            log::error!("No room for the focused pane in the target tab, not moving it");
+            return Ok(());
+        }
+        let extracted_pane = self
+            .get_active_tab_mut(client_id)
+            .and_then(|active_tab| active_tab.extract_focused_pane(client_id));
+        if let Some(pane) = extracted_pane {
+            self.switch_active_tab(target_tab_position, client_id)
+                .with_context(err_context)?;
+            if let Some(target_tab) = self.get_indexed_tab_mut(target_tab_index) {
+                target_tab
+                    .add_tiled_pane(pane, client_id)
+                    .with_context(err_context)?;
+            }
+            self.update_tabs().with_context(err_context)?;
+        }
+        Ok(())
+    }
+
+    /// Pins the floating pane focused by this client, so that it follows it to whichever [`Tab`]
+    /// it switches to, or unpins it if it is already pinned.
+    pub fn toggle_active_pane_pinned(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to toggle pinning the pane of client {client_id}");
+        let focused_floating_pane_id = match self.get_active_tab(client_id) {
+            Some(active_tab) if active_tab.are_floating_panes_visible() => {
+                active_tab.get_active_pane_id(client_id)
+            },
+            Some(_) => None,
+            None => {
+                log::error!("Active tab not found for client id: {client_id:?}");
+                return Ok(());
+            },
+        };
+        let message = match focused_floating_pane_id {
+            // plugins are bound to the tab they were loaded in
+            Some(pane_id @ PaneId::Terminal(_)) => {
+                if self.pinned_panes.remove(&pane_id).is_some() {
+                    "Pane unpinned"
+                } else {
+                    self.pinned_panes.insert(pane_id, client_id);
+                    "Pane pinned, it will stay on screen when switching tabs"
+                }
+            },
+            _ => "Only floating terminal panes can be pinned",
+        };
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::Update(
+                None,
+                Some(client_id),
+                Event::StatusMessage(message.to_owned()),
+            ))
+            .with_context(err_context)
+    }
+
+    /// Moves the panes this client pinned in the source [`Tab`] to the destination [`Tab`], keeping
+    /// them shown and focused if they were before. They are left where they are while other
+    /// clients are still looking at the source tab.
+    fn move_pinned_panes(
+        &mut self,
+        source_tab_index: usize,
+        destination_tab_index: usize,
+        client_id: ClientId,
+    ) -> Result<()> {
+        if self.pinned_panes.is_empty() {
+            return Ok(());
+        }
+        let err_context = || {
+            format!(
+                "failed to move pinned panes from tab {source_tab_index} to tab {destination_tab_index}"
+            )
+        };
+        let source_tab = self
+            .tabs
+            .get_mut(&source_tab_index)
+            .context("failed to get source tab by index")
+            .with_context(err_context)?;
+        if !source_tab.has_no_connected_clients() {
+            return Ok(());
+        }
+        let floating_panes_were_visible = source_tab.are_floating_panes_visible();
+        let focused_pane_id = source_tab.get_active_pane_id(client_id);
+        let mut pinned_panes = vec![];
+        let pinned_by_client = self
+            .pinned_panes
+            .iter()
+            .filter(|(_, pinned_by)| **pinned_by == client_id);
+        for (pane_id, _) in pinned_by_client {
+            if let Some(pane) = source_tab.extract_floating_pane(*pane_id) {
+                pinned_panes.push(pane);
+            }
+        }
+        let destination_tab = self
+            .tabs
+            .get_mut(&destination_tab_index)
+            .context("failed to get destination tab by index")
+            .with_context(err_context)?;
+        for pane in pinned_panes {
+            let focus = floating_panes_were_visible && focused_pane_id == Some(pane.pid());
+            destination_tab.add_floating_pane(pane, floating_panes_were_visible, focus, client_id);
+        }
+        // forget the pinned panes that have since been closed
+        let tabs = &self.tabs;
+        self.pinned_panes.retain(|pane_id, _| {
+            tabs.values()
+                .any(|tab| tab.get_all_pane_ids().contains(pane_id))
+        });
+        Ok(())
+    }
+
+    /// Creates a new [`Tab`], populates it with `populate_tab` and switches to it.
+    fn new_tab_with<F>(&mut self, client_id: ClientId, populate_tab: F) -> Result<()>
+    where
+        F: FnOnce(&mut Tab, usize, ClientId) -> Result<()>,
+    {
         let client_id = if self.get_active_tab(client_id).is_some() {
             client_id
         } else if let Some(first_client_id) = self.get_first_client_id() {
@@ -822,6 +1759,7 @@ impl Screen {
             client_id
         };
         let err_context = || format!("failed to create new tab for client {client_id:?}",);
+        let previous_tab_index = self.get_active_tab(client_id).map(|tab| tab.index);
         let tab_index = self.get_new_tab_index();
         let position = self.tabs.len();
         let mut tab = Tab::new(
@@ -847,9 +1785,12 @@ impl Screen {
             self.copy_options.clone(),
             self.terminal_emulator_colors.clone(),
             self.terminal_emulator_color_codes.clone(),
+            self.resize_percent,
+            self.undo_close_depth,
+            self.auto_layout,
         );
-        tab.apply_layout(layout, new_ids, tab_index, client_id)
-            .with_context(err_context)?;
+        tab.set_min_pane_size(self.min_pane_width, self.min_pane_height);
+        populate_tab(&mut tab, tab_index, client_id).with_context(err_context)?;
         if self.session_is_mirrored {
             if let Some(active_tab) = self.get_active_tab_mut(client_id) {
                 let client_mode_infos_in_source_tab = active_tab.drain_connected_clients(None);
@@ -877,6 +1818,10 @@ impl Screen {
         tab.update_input_modes().with_context(err_context)?;
         tab.visible(true).with_context(err_context)?;
         self.tabs.insert(tab_index, tab);
+        if let Some(previous_tab_index) = previous_tab_index {
+            self.move_pinned_panes(previous_tab_index, tab_index, client_id)
+                .with_context(err_context)?;
+        }
         if !self.active_tab_indices.contains_key(&client_id) {
             // this means this is a new client and we need to add it to our state properly
             self.add_client(client_id).with_context(err_context)?;
@@ -911,11 +1856,14 @@ impl Screen {
         self.active_tab_indices.insert(client_id, tab_index);
         self.connected_clients.borrow_mut().insert(client_id);
         self.tab_history.insert(client_id, tab_history);
-        self.tabs
+        let tab = self
+            .tabs
             .get_mut(&tab_index)
-            .with_context(|| err_context(tab_index))?
-            .add_client(client_id, None)
-            .with_context(|| err_context(tab_index))
+            .with_context(|| err_context(tab_index))?;
+        tab.add_client(client_id, None)
+            .with_context(|| err_context(tab_index))?;
+        // the tab might have been hidden if all clients were previously detached from it
+        tab.visible(true).with_context(|| err_context(tab_index))
     }
 
     pub fn remove_client(&mut self, client_id: ClientId) -> Result<()> {
@@ -933,6 +1881,8 @@ impl Screen {
         if self.tab_history.contains_key(&client_id) {
             self.tab_history.remove(&client_id);
         }
+        self.status_lines_in_title.remove(&client_id);
+        self.client_sizes.remove(&client_id);
         self.connected_clients.borrow_mut().remove(&client_id);
         self.update_tabs().with_context(err_context)
     }
@@ -962,8 +1912,10 @@ impl Screen {
                     is_sync_panes_active: tab.is_sync_panes_active(),
                     are_floating_panes_visible: tab.are_floating_panes_visible(),
                     other_focused_clients,
+                    has_unseen_bell: tab.has_unseen_bell,
                 });
             }
+            tab_data.sort_by_key(|tab_info| tab_info.position);
             self.bus
                 .senders
                 .send_to_plugin(PluginInstruction::Update(
@@ -997,7 +1949,7 @@ impl Screen {
                         },
                         c => {
                             // It only allows printable unicode
-                            if buf.iter().all(|u| matches!(u, 0x20..=0x7E)) {
+                            if c.chars().all(|c| !c.is_control()) {
                                 active_tab.name.push_str(c);
                             }
                         },
@@ -1067,6 +2019,23 @@ impl Screen {
             }
         }
 
+        let pane_close_is_pending = self
+            .pane_close_to_confirm
+            .map(|(pending_client_id, _)| pending_client_id == client_id)
+            .unwrap_or(false);
+        if pane_close_is_pending && mode_info.mode != InputMode::Prompt {
+            // leaving the prompt without answering it abandons the pane close
+            self.pane_close_to_confirm = None;
+            self.get_active_overlays_mut().pop();
+        }
+
+        if mode_info.mode == InputMode::Help && previous_mode != InputMode::Help {
+            self.get_active_overlays_mut()
+                .push(generate_help(mode_info.keybinds.clone()));
+        } else if previous_mode == InputMode::Help && mode_info.mode != InputMode::Help {
+            self.hide_help();
+        }
+
         if mode_info.mode == InputMode::RenameTab {
             if let Some(active_tab) = self.get_active_tab_mut(client_id) {
                 active_tab.prev_name = active_tab.name.clone();
@@ -1173,7 +2142,7 @@ impl Screen {
 #[allow(clippy::boxed_local)]
 pub(crate) fn screen_thread_main(
     bus: Bus<ScreenInstruction>,
-    max_panes: Option<usize>,
+    max_panes: Option<MaxPanes>,
     client_attributes: ClientAttributes,
     config_options: Box<Options>,
 ) -> Result<()> {
@@ -1184,7 +2153,29 @@ pub(crate) fn screen_thread_main(
         config_options.copy_command,
         config_options.copy_clipboard.unwrap_or_default(),
         config_options.copy_on_select.unwrap_or(true),
+        config_options.clipboard_from_panes.unwrap_or(true),
+        config_options.middle_click_paste.unwrap_or(true),
     );
+    let full_redraw = config_options.full_redraw.unwrap_or(false);
+    let move_tab_wraparound = config_options.move_tab_wraparound.unwrap_or(false);
+    let confirm_pane_close = config_options.confirm_pane_close.unwrap_or(true);
+    let resize_percent = config_options.resize_percent.unwrap_or(5).clamp(1, 100) as f64;
+    let status_in_title = config_options.status_in_title.unwrap_or(false);
+    let undo_close_depth = config_options.undo_close_depth.unwrap_or(10);
+    let auto_layout = config_options.auto_layout.unwrap_or_default();
+    let bell_style = config_options.bell_style.unwrap_or_default();
+    let go_to_tab_creates_tab = config_options.go_to_tab_creates_tab.unwrap_or(false);
+    let scroll_wheel_lines = config_options.scroll_wheel_lines.unwrap_or(3);
+    let min_pane_width = config_options.min_pane_width;
+    let min_pane_height = config_options.min_pane_height;
+    let persist_scrollback_max_size = if config_options.persist_scrollback.unwrap_or(false) {
+        let max_size_in_megabytes = config_options
+            .persist_scrollback_max_size
+            .unwrap_or(DEFAULT_PERSIST_SCROLLBACK_MAX_SIZE);
+        Some(max_size_in_megabytes * 1024 * 1024)
+    } else {
+        None
+    };
 
     let mut screen = Screen::new(
         bus,
@@ -1200,25 +2191,85 @@ pub(crate) fn screen_thread_main(
         draw_pane_frames,
         session_is_mirrored,
         copy_options,
+        full_redraw,
+        move_tab_wraparound,
+        confirm_pane_close,
+        resize_percent,
+        status_in_title,
+        undo_close_depth,
+        auto_layout,
+        bell_style,
+        persist_scrollback_max_size,
+        go_to_tab_creates_tab,
+        scroll_wheel_lines,
+        min_pane_width,
+        min_pane_height,
     );
+    if let Some(max_size) = screen.persist_scrollback_max_size {
+        screen.restore_persisted_scrollback();
+        screen.scrollback_writer = Some(persisted_scrollback::spawn_writer(
+            persisted_scrollback::scrollback_dir(),
+            max_size,
+        ));
+        let senders = screen.bus.senders.clone();
+        let _ = std::thread::Builder::new()
+            .name("persist_scrollback".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(PERSIST_SCROLLBACK_INTERVAL);
+                // the screen is gone once the session ends
+                if senders
+                    .send_to_screen(ScreenInstruction::PersistScrollback)
+                    .is_err()
+                {
+                    break;
+                }
+            });
+    }
 
     loop {
-        let (event, mut err_ctx) = screen
-            .bus
-            .recv()
-            .context("failed to receive event on channel")?;
+        if let Some(bell_flash_ends_at) = screen.bell_flash_ends_at {
+            if bell_flash_ends_at <= Instant::now() {
+                screen.bell_flash_ends_at = None;
+                screen.render()?;
+            }
+        }
+        let (event, mut err_ctx) = match screen.bell_flash_ends_at {
+            Some(bell_flash_ends_at) => {
+                let timeout = bell_flash_ends_at.saturating_duration_since(Instant::now());
+                match screen
+                    .bus
+                    .recv_timeout(timeout)
+                    .context("failed to receive event on channel")?
+                {
+                    Some(event) => event,
+                    None => continue,
+                }
+            },
+            None => screen
+                .bus
+                .recv()
+                .context("failed to receive event on channel")?,
+        };
         err_ctx.add_call(ContextType::Screen((&event).into()));
 
         match event {
             ScreenInstruction::PtyBytes(pid, vte_bytes) => {
+                screen.show_restored_scrollback(pid)?;
+                let mut tab_that_rang_bell = None;
                 let all_tabs = screen.get_tabs_mut();
-                for tab in all_tabs.values_mut() {
+                for (tab_index, tab) in all_tabs.iter_mut() {
                     if tab.has_terminal_pid(pid) {
                         tab.handle_pty_bytes(pid, vte_bytes)
                             .context("failed to process pty bytes")?;
+                        if tab.take_bell(pid) {
+                            tab_that_rang_bell = Some(*tab_index);
+                        }
                         break;
                     }
                 }
+                if let Some(tab_index) = tab_that_rang_bell {
+                    screen.ring_bell(tab_index, pid)?;
+                }
             },
             ScreenInstruction::Render => {
                 screen.render()?;
@@ -1267,6 +2318,10 @@ pub(crate) fn screen_thread_main(
 
                 screen.render()?;
             },
+            ScreenInstruction::TogglePanePinned(client_id) => {
+                screen.toggle_active_pane_pinned(client_id)?;
+                screen.render()?;
+            },
             ScreenInstruction::ToggleFloatingPanes(client_id, default_shell) => {
                 active_tab_and_connected_client_id!(screen, client_id, |tab: &mut Tab, client_id: ClientId| tab
                     .toggle_floating_panes(client_id, default_shell), ?);
@@ -1364,6 +2419,42 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
                 screen.render()?;
             },
+            ScreenInstruction::ResizeFine(direction, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.resize_fine(direction, client_id)
+                );
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::EqualizePanes(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, _client_id: ClientId| tab.equalize_panes()
+                );
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::ApplyAutoLayout(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, _client_id: ClientId| tab.apply_auto_layout()
+                );
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::ResizeToFraction(fraction, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .resize_to_fraction(fraction.as_f64(), client_id)
+                );
+                screen.render()?;
+            },
             ScreenInstruction::SwitchFocus(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -1450,6 +2541,22 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::PipePane(command, client_id, full) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.pipe_active_pane_to_command(
+                        &command,
+                        client_id,
+                        full
+                    ), ?
+                );
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::DumpLayout(file, client_id) => {
+                screen.dump_layout(file, client_id);
+                screen.unblock_input()?;
+            },
             ScreenInstruction::EditScrollback(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -1460,11 +2567,14 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
             },
             ScreenInstruction::ScrollUp(client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.scroll_active_terminal_up(client_id)
-                );
+                if !screen.scroll_help(-1) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .scroll_active_terminal_up(client_id), ?
+                    );
+                }
                 screen.unblock_input()?;
                 screen.render()?;
             },
@@ -1514,31 +2624,39 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
             },
             ScreenInstruction::ScrollUpAt(point, client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab
-                        .handle_scrollwheel_up(&point, 3, client_id), ?
-                );
+                let lines = screen.scroll_wheel_lines;
+                if !screen.scroll_help(-(lines as isize)) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .handle_scrollwheel_up(&point, lines, client_id), ?
+                    );
+                }
                 screen.render()?;
                 screen.unblock_input()?;
             },
             ScreenInstruction::ScrollDown(client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.scroll_active_terminal_down(client_id), ?
-                );
+                if !screen.scroll_help(1) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab.scroll_active_terminal_down(client_id), ?
+                    );
+                }
                 screen.render()?;
                 screen.unblock_input()?;
             },
             ScreenInstruction::ScrollDownAt(point, client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab
-                        .handle_scrollwheel_down(&point, 3, client_id), ?
-                );
+                let lines = screen.scroll_wheel_lines;
+                if !screen.scroll_help(lines as isize) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .handle_scrollwheel_down(&point, lines, client_id), ?
+                    );
+                }
                 screen.render()?;
                 screen.unblock_input()?;
             },
@@ -1552,42 +2670,89 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
                 screen.unblock_input()?;
             },
-            ScreenInstruction::PageScrollUp(client_id) => {
+            ScreenInstruction::ToggleFollowOutput(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_up_page(client_id)
+                        .toggle_active_pane_follow_output(client_id), ?
                 );
                 screen.render()?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::PageScrollUp(client_id) => {
+                if !screen.scroll_help(-(screen.size.rows as isize)) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .scroll_active_terminal_up_page(client_id), ?
+                    );
+                }
+                screen.render()?;
+                screen.unblock_input()?;
+            },
             ScreenInstruction::PageScrollDown(client_id) => {
+                if !screen.scroll_help(screen.size.rows as isize) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .scroll_active_terminal_down_page(client_id), ?
+                    );
+                }
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::HalfPageScrollUp(client_id) => {
+                if !screen.scroll_help(-(screen.size.rows as isize / 2)) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .scroll_active_terminal_up_half_page(client_id), ?
+                    );
+                }
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::HalfPageScrollDown(client_id) => {
+                if !screen.scroll_help(screen.size.rows as isize / 2) {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab
+                            .scroll_active_terminal_down_half_page(client_id), ?
+                    );
+                }
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ScrollLeft(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_down_page(client_id), ?
+                    |tab: &mut Tab, client_id: ClientId| tab.scroll_active_terminal_left(client_id)
                 );
                 screen.render()?;
                 screen.unblock_input()?;
             },
-            ScreenInstruction::HalfPageScrollUp(client_id) => {
+            ScreenInstruction::ScrollRight(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_up_half_page(client_id)
+                        .scroll_active_terminal_right(client_id)
                 );
                 screen.render()?;
                 screen.unblock_input()?;
             },
-            ScreenInstruction::HalfPageScrollDown(client_id) => {
+            ScreenInstruction::ToggleLineWrap(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_down_half_page(client_id), ?
+                        .toggle_active_pane_line_wrap(client_id)
                 );
                 screen.render()?;
                 screen.unblock_input()?;
@@ -1603,13 +2768,46 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
             },
             ScreenInstruction::CloseFocusedPane(client_id) => {
+                if !screen.prompt_before_closing_focused_pane(client_id)? {
+                    active_tab_and_connected_client_id!(
+                        screen,
+                        client_id,
+                        |tab: &mut Tab, client_id: ClientId| tab.close_focused_pane(client_id), ?
+                    );
+                    screen.update_tabs()?;
+                }
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::UndoClosePane(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.undo_close_pane(client_id),
+                    ?
+                );
+            },
+            ScreenInstruction::ReopenClosedPane(pid, closed_pane, client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.close_focused_pane(client_id), ?
+                    |tab: &mut Tab, client_id: ClientId| tab.reopen_closed_pane(
+                        pid,
+                        closed_pane,
+                        client_id
+                    ),
+                    ?
                 );
+                screen.unblock_input()?;
                 screen.update_tabs()?;
                 screen.render()?;
+            },
+            ScreenInstruction::SignalFocusedPane(signal, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.signal_focused_pane(client_id, signal)
+                );
                 screen.unblock_input()?;
             },
             ScreenInstruction::SetSelectable(id, selectable, tab_index) => {
@@ -1664,6 +2862,20 @@ pub(crate) fn screen_thread_main(
                 screen.update_tabs()?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::PersistScrollback => {
+                // failing to write the scrollback to disk should not take the session down
+                screen.persist_scrollback().non_fatal();
+            },
+            ScreenInstruction::RestartCommandInPane(id, run_command) => {
+                // the pane might have been closed while it was waiting to be restarted
+                if let Some(tab) = screen
+                    .tabs
+                    .values_mut()
+                    .find(|tab| tab.get_all_pane_ids().contains(&id))
+                {
+                    tab.restart_command_in_pane(id, run_command)?;
+                }
+            },
             ScreenInstruction::UpdatePaneName(c, client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -1711,6 +2923,26 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
                 screen.render()?;
             },
+            ScreenInstruction::MoveTabLeft(client_id) => {
+                screen.move_active_tab_left(client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::MoveTabRight(client_id) => {
+                screen.move_active_tab_right(client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::MovePaneToNewTab(client_id) => {
+                screen.move_focused_pane_to_new_tab(client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::MovePaneToTab(direction, client_id) => {
+                screen.move_focused_pane_to_adjacent_tab(direction, client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
             ScreenInstruction::CloseTab(client_id) => {
                 screen.close_tab(client_id)?;
                 screen.unblock_input()?;
@@ -1738,6 +2970,28 @@ pub(crate) fn screen_thread_main(
                     screen.render()?;
                 }
             },
+            ScreenInstruction::GoToTabWithId(tab_id, client_id) => {
+                screen.go_to_tab_with_id(tab_id as usize, client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::FocusPaneWithId(pane_id, client_id) => {
+                screen.focus_pane_with_id(pane_id, client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::RestoreFocus(client_id) => {
+                screen.restore_focus_after_cli_action(client_id)?;
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::ListPanes(client_id) => {
+                screen.list_panes(client_id)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ReportTabCount(client_id) => {
+                screen.report_tab_count(client_id)?;
+            },
             ScreenInstruction::UpdateTabName(c, client_id) => {
                 screen.update_active_tab_name(c, client_id)?;
                 screen.unblock_input()?;
@@ -1752,6 +3006,9 @@ pub(crate) fn screen_thread_main(
                 screen.resize_to_screen(new_size)?;
                 screen.render()?;
             },
+            ScreenInstruction::ClientResize(client_id, client_size) => {
+                screen.client_sizes.insert(client_id, client_size);
+            },
             ScreenInstruction::TerminalPixelDimensions(pixel_dimensions) => {
                 screen.update_pixel_dimensions(pixel_dimensions);
             },
@@ -1836,12 +3093,31 @@ pub(crate) fn screen_thread_main(
                     .handle_mouse_hold_middle(&point, client_id), ?);
                 screen.render()?;
             },
+            ScreenInstruction::MouseMove(point, client_id) => {
+                if screen.mouse_moved(point, client_id, Instant::now()) {
+                    screen.focus_follows_mouse_after(FOCUS_FOLLOWS_MOUSE_DEBOUNCE, client_id);
+                }
+            },
+            ScreenInstruction::FocusFollowsMouse(client_id) => {
+                if let Some(delay) = screen.mouse_rested(client_id, Instant::now())? {
+                    screen.focus_follows_mouse_after(delay, client_id);
+                }
+            },
             ScreenInstruction::Copy(client_id) => {
                 active_tab!(screen, client_id, |tab: &mut Tab| tab
                     .copy_selection(client_id), ?);
                 screen.render()?;
             },
             ScreenInstruction::Exit => {
+                // the session ended cleanly, the next one with its name starts afresh once the
+                // writes queued before are done
+                if let Some((writer, handle)) = screen.scrollback_writer.take() {
+                    if let Some(session_name) = screen.default_mode_info.session_name.clone() {
+                        let _ = writer.send(ScrollbackWrite::Remove(session_name));
+                    }
+                    drop(writer);
+                    let _ = handle.join();
+                }
                 break;
             },
             ScreenInstruction::ToggleTab(client_id) => {
@@ -1878,10 +3154,17 @@ pub(crate) fn screen_thread_main(
                         .send_to_server(*instruction)
                         .context("failed to confirm prompt")?;
                 }
+                screen.close_pane_to_confirm()?;
+                screen.render()?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::ConfirmQuit(client_id) => {
+                screen.confirm_quit(client_id)?;
+                screen.render()?;
+            },
             ScreenInstruction::DenyPrompt(_client_id) => {
                 screen.get_active_overlays_mut().pop();
+                screen.pane_close_to_confirm = None;
                 screen.render()?;
                 screen.unblock_input()?;
             },
@@ -1897,7 +3180,7 @@ pub(crate) fn screen_thread_main(
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.search_down(client_id)
+                    |tab: &mut Tab, client_id: ClientId| tab.search_down(client_id), ?
                 );
                 screen.render()?;
             },
@@ -1905,7 +3188,7 @@ pub(crate) fn screen_thread_main(
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.search_up(client_id)
+                    |tab: &mut Tab, client_id: ClientId| tab.search_up(client_id), ?
                 );
                 screen.render()?;
                 screen.unblock_input()?;
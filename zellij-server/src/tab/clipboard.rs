@@ -1,49 +1,64 @@
-use zellij_utils::{anyhow::Result, data::CopyDestination, input::options::Clipboard};
+use zellij_utils::{data::CopyDestination, input::options::Clipboard};
 
 use crate::ClientId;
 
 use super::{copy_command::CopyCommand, Output};
 
 pub(crate) enum ClipboardProvider {
-    Command(CopyCommand),
+    /// The copy command, and the clipboard to copy to with OSC 52 if it cannot be run
+    Command(CopyCommand, Clipboard),
     Osc52(Clipboard),
 }
 
 impl ClipboardProvider {
+    /// Copies `content`, returning where it was copied to. This can't fail, as copying with
+    /// OSC 52 is left to the terminal emulators of the clients.
     pub(crate) fn set_content(
         &self,
         content: &str,
         output: &mut Output,
         client_ids: impl Iterator<Item = ClientId>,
-    ) -> Result<()> {
+    ) -> CopyDestination {
         match &self {
-            ClipboardProvider::Command(command) => {
-                command.set(content.to_string())?;
+            ClipboardProvider::Command(command, fallback_clipboard) => {
+                match command.set(content.to_string()) {
+                    Ok(()) => CopyDestination::Command,
+                    Err(e) => {
+                        log::warn!("{:?}, copying with OSC 52 instead", e);
+                        set_content_with_osc52(fallback_clipboard, content, output, client_ids)
+                    },
+                }
             },
             ClipboardProvider::Osc52(clipboard) => {
-                let dest = match clipboard {
-                    #[cfg(not(target_os = "macos"))]
-                    Clipboard::Primary => 'p',
-                    #[cfg(target_os = "macos")] // primary selection does not exist on macos
-                    Clipboard::Primary => 'c',
-                    Clipboard::System => 'c',
-                };
-                output.add_pre_vte_instruction_to_multiple_clients(
-                    client_ids,
-                    &format!("\u{1b}]52;{};{}\u{1b}\\", dest, base64::encode(content)),
-                );
+                set_content_with_osc52(clipboard, content, output, client_ids)
             },
-        };
-        Ok(())
+        }
     }
+}
 
-    pub(crate) fn as_copy_destination(&self) -> CopyDestination {
-        match self {
-            ClipboardProvider::Command(_) => CopyDestination::Command,
-            ClipboardProvider::Osc52(clipboard) => match clipboard {
-                Clipboard::Primary => CopyDestination::Primary,
-                Clipboard::System => CopyDestination::System,
-            },
-        }
+fn set_content_with_osc52(
+    clipboard: &Clipboard,
+    content: &str,
+    output: &mut Output,
+    client_ids: impl Iterator<Item = ClientId>,
+) -> CopyDestination {
+    let dest = match clipboard {
+        #[cfg(not(target_os = "macos"))]
+        Clipboard::Primary => 'p',
+        #[cfg(target_os = "macos")] // primary selection does not exist on macos
+        Clipboard::Primary => 'c',
+        Clipboard::System => 'c',
+    };
+    output.add_pre_vte_instruction_to_multiple_clients(
+        client_ids,
+        &format!("\u{1b}]52;{};{}\u{1b}\\", dest, base64::encode(content)),
+    );
+    match clipboard {
+        Clipboard::Primary => CopyDestination::Primary,
+        Clipboard::System => CopyDestination::System,
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/clipboard_tests.rs"]
+mod clipboard_tests;
@@ -7,13 +7,15 @@ mod copy_command;
 use copy_command::CopyCommand;
 use std::env::temp_dir;
 use uuid::Uuid;
+use zellij_utils::consts::{DEFAULT_PENDING_OUTPUT_LIMIT, PENDING_OUTPUT_LIMIT};
 use zellij_utils::errors::prelude::*;
 use zellij_utils::input::command::RunCommand;
+use zellij_utils::nix::sys::signal::Signal;
 use zellij_utils::position::{Column, Line};
 use zellij_utils::{position::Position, serde};
 
 use crate::pty_writer::PtyWriteInstruction;
-use crate::screen::CopyOptions;
+use crate::screen::{CopyOptions, MaxPanes};
 use crate::ui::pane_boundaries_frame::FrameParams;
 
 use self::clipboard::ClipboardProvider;
@@ -22,7 +24,9 @@ use crate::{
     output::{CharacterChunk, Output, SixelImageChunk},
     panes::sixel::SixelImageStore,
     panes::{FloatingPanes, TiledPanes},
-    panes::{LinkHandler, PaneId, PluginPane, TerminalPane},
+    panes::{
+        LinkHandler, PaneId, PluginPane, TerminalPane, BRACKETED_PASTE_BEGIN, BRACKETED_PASTE_END,
+    },
     pty::{ClientOrTabIndex, PtyInstruction, VteBytes},
     thread_bus::ThreadSenders,
     wasm_vm::PluginInstruction,
@@ -40,8 +44,10 @@ use std::{
 use zellij_utils::{
     data::{Event, InputMode, ModeInfo, Palette, PaletteColor, Style},
     input::{
+        actions::ResizeDirection,
         command::TerminalAction,
-        layout::{PaneLayout, Run},
+        layout::{PaneLayout, Run, SplitDirection},
+        options::AutoLayout,
         parse_keys,
     },
     pane_size::{Offset, PaneGeom, Size, SizeInPixels, Viewport},
@@ -72,10 +78,11 @@ pub(crate) struct Tab {
     pub position: usize,
     pub name: String,
     pub prev_name: String,
+    pub has_unseen_bell: bool, // the bell rang in a pane while no client was looking at this tab
     tiled_panes: TiledPanes,
     floating_panes: FloatingPanes,
     suppressed_panes: HashMap<PaneId, Box<dyn Pane>>,
-    max_panes: Option<usize>,
+    max_panes: Option<MaxPanes>,
     viewport: Rc<RefCell<Viewport>>, // includes all non-UI panes
     display_area: Rc<RefCell<Size>>, // includes all panes (including eg. the status bar and tab bar in the default layout)
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
@@ -90,6 +97,9 @@ pub(crate) struct Tab {
     connected_clients: Rc<RefCell<HashSet<ClientId>>>,
     draw_pane_frames: bool,
     pending_vte_events: HashMap<u32, Vec<VteBytes>>,
+    // the number of bytes of pending vte events a pane holds on to before they are processed
+    // regardless of it being scrolled back
+    pending_output_limit: usize,
     pub selecting_with_mouse: bool, // this is only pub for the tests TODO: remove this once we combine write_text_to_clipboard with render
     link_handler: Rc<RefCell<LinkHandler>>,
     clipboard_provider: ClipboardProvider,
@@ -97,10 +107,26 @@ pub(crate) struct Tab {
     // it seems that optimization is possible using `active_panes`
     focus_pane_id: Option<PaneId>,
     copy_on_select: bool,
+    clipboard_from_panes: bool,
+    middle_click_paste: bool,
+    copied_text: Rc<RefCell<Option<String>>>,
     last_mouse_hold_position: Option<Position>,
     terminal_emulator_colors: Rc<RefCell<Palette>>,
     terminal_emulator_color_codes: Rc<RefCell<HashMap<usize, String>>>,
     pids_waiting_resize: HashSet<u32>, // u32 is the terminal_id
+    pane_runs: HashMap<PaneId, Run>,   // what the panes of the layout were started with
+    closed_panes: Vec<ClosedPane>,     // most recently closed last
+    undo_close_depth: usize,
+    horizontally_scrolled_panes: HashMap<ClientId, PaneId>,
+}
+
+/// A tiled pane closed by the user, remembered so that it can be reopened in the same place
+#[derive(Clone, Debug)]
+pub struct ClosedPane {
+    run: Option<Run>,
+    geom: PaneGeom,
+    geoms_before_closing: HashMap<PaneId, PaneGeom>,
+    geoms_after_closing: HashMap<PaneId, PaneGeom>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -155,6 +181,8 @@ pub trait Pane {
         text_color: PaletteColor,
     ) -> Option<String>;
     fn render_terminal_title(&mut self, _input_mode: InputMode) -> String;
+    /// The name the user gave the pane, or otherwise its title
+    fn current_title(&self) -> String;
     fn update_name(&mut self, name: &str);
     fn pid(&self) -> PaneId;
     fn reduce_height(&mut self, percent: f64);
@@ -172,8 +200,34 @@ pub trait Pane {
     fn scroll_down(&mut self, count: usize, client_id: ClientId);
     fn clear_scroll(&mut self);
     fn is_scrolled(&self) -> bool;
+    fn follows_output(&self) -> bool {
+        true
+    }
+    fn set_follow_output(&mut self, _follow_output: bool) {}
+    fn scrollback_position_and_length(&mut self) -> (usize, usize) {
+        (0, 0) // (position, length)
+    }
+    fn restore_scrollback_position(&mut self, _lines_from_top: usize) {}
+    fn scroll_left(&mut self, _count: usize) {}
+    /// Returns whether the pane actually scrolled
+    fn scroll_right(&mut self, _count: usize) -> bool {
+        false
+    }
+    fn clear_horizontal_scroll(&mut self) {}
+    fn toggle_line_wrap(&mut self) {}
     fn active_at(&self) -> Instant;
     fn set_active_at(&mut self, instant: Instant);
+    /// Whether the program running in this pane rang the bell since this was last called
+    fn take_bell(&mut self) -> bool {
+        false
+    }
+    /// Starts highlighting the frame of this pane for a moment, returns false if it already is
+    fn flash_bell(&mut self) -> bool {
+        false
+    }
+    fn is_flashing_bell(&self) -> bool {
+        false
+    }
     fn set_frame(&mut self, frame: bool);
     fn set_content_offset(&mut self, offset: Offset);
     fn cursor_shape_csi(&self) -> String {
@@ -338,6 +392,9 @@ pub trait Pane {
     fn search_up(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
+    fn search_found_nothing(&self) -> bool {
+        false
+    }
     fn toggle_search_case_sensitivity(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
@@ -378,7 +435,7 @@ impl Tab {
         sixel_image_store: Rc<RefCell<SixelImageStore>>,
         os_api: Box<dyn ServerOsApi>,
         senders: ThreadSenders,
-        max_panes: Option<usize>,
+        max_panes: Option<MaxPanes>,
         style: Style,
         default_mode_info: ModeInfo,
         draw_pane_frames: bool,
@@ -388,6 +445,9 @@ impl Tab {
         copy_options: CopyOptions,
         terminal_emulator_colors: Rc<RefCell<Palette>>,
         terminal_emulator_color_codes: Rc<RefCell<HashMap<usize, String>>>,
+        resize_percent: f64,
+        undo_close_depth: usize,
+        auto_layout: AutoLayout,
     ) -> Self {
         let name = if name.is_empty() {
             format!("Tab #{}", index + 1)
@@ -415,6 +475,8 @@ impl Tab {
             default_mode_info.clone(),
             style,
             os_api.clone(),
+            resize_percent,
+            auto_layout,
         );
         let floating_panes = FloatingPanes::new(
             display_area.clone(),
@@ -428,7 +490,9 @@ impl Tab {
         );
 
         let clipboard_provider = match copy_options.command {
-            Some(command) => ClipboardProvider::Command(CopyCommand::new(command)),
+            Some(command) => {
+                ClipboardProvider::Command(CopyCommand::new(command), copy_options.clipboard)
+            },
             None => ClipboardProvider::Osc52(copy_options.clipboard),
         };
 
@@ -440,6 +504,7 @@ impl Tab {
             suppressed_panes: HashMap::new(),
             name: name.clone(),
             prev_name: name,
+            has_unseen_bell: false,
             max_panes,
             viewport,
             display_area,
@@ -454,16 +519,27 @@ impl Tab {
             default_mode_info,
             draw_pane_frames,
             pending_vte_events: HashMap::new(),
+            pending_output_limit: PENDING_OUTPUT_LIMIT
+                .get()
+                .copied()
+                .unwrap_or(DEFAULT_PENDING_OUTPUT_LIMIT),
             connected_clients,
             selecting_with_mouse: false,
             link_handler: Rc::new(RefCell::new(LinkHandler::new())),
             clipboard_provider,
             focus_pane_id: None,
             copy_on_select: copy_options.copy_on_select,
+            clipboard_from_panes: copy_options.clipboard_from_panes,
+            middle_click_paste: copy_options.middle_click_paste,
+            copied_text: copy_options.copied_text,
             last_mouse_hold_position: None,
             terminal_emulator_colors,
             terminal_emulator_color_codes,
             pids_waiting_resize: HashSet::new(),
+            pane_runs: HashMap::new(),
+            horizontally_scrolled_panes: HashMap::new(),
+            closed_panes: vec![],
+            undo_close_depth,
         }
     }
 
@@ -529,6 +605,9 @@ impl Tab {
                         new_plugin.set_borderless(layout.borderless);
                         self.tiled_panes
                             .add_pane_with_existing_geom(PaneId::Plugin(pid), Box::new(new_plugin));
+                        if let Some(run) = layout.run.clone() {
+                            self.pane_runs.insert(PaneId::Plugin(pid), run);
+                        }
                         set_focus_pane_id(layout, PaneId::Plugin(pid));
                     } else {
                         // there are still panes left to fill, use the pids we received in this method
@@ -556,6 +635,9 @@ impl Tab {
                                 PaneId::Terminal(*pid),
                                 Box::new(new_pane),
                             );
+                            if let Some(run) = layout.run.clone() {
+                                self.pane_runs.insert(PaneId::Terminal(*pid), run);
+                            }
                             set_focus_pane_id(layout, PaneId::Terminal(*pid));
                         }
                     }
@@ -655,7 +737,10 @@ impl Tab {
                 // no panes here, bye bye
                 return Ok(());
             }
-            let focus_pane_id = if let Some(id) = self.focus_pane_id {
+            let focus_pane_id = if let Some(id) = self
+                .focus_pane_id
+                .filter(|id| self.tiled_panes.panes_contain(id))
+            {
                 id
             } else {
                 pane_ids.sort(); // TODO: make this predictable
@@ -694,7 +779,7 @@ impl Tab {
         Ok(())
     }
     pub fn remove_client(&mut self, client_id: ClientId) {
-        self.focus_pane_id = None;
+        self.remember_focused_pane(client_id);
         self.connected_clients.borrow_mut().remove(&client_id);
         self.set_force_render();
     }
@@ -705,7 +790,7 @@ impl Tab {
         // None => all clients
         let mut client_ids_to_mode_infos = vec![];
         let clients_to_drain = clients_to_drain
-            .unwrap_or_else(|| self.connected_clients.borrow_mut().drain().collect());
+            .unwrap_or_else(|| self.connected_clients.borrow().iter().copied().collect());
         for client_id in clients_to_drain {
             client_ids_to_mode_infos.push(self.drain_single_client(client_id));
         }
@@ -717,9 +802,19 @@ impl Tab {
             .borrow_mut()
             .remove(&client_id)
             .unwrap_or_else(|| self.default_mode_info.clone());
+        self.remember_focused_pane(client_id);
         self.connected_clients.borrow_mut().remove(&client_id);
         (client_id, client_mode_info)
     }
+    // the pane this client leaves the tab from is the one focused when a client comes back to
+    // it, be it switching back to it or reattaching to the session
+    fn remember_focused_pane(&mut self, client_id: ClientId) {
+        if self.connected_clients.borrow().contains(&client_id) {
+            if let Some(pane_id) = self.tiled_panes.get_active_pane_id(client_id) {
+                self.focus_pane_id = Some(pane_id);
+            }
+        }
+    }
     pub fn has_no_connected_clients(&self) -> bool {
         self.connected_clients.borrow().is_empty()
     }
@@ -741,6 +836,8 @@ impl Tab {
                     self.tiled_panes
                         .focus_pane(focused_floating_pane_id, client_id);
                     self.floating_panes.toggle_show_panes(false);
+                } else {
+                    log::error!("No room to embed floating pane, leaving it floating");
                 }
             }
         } else if let Some(focused_pane_id) = self.tiled_panes.focused_pane_id(client_id) {
@@ -758,10 +855,93 @@ impl Tab {
                     self.floating_panes.focus_pane(focused_pane_id, client_id);
                     self.floating_panes.toggle_show_panes(true);
                 }
+            } else {
+                log::error!("No room to float pane, leaving it embedded");
             }
         }
         Ok(())
     }
+    /// Removes the focused pane from this tab without closing it, so that it can be moved to
+    /// another tab. The remaining tiled panes grow to fill the space it leaves behind.
+    ///
+    /// Returns `None` if the focused pane is the only pane on screen (moving it would leave this
+    /// tab empty) or if it is a plugin pane, since plugins are bound to the tab they were loaded in.
+    pub fn extract_focused_pane(&mut self, client_id: ClientId) -> Option<Box<dyn Pane>> {
+        if self.floating_panes.panes_are_visible() {
+            if let Some(focused_floating_pane_id) = self.floating_panes.active_pane_id(client_id) {
+                if !matches!(focused_floating_pane_id, PaneId::Terminal(_)) {
+                    return None;
+                }
+                return self.close_pane(focused_floating_pane_id, true);
+            }
+        }
+        let focused_pane_id = self.tiled_panes.focused_pane_id(client_id)?;
+        if !matches!(focused_pane_id, PaneId::Terminal(_))
+            || self.get_selectable_tiled_panes().count() <= 1
+        {
+            return None;
+        }
+        self.close_pane(focused_pane_id, true)
+    }
+    /// Adds a pane extracted from another tab to this tab's tiled panes and focuses it.
+    /// If this tab has no tiled panes yet, the pane takes up the whole viewport.
+    pub fn add_tiled_pane(&mut self, mut pane: Box<dyn Pane>, client_id: ClientId) -> Result<()> {
+        let pane_id = pane.pid();
+        self.close_down_to_max_terminals()
+            .with_context(|| format!("failed to add pane with id {pane_id:?}"))?;
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        if self.tiled_panes.has_panes() {
+            self.tiled_panes.insert_pane(pane_id, pane);
+        } else {
+            let (viewport_cols, viewport_rows) = {
+                let viewport = self.viewport.borrow();
+                (viewport.cols, viewport.rows)
+            };
+            let mut pane_geom = PaneGeom::default();
+            pane_geom.cols.set_inner(viewport_cols);
+            pane_geom.rows.set_inner(viewport_rows);
+            pane.set_geom(pane_geom);
+            self.tiled_panes.add_pane_with_existing_geom(pane_id, pane);
+        }
+        self.tiled_panes.set_pane_frames(self.draw_pane_frames); // recalculate pane frames and update size
+        self.tiled_panes.focus_pane(pane_id, client_id);
+        self.floating_panes.toggle_show_panes(false);
+        self.should_clear_display_before_rendering = true;
+        self.set_force_render();
+        Ok(())
+    }
+    /// Removes a floating pane from this tab without closing it, so that it can be moved to
+    /// another tab.
+    pub fn extract_floating_pane(&mut self, pane_id: PaneId) -> Option<Box<dyn Pane>> {
+        if !self.floating_panes.panes_contain(&pane_id) {
+            return None;
+        }
+        self.close_pane(pane_id, true)
+    }
+    /// Adds a floating pane extracted from another tab to this tab's floating panes, keeping its
+    /// position and size.
+    pub fn add_floating_pane(
+        &mut self,
+        pane: Box<dyn Pane>,
+        show_floating_panes: bool,
+        focus: bool,
+        client_id: ClientId,
+    ) {
+        let pane_id = pane.pid();
+        self.floating_panes.add_pane(pane_id, pane);
+        if show_floating_panes {
+            self.floating_panes.toggle_show_panes(true);
+        }
+        if focus {
+            self.floating_panes.focus_pane(pane_id, client_id);
+        }
+        self.set_force_render();
+    }
+    pub fn has_room_for_new_tiled_pane(&mut self) -> bool {
+        !self.tiled_panes.has_panes() || self.tiled_panes.has_room_for_new_pane()
+    }
     pub fn toggle_floating_panes(
         &mut self,
         client_id: ClientId,
@@ -795,6 +975,7 @@ impl Tab {
             }
             self.floating_panes.set_force_render();
         }
+        self.clear_horizontal_scroll_of_unfocused_panes();
         self.set_force_render();
         Ok(())
     }
@@ -804,6 +985,33 @@ impl Tab {
         initial_pane_title: Option<String>,
         should_float: Option<bool>,
         client_id: Option<ClientId>,
+    ) -> Result<()> {
+        self.new_pane_or_reopen(pid, initial_pane_title, should_float, client_id, None)
+    }
+    /// Puts the new pane `pid` in the place of the closed pane [`Tab::undo_close_pane`] asked the
+    /// pty to reopen
+    pub fn reopen_closed_pane(
+        &mut self,
+        pid: PaneId,
+        closed_pane: ClosedPane,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let should_float = false;
+        self.new_pane_or_reopen(
+            pid,
+            None,
+            Some(should_float),
+            Some(client_id),
+            Some(closed_pane),
+        )
+    }
+    fn new_pane_or_reopen(
+        &mut self,
+        pid: PaneId,
+        initial_pane_title: Option<String>,
+        should_float: Option<bool>,
+        client_id: Option<ClientId>,
+        closed_pane: Option<ClosedPane>,
     ) -> Result<()> {
         match should_float {
             Some(true) => self.floating_panes.toggle_show_panes(true),
@@ -836,6 +1044,12 @@ impl Tab {
                 }
             }
         } else {
+            if self
+                .refuse_pane_over_max_panes(pid, client_id)
+                .with_context(|| format!("failed to create new pane with id {pid:?}"))?
+            {
+                return Ok(());
+            }
             if self.tiled_panes.fullscreen_is_active() {
                 self.tiled_panes.unset_fullscreen();
             }
@@ -855,7 +1069,24 @@ impl Tab {
                         self.terminal_emulator_color_codes.clone(),
                         initial_pane_title,
                     );
-                    self.tiled_panes.insert_pane(pid, Box::new(new_terminal));
+                    let new_terminal: Option<Box<dyn Pane>> = match closed_pane {
+                        Some(closed_pane) => {
+                            if let Some(run) = closed_pane.run {
+                                self.pane_runs.insert(pid, run);
+                            }
+                            self.tiled_panes.reinstate_pane(
+                                pid,
+                                Box::new(new_terminal),
+                                closed_pane.geom,
+                                &closed_pane.geoms_before_closing,
+                                &closed_pane.geoms_after_closing,
+                            )
+                        },
+                        None => Some(Box::new(new_terminal)),
+                    };
+                    if let Some(new_terminal) = new_terminal {
+                        self.tiled_panes.insert_pane(pid, new_terminal);
+                    }
                     self.should_clear_display_before_rendering = true;
                     if let Some(client_id) = client_id {
                         self.tiled_panes.focus_pane(pid, client_id);
@@ -923,39 +1154,12 @@ impl Tab {
         initial_pane_title: Option<String>,
         client_id: ClientId,
     ) -> Result<()> {
-        let err_context =
-            || format!("failed to split pane {pid:?} horizontally for client {client_id}");
-        if self.floating_panes.panes_are_visible() {
-            return Ok(());
-        }
-        self.close_down_to_max_terminals()
-            .with_context(err_context)?;
-        if self.tiled_panes.fullscreen_is_active() {
-            self.toggle_active_pane_fullscreen(client_id);
-        }
-        if self.tiled_panes.can_split_pane_horizontally(client_id) {
-            if let PaneId::Terminal(term_pid) = pid {
-                let next_terminal_position = self.get_next_terminal_position();
-                let new_terminal = TerminalPane::new(
-                    term_pid,
-                    PaneGeom::default(), // the initial size will be set later
-                    self.style,
-                    next_terminal_position,
-                    String::new(),
-                    self.link_handler.clone(),
-                    self.character_cell_size.clone(),
-                    self.sixel_image_store.clone(),
-                    self.terminal_emulator_colors.clone(),
-                    self.terminal_emulator_color_codes.clone(),
-                    initial_pane_title,
-                );
-                self.tiled_panes
-                    .split_pane_horizontally(pid, Box::new(new_terminal), client_id);
-                self.should_clear_display_before_rendering = true;
-                self.tiled_panes.focus_pane(pid, client_id);
-            }
-        }
-        Ok(())
+        self.split_focused_pane(
+            pid,
+            initial_pane_title,
+            client_id,
+            SplitDirection::Horizontal,
+        )
     }
     pub fn vertical_split(
         &mut self,
@@ -963,40 +1167,98 @@ impl Tab {
         initial_pane_title: Option<String>,
         client_id: ClientId,
     ) -> Result<()> {
-        let err_context =
-            || format!("failed to split pane {pid:?} vertically for client {client_id}");
+        self.split_focused_pane(pid, initial_pane_title, client_id, SplitDirection::Vertical)
+    }
+    /// Splits the focused pane in the requested direction, or in the other one if the pane is too
+    /// small for it
+    fn split_focused_pane(
+        &mut self,
+        pid: PaneId,
+        initial_pane_title: Option<String>,
+        client_id: ClientId,
+        split_direction: SplitDirection,
+    ) -> Result<()> {
+        let err_context = || format!("failed to split pane {pid:?} for client {client_id}");
         if self.floating_panes.panes_are_visible() {
             return Ok(());
         }
         self.close_down_to_max_terminals()
             .with_context(err_context)?;
+        if self
+            .refuse_pane_over_max_panes(pid, Some(client_id))
+            .with_context(err_context)?
+        {
+            return Ok(());
+        }
         if self.tiled_panes.fullscreen_is_active() {
             self.toggle_active_pane_fullscreen(client_id);
         }
-        if self.tiled_panes.can_split_pane_vertically(client_id) {
-            if let PaneId::Terminal(term_pid) = pid {
-                let next_terminal_position = self.get_next_terminal_position();
-                let new_terminal = TerminalPane::new(
-                    term_pid,
-                    PaneGeom::default(), // the initial size will be set later
-                    self.style,
-                    next_terminal_position,
-                    String::new(),
-                    self.link_handler.clone(),
-                    self.character_cell_size.clone(),
-                    self.sixel_image_store.clone(),
-                    self.terminal_emulator_colors.clone(),
-                    self.terminal_emulator_color_codes.clone(),
-                    initial_pane_title,
-                );
-                self.tiled_panes
-                    .split_pane_vertically(pid, Box::new(new_terminal), client_id);
-                self.should_clear_display_before_rendering = true;
-                self.tiled_panes.focus_pane(pid, client_id);
-            }
+        let split_direction = if self.can_split_focused_pane(split_direction, client_id) {
+            Some(split_direction)
+        } else if self.can_split_focused_pane(!split_direction, client_id) {
+            Some(!split_direction)
+        } else {
+            None
+        };
+        match split_direction {
+            Some(split_direction) => {
+                if let PaneId::Terminal(term_pid) = pid {
+                    let next_terminal_position = self.get_next_terminal_position();
+                    let new_terminal = Box::new(TerminalPane::new(
+                        term_pid,
+                        PaneGeom::default(), // the initial size will be set later
+                        self.style,
+                        next_terminal_position,
+                        String::new(),
+                        self.link_handler.clone(),
+                        self.character_cell_size.clone(),
+                        self.sixel_image_store.clone(),
+                        self.terminal_emulator_colors.clone(),
+                        self.terminal_emulator_color_codes.clone(),
+                        initial_pane_title,
+                    ));
+                    match split_direction {
+                        SplitDirection::Horizontal => {
+                            self.tiled_panes
+                                .split_pane_horizontally(pid, new_terminal, client_id)
+                        },
+                        SplitDirection::Vertical => {
+                            self.tiled_panes
+                                .split_pane_vertically(pid, new_terminal, client_id)
+                        },
+                    }
+                    self.should_clear_display_before_rendering = true;
+                    self.tiled_panes.focus_pane(pid, client_id);
+                }
+            },
+            None => {
+                let message = "Focused pane is too small to split";
+                log::error!("{}", message);
+                // the pty of the new pane was already opened, make sure it doesn't linger
+                self.senders
+                    .send_to_pty(PtyInstruction::ClosePane(pid))
+                    .with_context(err_context)?;
+                self.senders
+                    .send_to_plugin(PluginInstruction::Update(
+                        None,
+                        Some(client_id),
+                        Event::StatusMessage(message.to_owned()),
+                    ))
+                    .with_context(err_context)?;
+            },
         }
         Ok(())
     }
+    fn can_split_focused_pane(
+        &mut self,
+        split_direction: SplitDirection,
+        client_id: ClientId,
+    ) -> bool {
+        match split_direction {
+            SplitDirection::Horizontal => self.tiled_panes.can_split_pane_horizontally(client_id),
+            SplitDirection::Vertical => self.tiled_panes.can_split_pane_vertically(client_id),
+        }
+    }
 
     pub fn get_active_pane(&self, client_id: ClientId) -> Option<&dyn Pane> {
         self.get_active_pane_id(client_id).and_then(|ap| {
@@ -1060,15 +1322,27 @@ impl Tab {
                     .find(|s_p| s_p.pid() == PaneId::Terminal(pid))
             })
         {
-            // If the pane is scrolled buffer the vte events
-            if terminal_output.is_scrolled() {
+            // If the pane is scrolled or not following its output buffer the vte events
+            if terminal_output.is_scrolled() || !terminal_output.follows_output() {
                 self.pending_vte_events.entry(pid).or_default().push(bytes);
                 if let Some(evs) = self.pending_vte_events.get(&pid) {
                     // Reset scroll - and process all pending events for this pane
-                    if evs.len() >= MAX_PENDING_VTE_EVENTS {
+                    // processing them moves the oldest lines of the pane beyond its scrollback
+                    // limit out of it, so this is how much memory a flood of output can take
+                    let pending_bytes: usize = evs.iter().map(|ev| ev.len()).sum();
+                    if evs.len() >= MAX_PENDING_VTE_EVENTS
+                        || pending_bytes > self.pending_output_limit
+                    {
+                        let follows_output = terminal_output.follows_output();
+                        let (position, length) = terminal_output.scrollback_position_and_length();
                         terminal_output.clear_scroll();
                         self.process_pending_vte_events(pid)
                             .with_context(err_context)?;
+                        if !follows_output {
+                            // keep showing the lines the user scrolled to rather than the new
+                            // output
+                            self.restore_scrollback_position(pid, length.saturating_sub(position));
+                        }
                     }
                 }
                 return Ok(());
@@ -1076,6 +1350,41 @@ impl Tab {
         }
         self.process_pty_bytes(pid, bytes).with_context(err_context)
     }
+    /// Whether the program running in this terminal rang the bell since this was last called
+    pub fn take_bell(&mut self, pid: u32) -> bool {
+        self.get_terminal_pane_mut(pid)
+            .map(|terminal_pane| terminal_pane.take_bell())
+            .unwrap_or(false)
+    }
+    /// Highlights the frame of this terminal for a moment, returns false if it already is
+    pub fn flash_bell(&mut self, pid: u32) -> bool {
+        self.get_terminal_pane_mut(pid)
+            .map(|terminal_pane| terminal_pane.flash_bell())
+            .unwrap_or(false)
+    }
+    /// The scrollback of each terminal of this tab, to be saved to disk
+    pub fn scrollbacks_to_persist(&mut self) -> Vec<(u32, String)> {
+        let mut scrollbacks = vec![];
+        for pane_id in self.get_all_pane_ids() {
+            if let PaneId::Terminal(pid) = pane_id {
+                if let Some(terminal_pane) = self.get_terminal_pane_mut(pid) {
+                    // terminal panes show the same scrollback to every client
+                    scrollbacks.push((pid, terminal_pane.dump_screen(0, true)));
+                }
+            }
+        }
+        scrollbacks
+    }
+    fn get_terminal_pane_mut(&mut self, pid: u32) -> Option<&mut Box<dyn Pane>> {
+        self.tiled_panes
+            .get_pane_mut(PaneId::Terminal(pid))
+            .or_else(|| self.floating_panes.get_pane_mut(PaneId::Terminal(pid)))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.pid() == PaneId::Terminal(pid))
+            })
+    }
     pub fn process_pending_vte_events(&mut self, pid: u32) -> Result<()> {
         if let Some(pending_vte_events) = self.pending_vte_events.get_mut(&pid) {
             let vte_events: Vec<VteBytes> = pending_vte_events.drain(..).collect();
@@ -1086,6 +1395,20 @@ impl Tab {
         }
         Ok(())
     }
+    fn restore_scrollback_position(&mut self, pid: u32, lines_from_top: usize) {
+        if let Some(terminal_output) = self
+            .tiled_panes
+            .get_pane_mut(PaneId::Terminal(pid))
+            .or_else(|| self.floating_panes.get_pane_mut(PaneId::Terminal(pid)))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.pid() == PaneId::Terminal(pid))
+            })
+        {
+            terminal_output.restore_scrollback_position(lines_from_top);
+        }
+    }
     fn process_pty_bytes(&mut self, pid: u32, bytes: VteBytes) -> Result<()> {
         let err_context = || format!("failed to process pty bytes from pid {pid}");
         if let Some(terminal_output) = self
@@ -1108,7 +1431,7 @@ impl Tab {
                 self.write_to_pane_id(message, PaneId::Terminal(pid))
                     .with_context(err_context)?;
             }
-            if let Some(string) = clipboard_update {
+            if let Some(string) = clipboard_update.filter(|_| self.clipboard_from_panes) {
                 self.write_selection_to_clipboard(&string)
                     .with_context(err_context)?;
             }
@@ -1117,7 +1440,12 @@ impl Tab {
     }
 
     pub fn write_to_terminals_on_current_tab(&mut self, input_bytes: Vec<u8>) -> Result<()> {
-        let pane_ids = self.get_static_and_floating_pane_ids();
+        // keystrokes are only broadcast to terminals, plugin panes (eg. the tab-bar) should not
+        // receive keys just because they happen to be in a synchronized tab
+        let pane_ids = self
+            .get_static_and_floating_pane_ids()
+            .into_iter()
+            .filter(|pane_id| matches!(pane_id, PaneId::Terminal(_)));
         for pane_id in pane_ids {
             self.write_to_pane_id(input_bytes.clone(), pane_id)
                 .context("failed to write to terminals on current tab")?;
@@ -1309,6 +1637,27 @@ impl Tab {
         Ok(())
     }
 
+    // a pane scrolled horizontally goes back to its first column once the client that scrolled it
+    // moves focus away from it, so this is called whenever the focus might have changed
+    fn clear_horizontal_scroll_of_unfocused_panes(&mut self) {
+        let unfocused_panes: Vec<(ClientId, PaneId)> = self
+            .horizontally_scrolled_panes
+            .iter()
+            .filter(|(client_id, pane_id)| self.get_active_pane_id(**client_id) != Some(**pane_id))
+            .map(|(client_id, pane_id)| (*client_id, *pane_id))
+            .collect();
+        for (client_id, pane_id) in unfocused_panes {
+            self.horizontally_scrolled_panes.remove(&client_id);
+            if let Some(pane) = self
+                .tiled_panes
+                .get_pane_mut(pane_id)
+                .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            {
+                pane.clear_horizontal_scroll();
+            }
+        }
+    }
+
     pub fn render(&mut self, output: &mut Output, overlay: Option<String>) -> Result<()> {
         let err_context = || "failed to render tab".to_string();
 
@@ -1327,6 +1676,10 @@ impl Tab {
             floating_panes_stack,
         );
 
+        if self.tiled_panes.is_too_small() {
+            self.render_too_small_message(output);
+            return Ok(());
+        }
         self.hide_cursor_and_clear_display_as_needed(output);
         self.tiled_panes
             .render(output, self.floating_panes.panes_are_visible())
@@ -1349,6 +1702,20 @@ impl Tab {
         Ok(())
     }
 
+    fn render_too_small_message(&mut self, output: &mut Output) {
+        // drawn once after each resize rather than on every render, the panes can't change it
+        if self.should_clear_display_before_rendering {
+            let connected_clients: Vec<ClientId> =
+                { self.connected_clients.borrow().iter().copied().collect() };
+            // the top left corner is the one place the message is sure to be seen in
+            let too_small_message = "\u{1b}[?25l\u{1b}[2J\u{1b}[HTerminal too small";
+            output.add_post_vte_instruction_to_multiple_clients(
+                connected_clients.iter().copied(),
+                too_small_message,
+            );
+            self.should_clear_display_before_rendering = false;
+        }
+    }
     fn hide_cursor_and_clear_display_as_needed(&mut self, output: &mut Output) {
         let hide_cursor = "\u{1b}[?25l";
         let connected_clients: Vec<ClientId> =
@@ -1431,11 +1798,32 @@ impl Tab {
         selectable_tiled_panes.count() > 0
     }
     pub fn resize_whole_tab(&mut self, new_screen_size: Size) {
+        let was_too_small = self.tiled_panes.is_too_small();
         self.floating_panes.resize(new_screen_size);
         self.floating_panes.resize_pty_all_panes(&mut self.os_api); // we need to do this explicitly because floating_panes.resize does not do this
         self.tiled_panes.resize(new_screen_size);
+        if was_too_small && !self.tiled_panes.is_too_small() {
+            // the panes weren't rendered while the terminal was too small for them
+            self.set_force_render();
+        }
         self.should_clear_display_before_rendering = true;
     }
+    /// Sets the fewest columns and rows a tiled pane may have, rather than the defaults
+    pub fn set_min_pane_size(
+        &mut self,
+        min_pane_width: Option<usize>,
+        min_pane_height: Option<usize>,
+    ) {
+        self.tiled_panes
+            .set_min_pane_size(min_pane_width, min_pane_height);
+    }
+    /// The panes other than the fullscreen one are hidden, so rather than resizing them out of
+    /// sight they are brought back into view first
+    fn unset_fullscreen_to_resize(&mut self) {
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+    }
     pub fn resize_left(&mut self, client_id: ClientId) {
         if self.floating_panes.panes_are_visible() {
             let successfully_resized = self
@@ -1445,6 +1833,7 @@ impl Tab {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
+            self.unset_fullscreen_to_resize();
             self.tiled_panes.resize_active_pane_left(client_id);
         }
     }
@@ -1457,6 +1846,7 @@ impl Tab {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
+            self.unset_fullscreen_to_resize();
             self.tiled_panes.resize_active_pane_right(client_id);
         }
     }
@@ -1469,6 +1859,7 @@ impl Tab {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
+            self.unset_fullscreen_to_resize();
             self.tiled_panes.resize_active_pane_down(client_id);
         }
     }
@@ -1481,6 +1872,7 @@ impl Tab {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
+            self.unset_fullscreen_to_resize();
             self.tiled_panes.resize_active_pane_up(client_id);
         }
     }
@@ -1493,6 +1885,7 @@ impl Tab {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
+            self.unset_fullscreen_to_resize();
             self.tiled_panes.resize_active_pane_increase(client_id);
         }
     }
@@ -1505,9 +1898,88 @@ impl Tab {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
+            self.unset_fullscreen_to_resize();
             self.tiled_panes.resize_active_pane_decrease(client_id);
         }
     }
+    pub fn equalize_panes(&mut self) {
+        self.unset_fullscreen_to_resize();
+        self.tiled_panes.equalize();
+    }
+    pub fn apply_auto_layout(&mut self) {
+        self.unset_fullscreen_to_resize();
+        self.tiled_panes.apply_auto_layout();
+    }
+    /// Resizes the focused pane by a single cell, floating panes are resized as usual since they
+    /// are already resized by a few cells at a time
+    pub fn resize_fine(&mut self, direction: ResizeDirection, client_id: ClientId) {
+        if self.floating_panes.panes_are_visible() {
+            match direction {
+                ResizeDirection::Left => self.resize_left(client_id),
+                ResizeDirection::Right => self.resize_right(client_id),
+                ResizeDirection::Up => self.resize_up(client_id),
+                ResizeDirection::Down => self.resize_down(client_id),
+                ResizeDirection::Increase => self.resize_increase(client_id),
+                ResizeDirection::Decrease => self.resize_decrease(client_id),
+            }
+        } else {
+            self.unset_fullscreen_to_resize();
+            self.tiled_panes
+                .resize_active_pane_by_one_cell(direction, client_id);
+        }
+    }
+    pub fn resize_to_fraction(&mut self, fraction: f64, client_id: ClientId) {
+        if self.floating_panes.panes_are_visible() {
+            // floating panes are not part of a split
+            return;
+        }
+        self.unset_fullscreen_to_resize();
+        self.tiled_panes
+            .resize_active_pane_to_fraction(fraction, client_id);
+    }
+    /// Focuses the pane with the given id for the client, showing or hiding the floating panes
+    /// depending on whether it is one of them
+    pub fn focus_pane_with_id(&mut self, pane_id: PaneId, client_id: ClientId) {
+        if self.floating_panes.panes_contain(&pane_id) {
+            self.floating_panes.toggle_show_panes(true);
+            self.floating_panes.focus_pane(pane_id, client_id);
+        } else if self.tiled_panes.panes_contain(&pane_id) {
+            self.tiled_panes.focus_pane(pane_id, client_id);
+            self.floating_panes.toggle_show_panes(false);
+        } else {
+            return;
+        }
+        self.set_pane_active_at(pane_id);
+        self.clear_horizontal_scroll_of_unfocused_panes();
+        self.set_force_render();
+    }
+    /// A line describing each pane of the tab, for `zellij action list-panes`
+    pub fn list_panes(&self) -> Vec<String> {
+        let panes = self
+            .tiled_panes
+            .get_panes()
+            .map(|(pane_id, pane)| (pane_id, pane, false))
+            .chain(
+                self.floating_panes
+                    .get_panes()
+                    .map(|(pane_id, pane)| (pane_id, pane, true)),
+            );
+        panes
+            .map(|(pane_id, pane, is_floating)| {
+                format!(
+                    "{} tab_id={} x={} y={} cols={} rows={} floating={} title={:?}",
+                    pane_id,
+                    self.index,
+                    pane.x(),
+                    pane.y(),
+                    pane.cols(),
+                    pane.rows(),
+                    is_floating,
+                    pane.current_title()
+                )
+            })
+            .collect()
+    }
     fn set_pane_active_at(&mut self, pane_id: PaneId) {
         if let Some(pane) = self.tiled_panes.get_pane_mut(pane_id) {
             pane.set_active_at(Instant::now());
@@ -1521,9 +1993,10 @@ impl Tab {
         }
         if self.tiled_panes.fullscreen_is_active() {
             self.switch_next_pane_fullscreen(client_id);
-            return;
+        } else {
+            self.tiled_panes.focus_next_pane(client_id);
         }
-        self.tiled_panes.focus_next_pane(client_id);
+        self.clear_horizontal_scroll_of_unfocused_panes();
     }
     pub fn focus_previous_pane(&mut self, client_id: ClientId) {
         if !self.has_selectable_panes() {
@@ -1531,77 +2004,74 @@ impl Tab {
         }
         if self.tiled_panes.fullscreen_is_active() {
             self.switch_prev_pane_fullscreen(client_id);
-            return;
+        } else {
+            self.tiled_panes.focus_previous_pane(client_id);
         }
-        self.tiled_panes.focus_previous_pane(client_id);
+        self.clear_horizontal_scroll_of_unfocused_panes();
     }
     // returns a boolean that indicates whether the focus moved
     pub fn move_focus_left(&mut self, client_id: ClientId) -> bool {
-        if self.floating_panes.panes_are_visible() {
+        let focus_moved = if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_focus_left(
                 client_id,
                 &self.connected_clients.borrow().iter().copied().collect(),
             )
+        } else if !self.has_selectable_panes() {
+            false
+        } else if self.tiled_panes.fullscreen_is_active() {
+            self.switch_next_pane_fullscreen(client_id);
+            true
         } else {
-            if !self.has_selectable_panes() {
-                return false;
-            }
-            if self.tiled_panes.fullscreen_is_active() {
-                self.switch_next_pane_fullscreen(client_id);
-                return true;
-            }
             self.tiled_panes.move_focus_left(client_id)
-        }
+        };
+        self.clear_horizontal_scroll_of_unfocused_panes();
+        focus_moved
     }
     pub fn move_focus_down(&mut self, client_id: ClientId) -> bool {
-        if self.floating_panes.panes_are_visible() {
+        let focus_moved = if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_focus_down(
                 client_id,
                 &self.connected_clients.borrow().iter().copied().collect(),
             )
+        } else if !self.has_selectable_panes() || self.tiled_panes.fullscreen_is_active() {
+            false
         } else {
-            if !self.has_selectable_panes() {
-                return false;
-            }
-            if self.tiled_panes.fullscreen_is_active() {
-                return false;
-            }
             self.tiled_panes.move_focus_down(client_id)
-        }
+        };
+        self.clear_horizontal_scroll_of_unfocused_panes();
+        focus_moved
     }
     pub fn move_focus_up(&mut self, client_id: ClientId) -> bool {
-        if self.floating_panes.panes_are_visible() {
+        let focus_moved = if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_focus_up(
                 client_id,
                 &self.connected_clients.borrow().iter().copied().collect(),
             )
+        } else if !self.has_selectable_panes() || self.tiled_panes.fullscreen_is_active() {
+            false
         } else {
-            if !self.has_selectable_panes() {
-                return false;
-            }
-            if self.tiled_panes.fullscreen_is_active() {
-                return false;
-            }
             self.tiled_panes.move_focus_up(client_id)
-        }
+        };
+        self.clear_horizontal_scroll_of_unfocused_panes();
+        focus_moved
     }
     // returns a boolean that indicates whether the focus moved
     pub fn move_focus_right(&mut self, client_id: ClientId) -> bool {
-        if self.floating_panes.panes_are_visible() {
+        let focus_moved = if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_focus_right(
                 client_id,
                 &self.connected_clients.borrow().iter().copied().collect(),
             )
+        } else if !self.has_selectable_panes() {
+            false
+        } else if self.tiled_panes.fullscreen_is_active() {
+            self.switch_next_pane_fullscreen(client_id);
+            true
         } else {
-            if !self.has_selectable_panes() {
-                return false;
-            }
-            if self.tiled_panes.fullscreen_is_active() {
-                self.switch_next_pane_fullscreen(client_id);
-                return true;
-            }
             self.tiled_panes.move_focus_right(client_id)
-        }
+        };
+        self.clear_horizontal_scroll_of_unfocused_panes();
+        focus_moved
     }
     pub fn move_active_pane(&mut self, client_id: ClientId) {
         if !self.has_selectable_panes() {
@@ -1669,9 +2139,9 @@ impl Tab {
         }
     }
     fn close_down_to_max_terminals(&mut self) -> Result<()> {
-        if let Some(max_panes) = self.max_panes {
+        if let Some(max_panes) = self.max_panes.filter(|max_panes| max_panes.evict) {
             let terminals = self.get_tiled_pane_ids();
-            for &pid in terminals.iter().skip(max_panes - 1) {
+            for &pid in terminals.iter().skip(max_panes.count.saturating_sub(1)) {
                 self.senders
                     .send_to_pty(PtyInstruction::ClosePane(pid))
                     .context("failed to close down to max terminals")?;
@@ -1680,6 +2150,47 @@ impl Tab {
         }
         Ok(())
     }
+    /// Returns true if this tab has as many tiled panes as `max_panes` allows and we were not
+    /// asked to close old panes to make room for new ones.
+    pub fn is_at_max_panes(&self) -> bool {
+        match self.max_panes {
+            Some(max_panes) => {
+                !max_panes.evict && self.get_tiled_pane_ids().len() >= max_panes.count
+            },
+            None => false,
+        }
+    }
+    /// Returns true if a new tiled pane can't be opened because it would exceed `max_panes`
+    /// and we were not asked to close old panes to make room for it.
+    /// In this case the pty of the new pane is closed and the client is told why.
+    fn refuse_pane_over_max_panes(
+        &mut self,
+        pid: PaneId,
+        client_id: Option<ClientId>,
+    ) -> Result<bool> {
+        let err_context = || format!("failed to refuse pane {pid:?} over max panes");
+        match self.max_panes {
+            Some(max_panes) if self.is_at_max_panes() => {
+                let message = format!(
+                    "Max panes ({}) reached, refusing to open a new pane",
+                    max_panes.count
+                );
+                log::error!("{}", message);
+                self.senders
+                    .send_to_pty(PtyInstruction::ClosePane(pid))
+                    .with_context(err_context)?;
+                self.senders
+                    .send_to_plugin(PluginInstruction::Update(
+                        None,
+                        client_id,
+                        Event::StatusMessage(message),
+                    ))
+                    .with_context(err_context)?;
+                Ok(true)
+            },
+            _ => Ok(false),
+        }
+    }
     pub fn get_tiled_pane_ids(&self) -> Vec<PaneId> {
         self.get_tiled_panes().map(|(&pid, _)| pid).collect()
     }
@@ -1724,6 +2235,9 @@ impl Tab {
         //
         // TODO: separate the "close_pane" logic and the "move_pane_somewhere_else" logic, they're
         // overloaded here and that's not great
+        if !ignore_suppressed_panes {
+            self.pane_runs.remove(&id);
+        }
         if !ignore_suppressed_panes && self.suppressed_panes.contains_key(&id) {
             return self.replace_pane_with_suppressed_pane(id);
         }
@@ -1740,6 +2254,9 @@ impl Tab {
             if self.tiled_panes.fullscreen_is_active() {
                 self.tiled_panes.unset_fullscreen();
             }
+            if self.focus_pane_id == Some(id) {
+                self.focus_pane_id = self.tiled_panes.neighbouring_pane_id(id);
+            }
             let closed_pane = self.tiled_panes.remove_pane(id);
             self.set_force_render();
             self.tiled_panes.set_force_render();
@@ -1753,6 +2270,17 @@ impl Tab {
             self.tiled_panes.hold_pane(id, exit_status, run_command);
         }
     }
+    /// Runs the command of a pane again, its new pty is resized to the pane once it writes to it
+    pub fn restart_command_in_pane(&mut self, id: PaneId, run_command: RunCommand) -> Result<()> {
+        let err_context = || format!("failed to restart command in pane {id:?}");
+        if let PaneId::Terminal(terminal_id) = id {
+            self.pids_waiting_resize.insert(terminal_id);
+            self.senders
+                .send_to_pty(PtyInstruction::ReRunCommandInPane(id, run_command))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
     pub fn replace_pane_with_suppressed_pane(&mut self, pane_id: PaneId) -> Option<Box<dyn Pane>> {
         self.suppressed_panes
             .remove(&pane_id)
@@ -1796,13 +2324,97 @@ impl Tab {
             }
         }
         if let Some(active_pane_id) = self.tiled_panes.get_active_pane_id(client_id) {
+            let is_suppressing_a_pane = self.suppressed_panes.contains_key(&active_pane_id);
+            let geoms_before_closing = self.tiled_panes.pane_geoms();
+            let run = self.pane_runs.get(&active_pane_id).cloned();
             self.close_pane(active_pane_id, false);
+            if !is_suppressing_a_pane {
+                self.remember_closed_pane(active_pane_id, run, geoms_before_closing);
+            }
             self.senders
                 .send_to_pty(PtyInstruction::ClosePane(active_pane_id))
                 .with_context(|| err_context(active_pane_id))?;
         }
         Ok(())
     }
+    fn remember_closed_pane(
+        &mut self,
+        pane_id: PaneId,
+        run: Option<Run>,
+        mut geoms_before_closing: HashMap<PaneId, PaneGeom>,
+    ) {
+        if self.undo_close_depth == 0 || !matches!(pane_id, PaneId::Terminal(_)) {
+            return;
+        }
+        if let Some(geom) = geoms_before_closing.remove(&pane_id) {
+            self.closed_panes.push(ClosedPane {
+                run,
+                geom,
+                geoms_before_closing,
+                geoms_after_closing: self.tiled_panes.pane_geoms(),
+            });
+            if self.closed_panes.len() > self.undo_close_depth {
+                self.closed_panes.remove(0);
+            }
+        }
+    }
+    /// Reopens the most recently closed pane of the tab in a new terminal, running the command
+    /// the pane was started with again if it had one. The pane is put back in its old place
+    /// unless the other panes were moved or resized since.
+    pub fn undo_close_pane(&mut self, client_id: ClientId) -> Result<()> {
+        let closed_pane = match self.closed_panes.pop() {
+            Some(closed_pane) => closed_pane,
+            None => return Ok(()),
+        };
+        let terminal_action = match &closed_pane.run {
+            Some(Run::Command(run_command)) => {
+                Some(TerminalAction::RunCommand(run_command.clone()))
+            },
+            Some(Run::EditFile(file, line_number)) => {
+                Some(TerminalAction::OpenFile(file.clone(), *line_number))
+            },
+            _ => None,
+        };
+        let instruction = PtyInstruction::ReopenClosedPane(terminal_action, closed_pane, client_id);
+        self.senders
+            .send_to_pty(instruction)
+            .with_context(|| format!("failed to reopen a closed pane for client {client_id}"))
+    }
+    /// Returns the id of the pane [`Tab::close_focused_pane`] would close, if that pane is running
+    /// a program other than its shell in the foreground
+    fn focused_pane_id(&self, client_id: ClientId) -> Option<PaneId> {
+        let floating_pane_id = if self.floating_panes.panes_are_visible() {
+            self.floating_panes.active_pane_id(client_id)
+        } else {
+            None
+        };
+        floating_pane_id.or_else(|| self.tiled_panes.get_active_pane_id(client_id))
+    }
+    pub fn focused_pane_with_foreground_process(&self, client_id: ClientId) -> Option<PaneId> {
+        match self.focused_pane_id(client_id) {
+            Some(PaneId::Terminal(terminal_id))
+                if self.os_api.foreground_process_is_running(terminal_id) =>
+            {
+                Some(PaneId::Terminal(terminal_id))
+            },
+            _ => None,
+        }
+    }
+    pub fn signal_focused_pane(&self, client_id: ClientId, signal: Signal) {
+        if let Some(PaneId::Terminal(terminal_id)) = self.focused_pane_id(client_id) {
+            if let Err(e) = self
+                .os_api
+                .signal_foreground_process_group(terminal_id, signal)
+            {
+                log::error!(
+                    "Failed to send {:?} to terminal {}: {}",
+                    signal,
+                    terminal_id,
+                    e
+                );
+            }
+        }
+    }
     pub fn dump_active_terminal_screen(
         &mut self,
         file: Option<String>,
@@ -1814,6 +2426,59 @@ impl Tab {
             self.os_api.write_to_file(dump, file);
         }
     }
+    /// Writes the focused pane to the stdin of `command`, telling the client in the status bar if
+    /// the command could not be run
+    pub fn pipe_active_pane_to_command(
+        &mut self,
+        command: &[String],
+        client_id: ClientId,
+        full: bool,
+    ) -> Result<()> {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            let dump = active_pane.dump_screen(client_id, full);
+            if let Err(e) = self.os_api.pipe_to_command(dump, command) {
+                let message = format!("Failed to run {}: {}", command.join(" "), e);
+                log::error!("{}", message);
+                self.senders
+                    .send_to_plugin(PluginInstruction::Update(
+                        None,
+                        Some(client_id),
+                        Event::StatusMessage(message),
+                    ))
+                    .with_context(|| {
+                        format!("failed to report failed pipe to client {client_id}")
+                    })?;
+            }
+        }
+        Ok(())
+    }
+    /// Describes the tiled panes of the tab as a layout, including the commands and plugins that
+    /// the panes of the tab's own layout were started with
+    pub fn current_layout(&self, client_id: ClientId) -> PaneLayout {
+        let active_pane_id = self.tiled_panes.get_active_pane_id(client_id);
+        let layout = self.tiled_panes.to_layout(|pane_id, pane| PaneLayout {
+            run: self.pane_runs.get(&pane_id).cloned(),
+            borderless: pane.borderless(),
+            focus: if Some(pane_id) == active_pane_id {
+                Some(true)
+            } else {
+                None
+            },
+            ..Default::default()
+        });
+        if layout.children.is_empty() {
+            // a tab with a single pane
+            PaneLayout {
+                children: vec![layout],
+                ..Default::default()
+            }
+        } else {
+            layout
+        }
+    }
+    pub fn write_to_file(&mut self, buf: String, file: Option<String>) {
+        self.os_api.write_to_file(buf, file);
+    }
     pub fn edit_scrollback(&mut self, client_id: ClientId) -> Result<()> {
         let mut file = temp_dir();
         file.push(format!("{}.dump", Uuid::new_v4()));
@@ -1833,67 +2498,89 @@ impl Tab {
             ))
             .with_context(|| format!("failed to edit scrollback for client {client_id}"))
     }
-    pub fn scroll_active_terminal_up(&mut self, client_id: ClientId) {
-        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
-            active_pane.scroll_up(1, client_id);
-        }
+    pub fn scroll_active_terminal_up(&mut self, client_id: ClientId) -> Result<()> {
+        self.scroll_active_pane(client_id, |active_pane| active_pane.scroll_up(1, client_id))
+            .with_context(|| format!("failed to scroll up active pane for client {client_id}"))
     }
 
     pub fn scroll_active_terminal_down(&mut self, client_id: ClientId) -> Result<()> {
-        let err_context = || format!("failed to scroll down active pane for client {client_id}");
-
-        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
-            active_pane.scroll_down(1, client_id);
-            if !active_pane.is_scrolled() {
-                if let PaneId::Terminal(raw_fd) = active_pane.pid() {
-                    self.process_pending_vte_events(raw_fd)
-                        .with_context(err_context)?;
-                }
-            }
-        }
-        Ok(())
+        self.scroll_active_pane(client_id, |active_pane| {
+            active_pane.scroll_down(1, client_id)
+        })
+        .with_context(|| format!("failed to scroll down active pane for client {client_id}"))
     }
 
-    pub fn scroll_active_terminal_up_page(&mut self, client_id: ClientId) {
-        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+    pub fn scroll_active_terminal_up_page(&mut self, client_id: ClientId) -> Result<()> {
+        self.scroll_active_pane(client_id, |active_pane| {
             // prevent overflow when row == 0
             let scroll_rows = active_pane.rows().max(1) - 1;
             active_pane.scroll_up(scroll_rows, client_id);
-        }
+        })
+        .with_context(|| {
+            format!("failed to scroll up one page in active pane for client {client_id}")
+        })
     }
 
     pub fn scroll_active_terminal_down_page(&mut self, client_id: ClientId) -> Result<()> {
-        let err_context =
-            || format!("failed to scroll down one page in active pane for client {client_id}");
-
-        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+        self.scroll_active_pane(client_id, |active_pane| {
             let scroll_rows = active_pane.get_content_rows();
             active_pane.scroll_down(scroll_rows, client_id);
-            if !active_pane.is_scrolled() {
-                if let PaneId::Terminal(raw_fd) = active_pane.pid() {
-                    self.process_pending_vte_events(raw_fd)
-                        .with_context(err_context)?;
-                }
+        })
+        .with_context(|| {
+            format!("failed to scroll down one page in active pane for client {client_id}")
+        })
+    }
+
+    pub fn scroll_active_terminal_left(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            let scroll_columns = std::cmp::max(active_pane.get_content_columns() / 2, 1);
+            active_pane.scroll_left(scroll_columns);
+        }
+    }
+
+    pub fn scroll_active_terminal_right(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            let scroll_columns = std::cmp::max(active_pane.get_content_columns() / 2, 1);
+            if active_pane.scroll_right(scroll_columns) {
+                let pane_id = active_pane.pid();
+                self.horizontally_scrolled_panes.insert(client_id, pane_id);
             }
         }
-        Ok(())
     }
 
-    pub fn scroll_active_terminal_up_half_page(&mut self, client_id: ClientId) {
+    pub fn toggle_active_pane_line_wrap(&mut self, client_id: ClientId) {
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.toggle_line_wrap();
+        }
+    }
+
+    pub fn scroll_active_terminal_up_half_page(&mut self, client_id: ClientId) -> Result<()> {
+        self.scroll_active_pane(client_id, |active_pane| {
             // prevent overflow when row == 0
             let scroll_rows = (active_pane.rows().max(1) - 1) / 2;
             active_pane.scroll_up(scroll_rows, client_id);
-        }
+        })
+        .with_context(|| {
+            format!("failed to scroll up half a page in active pane for client {client_id}")
+        })
     }
 
     pub fn scroll_active_terminal_down_half_page(&mut self, client_id: ClientId) -> Result<()> {
+        self.scroll_active_pane(client_id, |active_pane| {
+            let scroll_rows = (active_pane.rows().max(1) - 1) / 2;
+            active_pane.scroll_down(scroll_rows, client_id);
+        })
+        .with_context(|| {
+            format!("failed to scroll down half a page in active pane for client {client_id}")
+        })
+    }
+
+    pub fn scroll_active_terminal_to_bottom(&mut self, client_id: ClientId) -> Result<()> {
         let err_context =
-            || format!("failed to scroll down half a page in active pane for client {client_id}");
+            || format!("failed to scroll to bottom in active pane for client {client_id}");
 
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
-            let scroll_rows = (active_pane.rows().max(1) - 1) / 2;
-            active_pane.scroll_down(scroll_rows, client_id);
+            active_pane.clear_scroll();
             if !active_pane.is_scrolled() {
                 if let PaneId::Terminal(raw_fd) = active_pane.pid() {
                     self.process_pending_vte_events(raw_fd)
@@ -1904,13 +2591,15 @@ impl Tab {
         Ok(())
     }
 
-    pub fn scroll_active_terminal_to_bottom(&mut self, client_id: ClientId) -> Result<()> {
+    pub fn toggle_active_pane_follow_output(&mut self, client_id: ClientId) -> Result<()> {
         let err_context =
-            || format!("failed to scroll to bottom in active pane for client {client_id}");
+            || format!("failed to toggle following output in active pane for client {client_id}");
 
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
-            active_pane.clear_scroll();
-            if !active_pane.is_scrolled() {
+            if active_pane.follows_output() {
+                active_pane.set_follow_output(false);
+            } else {
+                active_pane.clear_scroll();
                 if let PaneId::Terminal(raw_fd) = active_pane.pid() {
                     self.process_pending_vte_events(raw_fd)
                         .with_context(err_context)?;
@@ -1960,7 +2649,9 @@ impl Tab {
                         .with_context(err_context)?;
                 }
             } else {
-                pane.scroll_up(lines, client_id);
+                let pane_id = pane.pid();
+                self.scroll_pane(pane_id, client_id, |pane| pane.scroll_up(lines, client_id))
+                    .with_context(err_context)?;
             }
         }
         Ok(())
@@ -1991,18 +2682,92 @@ impl Tab {
                         .with_context(err_context)?;
                 }
             } else {
-                pane.scroll_down(lines, client_id);
-                if !pane.is_scrolled() {
-                    if let PaneId::Terminal(pid) = pane.pid() {
-                        self.process_pending_vte_events(pid)
-                            .with_context(err_context)?;
-                    }
-                }
+                let pane_id = pane.pid();
+                self.scroll_pane(pane_id, client_id, |pane| {
+                    pane.scroll_down(lines, client_id)
+                })
+                .with_context(err_context)?;
             }
         }
         Ok(())
     }
 
+    fn scroll_active_pane(
+        &mut self,
+        client_id: ClientId,
+        scroll: impl FnOnce(&mut Box<dyn Pane>),
+    ) -> Result<()> {
+        match self.get_active_pane_or_floating_pane_mut(client_id) {
+            Some(active_pane) => {
+                let pane_id = active_pane.pid();
+                self.scroll_pane(pane_id, client_id, scroll)
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Scrolls the pane with `scroll`, processing the output that was held back while it was
+    /// scrolled once it is back at the bottom
+    fn scroll_pane(
+        &mut self,
+        pane_id: PaneId,
+        client_id: ClientId,
+        scroll: impl FnOnce(&mut Box<dyn Pane>),
+    ) -> Result<()> {
+        let is_scrolled = match self
+            .tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+        {
+            Some(pane) => {
+                scroll(pane);
+                pane.is_scrolled()
+            },
+            None => return Ok(()),
+        };
+        if !is_scrolled {
+            if let PaneId::Terminal(pid) = pane_id {
+                self.process_pending_vte_events(pid)
+                    .context("failed to process output held back while scrolled")?;
+            }
+        }
+        self.show_scroll_position_of_frameless_pane(pane_id, client_id);
+        Ok(())
+    }
+
+    /// Panes without a frame have nowhere to show how far back they are scrolled, so they show
+    /// it in a status message instead
+    fn show_scroll_position_of_frameless_pane(&mut self, pane_id: PaneId, client_id: ClientId) {
+        let is_floating = self.floating_panes.panes_contain(&pane_id);
+        let draw_pane_frames = is_floating || self.draw_pane_frames;
+        let pane = if is_floating {
+            self.floating_panes.get_pane_mut(pane_id)
+        } else {
+            self.tiled_panes.get_pane_mut(pane_id)
+        };
+        let message = pane.and_then(|pane| {
+            if draw_pane_frames && !pane.borderless() {
+                return None;
+            }
+            let (position, length) = pane.scrollback_position_and_length();
+            // back at the bottom, the message is gone with the input that scrolled there
+            if position > 0 {
+                Some(format!("SCROLL: {}/{}", position, length))
+            } else {
+                None
+            }
+        });
+        if let Some(message) = message {
+            self.senders
+                .send_to_plugin(PluginInstruction::Update(
+                    None,
+                    Some(client_id),
+                    Event::StatusMessage(message),
+                ))
+                .context("failed to show the scroll position of a pane")
+                .non_fatal();
+        }
+    }
     fn get_pane_at(
         &mut self,
         point: &Position,
@@ -2041,15 +2806,19 @@ impl Tab {
                 .with_context(err_context)?;
             return Ok(self.tiled_panes.get_active_pane_id(first_client_id));
         }
+        // if the point is on a boundary shared by more than one pane, we prefer the pane
+        // to the top-left of it
         if search_selectable {
             Ok(self
                 .get_selectable_tiled_panes()
-                .find(|(_, p)| p.contains(point))
+                .filter(|(_, p)| p.contains(point))
+                .min_by_key(|(_, p)| (p.y(), p.x()))
                 .map(|(&id, _)| id))
         } else {
             Ok(self
                 .get_tiled_panes()
-                .find(|(_, p)| p.contains(point))
+                .filter(|(_, p)| p.contains(point))
+                .min_by_key(|(_, p)| (p.y(), p.x()))
                 .map(|(&id, _)| id))
         }
     }
@@ -2061,6 +2830,10 @@ impl Tab {
             )
         };
 
+        // clicking anywhere clears the previous selection, a new one is started below if needed
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.reset_selection();
+        }
         self.focus_pane_at(position, client_id)
             .with_context(err_context)?;
 
@@ -2141,11 +2914,25 @@ impl Tab {
                     self.write_to_active_terminal(mouse_event.into_bytes(), client_id)
                         .with_context(err_context)?;
                 }
+            } else if self.middle_click_paste && !pane.position_is_on_frame(position) {
+                self.paste_copied_text(client_id)
+                    .with_context(err_context)?;
             }
         };
         Ok(())
     }
 
+    fn paste_copied_text(&mut self, client_id: ClientId) -> Result<()> {
+        let copied_text = self.copied_text.borrow().clone();
+        if let Some(copied_text) = copied_text {
+            // these are dropped by panes that are not in bracketed paste mode
+            self.write_to_active_terminal(BRACKETED_PASTE_BEGIN.to_vec(), client_id)?;
+            self.write_to_active_terminal(copied_text.into_bytes(), client_id)?;
+            self.write_to_active_terminal(BRACKETED_PASTE_END.to_vec(), client_id)?;
+        }
+        Ok(())
+    }
+
     fn focus_pane_at(&mut self, point: &Position, client_id: ClientId) -> Result<()> {
         let err_context =
             || format!("failed to focus pane at position {point:?} for client {client_id}");
@@ -2154,6 +2941,7 @@ impl Tab {
             if let Some(clicked_pane) = self.floating_panes.get_pane_id_at(point, true) {
                 self.floating_panes.focus_pane(clicked_pane, client_id);
                 self.set_pane_active_at(clicked_pane);
+                self.clear_horizontal_scroll_of_unfocused_panes();
                 return Ok(());
             }
         }
@@ -2164,6 +2952,7 @@ impl Tab {
                 self.floating_panes.toggle_show_panes(false);
                 self.set_force_render();
             }
+            self.clear_horizontal_scroll_of_unfocused_panes();
         }
         Ok(())
     }
@@ -2447,6 +3236,29 @@ impl Tab {
         Ok(false) // we shouldn't even get here, but might as well not needlessly render if we do
     }
 
+    /// Focuses the pane under the mouse pointer, staying within the floating panes while they
+    /// are shown. Returns whether this changed the focus.
+    pub fn handle_mouse_move(&mut self, position: &Position, client_id: ClientId) -> Result<bool> {
+        let err_context = || {
+            format!("failed to handle mouse move to position {position:?} for client {client_id}")
+        };
+
+        let hovered_pane = if self.floating_panes.panes_are_visible() {
+            self.floating_panes.get_pane_id_at(position, true)
+        } else {
+            self.get_pane_id_at(position, true)
+                .with_context(err_context)?
+        };
+        if let Some(hovered_pane) = hovered_pane {
+            if Some(hovered_pane) != self.get_active_pane_id(client_id) {
+                self.focus_pane_at(position, client_id)
+                    .with_context(err_context)?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     pub fn copy_selection(&self, client_id: ClientId) -> Result<()> {
         let selected_text = self
             .get_active_pane(client_id)
@@ -2473,33 +3285,21 @@ impl Tab {
     fn write_selection_to_clipboard(&self, selection: &str) -> Result<()> {
         let err_context = || format!("failed to write selection to clipboard: '{}'", selection);
 
+        *self.copied_text.borrow_mut() = Some(selection.to_owned());
+
         let mut output = Output::default();
         let connected_clients: HashSet<ClientId> =
             { self.connected_clients.borrow().iter().copied().collect() };
         output.add_clients(&connected_clients, self.link_handler.clone(), None);
         let client_ids = connected_clients.iter().copied();
-        let clipboard_event =
-            match self
-                .clipboard_provider
-                .set_content(selection, &mut output, client_ids)
-            {
-                Ok(_) => output
-                    .serialize()
-                    .and_then(|serialized_output| {
-                        self.senders
-                            .send_to_server(ServerInstruction::Render(Some(serialized_output)))
-                    })
-                    .and_then(|_| {
-                        Ok(Event::CopyToClipboard(
-                            self.clipboard_provider.as_copy_destination(),
-                        ))
-                    })
-                    .with_context(err_context)?,
-                Err(err) => {
-                    Err::<(), _>(err).with_context(err_context).non_fatal();
-                    Event::SystemClipboardFailure
-                },
-            };
+        let copy_destination =
+            self.clipboard_provider
+                .set_content(selection, &mut output, client_ids);
+        let serialized_output = output.serialize().with_context(err_context)?;
+        self.senders
+            .send_to_server(ServerInstruction::Render(Some(serialized_output)))
+            .with_context(err_context)?;
+        let clipboard_event = Event::CopyToClipboard(copy_destination);
         self.senders
             .send_to_plugin(PluginInstruction::Update(None, None, clipboard_event))
             .context("failed to notify plugins about new clipboard event")
@@ -2607,6 +3407,7 @@ impl Tab {
     }
 
     pub fn set_pane_frames(&mut self, should_set_pane_frames: bool) {
+        self.draw_pane_frames = should_set_pane_frames;
         self.tiled_panes.set_pane_frames(should_set_pane_frames);
         self.should_clear_display_before_rendering = true;
         self.set_force_render();
@@ -2626,19 +3427,40 @@ impl Tab {
                 active_pane.update_search_term(s);
             }
         }
-        Ok(())
+        self.report_search_without_matches(client_id)
     }
 
-    pub fn search_down(&mut self, client_id: ClientId) {
+    pub fn search_down(&mut self, client_id: ClientId) -> Result<()> {
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.search_down();
         }
+        self.report_search_without_matches(client_id)
     }
 
-    pub fn search_up(&mut self, client_id: ClientId) {
+    pub fn search_up(&mut self, client_id: ClientId) -> Result<()> {
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.search_up();
         }
+        self.report_search_without_matches(client_id)
+    }
+
+    fn report_search_without_matches(&mut self, client_id: ClientId) -> Result<()> {
+        let found_nothing = self
+            .get_active_pane_or_floating_pane_mut(client_id)
+            .map(|active_pane| active_pane.search_found_nothing())
+            .unwrap_or(false);
+        if found_nothing {
+            self.senders
+                .send_to_plugin(PluginInstruction::Update(
+                    None,
+                    Some(client_id),
+                    Event::StatusMessage("No matches found".to_owned()),
+                ))
+                .with_context(|| {
+                    format!("failed to report search results to client {client_id}")
+                })?;
+        }
+        Ok(())
     }
 
     pub fn toggle_search_case_sensitivity(&mut self, client_id: ClientId) {
@@ -0,0 +1,31 @@
+use super::*;
+use crate::panes::LinkHandler;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+#[test]
+fn copy_command_that_cannot_be_run_falls_back_to_osc52() {
+    let client_id = 1;
+    let provider = ClipboardProvider::Command(
+        CopyCommand::new(String::from("/no/such/copy-command --flag")),
+        Clipboard::System,
+    );
+    let mut output = Output::default();
+    output.add_clients(
+        &HashSet::from([client_id]),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        None,
+    );
+    let copy_destination =
+        provider.set_content("copied text", &mut output, [client_id].into_iter());
+    assert_eq!(copy_destination, CopyDestination::System);
+    let serialized_output = output.serialize().unwrap();
+    assert!(
+        serialized_output
+            .get(&client_id)
+            .unwrap()
+            .contains(&format!("\u{1b}]52;c;{}", base64::encode("copied text"))),
+        "text copied with OSC 52"
+    );
+}
@@ -14,6 +14,7 @@ use zellij_utils::channels::Receiver;
 use zellij_utils::envs::set_session_name;
 use zellij_utils::errors::ErrorContext;
 use zellij_utils::input::layout::{Layout, PaneLayout};
+use zellij_utils::input::options::AutoLayout;
 use zellij_utils::ipc::IpcReceiverWithContext;
 use zellij_utils::pane_size::{Size, SizeInPixels};
 use zellij_utils::position::Position;
@@ -52,6 +53,9 @@ impl ServerOsApi for FakeInputOutput {
     ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
         unimplemented!()
     }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        unimplemented!()
+    }
     fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
         unimplemented!()
     }
@@ -70,6 +74,9 @@ impl ServerOsApi for FakeInputOutput {
     fn force_kill(&self, _pid: Pid) -> Result<(), nix::Error> {
         unimplemented!()
     }
+    fn hang_up_process_group(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
     fn box_clone(&self) -> Box<dyn ServerOsApi> {
         Box::new((*self).clone())
     }
@@ -114,6 +121,19 @@ impl ServerOsApi for FakeInputOutput {
     fn clear_terminal_id(&self, _terminal_id: u32) {
         unimplemented!()
     }
+    fn foreground_process_is_running(&self, _terminal_id: u32) -> bool {
+        false
+    }
+    fn signal_foreground_process_group(
+        &self,
+        _terminal_id: u32,
+        _signal: nix::sys::signal::Signal,
+    ) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn pipe_to_command(&self, _buf: String, _command: &[String]) -> std::io::Result<()> {
+        unimplemented!()
+    }
 }
 
 struct MockPtyInstructionBus {
@@ -202,6 +222,9 @@ fn create_new_tab(size: Size, default_mode: ModeInfo) -> Tab {
     let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
     let copy_options = CopyOptions::default();
     let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
     let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
     let mut tab = Tab::new(
         index,
@@ -222,6 +245,9 @@ fn create_new_tab(size: Size, default_mode: ModeInfo) -> Tab {
         copy_options,
         terminal_emulator_colors,
         terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
     );
     tab.apply_layout(PaneLayout::default(), vec![1], index, client_id)
         .unwrap();
@@ -250,6 +276,9 @@ fn create_new_tab_with_layout(size: Size, default_mode: ModeInfo, layout: &str)
     let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
     let copy_options = CopyOptions::default();
     let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
     let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
     let layout = Layout::from_str(layout, "layout_file_name".into(), None).unwrap();
     let tab_layout = layout.new_tab();
@@ -272,6 +301,9 @@ fn create_new_tab_with_layout(size: Size, default_mode: ModeInfo, layout: &str)
         copy_options,
         terminal_emulator_colors,
         terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
     );
     let pane_ids = tab_layout
         .extract_run_instructions()
@@ -311,6 +343,9 @@ fn create_new_tab_with_mock_pty_writer(
     let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
     let copy_options = CopyOptions::default();
     let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
     let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
     let mut tab = Tab::new(
         index,
@@ -331,6 +366,9 @@ fn create_new_tab_with_mock_pty_writer(
         copy_options,
         terminal_emulator_colors,
         terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
     );
     tab.apply_layout(
         // LayoutTemplate::default().try_into().unwrap(),
@@ -373,6 +411,9 @@ fn create_new_tab_with_sixel_support(
     let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
     let copy_options = CopyOptions::default();
     let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
     let mut tab = Tab::new(
         index,
         position,
@@ -392,6 +433,9 @@ fn create_new_tab_with_sixel_support(
         copy_options,
         terminal_emulator_colors,
         terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
     );
     tab.apply_layout(PaneLayout::default(), vec![1], index, client_id)
         .unwrap();
@@ -2096,6 +2140,43 @@ fn enter_search_floating_pane() {
     assert_snapshot!("search_floating_tab_highlight_fring", snapshot);
 }
 
+#[test]
+fn middle_click_pastes_text_selected_with_the_mouse() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+
+    let mut pty_instruction_bus = MockPtyInstructionBus::new();
+    let mut tab = create_new_tab_with_mock_pty_writer(
+        size,
+        ModeInfo::default(),
+        pty_instruction_bus.pty_write_sender(),
+    );
+    pty_instruction_bus.start();
+
+    tab.handle_pty_bytes(1, "hello world".as_bytes().to_vec())
+        .unwrap();
+    tab.handle_left_click(&Position::new(1, 1), client_id)
+        .unwrap();
+    tab.handle_left_mouse_release(&Position::new(1, 6), client_id)
+        .unwrap();
+    tab.handle_middle_click(&Position::new(5, 71), client_id)
+        .unwrap();
+
+    pty_instruction_bus.exit();
+
+    assert_eq!(
+        pty_instruction_bus.clone_output(),
+        vec![
+            "".to_string(), // bracketed paste start, dropped since the pane is not in bracketed paste mode
+            "hello".to_string(),
+            "".to_string(), // bracketed paste end
+        ]
+    );
+}
+
 #[test]
 fn pane_in_sgr_button_event_tracking_mouse_mode() {
     let size = Size {
@@ -2531,3 +2612,97 @@ fn pane_faux_scrolling_in_alternate_mode() {
 
     assert_eq!(pty_instruction_bus.clone_output(), expected);
 }
+
+#[test]
+fn closing_a_layout_pane_forgets_what_it_was_started_with() {
+    let layout = r#"
+        layout {
+            pane split_direction="Vertical" {
+                pane command="htop"
+                pane
+            }
+        }
+    "#;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab_with_layout(size, ModeInfo::default(), layout);
+    assert!(
+        tab.pane_runs.contains_key(&PaneId::Terminal(0)),
+        "command pane remembers its command"
+    );
+    tab.close_pane(PaneId::Terminal(0), false);
+    assert!(
+        !tab.pane_runs.contains_key(&PaneId::Terminal(0)),
+        "closed pane is forgotten"
+    );
+    let current_layout = tab.current_layout(client_id);
+    assert!(
+        current_layout
+            .children
+            .iter()
+            .all(|pane| pane.run.is_none())
+            && current_layout.run.is_none(),
+        "closed pane's command is not in the layout"
+    );
+}
+
+#[test]
+fn horizontal_scroll_is_cleared_once_focus_moves_away_from_the_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    tab.move_focus_left(client_id);
+    // a line longer than the pane, written with linewrap disabled
+    let mut long_line = b"\x1b[?7l".to_vec();
+    long_line.extend("0123456789".repeat(20).into_bytes());
+    tab.handle_pty_bytes(1, long_line).unwrap();
+    let first_rendered_line = |tab: &mut Tab| {
+        let mut output = Output::default();
+        tab.render(&mut output, None).unwrap();
+        let snapshot = take_snapshot(
+            output.serialize().unwrap().get(&client_id).unwrap(),
+            size.rows,
+            size.cols,
+            Palette::default(),
+        );
+        snapshot.lines().nth(1).unwrap().to_owned()
+    };
+    assert!(
+        first_rendered_line(&mut tab).contains("│0123456789"),
+        "line rendered from its start"
+    );
+    tab.scroll_active_terminal_right(client_id);
+    assert!(
+        first_rendered_line(&mut tab).contains("│9012345678"),
+        "line rendered half a pane to the right"
+    );
+    tab.move_focus_right(client_id);
+    assert!(
+        first_rendered_line(&mut tab).contains("│0123456789"),
+        "horizontal scroll cleared as soon as the focus moved away"
+    );
+}
+
+#[test]
+fn pane_is_not_recorded_as_scrolled_when_its_lines_fit() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.handle_pty_bytes(1, b"short line".to_vec()).unwrap();
+    tab.scroll_active_terminal_right(client_id);
+    assert!(
+        tab.horizontally_scrolled_panes.is_empty(),
+        "nothing scrolled, so nothing to clear on focus change"
+    );
+}
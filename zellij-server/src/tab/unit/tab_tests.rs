@@ -1,16 +1,22 @@
 use super::Tab;
 use crate::panes::sixel::SixelImageStore;
-use crate::screen::CopyOptions;
+use crate::panes::PluginPane;
+use crate::pty_writer::PtyWriteInstruction;
+use crate::screen::{CopyOptions, MaxPanes};
 use crate::{
     os_input_output::{AsyncReader, Pid, ServerOsApi, SpawnTerminalError},
     panes::PaneId,
     thread_bus::ThreadSenders,
+    wasm_vm::PluginInstruction,
     ClientId,
 };
 use std::path::PathBuf;
+use zellij_utils::input::actions::ResizeDirection;
 use zellij_utils::input::layout::PaneLayout;
+use zellij_utils::input::options::AutoLayout;
 use zellij_utils::ipc::IpcReceiverWithContext;
-use zellij_utils::pane_size::{Size, SizeInPixels};
+use zellij_utils::pane_size::{PaneGeom, Size, SizeInPixels};
+use zellij_utils::position::Position;
 
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -20,14 +26,17 @@ use std::rc::Rc;
 use zellij_utils::nix;
 
 use zellij_utils::{
-    data::{ModeInfo, Palette, Style},
+    channels::{self, ChannelWithContext, SenderWithContext},
+    data::{Event, ModeInfo, Palette, Style},
     input::command::{RunCommand, TerminalAction},
     interprocess::local_socket::LocalSocketStream,
     ipc::{ClientToServerMsg, ServerToClientMsg},
 };
 
-#[derive(Clone)]
-struct FakeInputOutput {}
+#[derive(Clone, Default)]
+struct FakeInputOutput {
+    terminals_running_a_program: HashSet<u32>,
+}
 
 impl ServerOsApi for FakeInputOutput {
     fn set_terminal_size_using_terminal_id(&self, _id: u32, _cols: u16, _rows: u16) {
@@ -41,6 +50,9 @@ impl ServerOsApi for FakeInputOutput {
     ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
         unimplemented!()
     }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        unimplemented!()
+    }
     fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
         unimplemented!()
     }
@@ -59,6 +71,9 @@ impl ServerOsApi for FakeInputOutput {
     fn force_kill(&self, _pid: Pid) -> Result<(), nix::Error> {
         unimplemented!()
     }
+    fn hang_up_process_group(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
     fn box_clone(&self) -> Box<dyn ServerOsApi> {
         Box::new((*self).clone())
     }
@@ -100,13 +115,26 @@ impl ServerOsApi for FakeInputOutput {
     fn clear_terminal_id(&self, _terminal_id: u32) {
         unimplemented!()
     }
+    fn foreground_process_is_running(&self, terminal_id: u32) -> bool {
+        self.terminals_running_a_program.contains(&terminal_id)
+    }
+    fn signal_foreground_process_group(
+        &self,
+        _terminal_id: u32,
+        _signal: nix::sys::signal::Signal,
+    ) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn pipe_to_command(&self, _buf: String, _command: &[String]) -> std::io::Result<()> {
+        unimplemented!()
+    }
 }
 
 fn create_new_tab(size: Size) -> Tab {
     let index = 0;
     let position = 0;
     let name = String::new();
-    let os_api = Box::new(FakeInputOutput {});
+    let os_api = Box::new(FakeInputOutput::default());
     let senders = ThreadSenders::default().silently_fail_on_send();
     let max_panes = None;
     let mode_info = ModeInfo::default();
@@ -122,6 +150,9 @@ fn create_new_tab(size: Size) -> Tab {
     let copy_options = CopyOptions::default();
     let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
     let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
     let mut tab = Tab::new(
         index,
         position,
@@ -141,6 +172,9 @@ fn create_new_tab(size: Size) -> Tab {
         copy_options,
         terminal_emulator_colors,
         terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
     );
     tab.apply_layout(PaneLayout::default(), vec![1], index, client_id)
         .unwrap();
@@ -154,7 +188,7 @@ fn create_new_tab_with_cell_size(
     let index = 0;
     let position = 0;
     let name = String::new();
-    let os_api = Box::new(FakeInputOutput {});
+    let os_api = Box::new(FakeInputOutput::default());
     let senders = ThreadSenders::default().silently_fail_on_send();
     let max_panes = None;
     let mode_info = ModeInfo::default();
@@ -169,6 +203,9 @@ fn create_new_tab_with_cell_size(
     let copy_options = CopyOptions::default();
     let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
     let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
     let mut tab = Tab::new(
         index,
         position,
@@ -188,6 +225,59 @@ fn create_new_tab_with_cell_size(
         copy_options,
         terminal_emulator_colors,
         terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
+    );
+    tab.apply_layout(PaneLayout::default(), vec![1], index, client_id)
+        .unwrap();
+    tab
+}
+
+fn create_new_tab_with_auto_layout(size: Size, auto_layout: AutoLayout) -> Tab {
+    let index = 0;
+    let position = 0;
+    let name = String::new();
+    let os_api = Box::new(FakeInputOutput::default());
+    let senders = ThreadSenders::default().silently_fail_on_send();
+    let max_panes = None;
+    let mode_info = ModeInfo::default();
+    let style = Style::default();
+    let draw_pane_frames = true;
+    let client_id = 1;
+    let session_is_mirrored = true;
+    let mut connected_clients = HashSet::new();
+    let character_cell_info = Rc::new(RefCell::new(None));
+    connected_clients.insert(client_id);
+    let connected_clients = Rc::new(RefCell::new(connected_clients));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let copy_options = CopyOptions::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let resize_percent = 5.0;
+    let undo_close_depth = 10;
+    let mut tab = Tab::new(
+        index,
+        position,
+        name,
+        size,
+        character_cell_info,
+        sixel_image_store,
+        os_api,
+        senders,
+        max_panes,
+        style,
+        mode_info,
+        draw_pane_frames,
+        connected_clients,
+        session_is_mirrored,
+        client_id,
+        copy_options,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        resize_percent,
+        undo_close_depth,
+        auto_layout,
     );
     tab.apply_layout(PaneLayout::default(), vec![1], index, client_id)
         .unwrap();
@@ -596,7 +686,7 @@ fn split_largest_pane() {
 
 #[test]
 pub fn cannot_split_panes_vertically_when_active_pane_is_too_small() {
-    let size = Size { cols: 8, rows: 20 };
+    let size = Size { cols: 8, rows: 4 };
     let mut tab = create_new_tab(size);
     tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
     assert_eq!(
@@ -608,9 +698,54 @@ pub fn cannot_split_panes_vertically_when_active_pane_is_too_small() {
 
 #[test]
 pub fn cannot_split_panes_horizontally_when_active_pane_is_too_small() {
+    let size = Size { cols: 8, rows: 4 };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    assert_eq!(
+        tab.tiled_panes.panes.len(),
+        1,
+        "Tab still has only one pane"
+    );
+}
+
+#[test]
+pub fn horizontal_split_of_a_pane_too_short_for_it_falls_back_to_a_vertical_split() {
     let size = Size { cols: 121, rows: 4 };
     let mut tab = create_new_tab(size);
     tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "Tab has two panes");
+    let new_pane = tab.tiled_panes.panes.get(&PaneId::Terminal(2)).unwrap();
+    assert_eq!(new_pane.y(), 0, "new pane is next to the focused pane");
+    assert_eq!(
+        new_pane.x(),
+        61,
+        "new pane is to the right of the focused pane"
+    );
+    assert_eq!(new_pane.rows(), 4, "new pane keeps the full height");
+}
+
+#[test]
+pub fn vertical_split_of_a_pane_too_narrow_for_it_falls_back_to_a_horizontal_split() {
+    let size = Size { cols: 8, rows: 20 };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "Tab has two panes");
+    let new_pane = tab.tiled_panes.panes.get(&PaneId::Terminal(2)).unwrap();
+    assert_eq!(new_pane.x(), 0, "new pane is under the focused pane");
+    assert_eq!(new_pane.y(), 10, "new pane is below the focused pane");
+    assert_eq!(new_pane.cols(), 8, "new pane keeps the full width");
+}
+
+#[test]
+pub fn cannot_split_panes_horizontally_below_the_min_pane_height() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    // the pane can't be split vertically either, which the split would fall back to
+    tab.set_min_pane_size(Some(61), Some(11));
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
     assert_eq!(
         tab.tiled_panes.panes.len(),
         1,
@@ -618,6 +753,36 @@ pub fn cannot_split_panes_horizontally_when_active_pane_is_too_small() {
     );
 }
 
+#[test]
+pub fn panes_keep_their_min_pane_size_while_the_terminal_is_too_small() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.set_min_pane_size(Some(40), None);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    let pane_widths = |tab: &Tab| -> Vec<usize> {
+        tab.tiled_panes
+            .panes
+            .values()
+            .map(|pane| pane.cols())
+            .collect()
+    };
+
+    tab.resize_whole_tab(Size { cols: 60, rows: 20 });
+    assert!(tab.tiled_panes.is_too_small(), "Terminal too small");
+    assert_eq!(pane_widths(&tab), vec![61, 60], "Panes were not shrunk");
+
+    tab.resize_whole_tab(Size { cols: 81, rows: 20 });
+    assert!(!tab.tiled_panes.is_too_small(), "Panes fit again");
+    assert_eq!(
+        pane_widths(&tab),
+        vec![41, 40],
+        "Panes kept their proportions"
+    );
+}
+
 #[test]
 pub fn cannot_split_largest_pane_when_there_is_no_room() {
     let size = Size { cols: 8, rows: 4 };
@@ -631,6 +796,79 @@ pub fn cannot_split_largest_pane_when_there_is_no_room() {
     );
 }
 
+#[test]
+pub fn cannot_open_new_pane_over_max_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.max_panes = Some(MaxPanes::new(2, false));
+    let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
+    tab.senders = ThreadSenders {
+        to_plugin: Some(SenderWithContext::new(to_plugin)),
+        ..Default::default()
+    }
+    .silently_fail_on_send();
+    tab.new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
+    tab.vertical_split(PaneId::Terminal(3), None, 1).unwrap();
+    tab.horizontal_split(PaneId::Terminal(4), None, 1).unwrap();
+    tab.new_pane(PaneId::Terminal(5), None, None, Some(1))
+        .unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "Tab has only two panes");
+    assert!(
+        tab.tiled_panes.panes.contains_key(&PaneId::Terminal(1))
+            && tab.tiled_panes.panes.contains_key(&PaneId::Terminal(2)),
+        "Existing panes were not closed"
+    );
+    let status_messages: Vec<String> = plugin_receiver
+        .try_iter()
+        .filter_map(|(instruction, _)| match instruction {
+            PluginInstruction::Update(_, Some(1), Event::StatusMessage(message)) => Some(message),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        status_messages,
+        vec!["Max panes (2) reached, refusing to open a new pane".to_owned(); 3],
+        "Client told why each pane was refused"
+    );
+}
+
+#[test]
+pub fn close_old_panes_over_max_panes_when_evicting() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.max_panes = Some(MaxPanes::new(2, true));
+    tab.new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
+    tab.new_pane(PaneId::Terminal(3), None, None, Some(1))
+        .unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "Tab has only two panes");
+    assert!(
+        tab.tiled_panes.panes.contains_key(&PaneId::Terminal(3)),
+        "New pane was opened"
+    );
+}
+
+#[test]
+pub fn move_active_pane_without_focused_pane_is_a_noop() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
+    let client_without_focused_pane = 2;
+    tab.move_active_pane(client_without_focused_pane);
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "Tab still has both panes");
+}
+
 #[test]
 pub fn toggle_focused_pane_fullscreen() {
     let size = Size {
@@ -704,6 +942,393 @@ pub fn toggle_focused_pane_fullscreen() {
     // function and we already test that in the e2e tests
 }
 
+#[test]
+fn resizing_focused_pane_exits_fullscreen() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.toggle_active_pane_fullscreen(1);
+    assert!(tab.is_fullscreen_active(), "Pane is fullscreen");
+    tab.resize_left(1);
+    assert!(
+        !tab.is_fullscreen_active(),
+        "Resizing the pane exits fullscreen"
+    );
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(2))
+            .unwrap()
+            .position_and_size()
+            .x,
+        54,
+        "Pane resized after exiting fullscreen"
+    );
+}
+
+#[test]
+fn equalize_panes_after_resizing() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, 1).unwrap();
+    let pane_sizes = |tab: &Tab| -> Vec<(usize, usize, usize, usize)> {
+        (1..=3)
+            .map(|id| {
+                let geom = tab
+                    .tiled_panes
+                    .panes
+                    .get(&PaneId::Terminal(id))
+                    .unwrap()
+                    .position_and_size();
+                (geom.x, geom.y, geom.cols.as_usize(), geom.rows.as_usize())
+            })
+            .collect()
+    };
+    let sizes_after_splitting = pane_sizes(&tab);
+    tab.resize_left(1);
+    tab.resize_up(1);
+    assert_ne!(
+        pane_sizes(&tab),
+        sizes_after_splitting,
+        "Panes were resized"
+    );
+    tab.equalize_panes();
+    assert_eq!(
+        pane_sizes(&tab),
+        sizes_after_splitting,
+        "Panes are back to an equal share of their splits"
+    );
+}
+
+#[test]
+fn resize_focused_pane_to_fraction_of_split() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.move_focus_left(1);
+    tab.resize_to_fraction(1.0 / 3.0, 1);
+    let pane_cols = |tab: &Tab, id: u32| {
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(id))
+            .unwrap()
+            .position_and_size()
+            .cols
+            .as_usize()
+    };
+    assert_eq!(
+        pane_cols(&tab, 1),
+        40,
+        "focused pane takes a third of the split"
+    );
+    assert_eq!(
+        pane_cols(&tab, 2),
+        81,
+        "other pane takes the rest of the split"
+    );
+    tab.resize_to_fraction(2.0 / 3.0, 1);
+    assert_eq!(
+        pane_cols(&tab, 1),
+        81,
+        "focused pane takes two thirds of the split"
+    );
+    assert_eq!(
+        pane_cols(&tab, 2),
+        40,
+        "other pane takes the rest of the split"
+    );
+}
+
+#[test]
+fn fine_resize_changes_the_focused_pane_by_a_single_cell() {
+    let size = Size {
+        cols: 120,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.move_focus_left(1);
+    let pane_cols = |tab: &Tab, id: u32| {
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(id))
+            .unwrap()
+            .position_and_size()
+            .cols
+            .as_usize()
+    };
+    assert_eq!(pane_cols(&tab, 1), 60, "pane 1 starts with half the tab");
+    tab.resize_fine(ResizeDirection::Right, 1);
+    assert_eq!(pane_cols(&tab, 1), 61, "pane 1 grew by a single column");
+    assert_eq!(pane_cols(&tab, 2), 59, "pane 2 shrunk by a single column");
+    tab.resize_fine(ResizeDirection::Left, 1);
+    assert_eq!(
+        pane_cols(&tab, 1),
+        60,
+        "pane 1 shrunk back by a single column"
+    );
+    assert_eq!(
+        pane_cols(&tab, 2),
+        60,
+        "pane 2 grew back by a single column"
+    );
+    tab.resize_right(1);
+    assert!(
+        pane_cols(&tab, 1) > 61,
+        "a regular resize still grows the pane by the configured step"
+    );
+}
+
+#[test]
+fn undo_close_pane_reopens_pane_in_place() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    let pane_geom = |tab: &Tab, id: u32| {
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(id))
+            .unwrap()
+            .position_and_size()
+    };
+    let first_pane_geom = pane_geom(&tab, 1);
+    let closed_pane_geom = pane_geom(&tab, 2);
+    tab.close_focused_pane(1).unwrap();
+    assert_eq!(
+        pane_geom(&tab, 1).cols.as_usize(),
+        121,
+        "remaining pane takes up the closed pane's space"
+    );
+    // the pty sends the closed pane back along with the terminal it reopened it in
+    let closed_pane = tab.closed_panes.pop().unwrap();
+    tab.reopen_closed_pane(PaneId::Terminal(3), closed_pane, 1)
+        .unwrap();
+    assert_eq!(
+        pane_geom(&tab, 3),
+        closed_pane_geom,
+        "reopened pane takes the closed pane's place"
+    );
+    assert_eq!(
+        pane_geom(&tab, 1),
+        first_pane_geom,
+        "other pane is restored to its size before the close"
+    );
+}
+
+#[test]
+fn clicking_a_point_shared_by_two_panes_focuses_the_top_left_one() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    assert_eq!(tab.get_active_pane_id(1), Some(PaneId::Terminal(2)));
+    // have the right pane overlap the last column of the left one
+    let left_pane_geom = tab
+        .tiled_panes
+        .get_pane_mut(PaneId::Terminal(1))
+        .unwrap()
+        .position_and_size();
+    let right_pane = tab.tiled_panes.get_pane_mut(PaneId::Terminal(2)).unwrap();
+    let mut overlapping_geom = right_pane.position_and_size();
+    overlapping_geom.x -= 1;
+    overlapping_geom
+        .cols
+        .set_inner(overlapping_geom.cols.as_usize() + 1);
+    right_pane.set_geom_override(overlapping_geom);
+    let shared_column = left_pane_geom.x + left_pane_geom.cols.as_usize() - 1;
+    tab.handle_left_click(&Position::new(5, shared_column as u16), 1)
+        .unwrap();
+    assert_eq!(
+        tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(1)),
+        "pane to the left of the shared column is focused"
+    );
+}
+
+#[test]
+fn moving_the_mouse_focuses_the_pane_under_it() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    let focus_changed = tab.handle_mouse_move(&Position::new(5, 10), 1).unwrap();
+    assert_eq!(
+        tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(1)),
+        "hovered pane is focused"
+    );
+    assert!(focus_changed, "focus change reported");
+    let focus_changed = tab.handle_mouse_move(&Position::new(6, 12), 1).unwrap();
+    assert!(
+        !focus_changed,
+        "moving within the focused pane does not change the focus"
+    );
+}
+
+fn pane_geoms(tab: &Tab) -> Vec<(usize, usize, usize, usize)> {
+    let mut geoms: Vec<(usize, usize, usize, usize)> = tab
+        .tiled_panes
+        .get_panes()
+        .map(|(_pane_id, pane)| {
+            let geom = pane.position_and_size();
+            (geom.x, geom.y, geom.cols.as_usize(), geom.rows.as_usize())
+        })
+        .collect();
+    geoms.sort_unstable();
+    geoms
+}
+
+#[test]
+fn new_panes_are_arranged_in_an_even_grid() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab_with_auto_layout(size, AutoLayout::EvenGrid);
+    for pane_id in 2..=4 {
+        tab.new_pane(PaneId::Terminal(pane_id), None, None, Some(1))
+            .unwrap();
+    }
+    assert_eq!(
+        pane_geoms(&tab),
+        vec![
+            (0, 0, 61, 10),
+            (0, 10, 61, 10),
+            (61, 0, 60, 10),
+            (61, 10, 60, 10)
+        ],
+        "panes arranged in two rows and two columns"
+    );
+    tab.close_pane(PaneId::Terminal(4), false);
+    assert_eq!(
+        pane_geoms(&tab),
+        vec![(0, 0, 61, 10), (0, 10, 61, 10), (61, 0, 60, 20)],
+        "panes arranged again after one was closed"
+    );
+}
+
+#[test]
+fn manual_resize_stops_auto_layout_until_it_is_applied_again() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab_with_auto_layout(size, AutoLayout::MainAndStack);
+    for pane_id in 2..=3 {
+        tab.new_pane(PaneId::Terminal(pane_id), None, None, Some(1))
+            .unwrap();
+    }
+    assert_eq!(
+        pane_geoms(&tab),
+        vec![(0, 0, 61, 20), (61, 0, 60, 10), (61, 10, 60, 10)],
+        "main pane on the left and the other panes stacked on the right"
+    );
+    tab.resize_left(1);
+    tab.new_pane(PaneId::Terminal(4), None, None, Some(1))
+        .unwrap();
+    let main_and_stack = vec![
+        (0, 0, 61, 20),
+        (61, 0, 60, 7),
+        (61, 7, 60, 7),
+        (61, 14, 60, 6),
+    ];
+    assert_ne!(
+        pane_geoms(&tab),
+        main_and_stack,
+        "new pane not arranged after a manual resize"
+    );
+    tab.apply_auto_layout();
+    assert_eq!(
+        pane_geoms(&tab),
+        main_and_stack,
+        "panes arranged when the auto-layout is applied"
+    );
+}
+
+#[test]
+fn output_of_scrolled_pane_is_processed_once_it_exceeds_pending_output_limit() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.pending_output_limit = 100;
+    for i in 0..50 {
+        tab.handle_pty_bytes(1, format!("line {}\n\r", i).into_bytes())
+            .unwrap();
+    }
+    tab.scroll_active_terminal_up(1).unwrap();
+    tab.handle_pty_bytes(1, vec![b'a'; 60]).unwrap();
+    assert_eq!(
+        tab.pending_vte_events.get(&1).map(|evs| evs.len()),
+        Some(1),
+        "output of a scrolled pane is held back"
+    );
+    tab.handle_pty_bytes(1, vec![b'a'; 60]).unwrap();
+    assert_eq!(
+        tab.pending_vte_events.get(&1).map(|evs| evs.len()),
+        Some(0),
+        "held back output is processed once it exceeds the limit"
+    );
+}
+
+#[test]
+fn scrolling_a_pane_without_a_frame_shows_how_far_back_it_is_scrolled() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
+    tab.senders = ThreadSenders {
+        to_plugin: Some(SenderWithContext::new(to_plugin)),
+        ..Default::default()
+    }
+    .silently_fail_on_send();
+    tab.set_pane_frames(false);
+    for i in 0..50 {
+        tab.handle_pty_bytes(1, format!("line {}\n\r", i).into_bytes())
+            .unwrap();
+    }
+    tab.scroll_active_terminal_up(1).unwrap();
+    tab.scroll_active_terminal_down(1).unwrap();
+
+    let status_messages: Vec<String> = plugin_receiver
+        .try_iter()
+        .filter_map(|(instruction, _)| match instruction {
+            PluginInstruction::Update(_, _, Event::StatusMessage(message)) => Some(message),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        status_messages.len(),
+        1,
+        "scroll position shown while scrolled, but not once back at the bottom"
+    );
+    assert!(
+        status_messages[0].starts_with("SCROLL: 1/"),
+        "scroll position shown in a status message: {:?}",
+        status_messages
+    );
+}
+
 #[test]
 fn switch_to_next_pane_fullscreen() {
     let size = Size {
@@ -2734,6 +3359,34 @@ pub fn move_focus_down_to_the_most_recently_used_pane() {
     );
 }
 
+#[test]
+pub fn move_focus_down_to_the_pane_with_the_longest_shared_edge() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.vertical_split(PaneId::Terminal(3), None, 1).unwrap();
+    tab.move_focus_left(1);
+    tab.vertical_split(PaneId::Terminal(4), None, 1).unwrap();
+    tab.vertical_split(PaneId::Terminal(5), None, 1).unwrap();
+    tab.move_focus_up(1);
+    tab.vertical_split(PaneId::Terminal(6), None, 1).unwrap();
+    tab.move_focus_left(1);
+    // below the focused pane are pane 2, which covers half of its bottom edge, and panes 4 and 5,
+    // which cover a quarter each - the pane we came from is to the right, so pane 2 wins over the
+    // more recently used pane 5
+    tab.move_focus_down(1);
+
+    assert_eq!(
+        tab.get_active_pane(1).unwrap().pid(),
+        PaneId::Terminal(2),
+        "Active pane is the one with the longest shared edge"
+    );
+}
+
 #[test]
 pub fn move_focus_up() {
     let size = Size {
@@ -14070,3 +14723,117 @@ fn correctly_resize_frameless_panes_on_pane_close() {
     let content_size = (pane.get_content_columns(), pane.get_content_rows());
     assert_eq!(content_size, (cols, rows));
 }
+
+#[test]
+fn focused_pane_is_restored_when_coming_back_to_the_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    tab.vertical_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    tab.focus_pane_with_id(PaneId::Terminal(2), client_id);
+    let client_mode_infos = tab.drain_connected_clients(None);
+    tab.add_multiple_clients(client_mode_infos).unwrap();
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(2)),
+        "last focused pane focused again"
+    );
+
+    let client_mode_infos = tab.drain_connected_clients(None);
+    tab.close_pane(PaneId::Terminal(2), false);
+    tab.add_multiple_clients(client_mode_infos).unwrap();
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(1)),
+        "pane next to the closed one focused instead"
+    );
+}
+
+#[test]
+fn focused_pane_running_a_program_is_found() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.os_api = Box::new(FakeInputOutput {
+        terminals_running_a_program: HashSet::from([1]),
+    });
+    assert_eq!(
+        tab.focused_pane_with_foreground_process(1),
+        Some(PaneId::Terminal(1)),
+        "focused pane running a program found"
+    );
+}
+
+#[test]
+fn focused_pane_at_its_shell_prompt_is_not_found() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.os_api = Box::new(FakeInputOutput {
+        terminals_running_a_program: HashSet::from([1]),
+    });
+    tab.new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
+    assert_eq!(
+        tab.focused_pane_with_foreground_process(1),
+        None,
+        "focused pane is at its shell prompt, only an unfocused pane runs a program"
+    );
+}
+
+#[test]
+fn synchronized_input_is_only_written_to_terminal_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    let (to_pty_writer, pty_writer_receiver): ChannelWithContext<PtyWriteInstruction> =
+        channels::unbounded();
+    let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
+    tab.senders = ThreadSenders {
+        to_pty_writer: Some(SenderWithContext::new(to_pty_writer)),
+        to_plugin: Some(SenderWithContext::new(to_plugin.clone())),
+        ..Default::default()
+    }
+    .silently_fail_on_send();
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.tiled_panes.add_pane_with_existing_geom(
+        PaneId::Plugin(1),
+        Box::new(PluginPane::new(
+            1,
+            PaneGeom::default(),
+            SenderWithContext::new(to_plugin),
+            String::from("tab-bar"),
+            String::new(),
+        )),
+    );
+    tab.write_to_terminals_on_current_tab(b"a".to_vec())
+        .unwrap();
+    let mut written_to: Vec<u32> = pty_writer_receiver
+        .try_iter()
+        .filter_map(|(instruction, _)| match instruction {
+            PtyWriteInstruction::Write(_, terminal_id) => Some(terminal_id),
+            _ => None,
+        })
+        .collect();
+    written_to.sort();
+    assert_eq!(written_to, vec![1, 2], "input is written to both terminals");
+    assert!(
+        !plugin_receiver.try_iter().any(|(instruction, _)| matches!(
+            instruction,
+            PluginInstruction::Update(_, _, Event::Key(_))
+        )),
+        "plugin pane does not receive the synchronized input"
+    );
+}
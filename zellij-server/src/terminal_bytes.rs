@@ -10,6 +10,7 @@ use std::{
 };
 use zellij_utils::{
     async_std,
+    consts::{DEFAULT_PTY_READ_BUFFER_SIZE, PTY_READ_BUFFER_SIZE},
     errors::{get_current_ctx, prelude::*, ContextType},
     logging::debug_to_file,
 };
@@ -75,11 +76,20 @@ impl TerminalBytes {
         // only send a render instruction sparingly, giving screen time to process bytes and render
         // while still allowing the user to see an indication that things are happening (the
         // sparing render instructions)
+        //
+        // Bytes are read into a buffer of a fixed size and the next read only happens once screen
+        // took in the previous one. As the channel to screen is bounded, a pane flooding us with
+        // output is made to wait (its writes to the pty block once the pty is full) rather than
+        // queueing up an unbounded amount of bytes in memory
         let err_context = || "failed to listen for bytes from PTY".to_string();
 
         let mut err_ctx = get_current_ctx();
         err_ctx.add_call(ContextType::AsyncTask);
-        let mut buf = [0u8; 65536];
+        let read_buffer_size = PTY_READ_BUFFER_SIZE
+            .get()
+            .copied()
+            .unwrap_or(DEFAULT_PTY_READ_BUFFER_SIZE);
+        let mut buf = vec![0u8; read_buffer_size];
         loop {
             match self.deadline_read(&mut buf).await {
                 ReadResult::Ok(0) | ReadResult::Err(_) => break, // EOF or error
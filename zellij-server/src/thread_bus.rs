@@ -7,6 +7,8 @@ use crate::{
 use zellij_utils::errors::prelude::*;
 use zellij_utils::{channels, channels::SenderWithContext, errors::ErrorContext};
 
+use std::time::Duration;
+
 /// A container for senders to the different threads in zellij on the server side
 #[derive(Default, Clone)]
 pub(crate) struct ThreadSenders {
@@ -189,4 +191,21 @@ impl<T> Bus<T> {
         let idx = oper.index();
         oper.recv(&self.receivers[idx])
     }
+    /// Like [`Bus::recv`], but gives up and returns `None` once `timeout` passes without a message
+    pub fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<(T, ErrorContext)>, channels::RecvError> {
+        let mut selector = channels::Select::new();
+        self.receivers.iter().for_each(|r| {
+            selector.recv(r);
+        });
+        match selector.select_timeout(timeout) {
+            Ok(oper) => {
+                let idx = oper.index();
+                oper.recv(&self.receivers[idx]).map(Some)
+            },
+            Err(_timeout) => Ok(None),
+        }
+    }
 }
@@ -1,15 +1,22 @@
 use zellij_utils::pane_size::Viewport;
 
 use crate::output::CharacterChunk;
-use crate::panes::terminal_character::{TerminalCharacter, EMPTY_TERMINAL_CHARACTER, RESET_STYLES};
+use crate::panes::terminal_character::{
+    AnsiCode, CombiningMarks, TerminalCharacter, EMPTY_TERMINAL_CHARACTER, RESET_STYLES,
+};
 use crate::tab::Pane;
 use ansi_term::Colour::{Fixed, RGB};
 use std::collections::HashMap;
 use zellij_utils::errors::prelude::*;
-use zellij_utils::{data::PaletteColor, shared::colors};
+use zellij_utils::{
+    data::{FrameStyle, PaletteColor},
+    shared::colors,
+};
 
 use std::fmt::{Display, Error, Formatter};
 pub mod boundary_type {
+    use zellij_utils::data::FrameStyle;
+
     pub const TOP_RIGHT: &str = "┐";
     pub const TOP_RIGHT_ROUND: &str = "╮";
     pub const VERTICAL: &str = "│";
@@ -25,6 +32,48 @@ pub mod boundary_type {
     pub const HORIZONTAL_DOWN: &str = "┬";
     pub const HORIZONTAL_UP: &str = "┴";
     pub const CROSS: &str = "┼";
+
+    pub const TOP_RIGHT_HEAVY: &str = "┓";
+    pub const VERTICAL_HEAVY: &str = "┃";
+    pub const HORIZONTAL_HEAVY: &str = "━";
+    pub const TOP_LEFT_HEAVY: &str = "┏";
+    pub const BOTTOM_RIGHT_HEAVY: &str = "┛";
+    pub const BOTTOM_LEFT_HEAVY: &str = "┗";
+    pub const VERTICAL_LEFT_HEAVY: &str = "┫";
+    pub const VERTICAL_RIGHT_HEAVY: &str = "┣";
+    pub const HORIZONTAL_DOWN_HEAVY: &str = "┳";
+    pub const HORIZONTAL_UP_HEAVY: &str = "┻";
+    pub const CROSS_HEAVY: &str = "╋";
+
+    pub const VERTICAL_ASCII: &str = "|";
+    pub const HORIZONTAL_ASCII: &str = "-";
+    pub const JUNCTION_ASCII: &str = "+";
+
+    /// Translate one of the (light) symbols above into its equivalent in `frame_style`
+    pub fn styled(boundary_type: &'static str, frame_style: FrameStyle) -> &'static str {
+        match frame_style {
+            FrameStyle::Light => boundary_type,
+            FrameStyle::Heavy => match boundary_type {
+                TOP_RIGHT | TOP_RIGHT_ROUND => TOP_RIGHT_HEAVY,
+                VERTICAL => VERTICAL_HEAVY,
+                HORIZONTAL => HORIZONTAL_HEAVY,
+                TOP_LEFT | TOP_LEFT_ROUND => TOP_LEFT_HEAVY,
+                BOTTOM_RIGHT | BOTTOM_RIGHT_ROUND => BOTTOM_RIGHT_HEAVY,
+                BOTTOM_LEFT | BOTTOM_LEFT_ROUND => BOTTOM_LEFT_HEAVY,
+                VERTICAL_LEFT => VERTICAL_LEFT_HEAVY,
+                VERTICAL_RIGHT => VERTICAL_RIGHT_HEAVY,
+                HORIZONTAL_DOWN => HORIZONTAL_DOWN_HEAVY,
+                HORIZONTAL_UP => HORIZONTAL_UP_HEAVY,
+                CROSS => CROSS_HEAVY,
+                _ => boundary_type,
+            },
+            FrameStyle::Ascii => match boundary_type {
+                VERTICAL => VERTICAL_ASCII,
+                HORIZONTAL => HORIZONTAL_ASCII,
+                _ => JUNCTION_ASCII,
+            },
+        }
+    }
 }
 
 pub(crate) type BoundaryType = &'static str; // easy way to refer to boundary_type above
@@ -63,11 +112,16 @@ impl BoundarySymbol {
                         self.boundary_type
                     )
                 })?;
+            // colored boundaries belong to the focused pane, we also make them bold so that
+            // they still stand out on terminals that do not render the color
+            let bold = self.color.map(|_| AnsiCode::On);
             TerminalCharacter {
                 character,
                 width: 1,
                 styles: RESET_STYLES
-                    .foreground(self.color.map(|palette_color| palette_color.into())),
+                    .foreground(self.color.map(|palette_color| palette_color.into()))
+                    .bold(bold),
+                combining_marks: CombiningMarks::new(),
             }
         };
         Ok(tc)
@@ -81,10 +135,10 @@ impl Display for BoundarySymbol {
             false => match self.color {
                 Some(color) => match color {
                     PaletteColor::Rgb((r, g, b)) => {
-                        write!(f, "{}", RGB(r, g, b).paint(self.boundary_type))
+                        write!(f, "{}", RGB(r, g, b).bold().paint(self.boundary_type))
                     },
                     PaletteColor::EightBit(color) => {
-                        write!(f, "{}", Fixed(color).paint(self.boundary_type))
+                        write!(f, "{}", Fixed(color).bold().paint(self.boundary_type))
                     },
                 },
                 None => write!(f, "{}", self.boundary_type),
@@ -480,7 +534,9 @@ impl Boundaries {
                 let coordinates = Coordinates::new(col, boundary_y_coords);
                 let symbol_to_add = if col == first_col_coordinates && col != self.viewport.x {
                     BoundarySymbol::new(boundary_type::TOP_LEFT).color(color)
-                } else if col == last_col_coordinates - 1 && col != self.viewport.cols - 1 {
+                } else if col == last_col_coordinates - 1
+                    && col != self.viewport.x + self.viewport.cols - 1
+                {
                     BoundarySymbol::new(boundary_type::TOP_RIGHT).color(color)
                 } else {
                     BoundarySymbol::new(boundary_type::HORIZONTAL).color(color)
@@ -526,7 +582,9 @@ impl Boundaries {
                 let coordinates = Coordinates::new(col, boundary_y_coords);
                 let symbol_to_add = if col == first_col_coordinates && col != self.viewport.x {
                     BoundarySymbol::new(boundary_type::BOTTOM_LEFT).color(color)
-                } else if col == last_col_coordinates - 1 && col != self.viewport.cols - 1 {
+                } else if col == last_col_coordinates - 1
+                    && col != self.viewport.x + self.viewport.cols - 1
+                {
                     BoundarySymbol::new(boundary_type::BOTTOM_RIGHT).color(color)
                 } else {
                     BoundarySymbol::new(boundary_type::HORIZONTAL).color(color)
@@ -540,9 +598,13 @@ impl Boundaries {
             }
         }
     }
-    pub fn render(&self) -> Result<Vec<CharacterChunk>> {
+    pub fn render(&self, frame_style: FrameStyle) -> Result<Vec<CharacterChunk>> {
         let mut character_chunks = vec![];
         for (coordinates, boundary_character) in &self.boundary_characters {
+            // symbols are combined in their light form, so only now do we switch to the style
+            let mut boundary_character = *boundary_character;
+            boundary_character.boundary_type =
+                boundary_type::styled(boundary_character.boundary_type, frame_style);
             character_chunks.push(CharacterChunk::new(
                 vec![boundary_character
                     .as_terminal_character()
@@ -554,7 +616,7 @@ impl Boundaries {
         Ok(character_chunks)
     }
     fn rect_right_boundary_is_before_screen_edge(&self, rect: &dyn Pane) -> bool {
-        rect.x() + rect.cols() < self.viewport.cols
+        rect.x() + rect.cols() < self.viewport.x + self.viewport.cols
     }
     fn rect_bottom_boundary_is_before_screen_edge(&self, rect: &dyn Pane) -> bool {
         rect.y() + rect.rows() < self.viewport.y + self.viewport.rows
@@ -570,8 +632,8 @@ impl Boundaries {
         rect.y() + rect.rows()
     }
     fn rect_bottom_boundary_col_start(&self, rect: &dyn Pane) -> usize {
-        if rect.x() == 0 {
-            0
+        if rect.x() == self.viewport.x {
+            self.viewport.x
         } else {
             rect.x() - 1
         }
@@ -586,3 +648,7 @@ impl Boundaries {
             && rect.y() + rect.rows() <= self.viewport.y + self.viewport.rows
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/boundaries_tests.rs"]
+mod boundaries_tests;
@@ -0,0 +1,175 @@
+use zellij_utils::data::{InputMode, Key, KeybindsVec};
+use zellij_utils::input::actions::Action;
+use zellij_utils::pane_size::Size;
+
+use super::{Overlay, OverlayType, Overlayable};
+use zellij_utils::errors::prelude::*;
+
+use std::fmt::{Debug, Write};
+
+const COLUMN_GAP: usize = 2;
+/// The title and the empty line below it, which stay in place while the help is scrolled
+const HEADER_ROWS: usize = 2;
+
+/// Lists the keybindings of every input mode on top of the panes
+#[derive(Clone, Debug)]
+pub struct Help {
+    keybinds: KeybindsVec,
+    scroll_offset: usize,
+}
+
+/// Spells out the names and values in the debug output of `value` as lowercase words, eg.
+/// "move focus left" for `MoveFocus(Left)`, leaving out nested structures which would not fit on
+/// a line
+fn debug_as_words(value: &impl Debug) -> String {
+    let debug = format!("{:?}", value);
+    let debug = match debug.find('{') {
+        Some(index) => &debug[..index],
+        None => &debug,
+    };
+    let mut words: Vec<String> = vec![];
+    for token in debug.split(|c: char| !c.is_alphanumeric()) {
+        if token.is_empty() || token == "Some" || token == "None" {
+            continue;
+        }
+        let mut word = String::new();
+        for c in token.chars() {
+            if c.is_uppercase() && !word.is_empty() {
+                words.push(word.to_lowercase());
+                word = String::new();
+            }
+            word.push(c);
+        }
+        words.push(word.to_lowercase());
+    }
+    words.join(" ")
+}
+
+/// What an action does in a few words, eg. "Switch to pane mode" for `SwitchToMode(Pane)`
+fn describe_action(action: &Action) -> String {
+    let description = match action {
+        Action::SwitchToMode(mode) => format!("switch to {} mode", debug_as_words(mode)),
+        Action::Write(bytes) => format!("write {:?}", String::from_utf8_lossy(bytes)),
+        Action::WriteChars(chars) => format!("write {:?}", chars),
+        Action::Run(run_command) => format!("run {}", run_command.command.display()),
+        Action::NewTab(_, Some(name)) => format!("new tab {:?}", name),
+        Action::NewTabWithLayout(layout) => format!("new tab with {}", layout.display()),
+        Action::SkipConfirm(action) => describe_action(action),
+        action => debug_as_words(action),
+    };
+    let mut chars = description.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => description,
+    }
+}
+
+impl Help {
+    pub fn new(keybinds: KeybindsVec) -> Self {
+        Help {
+            keybinds,
+            scroll_offset: 0,
+        }
+    }
+    /// Scrolls the keybindings by `lines`, up if negative, without going past either end of them
+    pub fn scroll(&mut self, lines: isize, size: Size) {
+        let visible_rows = size.rows.saturating_sub(HEADER_ROWS);
+        let max_offset = self.lines(size.cols).len().saturating_sub(visible_rows);
+        let offset = self.scroll_offset as isize + lines;
+        self.scroll_offset = std::cmp::min(std::cmp::max(offset, 0) as usize, max_offset);
+    }
+    /// The bindings of a mode, with the keys that trigger the same actions merged into one entry
+    fn entries_for_mode(&self, mode: InputMode) -> Vec<String> {
+        let mut bindings: Vec<(Vec<Key>, &Vec<Action>)> = vec![];
+        let mode_keybinds = self
+            .keybinds
+            .iter()
+            .filter(|(keybinds_mode, _)| *keybinds_mode == mode)
+            .flat_map(|(_, keybinds)| keybinds.iter());
+        for (key, actions) in mode_keybinds {
+            match bindings
+                .iter_mut()
+                .find(|(_, bound_actions)| *bound_actions == actions)
+            {
+                Some((keys, _)) => keys.push(key.clone()),
+                None => bindings.push((vec![key.clone()], actions)),
+            }
+        }
+        let mut entries: Vec<String> = bindings
+            .iter_mut()
+            .map(|(keys, actions)| {
+                keys.sort_by_key(|key| key.to_string());
+                let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+                let actions: Vec<String> = actions.iter().map(describe_action).collect();
+                format!("{}: {}", keys.join(", "), actions.join(", then "))
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+    /// Lays the entries of each mode out in as many columns as fit in `cols`
+    fn lines(&self, cols: usize) -> Vec<String> {
+        let mut modes: Vec<InputMode> = self.keybinds.iter().map(|(mode, _)| *mode).collect();
+        modes.sort();
+        modes.dedup();
+        let mut lines = vec![];
+        for mode in modes {
+            let entries = self.entries_for_mode(mode);
+            if entries.is_empty() {
+                continue;
+            }
+            let available_cols = cols.saturating_sub(COLUMN_GAP);
+            let column_width = entries
+                .iter()
+                .map(|entry| entry.chars().count())
+                .max()
+                .unwrap_or(0)
+                .min(available_cols)
+                .max(1);
+            let column_count = std::cmp::max(1, available_cols / (column_width + COLUMN_GAP));
+            lines.push(format!(" {:?}", mode).to_uppercase());
+            for row in entries.chunks(column_count) {
+                let mut line = String::new();
+                for entry in row {
+                    line.push_str(&" ".repeat(COLUMN_GAP));
+                    let entry: String = entry.chars().take(column_width).collect();
+                    line.push_str(&format!("{:width$}", entry, width = column_width));
+                }
+                lines.push(line);
+            }
+            lines.push(String::new());
+        }
+        lines
+    }
+}
+
+impl Overlayable for Help {
+    fn generate_overlay(&self, size: Size) -> Result<String> {
+        let mut output = String::new();
+        let mut lines = vec![
+            " Keybindings (scroll with Up/Down or PageUp/PageDown, press any other key to close)"
+                .to_owned(),
+            String::new(),
+        ];
+        lines.extend(self.lines(size.cols).into_iter().skip(self.scroll_offset));
+        lines.resize(size.rows, String::new());
+        for (y, line) in lines.iter().enumerate() {
+            let line: String = line.chars().take(size.cols).collect();
+            write!(
+                &mut output,
+                "\u{1b}[{};1H\u{1b}[48;5;238m{:width$}\u{1b}[m",
+                y + 1,
+                line,
+                width = size.cols,
+            )
+            .context("failed to generate VTE output from help")?;
+        }
+        Ok(output)
+    }
+}
+
+pub fn generate_help(keybinds: KeybindsVec) -> Overlay {
+    Overlay {
+        overlay_type: OverlayType::Help(Help::new(keybinds)),
+    }
+}
@@ -5,7 +5,10 @@
 //! prompt's:
 //!
 //! notification's:
+//!
+//! the keybinding help:
 
+pub mod help;
 pub mod prompt;
 
 use crate::ServerInstruction;
@@ -26,6 +29,7 @@ pub trait Overlayable {
 #[derive(Clone, Debug)]
 pub enum OverlayType {
     Prompt(prompt::Prompt),
+    Help(help::Help),
 }
 
 impl Overlayable for OverlayType {
@@ -34,6 +38,9 @@ impl Overlayable for OverlayType {
             OverlayType::Prompt(prompt) => prompt
                 .generate_overlay(size)
                 .context("failed to generate VTE output from overlay type"),
+            OverlayType::Help(help) => help
+                .generate_overlay(size)
+                .context("failed to generate VTE output from overlay type"),
         }
     }
 }
@@ -65,11 +72,13 @@ impl Overlay {
     pub fn prompt_confirm(self) -> Option<Box<ServerInstruction>> {
         match self.overlay_type {
             OverlayType::Prompt(p) => p.confirm(),
+            OverlayType::Help(_) => None,
         }
     }
     pub fn prompt_deny(self) -> Option<Box<ServerInstruction>> {
         match self.overlay_type {
             OverlayType::Prompt(p) => p.deny(),
+            OverlayType::Help(_) => None,
         }
     }
 }
@@ -83,6 +92,15 @@ impl Overlayable for Overlay {
 }
 
 impl Overlay {
+    pub fn is_help(&self) -> bool {
+        matches!(self.overlay_type, OverlayType::Help(_))
+    }
+    pub fn help_mut(&mut self) -> Option<&mut help::Help> {
+        match &mut self.overlay_type {
+            OverlayType::Help(help) => Some(help),
+            _ => None,
+        }
+    }
     pub fn new(overlay_type: OverlayType) -> Self {
         Self { overlay_type }
     }
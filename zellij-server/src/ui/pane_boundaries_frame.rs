@@ -1,5 +1,7 @@
 use crate::output::CharacterChunk;
-use crate::panes::{AnsiCode, CharacterStyles, TerminalCharacter, EMPTY_TERMINAL_CHARACTER};
+use crate::panes::{
+    AnsiCode, CharacterStyles, CombiningMarks, TerminalCharacter, EMPTY_TERMINAL_CHARACTER,
+};
 use crate::ui::boundaries::boundary_type;
 use crate::ClientId;
 use zellij_utils::data::{client_id_to_colors, PaletteColor, Style};
@@ -29,6 +31,7 @@ fn foreground_color(characters: &str, color: Option<PaletteColor>) -> Vec<Termin
             character,
             styles,
             width: character.width().unwrap_or(0),
+            combining_marks: CombiningMarks::new(),
         };
         colored_string.push(terminal_character);
     }
@@ -56,6 +59,7 @@ fn background_color(characters: &str, color: Option<PaletteColor>) -> Vec<Termin
             character,
             styles,
             width: character.width().unwrap_or(0),
+            combining_marks: CombiningMarks::new(),
         };
         colored_string.push(terminal_character);
     }
@@ -66,6 +70,7 @@ fn background_color(characters: &str, color: Option<PaletteColor>) -> Vec<Termin
 enum ExitStatus {
     Code(i32),
     Exited,
+    Suspended,
 }
 
 pub struct FrameParams {
@@ -117,12 +122,15 @@ impl PaneFrame {
             None => Some(ExitStatus::Exited),
         };
     }
+    pub fn add_suspended_status(&mut self) {
+        self.exit_status = Some(ExitStatus::Suspended);
+    }
     fn client_cursor(&self, client_id: ClientId) -> Vec<TerminalCharacter> {
         let color = client_id_to_colors(client_id, self.style.colors);
         background_color(" ", color.map(|c| c.0))
     }
     fn get_corner(&self, corner: &'static str) -> &'static str {
-        if self.style.rounded_corners {
+        let corner = if self.style.rounded_corners {
             match corner {
                 boundary_type::TOP_RIGHT => boundary_type::TOP_RIGHT_ROUND,
                 boundary_type::TOP_LEFT => boundary_type::TOP_LEFT_ROUND,
@@ -132,14 +140,19 @@ impl PaneFrame {
             }
         } else {
             corner
-        }
+        };
+        boundary_type::styled(corner, self.style.frame_style)
+    }
+    fn get_boundary(&self, boundary: &'static str) -> &'static str {
+        boundary_type::styled(boundary, self.style.frame_style)
     }
     fn render_title_right_side(
         &self,
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
         // string and length because of color
-        if self.scroll_position.0 > 0 || self.scroll_position.1 > 0 {
+        // shown only while scrolled back from the bottom
+        if self.scroll_position.0 > 0 {
             let prefix = " SCROLL: ";
             let full_indication =
                 format!(" {}/{} ", self.scroll_position.0, self.scroll_position.1);
@@ -170,8 +183,10 @@ impl PaneFrame {
         }
     }
     fn render_my_focus(&self, max_length: usize) -> Option<(Vec<TerminalCharacter>, usize)> {
-        let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
-        let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
+        let mut left_separator =
+            foreground_color(self.get_boundary(boundary_type::VERTICAL_LEFT), self.color);
+        let mut right_separator =
+            foreground_color(self.get_boundary(boundary_type::VERTICAL_RIGHT), self.color);
         let full_indication_text = "MY FOCUS";
         let mut full_indication = vec![];
         full_indication.append(&mut left_separator);
@@ -200,8 +215,10 @@ impl PaneFrame {
         &self,
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
-        let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
-        let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
+        let mut left_separator =
+            foreground_color(self.get_boundary(boundary_type::VERTICAL_LEFT), self.color);
+        let mut right_separator =
+            foreground_color(self.get_boundary(boundary_type::VERTICAL_RIGHT), self.color);
         let full_indication_text = "MY FOCUS AND:";
         let short_indication_text = "+";
         let mut full_indication = foreground_color(full_indication_text, self.color);
@@ -242,8 +259,10 @@ impl PaneFrame {
         &self,
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
-        let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
-        let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
+        let mut left_separator =
+            foreground_color(self.get_boundary(boundary_type::VERTICAL_LEFT), self.color);
+        let mut right_separator =
+            foreground_color(self.get_boundary(boundary_type::VERTICAL_RIGHT), self.color);
         let full_indication_text = if self.other_focused_clients.len() == 1 {
             "FOCUSED USER:"
         } else {
@@ -411,7 +430,10 @@ impl PaneFrame {
                 col += right_side_len;
                 continue;
             } else {
-                title_line.append(&mut foreground_color(boundary_type::HORIZONTAL, self.color));
+                title_line.append(&mut foreground_color(
+                    self.get_boundary(boundary_type::HORIZONTAL),
+                    self.color,
+                ));
             }
             if col == self.geom.x + self.geom.cols - 1 {
                 break;
@@ -453,7 +475,10 @@ impl PaneFrame {
                 col += *middle_len;
                 continue;
             } else {
-                title_line.append(&mut foreground_color(boundary_type::HORIZONTAL, self.color));
+                title_line.append(&mut foreground_color(
+                    self.get_boundary(boundary_type::HORIZONTAL),
+                    self.color,
+                ));
             }
             if col == self.geom.x + self.geom.cols - 1 {
                 break;
@@ -488,7 +513,10 @@ impl PaneFrame {
                 col += *middle_len;
                 continue;
             } else {
-                title_line.append(&mut foreground_color(boundary_type::HORIZONTAL, self.color));
+                title_line.append(&mut foreground_color(
+                    self.get_boundary(boundary_type::HORIZONTAL),
+                    self.color,
+                ));
             }
             if col == self.geom.x + self.geom.cols - 1 {
                 break;
@@ -511,7 +539,7 @@ impl PaneFrame {
         let total_title_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
         let mut middle = String::new();
         for _ in (left_side_len + right_side_len)..total_title_length {
-            middle.push_str(boundary_type::HORIZONTAL);
+            middle.push_str(self.get_boundary(boundary_type::HORIZONTAL));
         }
         let mut ret = vec![];
         ret.append(&mut left_boundary);
@@ -533,7 +561,7 @@ impl PaneFrame {
         let total_title_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
         let mut middle_padding = String::new();
         for _ in *left_side_len..total_title_length {
-            middle_padding.push_str(boundary_type::HORIZONTAL);
+            middle_padding.push_str(self.get_boundary(boundary_type::HORIZONTAL));
         }
         let mut ret = vec![];
         ret.append(&mut left_boundary);
@@ -550,7 +578,7 @@ impl PaneFrame {
         let total_title_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
         let mut middle_padding = String::new();
         for _ in 0..total_title_length {
-            middle_padding.push_str(boundary_type::HORIZONTAL);
+            middle_padding.push_str(self.get_boundary(boundary_type::HORIZONTAL));
         }
         let mut ret = vec![];
         ret.append(&mut left_boundary);
@@ -621,13 +649,13 @@ impl PaneFrame {
         let mut right_boundary =
             foreground_color(self.get_corner(boundary_type::BOTTOM_RIGHT), self.color);
         let res = if self.is_main_client {
-            let (mut second_part, second_part_len) = self.second_held_title_part_full();
+            let (mut second_part, second_part_len) = self.second_held_title_part_full(exit_status);
             let full_text_len = first_part_len + second_part_len;
             if full_text_len <= max_undertitle_length {
                 // render exit status and tips
                 let mut padding = String::new();
                 for _ in full_text_len..max_undertitle_length {
-                    padding.push_str(boundary_type::HORIZONTAL);
+                    padding.push_str(self.get_boundary(boundary_type::HORIZONTAL));
                 }
                 let mut ret = vec![];
                 ret.append(&mut left_boundary);
@@ -640,7 +668,7 @@ impl PaneFrame {
                 // render only exit status
                 let mut padding = String::new();
                 for _ in first_part_len..max_undertitle_length {
-                    padding.push_str(boundary_type::HORIZONTAL);
+                    padding.push_str(self.get_boundary(boundary_type::HORIZONTAL));
                 }
                 let mut ret = vec![];
                 ret.append(&mut left_boundary);
@@ -657,7 +685,7 @@ impl PaneFrame {
                 let full_text_len = first_part_len;
                 let mut padding = String::new();
                 for _ in full_text_len..max_undertitle_length {
-                    padding.push_str(boundary_type::HORIZONTAL);
+                    padding.push_str(self.get_boundary(boundary_type::HORIZONTAL));
                 }
                 let mut ret = vec![];
                 ret.append(&mut left_boundary);
@@ -701,7 +729,7 @@ impl PaneFrame {
                             // bottom right corner
                             self.get_corner(boundary_type::BOTTOM_RIGHT)
                         } else {
-                            boundary_type::HORIZONTAL
+                            self.get_boundary(boundary_type::HORIZONTAL)
                         };
 
                         let mut boundary_character = foreground_color(boundary, self.color);
@@ -712,9 +740,10 @@ impl PaneFrame {
                     character_chunks.push(CharacterChunk::new(bottom_row, x, y));
                 }
             } else {
-                let boundary_character_left = foreground_color(boundary_type::VERTICAL, self.color);
+                let boundary_character_left =
+                    foreground_color(self.get_boundary(boundary_type::VERTICAL), self.color);
                 let boundary_character_right =
-                    foreground_color(boundary_type::VERTICAL, self.color);
+                    foreground_color(self.get_boundary(boundary_type::VERTICAL), self.color);
 
                 let x = self.geom.x;
                 let y = self.geom.y + row;
@@ -775,15 +804,38 @@ impl PaneFrame {
                     left_bracket.len() + exited_text.len() + right_bracket.len(),
                 )
             },
+            ExitStatus::Suspended => {
+                let mut first_part = vec![];
+                let left_bracket = " [ ";
+                let suspended_text = "SUSPENDED";
+                let right_bracket = " ] ";
+                first_part.append(&mut foreground_color(left_bracket, self.color));
+                first_part.append(&mut foreground_color(
+                    suspended_text,
+                    Some(self.style.colors.orange),
+                ));
+                first_part.append(&mut foreground_color(right_bracket, self.color));
+                (
+                    first_part,
+                    left_bracket.len() + suspended_text.len() + right_bracket.len(),
+                )
+            },
         }
     }
-    fn second_held_title_part_full(&self) -> (Vec<TerminalCharacter>, usize) {
+    fn second_held_title_part_full(
+        &self,
+        exit_status: ExitStatus,
+    ) -> (Vec<TerminalCharacter>, usize) {
         // (title part, length)
         let mut second_part = vec![];
         let left_enter_bracket = "<";
         let enter_text = "ENTER";
         let right_enter_bracket = ">";
-        let enter_tip = " to re-run, ";
+        let enter_tip = if exit_status == ExitStatus::Suspended {
+            " to run, "
+        } else {
+            " to re-run, "
+        };
         let left_break_bracket = "<";
         let break_text = "Ctrl-c";
         let right_break_bracket = ">";
@@ -822,7 +874,7 @@ impl PaneFrame {
         let mut ret = vec![];
         let mut padding = String::new();
         for _ in 0..max_undertitle_length {
-            padding.push_str(boundary_type::HORIZONTAL);
+            padding.push_str(self.get_boundary(boundary_type::HORIZONTAL));
         }
         ret.append(&mut left_boundary);
         ret.append(&mut foreground_color(&padding, self.color));
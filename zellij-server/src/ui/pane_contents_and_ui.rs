@@ -224,6 +224,9 @@ impl<'a> PaneContentsAndUi<'a> {
         mode: InputMode,
         session_is_mirrored: bool,
     ) -> Option<PaletteColor> {
+        if self.pane.is_flashing_bell() {
+            return Some(self.style.colors.red);
+        }
         let pane_focused_for_client_id = self.focused_clients.contains(&client_id);
         if pane_focused_for_client_id {
             match mode {
@@ -0,0 +1,156 @@
+use super::{boundary_type, Boundaries, Coordinates};
+use crate::panes::sixel::SixelImageStore;
+use crate::panes::terminal_character::{AnsiCode, CharacterStyles};
+use crate::panes::{LinkHandler, TerminalPane};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use zellij_utils::{
+    data::{FrameStyle, Palette, PaletteColor, Style},
+    pane_size::{PaneGeom, Viewport},
+};
+
+fn terminal_pane(pid: u32, x: usize, y: usize, cols: usize, rows: usize) -> TerminalPane {
+    let mut geom = PaneGeom::default();
+    geom.x = x;
+    geom.y = y;
+    geom.cols.set_inner(cols);
+    geom.rows.set_inner(rows);
+    TerminalPane::new(
+        pid,
+        geom,
+        Style::default(),
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        Rc::new(RefCell::new(SixelImageStore::default())),
+        Rc::new(RefCell::new(Palette::default())),
+        Rc::new(RefCell::new(HashMap::new())),
+        None,
+    )
+}
+
+fn boundary_type_at(boundaries: &Boundaries, x: usize, y: usize) -> Option<&'static str> {
+    boundaries
+        .boundary_characters
+        .get(&Coordinates::new(x, y))
+        .map(|symbol| symbol.boundary_type)
+}
+
+#[test]
+fn separators_end_in_half_junctions_at_the_edges_of_an_offset_viewport() {
+    // a viewport that starts to the right of a 10 column wide pane outside of it (eg. a plugin)
+    let viewport = Viewport {
+        x: 10,
+        y: 0,
+        cols: 20,
+        rows: 10,
+    };
+    let left_pane = terminal_pane(1, 10, 0, 10, 10);
+    let top_right_pane = terminal_pane(2, 20, 0, 10, 5);
+    let bottom_right_pane = terminal_pane(3, 20, 5, 10, 5);
+    let mut boundaries = Boundaries::new(viewport);
+    boundaries.add_rect(&left_pane, None);
+    boundaries.add_rect(&top_right_pane, None);
+    boundaries.add_rect(&bottom_right_pane, None);
+
+    assert_eq!(
+        boundary_type_at(&boundaries, 19, 0),
+        Some(boundary_type::VERTICAL),
+        "vertical separator reaches the top edge without a corner"
+    );
+    assert_eq!(
+        boundary_type_at(&boundaries, 19, 4),
+        Some(boundary_type::VERTICAL_RIGHT),
+        "horizontal separator meets the vertical one in a half junction"
+    );
+    assert_eq!(
+        boundary_type_at(&boundaries, 29, 4),
+        Some(boundary_type::HORIZONTAL),
+        "horizontal separator reaches the right edge without a corner"
+    );
+    assert_eq!(
+        boundary_type_at(&boundaries, 9, 4),
+        None,
+        "nothing is drawn outside of the viewport"
+    );
+}
+
+#[test]
+fn boundaries_of_the_focused_pane_are_bold_as_well_as_colored() {
+    let viewport = Viewport {
+        x: 0,
+        y: 0,
+        cols: 30,
+        rows: 10,
+    };
+    let left_pane = terminal_pane(1, 0, 0, 15, 10);
+    let right_pane = terminal_pane(2, 15, 0, 15, 10);
+    let separator_styles = |color: Option<PaletteColor>| -> CharacterStyles {
+        let mut boundaries = Boundaries::new(viewport);
+        boundaries.add_rect(&right_pane, None);
+        boundaries.add_rect(&left_pane, color);
+        boundaries
+            .boundary_characters
+            .get(&Coordinates::new(14, 5))
+            .unwrap()
+            .as_terminal_character()
+            .unwrap()
+            .styles
+    };
+    let focused_color = PaletteColor::EightBit(2);
+    let focused_separator = separator_styles(Some(focused_color));
+    assert_eq!(
+        focused_separator.foreground,
+        Some(focused_color.into()),
+        "focused pane's boundary is colored"
+    );
+    assert_eq!(
+        focused_separator.bold,
+        Some(AnsiCode::On),
+        "and bold, for terminals without colors"
+    );
+    assert_eq!(
+        separator_styles(None).bold,
+        None,
+        "other boundaries are not bold"
+    );
+}
+
+#[test]
+fn boundaries_are_rendered_in_the_configured_frame_style() {
+    let viewport = Viewport {
+        x: 0,
+        y: 0,
+        cols: 30,
+        rows: 10,
+    };
+    let left_pane = terminal_pane(1, 0, 0, 15, 10);
+    let top_right_pane = terminal_pane(2, 15, 0, 15, 5);
+    let bottom_right_pane = terminal_pane(3, 15, 5, 15, 5);
+    let mut boundaries = Boundaries::new(viewport);
+    boundaries.add_rect(&left_pane, None);
+    boundaries.add_rect(&top_right_pane, None);
+    boundaries.add_rect(&bottom_right_pane, None);
+    let rendered = |frame_style: FrameStyle| -> HashMap<(usize, usize), char> {
+        boundaries
+            .render(frame_style)
+            .unwrap()
+            .into_iter()
+            .map(|chunk| ((chunk.x, chunk.y), chunk.terminal_characters[0].character))
+            .collect()
+    };
+    // a vertical separator, a horizontal one and the junction where they meet
+    let separators = [(14, 0), (20, 4), (14, 4)];
+    let characters_in = |frame_style: FrameStyle| -> Vec<char> {
+        let rendered = rendered(frame_style);
+        separators
+            .iter()
+            .map(|coordinates| rendered[coordinates])
+            .collect()
+    };
+    assert_eq!(characters_in(FrameStyle::Light), vec!['│', '─', '├']);
+    assert_eq!(characters_in(FrameStyle::Heavy), vec!['┃', '━', '┣']);
+    assert_eq!(characters_in(FrameStyle::Ascii), vec!['|', '-', '+']);
+}
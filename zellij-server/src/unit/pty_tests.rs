@@ -0,0 +1,485 @@
+use super::{pty_thread_main, split_shell_args, ClientOrTabIndex, Pty, PtyInstruction};
+use crate::{
+    os_input_output::{AsyncReader, Pid, ServerOsApi, SpawnTerminalError},
+    panes::PaneId,
+    screen::ScreenInstruction,
+    thread_bus::Bus,
+    ClientId,
+};
+use std::path::PathBuf;
+use zellij_utils::channels::{self, ChannelWithContext, SenderWithContext};
+use zellij_utils::errors::ErrorContext;
+use zellij_utils::input::layout::{Layout, PaneLayout, Run};
+use zellij_utils::ipc::IpcReceiverWithContext;
+
+use std::os::unix::io::RawFd;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use zellij_utils::nix;
+
+use zellij_utils::{
+    data::Palette,
+    input::command::{RunCommand, TerminalAction},
+    interprocess::local_socket::LocalSocketStream,
+    ipc::{ClientToServerMsg, ServerToClientMsg},
+};
+
+type QuitCallback = Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>;
+
+#[derive(Clone, Default)]
+struct FakeInputOutput {
+    killed: Arc<Mutex<Vec<Pid>>>,
+    hung_up_process_groups: Arc<Mutex<Vec<Pid>>>,
+    spawned: Arc<Mutex<Vec<(TerminalAction, QuitCallback)>>>,
+}
+
+impl ServerOsApi for FakeInputOutput {
+    fn set_terminal_size_using_terminal_id(&self, _id: u32, _cols: u16, _rows: u16) {
+        // noop
+    }
+    fn spawn_terminal(
+        &self,
+        file_to_open: TerminalAction,
+        quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>,
+        _default_editor: Option<PathBuf>,
+    ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
+        let mut spawned = self.spawned.lock().unwrap();
+        spawned.push((file_to_open, quit_cb));
+        // none of the commands the tests start exist
+        Err(SpawnTerminalError::CommandNotFound(spawned.len() as u32))
+    }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        unimplemented!()
+    }
+    fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn async_file_reader(&self, _fd: RawFd) -> Box<dyn AsyncReader> {
+        unimplemented!()
+    }
+    fn write_to_tty_stdin(&self, _id: u32, _buf: &[u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn tcdrain(&self, _id: u32) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn kill(&self, pid: Pid) -> Result<(), nix::Error> {
+        self.killed.lock().unwrap().push(pid);
+        Ok(())
+    }
+    fn force_kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn hang_up_process_group(&self, pid: Pid) -> Result<(), nix::Error> {
+        self.hung_up_process_groups.lock().unwrap().push(pid);
+        Ok(())
+    }
+    fn box_clone(&self) -> Box<dyn ServerOsApi> {
+        Box::new((*self).clone())
+    }
+    fn send_to_client(
+        &self,
+        _client_id: ClientId,
+        _msg: ServerToClientMsg,
+    ) -> Result<(), &'static str> {
+        unimplemented!()
+    }
+    fn new_client(
+        &mut self,
+        _client_id: ClientId,
+        _stream: LocalSocketStream,
+    ) -> IpcReceiverWithContext<ClientToServerMsg> {
+        unimplemented!()
+    }
+    fn remove_client(&mut self, _client_id: ClientId) {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        unimplemented!()
+    }
+    fn get_cwd(&self, _pid: Pid) -> Option<PathBuf> {
+        unimplemented!()
+    }
+
+    fn write_to_file(&mut self, _buf: String, _name: Option<String>) {
+        unimplemented!()
+    }
+    fn re_run_command_in_terminal(
+        &self,
+        _terminal_id: u32,
+        _run_command: RunCommand,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
+    ) -> Result<(RawFd, RawFd), SpawnTerminalError> {
+        unimplemented!()
+    }
+    fn clear_terminal_id(&self, _terminal_id: u32) {
+        // noop
+    }
+    fn foreground_process_is_running(&self, _terminal_id: u32) -> bool {
+        false
+    }
+    fn signal_foreground_process_group(
+        &self,
+        _terminal_id: u32,
+        _signal: nix::sys::signal::Signal,
+    ) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn pipe_to_command(&self, _buf: String, _command: &[String]) -> std::io::Result<()> {
+        unimplemented!()
+    }
+}
+
+fn create_pty(os_input: &FakeInputOutput) -> Pty {
+    let bus = Bus::new(
+        vec![],
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(os_input.box_clone()),
+    );
+    Pty::new(bus, false, None, false, None, None, None, None)
+}
+
+#[test]
+fn dropping_the_pty_hangs_up_on_the_process_group_of_every_pane() {
+    let os_input = FakeInputOutput::default();
+    let mut pty = create_pty(&os_input);
+    pty.id_to_child_pid.insert(1, 100);
+    pty.id_to_child_pid.insert(2, 200);
+    drop(pty);
+
+    let mut hung_up_process_groups = os_input.hung_up_process_groups.lock().unwrap().clone();
+    hung_up_process_groups.sort_by_key(|pid| pid.as_raw());
+    assert_eq!(
+        hung_up_process_groups,
+        vec![Pid::from_raw(100), Pid::from_raw(200)],
+        "process group of every pane hung up on"
+    );
+    assert_eq!(
+        os_input.killed.lock().unwrap().len(),
+        2,
+        "every pane closed"
+    );
+}
+
+#[test]
+fn closing_a_pane_does_not_hang_up_on_the_others() {
+    let os_input = FakeInputOutput::default();
+    let mut pty = create_pty(&os_input);
+    pty.id_to_child_pid.insert(1, 100);
+    pty.id_to_child_pid.insert(2, 200);
+    pty.close_pane(PaneId::Terminal(1)).unwrap();
+
+    assert!(
+        os_input.hung_up_process_groups.lock().unwrap().is_empty(),
+        "no process group hung up on"
+    );
+    assert_eq!(
+        *os_input.killed.lock().unwrap(),
+        vec![Pid::from_raw(100)],
+        "only the closed pane killed"
+    );
+}
+
+fn create_pty_with_screen(
+    os_input: &FakeInputOutput,
+    receivers: Vec<channels::Receiver<(PtyInstruction, ErrorContext)>>,
+) -> (Pty, channels::Receiver<(ScreenInstruction, ErrorContext)>) {
+    let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
+    let to_screen = SenderWithContext::new(to_screen);
+    let bus = Bus::new(
+        receivers,
+        Some(&to_screen),
+        None,
+        None,
+        None,
+        None,
+        Some(os_input.box_clone()),
+    );
+    (
+        Pty::new(bus, false, None, false, None, None, None, None),
+        screen_receiver,
+    )
+}
+
+fn says_command_not_found(instruction: &ScreenInstruction) -> bool {
+    match instruction {
+        ScreenInstruction::PtyBytes(1, bytes) => {
+            String::from_utf8_lossy(bytes).starts_with("Command not found")
+        },
+        _ => false,
+    }
+}
+
+#[test]
+fn default_shell_args_are_split_like_a_shell_would() {
+    assert_eq!(
+        split_shell_args("-l  --no-rcs"),
+        vec!["-l".to_string(), "--no-rcs".to_string()],
+        "arguments split on any whitespace"
+    );
+    assert_eq!(
+        split_shell_args(r#"-c "echo hi" 'it''s' a\ b """#),
+        vec![
+            "-c".to_string(),
+            "echo hi".to_string(),
+            "its".to_string(),
+            "a b".to_string(),
+            "".to_string(),
+        ],
+        "quoted and escaped arguments kept whole"
+    );
+    assert!(split_shell_args("  ").is_empty(), "no arguments");
+}
+
+#[test]
+fn default_shell_that_is_not_found_is_reported_in_a_new_pane() {
+    let os_input = FakeInputOutput::default();
+    let (to_pty, pty_receiver): ChannelWithContext<PtyInstruction> = channels::unbounded();
+    let to_pty = SenderWithContext::new(to_pty);
+    let (pty, screen_receiver) = create_pty_with_screen(&os_input, vec![pty_receiver]);
+    to_pty
+        .send(PtyInstruction::SpawnTerminal(
+            None,
+            Some(false),
+            None,
+            ClientOrTabIndex::TabIndex(0),
+        ))
+        .unwrap();
+    to_pty.send(PtyInstruction::Exit).unwrap();
+    pty_thread_main(pty, Box::new(Layout::default())).unwrap();
+
+    let instructions: Vec<ScreenInstruction> = screen_receiver
+        .try_iter()
+        .map(|(instruction, _)| instruction)
+        .collect();
+    assert!(
+        matches!(
+            instructions[0],
+            ScreenInstruction::NewPane(PaneId::Terminal(1), ..)
+        ),
+        "pane opened for the shell"
+    );
+    assert!(
+        says_command_not_found(&instructions[1]),
+        "pane says the shell was not found"
+    );
+    assert!(
+        matches!(
+            instructions[2],
+            ScreenInstruction::HoldPane(PaneId::Terminal(1), Some(2), ..)
+        ),
+        "pane held open"
+    );
+}
+
+#[test]
+fn default_shell_that_is_not_found_is_reported_in_a_layout_pane_with_a_cwd() {
+    let os_input = FakeInputOutput::default();
+    let (mut pty, screen_receiver) = create_pty_with_screen(&os_input, vec![]);
+    let layout = PaneLayout {
+        children: vec![PaneLayout {
+            run: Some(Run::Cwd(PathBuf::from("/tmp"))),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    pty.spawn_terminals_for_layout(layout, None, 1).unwrap();
+
+    let instructions: Vec<ScreenInstruction> = screen_receiver
+        .try_iter()
+        .map(|(instruction, _)| instruction)
+        .collect();
+    assert!(
+        matches!(instructions[0], ScreenInstruction::NewTab(_, ref ids, 1) if ids == &vec![1]),
+        "tab opened with the pane"
+    );
+    assert!(
+        says_command_not_found(&instructions[1]),
+        "pane says the shell was not found"
+    );
+    assert!(
+        matches!(
+            instructions[2],
+            ScreenInstruction::HoldPane(PaneId::Terminal(1), Some(2), ref run_command, None)
+                if run_command.cwd == Some(PathBuf::from("/tmp"))
+        ),
+        "pane held open to rerun the shell in its cwd"
+    );
+}
+
+#[test]
+fn layout_pane_runs_its_command_and_is_held_with_its_exit_status() {
+    let os_input = FakeInputOutput::default();
+    let (mut pty, screen_receiver) = create_pty_with_screen(&os_input, vec![]);
+    let command = RunCommand {
+        command: PathBuf::from("htop"),
+        args: vec!["-d".to_owned(), "10".to_owned()],
+        cwd: Some(PathBuf::from("/tmp")),
+        hold_on_close: true,
+        ..Default::default()
+    };
+    let layout = PaneLayout {
+        children: vec![PaneLayout {
+            run: Some(Run::Command(command.clone())),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    pty.spawn_terminals_for_layout(layout, None, 1).unwrap();
+
+    let spawned = os_input.spawned.lock().unwrap();
+    assert!(
+        matches!(spawned[0].0, TerminalAction::RunCommand(ref spawned_command) if spawned_command == &command),
+        "command spawned with its args and cwd instead of the default shell"
+    );
+    screen_receiver.try_iter().for_each(drop);
+    spawned[0].1(PaneId::Terminal(1), Some(0), command.clone());
+    assert!(
+        matches!(
+            screen_receiver.try_recv(),
+            Ok((ScreenInstruction::HoldPane(PaneId::Terminal(1), Some(0), ref run_command, None), _))
+                if run_command == &command
+        ),
+        "pane held with the exit status of its command"
+    );
+}
+
+#[test]
+fn layout_pane_that_closes_on_exit_is_closed_once_its_command_exits() {
+    let os_input = FakeInputOutput::default();
+    let (mut pty, screen_receiver) = create_pty_with_screen(&os_input, vec![]);
+    let command = RunCommand {
+        command: PathBuf::from("htop"),
+        hold_on_close: false,
+        ..Default::default()
+    };
+    let layout = PaneLayout {
+        children: vec![PaneLayout {
+            run: Some(Run::Command(command.clone())),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    pty.spawn_terminals_for_layout(layout, None, 1).unwrap();
+
+    screen_receiver.try_iter().for_each(drop);
+    os_input.spawned.lock().unwrap()[0].1(PaneId::Terminal(1), Some(0), command);
+    assert!(
+        matches!(
+            screen_receiver.try_recv(),
+            Ok((ScreenInstruction::ClosePane(PaneId::Terminal(1), None), _))
+        ),
+        "pane closed"
+    );
+}
+
+fn restarting_command() -> RunCommand {
+    RunCommand {
+        command: PathBuf::from("false"),
+        restart_on_exit: true,
+        ..Default::default()
+    }
+}
+
+fn restarts_and_give_ups(
+    screen_receiver: &channels::Receiver<(ScreenInstruction, ErrorContext)>,
+) -> Vec<&'static str> {
+    screen_receiver
+        .try_iter()
+        .filter_map(|(instruction, _)| match instruction {
+            ScreenInstruction::PtyBytes(_, bytes)
+                if String::from_utf8_lossy(&bytes).contains("restarting it in") =>
+            {
+                Some("restart")
+            },
+            ScreenInstruction::HoldPane(..) => Some("give up"),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn command_is_restarted_at_most_max_command_restarts_times() {
+    let os_input = FakeInputOutput::default();
+    let (mut pty, screen_receiver) = create_pty_with_screen(&os_input, vec![]);
+    pty.max_command_restarts = 2;
+    let started_at = Instant::now();
+    for seconds in 0..3 {
+        pty.command_exited(
+            PaneId::Terminal(1),
+            Some(1),
+            restarting_command(),
+            started_at + Duration::from_secs(seconds),
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        restarts_and_give_ups(&screen_receiver),
+        vec!["restart", "restart", "give up"],
+        "command held once it exited too often"
+    );
+}
+
+#[test]
+fn restarts_older_than_the_restart_window_do_not_count() {
+    let os_input = FakeInputOutput::default();
+    let (mut pty, screen_receiver) = create_pty_with_screen(&os_input, vec![]);
+    pty.max_command_restarts = 2;
+    pty.command_restart_window = Duration::from_secs(10);
+    let started_at = Instant::now();
+    for seconds in [0, 1, 11, 12] {
+        pty.command_exited(
+            PaneId::Terminal(1),
+            Some(1),
+            restarting_command(),
+            started_at + Duration::from_secs(seconds),
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        restarts_and_give_ups(&screen_receiver),
+        vec!["restart", "restart", "restart", "restart"],
+        "only the restarts within the window counted"
+    );
+}
+
+#[test]
+fn new_tab_layout_that_cannot_be_loaded_opens_a_single_pane() {
+    let os_input = FakeInputOutput::default();
+    let bus = Bus::new(
+        vec![],
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(os_input.box_clone()),
+    );
+    let pty = Pty::new(
+        bus,
+        false,
+        None,
+        false,
+        None,
+        None,
+        Some(PathBuf::from("i_do_not_exist")),
+        None,
+    );
+    let session_layout = Layout {
+        template: Some(PaneLayout {
+            children: vec![PaneLayout::default(), PaneLayout::default()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        pty.new_tab_layout(None, &session_layout, Some("my tab".to_owned()), 1),
+        (PaneLayout::default(), Some("my tab".to_owned())),
+        "single pane opened instead of the session's default tab"
+    );
+}
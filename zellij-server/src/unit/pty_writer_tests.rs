@@ -0,0 +1,209 @@
+use super::{WriteQueues, QUEUED_BYTES_WARNING_THRESHOLD};
+use crate::{
+    os_input_output::{AsyncReader, Pid, ServerOsApi, SpawnTerminalError},
+    panes::PaneId,
+    ClientId,
+};
+use std::path::PathBuf;
+use zellij_utils::ipc::IpcReceiverWithContext;
+
+use std::os::unix::io::RawFd;
+use std::sync::{Arc, Mutex};
+
+use zellij_utils::nix;
+
+use zellij_utils::{
+    data::Palette,
+    input::command::{RunCommand, TerminalAction},
+    interprocess::local_socket::LocalSocketStream,
+    ipc::{ClientToServerMsg, ServerToClientMsg},
+};
+
+/// A non-blocking pty that takes at most `write_limit` bytes per write, and none while it has no
+/// room
+#[derive(Clone)]
+struct FakeInputOutput {
+    written: Arc<Mutex<Vec<u8>>>,
+    has_room: Arc<Mutex<bool>>,
+    write_limit: usize,
+}
+
+impl ServerOsApi for FakeInputOutput {
+    fn set_terminal_size_using_terminal_id(&self, _id: u32, _cols: u16, _rows: u16) {
+        // noop
+    }
+    fn spawn_terminal(
+        &self,
+        _file_to_open: TerminalAction,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>,
+        _default_editor: Option<PathBuf>,
+    ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
+        unimplemented!()
+    }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        unimplemented!()
+    }
+    fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn async_file_reader(&self, _fd: RawFd) -> Box<dyn AsyncReader> {
+        unimplemented!()
+    }
+    fn write_to_tty_stdin(&self, _id: u32, buf: &[u8]) -> Result<usize, nix::Error> {
+        if !*self.has_room.lock().unwrap() {
+            return Err(nix::Error::EAGAIN);
+        }
+        let written = std::cmp::min(buf.len(), self.write_limit);
+        self.written
+            .lock()
+            .unwrap()
+            .extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+    fn tcdrain(&self, _id: u32) -> Result<(), nix::Error> {
+        Ok(())
+    }
+    fn kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn force_kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn hang_up_process_group(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ServerOsApi> {
+        Box::new((*self).clone())
+    }
+    fn send_to_client(
+        &self,
+        _client_id: ClientId,
+        _msg: ServerToClientMsg,
+    ) -> Result<(), &'static str> {
+        unimplemented!()
+    }
+    fn new_client(
+        &mut self,
+        _client_id: ClientId,
+        _stream: LocalSocketStream,
+    ) -> IpcReceiverWithContext<ClientToServerMsg> {
+        unimplemented!()
+    }
+    fn remove_client(&mut self, _client_id: ClientId) {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        unimplemented!()
+    }
+    fn get_cwd(&self, _pid: Pid) -> Option<PathBuf> {
+        unimplemented!()
+    }
+
+    fn write_to_file(&mut self, _buf: String, _name: Option<String>) {
+        unimplemented!()
+    }
+    fn re_run_command_in_terminal(
+        &self,
+        _terminal_id: u32,
+        _run_command: RunCommand,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
+    ) -> Result<(RawFd, RawFd), SpawnTerminalError> {
+        unimplemented!()
+    }
+    fn clear_terminal_id(&self, _terminal_id: u32) {
+        unimplemented!()
+    }
+    fn foreground_process_is_running(&self, _terminal_id: u32) -> bool {
+        false
+    }
+    fn signal_foreground_process_group(
+        &self,
+        _terminal_id: u32,
+        _signal: nix::sys::signal::Signal,
+    ) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn pipe_to_command(&self, _buf: String, _command: &[String]) -> std::io::Result<()> {
+        unimplemented!()
+    }
+}
+
+fn fake_pty(write_limit: usize) -> FakeInputOutput {
+    FakeInputOutput {
+        written: Arc::new(Mutex::new(vec![])),
+        has_room: Arc::new(Mutex::new(true)),
+        write_limit,
+    }
+}
+
+#[test]
+fn partially_written_bytes_are_written_in_order() {
+    let os_input = fake_pty(100);
+    let bytes: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let mut write_queues = WriteQueues::default();
+    write_queues.push(1, bytes.clone());
+    while !write_queues.is_empty() {
+        write_queues.write_to_ptys(&os_input);
+    }
+    assert_eq!(
+        *os_input.written.lock().unwrap(),
+        bytes,
+        "all bytes written in the order they were queued"
+    );
+}
+
+#[test]
+fn bytes_stay_queued_while_the_pty_has_no_room() {
+    let os_input = fake_pty(1024);
+    *os_input.has_room.lock().unwrap() = false;
+    let mut write_queues = WriteQueues::default();
+    write_queues.push(1, b"first ".to_vec());
+    write_queues.write_to_ptys(&os_input);
+    assert!(
+        os_input.written.lock().unwrap().is_empty(),
+        "nothing written to a full pty"
+    );
+    assert!(!write_queues.is_empty(), "bytes are kept for later");
+
+    write_queues.push(1, b"second".to_vec());
+    *os_input.has_room.lock().unwrap() = true;
+    write_queues.write_to_ptys(&os_input);
+    assert_eq!(
+        *os_input.written.lock().unwrap(),
+        b"first second".to_vec(),
+        "queued bytes written once the pty has room"
+    );
+    assert!(write_queues.is_empty(), "queue emptied");
+}
+
+#[test]
+fn a_queue_growing_too_long_is_only_warned_about_once() {
+    let os_input = fake_pty(1024);
+    *os_input.has_room.lock().unwrap() = false;
+    let mut write_queues = WriteQueues::default();
+    assert_eq!(
+        write_queues.push(1, vec![0; QUEUED_BYTES_WARNING_THRESHOLD]),
+        None,
+        "no warning up to the threshold"
+    );
+    assert!(
+        write_queues.push(1, vec![0]).is_some(),
+        "warning once the queue is too long"
+    );
+    assert_eq!(
+        write_queues.push(1, vec![0]),
+        None,
+        "no second warning while the queue is still too long"
+    );
+    write_queues.write_to_ptys(&os_input);
+    *os_input.has_room.lock().unwrap() = true;
+    while !write_queues.is_empty() {
+        write_queues.write_to_ptys(&os_input);
+    }
+    assert!(
+        write_queues
+            .push(1, vec![0; QUEUED_BYTES_WARNING_THRESHOLD + 1])
+            .is_some(),
+        "warned again once the queue was emptied"
+    );
+}
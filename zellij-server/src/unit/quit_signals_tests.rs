@@ -0,0 +1,28 @@
+use super::*;
+use zellij_utils::signal_hook::low_level::raise;
+
+fn kills_the_session_on(signal: i32) {
+    let (to_server, server_receiver): ChannelWithContext<ServerInstruction> = channels::bounded(50);
+    let to_server = SenderWithContext::new(to_server);
+    // listen before raising the signal, otherwise it would terminate the tests
+    let signals = Signals::new(&[signal]).unwrap();
+    raise(signal).unwrap();
+    kill_session_on_quit_signal(signals, &to_server);
+
+    let (instruction, _) = server_receiver.try_recv().unwrap();
+    assert!(
+        matches!(instruction, ServerInstruction::KillSession),
+        "session killed on signal {}",
+        signal
+    );
+}
+
+#[test]
+fn session_is_killed_on_sigterm() {
+    kills_the_session_on(SIGTERM);
+}
+
+#[test]
+fn session_is_killed_on_sigint() {
+    kills_the_session_on(SIGINT);
+}
@@ -0,0 +1,182 @@
+use super::should_route_action;
+use crate::{
+    os_input_output::{AsyncReader, Pid, ServerOsApi, SpawnTerminalError},
+    panes::PaneId,
+    ClientId,
+};
+use std::path::PathBuf;
+use zellij_utils::ipc::IpcReceiverWithContext;
+
+use std::os::unix::io::RawFd;
+use std::sync::{Arc, Mutex};
+
+use zellij_utils::nix;
+
+use zellij_utils::{
+    data::{InputMode, Palette},
+    input::{
+        actions::{Action, Direction},
+        command::{RunCommand, TerminalAction},
+    },
+    interprocess::local_socket::LocalSocketStream,
+    ipc::{ClientToServerMsg, ServerToClientMsg},
+};
+
+#[derive(Clone, Default)]
+struct FakeInputOutput {
+    sent_to_clients: Arc<Mutex<Vec<(ClientId, ServerToClientMsg)>>>,
+}
+
+impl ServerOsApi for FakeInputOutput {
+    fn set_terminal_size_using_terminal_id(&self, _id: u32, _cols: u16, _rows: u16) {
+        // noop
+    }
+    fn spawn_terminal(
+        &self,
+        _file_to_open: TerminalAction,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>,
+        _default_editor: Option<PathBuf>,
+    ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
+        unimplemented!()
+    }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        unimplemented!()
+    }
+    fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn async_file_reader(&self, _fd: RawFd) -> Box<dyn AsyncReader> {
+        unimplemented!()
+    }
+    fn write_to_tty_stdin(&self, _id: u32, _buf: &[u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn tcdrain(&self, _id: u32) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn force_kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn hang_up_process_group(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ServerOsApi> {
+        Box::new((*self).clone())
+    }
+    fn send_to_client(
+        &self,
+        client_id: ClientId,
+        msg: ServerToClientMsg,
+    ) -> Result<(), &'static str> {
+        self.sent_to_clients.lock().unwrap().push((client_id, msg));
+        Ok(())
+    }
+    fn new_client(
+        &mut self,
+        _client_id: ClientId,
+        _stream: LocalSocketStream,
+    ) -> IpcReceiverWithContext<ClientToServerMsg> {
+        unimplemented!()
+    }
+    fn remove_client(&mut self, _client_id: ClientId) {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        unimplemented!()
+    }
+    fn get_cwd(&self, _pid: Pid) -> Option<PathBuf> {
+        unimplemented!()
+    }
+
+    fn write_to_file(&mut self, _buf: String, _name: Option<String>) {
+        unimplemented!()
+    }
+    fn re_run_command_in_terminal(
+        &self,
+        _terminal_id: u32,
+        _run_command: RunCommand,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
+    ) -> Result<(RawFd, RawFd), SpawnTerminalError> {
+        unimplemented!()
+    }
+    fn clear_terminal_id(&self, _terminal_id: u32) {
+        unimplemented!()
+    }
+    fn foreground_process_is_running(&self, _terminal_id: u32) -> bool {
+        unimplemented!()
+    }
+    fn signal_foreground_process_group(
+        &self,
+        _terminal_id: u32,
+        _signal: nix::sys::signal::Signal,
+    ) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn pipe_to_command(&self, _buf: String, _command: &[String]) -> std::io::Result<()> {
+        unimplemented!()
+    }
+}
+
+fn unblocked_clients(os_input: &FakeInputOutput) -> Vec<ClientId> {
+    os_input
+        .sent_to_clients
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, msg)| matches!(msg, ServerToClientMsg::UnblockInputThread))
+        .map(|(client_id, _)| *client_id)
+        .collect()
+}
+
+#[test]
+fn read_only_client_is_unblocked_when_its_action_is_dropped() {
+    let os_input = FakeInputOutput::default();
+    let client_id = 2;
+    let should_route = should_route_action(
+        &Action::MoveFocusOrTab(Direction::Left),
+        true,
+        &os_input,
+        client_id,
+    );
+    assert!(!should_route, "action of read-only client dropped");
+    assert_eq!(
+        unblocked_clients(&os_input),
+        vec![client_id],
+        "client unblocked"
+    );
+}
+
+#[test]
+fn read_only_client_can_switch_input_modes() {
+    let os_input = FakeInputOutput::default();
+    let should_route = should_route_action(
+        &Action::SwitchToMode(InputMode::Session),
+        true,
+        &os_input,
+        1,
+    );
+    assert!(should_route, "mode switch routed");
+    assert!(
+        os_input.sent_to_clients.lock().unwrap().is_empty(),
+        "nothing sent back to the client"
+    );
+}
+
+#[test]
+fn actions_of_clients_that_are_not_read_only_are_routed() {
+    let os_input = FakeInputOutput::default();
+    let should_route = should_route_action(
+        &Action::MoveFocusOrTab(Direction::Left),
+        false,
+        &os_input,
+        1,
+    );
+    assert!(should_route, "action routed");
+    assert!(
+        os_input.sent_to_clients.lock().unwrap().is_empty(),
+        "nothing sent back to the client"
+    );
+}
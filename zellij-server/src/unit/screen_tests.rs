@@ -1,8 +1,10 @@
-use super::{screen_thread_main, CopyOptions, Screen, ScreenInstruction};
-use crate::panes::PaneId;
+use super::{screen_thread_main, CopyOptions, MaxPanes, Screen, ScreenInstruction};
+use crate::panes::{PaneId, BELL_FLASH_DURATION};
+use crate::ui::overlay::Overlayable;
 use crate::{
     channels::SenderWithContext,
     os_input_output::{AsyncReader, Pid, ServerOsApi, SpawnTerminalError},
+    output::Output,
     route::route_action,
     thread_bus::Bus,
     ClientId, ServerInstruction, SessionMetaData, ThreadSenders,
@@ -13,8 +15,10 @@ use zellij_utils::cli::CliAction;
 use zellij_utils::errors::ErrorContext;
 use zellij_utils::input::actions::{Action, Direction, ResizeDirection};
 use zellij_utils::input::command::{RunCommand, TerminalAction};
-use zellij_utils::input::layout::{PaneLayout, SplitDirection};
-use zellij_utils::input::options::Options;
+use zellij_utils::input::layout::{
+    Layout, PaneLayout, Run, RunPlugin, RunPluginLocation, SplitDirection, SplitSize,
+};
+use zellij_utils::input::options::{AutoLayout, BellStyle, Options};
 use zellij_utils::ipc::IpcReceiverWithContext;
 use zellij_utils::pane_size::{Size, SizeInPixels};
 
@@ -28,7 +32,7 @@ use zellij_utils::ipc::PixelDimensions;
 use zellij_utils::nix;
 use zellij_utils::{
     channels::{self, ChannelWithContext, Receiver},
-    data::{InputMode, ModeInfo, Palette, PluginCapabilities},
+    data::{Event, InputMode, Key, ModeInfo, Palette, PluginCapabilities, PluginTag},
     interprocess::local_socket::LocalSocketStream,
     ipc::{ClientAttributes, ClientToServerMsg, ServerToClientMsg},
 };
@@ -39,6 +43,8 @@ use crate::panes::sixel::SixelImageStore;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
+use zellij_utils::position::Position;
 use zellij_utils::vte;
 
 // TODO: deduplicate with identical function in tab_integration_tests
@@ -124,6 +130,7 @@ fn send_cli_action_to_server(
 struct FakeInputOutput {
     fake_filesystem: Arc<Mutex<HashMap<String, String>>>,
     server_to_client_messages: Arc<Mutex<HashMap<ClientId, Vec<ServerToClientMsg>>>>,
+    terminals_run_a_program: bool,
 }
 
 impl ServerOsApi for FakeInputOutput {
@@ -138,6 +145,9 @@ impl ServerOsApi for FakeInputOutput {
     ) -> Result<(u32, RawFd, RawFd), SpawnTerminalError> {
         unimplemented!()
     }
+    fn reserve_terminal_id(&self) -> Option<u32> {
+        unimplemented!()
+    }
     fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
         unimplemented!()
     }
@@ -156,6 +166,9 @@ impl ServerOsApi for FakeInputOutput {
     fn force_kill(&self, _pid: Pid) -> Result<(), nix::Error> {
         unimplemented!()
     }
+    fn hang_up_process_group(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
     fn box_clone(&self) -> Box<dyn ServerOsApi> {
         Box::new((*self).clone())
     }
@@ -207,6 +220,23 @@ impl ServerOsApi for FakeInputOutput {
     fn clear_terminal_id(&self, _terminal_id: u32) {
         unimplemented!()
     }
+    fn foreground_process_is_running(&self, _terminal_id: u32) -> bool {
+        self.terminals_run_a_program
+    }
+    fn signal_foreground_process_group(
+        &self,
+        _terminal_id: u32,
+        _signal: nix::sys::signal::Signal,
+    ) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn pipe_to_command(&self, buf: String, command: &[String]) -> std::io::Result<()> {
+        self.fake_filesystem
+            .lock()
+            .unwrap()
+            .insert(command.join(" "), buf);
+        Ok(())
+    }
 }
 
 fn create_new_screen(size: Size) -> Screen {
@@ -223,6 +253,19 @@ fn create_new_screen(size: Size) -> Screen {
     let draw_pane_frames = false;
     let session_is_mirrored = true;
     let copy_options = CopyOptions::default();
+    let full_redraw = false;
+    let move_tab_wraparound = false;
+    let confirm_pane_close = false;
+    let resize_percent = 5.0;
+    let status_in_title = false;
+    let undo_close_depth = 10;
+    let auto_layout = AutoLayout::Manual;
+    let bell_style = BellStyle::Audible;
+    let persist_scrollback_max_size = None;
+    let go_to_tab_creates_tab = false;
+    let scroll_wheel_lines = 3;
+    let min_pane_width = None;
+    let min_pane_height = None;
 
     Screen::new(
         bus,
@@ -232,6 +275,19 @@ fn create_new_screen(size: Size) -> Screen {
         draw_pane_frames,
         session_is_mirrored,
         copy_options,
+        full_redraw,
+        move_tab_wraparound,
+        confirm_pane_close,
+        resize_percent,
+        status_in_title,
+        undo_close_depth,
+        auto_layout,
+        bell_style,
+        persist_scrollback_max_size,
+        go_to_tab_creates_tab,
+        scroll_wheel_lines,
+        min_pane_width,
+        min_pane_height,
     )
 }
 
@@ -469,6 +525,188 @@ pub fn switch_to_prev_tab() {
     );
 }
 
+#[test]
+pub fn move_active_tab_left() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    new_tab(&mut screen, 3);
+    screen.move_active_tab_left(1).expect("TEST");
+
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.index, 2, "Same tab still active after moving");
+    assert_eq!(active_tab.position, 1, "Active tab moved to the left");
+    assert_eq!(
+        screen.tabs.get(&1).unwrap().position,
+        2,
+        "Neighbouring tab swapped into the active tab's position"
+    );
+}
+
+#[test]
+pub fn move_active_tab_right_at_the_end_is_a_noop() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.move_active_tab_right(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Last tab did not move"
+    );
+    assert_eq!(
+        screen.tabs.get(&0).unwrap().position,
+        0,
+        "First tab did not move"
+    );
+}
+
+#[test]
+pub fn move_active_tab_right_wraps_around() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.move_tab_wraparound = true;
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    new_tab(&mut screen, 3);
+    screen.move_active_tab_right(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Last tab wrapped around to the start"
+    );
+    assert_eq!(
+        screen.tabs.get(&0).unwrap().position,
+        1,
+        "First tab shifted to the right"
+    );
+    assert_eq!(
+        screen.tabs.get(&1).unwrap().position,
+        2,
+        "Second tab shifted to the right"
+    );
+}
+
+#[test]
+fn control_characters_are_stripped_from_the_status_line_in_the_title() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    screen.get_active_tab_mut(1).unwrap().name = String::from("tab\u{7}\u{1b}]2;evil");
+    screen.render_status_lines_in_title(&mut Output::default());
+    assert_eq!(
+        screen.status_lines_in_title.get(&1).map(String::as_str),
+        Some("zellij-test | tab]2;evil (1/1) | NORMAL | 1 pane"),
+        "control characters left out of the title"
+    );
+}
+
+#[test]
+pub fn rename_tab_with_unicode_characters() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    screen.update_active_tab_name(vec![0], 1).expect("TEST");
+    for c in ["t", "a", "b", "ü", "🦀"] {
+        screen
+            .update_active_tab_name(c.as_bytes().to_vec(), 1)
+            .expect("TEST");
+    }
+    screen.update_active_tab_name(vec![27], 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().name,
+        "tabü🦀",
+        "Unicode characters added to tab name, control characters ignored"
+    );
+    screen.update_active_tab_name(vec![127], 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().name,
+        "tabü",
+        "Backspace removes the last character"
+    );
+}
+
+#[test]
+pub fn bell_in_a_tab_no_one_is_looking_at_marks_the_tab_until_it_is_visited() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    let first_tab = screen.tabs.get_mut(&0).unwrap();
+    first_tab
+        .handle_pty_bytes(1, "\u{7}".as_bytes().to_vec())
+        .expect("TEST");
+    assert!(first_tab.take_bell(1), "bell rung in the first tab");
+    screen.ring_bell(0, 1).expect("TEST");
+
+    assert!(
+        screen.clients_to_ring.is_empty(),
+        "no client is looking at the tab to hear the bell"
+    );
+    assert!(
+        screen.tabs.get(&0).unwrap().has_unseen_bell,
+        "tab marked with the unseen bell"
+    );
+    screen.switch_tab_prev(1).expect("TEST");
+    assert!(
+        !screen.tabs.get(&0).unwrap().has_unseen_bell,
+        "mark cleared once the tab is visited"
+    );
+}
+
+#[test]
+pub fn visual_bell_schedules_a_render_for_the_end_of_the_flash() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.bell_style = BellStyle::Visual;
+
+    new_tab(&mut screen, 1);
+    let rung_at = Instant::now();
+    screen.ring_bell(0, 1).expect("TEST");
+
+    let bell_flash_ends_at = screen
+        .bell_flash_ends_at
+        .expect("render scheduled for the end of the flash");
+    assert!(bell_flash_ends_at >= rung_at + BELL_FLASH_DURATION);
+    screen.ring_bell(0, 1).expect("TEST");
+    assert_eq!(
+        screen.bell_flash_ends_at,
+        Some(bell_flash_ends_at),
+        "ringing again while the pane flashes does not push the render back"
+    );
+}
+
 #[test]
 pub fn switch_to_next_tab() {
     let size = Size {
@@ -503,14 +741,461 @@ pub fn close_tab() {
 
     assert_eq!(screen.tabs.len(), 1, "Only one tab left");
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab switched to previous tab"
+    );
+}
+
+#[test]
+pub fn close_the_middle_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    new_tab(&mut screen, 3);
+    screen.switch_tab_prev(1).expect("TEST");
+    screen.close_tab(1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 2, "Two tabs left");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab switched to previous tab"
+    );
+}
+
+#[test]
+pub fn move_focused_pane_to_new_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
+    screen.move_focused_pane_to_new_tab(1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 2, "Pane was moved to a new tab");
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.position, 1, "Active tab switched to new tab");
+    assert_eq!(
+        active_tab.get_all_pane_ids(),
+        vec![PaneId::Terminal(2)],
+        "New tab only contains the moved pane"
+    );
+    assert_eq!(
+        screen.tabs.get(&0).unwrap().get_all_pane_ids(),
+        vec![PaneId::Terminal(1)],
+        "Moved pane was removed from its original tab"
+    );
+}
+
+#[test]
+pub fn move_only_pane_to_new_tab_is_a_noop() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    screen.move_focused_pane_to_new_tab(1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 1, "No empty tab was left behind");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().get_all_pane_ids(),
+        vec![PaneId::Terminal(1)],
+        "Pane stayed in its tab"
+    );
+}
+
+#[test]
+pub fn quitting_with_several_tabs_open_asks_for_confirmation() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.confirm_quit(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_overlays_mut().len(),
+        1,
+        "Quit confirmation prompt is shown"
+    );
+    assert_eq!(
+        screen.mode_info.get(&1).unwrap().mode,
+        InputMode::Prompt,
+        "Client switched to the prompt mode"
+    );
+}
+
+#[test]
+pub fn switching_to_help_mode_shows_the_keybindings_until_it_is_left() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    let mut mode_info = screen.default_mode_info.clone();
+    mode_info.mode = InputMode::Help;
+    screen.change_mode(mode_info.clone(), 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_overlays_mut().len(),
+        1,
+        "Help is shown in help mode"
+    );
+
+    mode_info.mode = InputMode::Normal;
+    screen.change_mode(mode_info, 1).expect("TEST");
+    assert!(
+        screen.get_active_overlays_mut().is_empty(),
+        "Help is hidden when leaving help mode"
+    );
+}
+
+#[test]
+pub fn help_is_scrolled_instead_of_the_pane_while_it_is_shown() {
+    let size = Size { cols: 20, rows: 5 };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    assert!(
+        !screen.scroll_help(1),
+        "Nothing to scroll when the help is not shown"
+    );
+    let mut mode_info = screen.default_mode_info.clone();
+    mode_info.mode = InputMode::Help;
+    mode_info.keybinds = vec![(
+        InputMode::Normal,
+        ('a'..='j')
+            .enumerate()
+            .map(|(i, c)| (Key::Char(c), vec![Action::GoToTab(i as u32 + 1)]))
+            .collect(),
+    )];
+    screen.change_mode(mode_info, 1).expect("TEST");
+    let help = |screen: &mut Screen| {
+        screen.get_active_overlays_mut()[0]
+            .generate_overlay(size)
+            .unwrap()
+    };
+    let top_of_help = help(&mut screen);
+    assert!(
+        top_of_help.contains("NORMAL") && !top_of_help.contains("j: Go to tab 10"),
+        "Help starts at the top"
+    );
+
+    assert!(screen.scroll_help(-1), "Help is scrolled while it is shown");
+    assert_eq!(
+        help(&mut screen),
+        top_of_help,
+        "Help is not scrolled past the top"
+    );
+    assert!(
+        screen.scroll_help(100),
+        "Help is scrolled while it is shown"
+    );
+    let bottom_of_help = help(&mut screen);
+    assert!(
+        bottom_of_help.contains("Keybindings") && bottom_of_help.contains("j: Go to tab 10"),
+        "Help is scrolled to its end below the title"
+    );
+    screen.scroll_help(1);
+    assert_eq!(
+        help(&mut screen),
+        bottom_of_help,
+        "Help is not scrolled past the end"
+    );
+}
+
+#[test]
+pub fn quitting_with_a_single_tab_open_does_not_ask_for_confirmation() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    screen.confirm_quit(1).expect("TEST");
+
+    assert!(
+        screen.get_active_overlays_mut().is_empty(),
+        "No prompt is shown"
+    );
+}
+
+#[test]
+pub fn move_focused_pane_to_next_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.switch_tab_prev(1).expect("TEST");
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(3), None, None, Some(1))
+        .unwrap();
+    screen
+        .move_focused_pane_to_adjacent_tab(Direction::Right, 1)
+        .expect("TEST");
+
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(
+        active_tab.position, 1,
+        "Focus followed the pane to the next tab"
+    );
+    assert_eq!(
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Moved pane is focused in the next tab"
+    );
+    assert_eq!(
+        screen.tabs.get(&0).unwrap().get_all_pane_ids(),
+        vec![PaneId::Terminal(1)],
+        "Moved pane was removed from its original tab"
+    );
+}
+
+#[test]
+pub fn pinned_pane_follows_the_active_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.switch_tab_prev(1).expect("TEST");
+    {
+        let active_tab = screen.get_active_tab_mut(1).unwrap();
+        active_tab
+            .new_pane(PaneId::Terminal(3), None, None, Some(1))
+            .unwrap();
+        active_tab.toggle_pane_embed_or_floating(1).unwrap();
+    }
+    screen.toggle_active_pane_pinned(1).expect("TEST");
+    screen.switch_tab_next(1).expect("TEST");
+
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.position, 1, "Switched to the next tab");
+    assert!(
+        active_tab.are_floating_panes_visible(),
+        "Pinned pane is shown in the next tab"
+    );
+    assert_eq!(
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Pinned pane is still focused in the next tab"
+    );
+    assert_eq!(
+        screen.tabs.get(&0).unwrap().get_all_pane_ids(),
+        vec![PaneId::Terminal(1)],
+        "Pinned pane was removed from its original tab"
+    );
+
+    screen.toggle_active_pane_pinned(1).expect("TEST");
+    screen.switch_tab_prev(1).expect("TEST");
+    assert!(
+        screen
+            .tabs
+            .get(&1)
+            .unwrap()
+            .get_all_pane_ids()
+            .contains(&PaneId::Terminal(3)),
+        "Unpinned pane stays in the tab it was in"
+    );
+}
+
+#[test]
+pub fn pinned_pane_stays_while_another_client_looks_at_its_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.switch_tab_prev(1).expect("TEST");
+    screen.add_client(2).expect("TEST");
+    screen.session_is_mirrored = false;
+    {
+        let active_tab = screen.get_active_tab_mut(1).unwrap();
+        active_tab
+            .new_pane(PaneId::Terminal(3), None, None, Some(1))
+            .unwrap();
+        active_tab.toggle_pane_embed_or_floating(1).unwrap();
+    }
+    screen.toggle_active_pane_pinned(1).expect("TEST");
+    screen.switch_tab_next(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "First client switched to the next tab"
+    );
+    assert!(
+        screen
+            .get_active_tab(2)
+            .unwrap()
+            .get_all_pane_ids()
+            .contains(&PaneId::Terminal(3)),
+        "Pinned pane stays in the tab the second client is looking at"
+    );
+}
+
+#[test]
+pub fn move_focused_pane_to_tab_at_max_panes_is_refused() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.max_panes = Some(MaxPanes::new(2, false));
+
+    new_tab(&mut screen, 1);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
+    new_tab(&mut screen, 3);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(4), None, None, Some(1))
+        .unwrap();
+    screen
+        .move_focused_pane_to_adjacent_tab(Direction::Left, 1)
+        .expect("TEST");
+
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.position, 1, "Did not switch tabs");
+    assert_eq!(
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(4)),
+        "Pane stayed in its tab"
+    );
+    assert_eq!(
+        screen.tabs.get(&0).unwrap().get_all_pane_ids().len(),
+        2,
+        "No pane was added to the full tab"
+    );
+}
+
+#[test]
+fn move_focus_left_at_left_screen_edge_changes_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    new_tab(&mut screen, 3);
+    screen.switch_tab_prev(1).expect("TEST");
+    screen.move_focus_left_or_previous_tab(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab switched to previous"
+    );
+}
+
+#[test]
+fn move_focus_right_at_right_screen_edge_changes_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    new_tab(&mut screen, 3);
+    screen.switch_tab_prev(1).expect("TEST");
+    screen.move_focus_right_or_next_tab(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        2,
+        "Active tab switched to next"
+    );
+}
+
+#[test]
+pub fn go_to_tab_past_the_last_tab_is_a_noop() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.go_to_tab(1, 1).expect("TEST");
+    screen.go_to_tab(5, 1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 2, "No tab was created");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab did not change"
+    );
+}
+
+#[test]
+pub fn tab_ids_are_not_reused_after_a_tab_is_closed() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1);
+    new_tab(&mut screen, 2);
+    screen.close_tab(1).expect("TEST");
+    new_tab(&mut screen, 3);
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().index,
+        2,
+        "New tab did not get the id of the closed tab"
+    );
+    screen.go_to_tab_with_id(0, 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().index,
         0,
-        "Active tab switched to previous tab"
+        "Switched to the tab by its id"
     );
 }
 
 #[test]
-pub fn close_the_middle_tab() {
+pub fn focus_pane_with_id_switches_to_its_tab() {
     let size = Size {
         cols: 121,
         rows: 20,
@@ -518,42 +1203,64 @@ pub fn close_the_middle_tab() {
     let mut screen = create_new_screen(size);
 
     new_tab(&mut screen, 1);
-    new_tab(&mut screen, 2);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
     new_tab(&mut screen, 3);
-    screen.switch_tab_prev(1).expect("TEST");
-    screen.close_tab(1).expect("TEST");
+    screen
+        .focus_pane_with_id(PaneId::Terminal(1), 1)
+        .expect("TEST");
 
-    assert_eq!(screen.tabs.len(), 2, "Two tabs left");
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.position, 0, "Switched to the tab of the pane");
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        1,
-        "Active tab switched to previous tab"
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(1)),
+        "Pane focused"
     );
 }
 
 #[test]
-fn move_focus_left_at_left_screen_edge_changes_tab() {
+pub fn focus_is_restored_after_cli_action_targeted_at_another_pane() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
+    let cli_client_id = 10;
 
     new_tab(&mut screen, 1);
-    new_tab(&mut screen, 2);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, Some(1))
+        .unwrap();
     new_tab(&mut screen, 3);
-    screen.switch_tab_prev(1).expect("TEST");
-    screen.move_focus_left_or_previous_tab(1).expect("TEST");
-
+    screen
+        .focus_pane_with_id(PaneId::Terminal(1), cli_client_id)
+        .expect("TEST");
     assert_eq!(
         screen.get_active_tab(1).unwrap().position,
         0,
-        "Active tab switched to previous"
+        "Focus moved for the cli action"
+    );
+    screen
+        .restore_focus_after_cli_action(cli_client_id)
+        .expect("TEST");
+
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.position, 1, "Back on the previous tab");
+    assert_eq!(
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Previous pane focused again"
     );
 }
 
 #[test]
-fn move_focus_right_at_right_screen_edge_changes_tab() {
+pub fn focus_follows_the_mouse_once_it_rests() {
     let size = Size {
         cols: 121,
         rows: 20,
@@ -561,15 +1268,47 @@ fn move_focus_right_at_right_screen_edge_changes_tab() {
     let mut screen = create_new_screen(size);
 
     new_tab(&mut screen, 1);
-    new_tab(&mut screen, 2);
-    new_tab(&mut screen, 3);
-    screen.switch_tab_prev(1).expect("TEST");
-    screen.move_focus_right_or_next_tab(1).expect("TEST");
-
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .vertical_split(PaneId::Terminal(2), None, 1)
+        .unwrap();
+    let moved_at = Instant::now();
+    assert!(
+        screen.mouse_moved(Position::new(5, 100), 1, moved_at),
+        "first move has the focus follow the mouse later"
+    );
+    assert!(
+        !screen.mouse_moved(
+            Position::new(5, 10),
+            1,
+            moved_at + Duration::from_millis(50)
+        ),
+        "further moves are picked up by the same check"
+    );
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        2,
-        "Active tab switched to next"
+        screen
+            .mouse_rested(1, moved_at + Duration::from_millis(100))
+            .unwrap(),
+        Some(Duration::from_millis(50)),
+        "the mouse only rested for half of the time after the last move"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().get_active_pane_id(1),
+        Some(PaneId::Terminal(2)),
+        "focus did not follow the mouse yet"
+    );
+    assert_eq!(
+        screen
+            .mouse_rested(1, moved_at + Duration::from_millis(150))
+            .unwrap(),
+        None,
+        "the mouse rested long enough"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().get_active_pane_id(1),
+        Some(PaneId::Terminal(1)),
+        "focus followed the mouse to where it was last moved to"
     );
 }
 
@@ -885,6 +1624,52 @@ fn attach_after_first_tab_closed() {
     screen.add_client(1).expect("TEST");
 }
 
+#[test]
+fn tab_is_visible_again_once_a_client_reattaches() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
+    screen.bus.senders = ThreadSenders {
+        to_plugin: Some(SenderWithContext::new(to_plugin)),
+        ..Default::default()
+    }
+    .silently_fail_on_send();
+    let plugin_thread = std::thread::spawn(move || {
+        let mut visibility_changes = vec![];
+        for (instruction, _) in plugin_receiver.iter() {
+            match instruction {
+                PluginInstruction::Load(pid_tx, ..) => pid_tx.send(1).unwrap(),
+                PluginInstruction::Update(_, _, Event::Visible(visible)) => {
+                    visibility_changes.push(visible)
+                },
+                _ => {},
+            }
+        }
+        visibility_changes
+    });
+    let plugin_layout = PaneLayout {
+        run: Some(Run::Plugin(RunPlugin {
+            _allow_exec_host_cmd: false,
+            location: RunPluginLocation::Zellij(PluginTag::new("tab-bar")),
+        })),
+        ..Default::default()
+    };
+    screen.new_tab(plugin_layout, vec![], 1).expect("TEST");
+    screen.remove_client(1).expect("TEST");
+    screen.add_client(1).expect("TEST");
+    // closes the plugin channel
+    drop(screen);
+    let visibility_changes = plugin_thread.join().unwrap();
+    assert!(
+        visibility_changes.ends_with(&[false, true]),
+        "tab hidden once its only client detached and shown again when it reattached: {:?}",
+        visibility_changes
+    );
+}
+
 // Following are tests for sending CLI actions
 // these tests are only partially relevant to Screen
 // and are included here for two reasons:
@@ -944,6 +1729,86 @@ pub fn send_cli_write_action_to_screen() {
     assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
 }
 
+#[test]
+pub fn tab_count_is_reported_to_the_server() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(None);
+    mock_screen.new_tab(PaneLayout::default());
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ReportTabCount(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let reported_tab_counts: Vec<(ClientId, usize)> = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            ServerInstruction::ActiveClients(client_id, tab_count) => {
+                Some((*client_id, *tab_count))
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(reported_tab_counts, vec![(1, 2)]);
+}
+
+#[test]
+pub fn output_is_clipped_to_a_client_smaller_than_the_screen() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_size = Size { cols: 20, rows: 5 };
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(None);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ClientResize(1, client_size));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "a".repeat(200).as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let (_cursor_coordinates, last_snapshot) = snapshots.last().unwrap();
+    assert!(
+        last_snapshot.contains('a'),
+        "pane content rendered:\n{}",
+        last_snapshot
+    );
+    for (y, line) in last_snapshot.lines().enumerate() {
+        // skip the "00 (C): " line prefix
+        let columns = line.chars().skip(8);
+        let columns_past_the_client: String = if y < client_size.rows {
+            columns.skip(client_size.cols).collect()
+        } else {
+            columns.collect()
+        };
+        assert!(
+            columns_past_the_client.trim().is_empty(),
+            "nothing rendered past the client's terminal:\n{}",
+            last_snapshot
+        );
+    }
+}
+
 #[test]
 pub fn send_cli_resize_action_to_screen() {
     let size = Size { cols: 80, rows: 20 };
@@ -1206,6 +2071,190 @@ pub fn send_cli_dump_screen_action() {
     ));
 }
 
+#[test]
+pub fn send_cli_pipe_pane_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = PaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![PaneLayout::default(), PaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout));
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let cli_action = CliAction::PipePane {
+        full: false,
+        command: vec!["grep".into(), "something".into()],
+    };
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "fill pane up with something".as_bytes().to_vec(),
+    ));
+    send_cli_action_to_server(&session_metadata, cli_action, &mut mock_screen, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let piped = mock_screen
+        .os_input
+        .fake_filesystem
+        .lock()
+        .unwrap()
+        .get("grep something")
+        .cloned()
+        .expect("pane was piped to the command");
+    assert!(
+        piped.starts_with("fill pane up with something"),
+        "pane content was piped"
+    );
+}
+
+#[test]
+pub fn send_cli_dump_layout_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = PaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    let command_pane = PaneLayout {
+        split_size: Some(SplitSize::Percent(25)),
+        run: Some(Run::Command(RunCommand {
+            command: PathBuf::from("htop"),
+            args: vec!["-d".into(), "10".into()],
+            cwd: None,
+            hold_on_close: true,
+            restart_on_exit: false,
+            start_suspended: false,
+        })),
+        ..Default::default()
+    };
+    initial_layout.children = vec![command_pane, PaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout.clone()));
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let cli_action = CliAction::DumpLayout {
+        path: PathBuf::from("/tmp/layout.kdl"),
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, &mut mock_screen, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let dumped_layout = mock_screen
+        .os_input
+        .fake_filesystem
+        .lock()
+        .unwrap()
+        .get("/tmp/layout.kdl")
+        .cloned()
+        .expect("layout was written to the file");
+    let layout = Layout::from_kdl(&dumped_layout, "layout.kdl".into(), None).unwrap();
+    assert_eq!(layout.tabs.len(), 1, "one tab in the dumped layout");
+    let (_tab_name, tab_layout) = &layout.tabs[0];
+    assert_eq!(
+        tab_layout.children_split_direction,
+        SplitDirection::Vertical,
+        "split direction is kept"
+    );
+    assert_eq!(
+        tab_layout.children[0].split_size,
+        Some(SplitSize::Percent(25)),
+        "split size is kept"
+    );
+    assert_eq!(
+        tab_layout.children[0].run, initial_layout.children[0].run,
+        "pane command is kept"
+    );
+    assert_eq!(tab_layout.children[1].run, None, "plain pane stays plain");
+}
+
+fn closed_panes(received_pty_instructions: &Arc<Mutex<Vec<PtyInstruction>>>) -> Vec<PaneId> {
+    received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PtyInstruction::ClosePane(pane_id) => Some(*pane_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+pub fn closing_a_pane_running_a_program_is_confirmed_with_a_second_keypress() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut initial_layout = PaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![PaneLayout::default(), PaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.os_input.terminals_run_a_program = true;
+    let screen_thread = mock_screen.run(Some(initial_layout));
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CloseFocusedPane(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(
+        closed_panes(&received_pty_instructions).is_empty(),
+        "pane kept open until the close is confirmed"
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ConfirmPrompt(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_eq!(
+        closed_panes(&received_pty_instructions),
+        vec![PaneId::Terminal(1)],
+        "pane closed once the close is confirmed"
+    );
+}
+
+#[test]
+pub fn closing_a_pane_running_a_program_is_cancelled_when_denied() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut initial_layout = PaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![PaneLayout::default(), PaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.os_input.terminals_run_a_program = true;
+    let screen_thread = mock_screen.run(Some(initial_layout));
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CloseFocusedPane(1));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::DenyPrompt(1));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ConfirmPrompt(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert!(
+        closed_panes(&received_pty_instructions).is_empty(),
+        "pane kept open after the close was denied"
+    );
+}
+
 #[test]
 pub fn send_cli_edit_scrollback_action() {
     let size = Size { cols: 80, rows: 20 };
@@ -2228,6 +3277,39 @@ pub fn send_cli_new_tab_action_default_params() {
     assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
 }
 
+#[test]
+pub fn send_cli_goto_tab_past_the_last_tab_creates_a_tab_when_configured() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.config_options.go_to_tab_creates_tab = Some(true);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let goto_tab_action = CliAction::GoToTab { index: 5 };
+    send_cli_action_to_server(
+        &session_metadata,
+        goto_tab_action,
+        &mut mock_screen,
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    let new_tab_count = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|instruction| matches!(instruction, PtyInstruction::NewTab(..)))
+        .count();
+    assert_eq!(new_tab_count, 1, "new tab opened");
+}
+
 #[test]
 pub fn send_cli_new_tab_action_with_name_and_layout() {
     let size = Size { cols: 80, rows: 10 };
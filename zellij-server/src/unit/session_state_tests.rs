@@ -0,0 +1,142 @@
+use super::*;
+
+#[test]
+fn session_is_sized_to_the_smallest_attached_client() {
+    let mut session_state = SessionState::new();
+    let first_client = session_state.new_client();
+    let second_client = session_state.new_client();
+    assert_ne!(first_client, second_client, "clients get their own ids");
+    assert_eq!(
+        session_state.min_client_terminal_size(),
+        None,
+        "no size before the clients report one"
+    );
+    session_state.set_client_size(
+        first_client,
+        Size {
+            rows: 50,
+            cols: 100,
+        },
+    );
+    session_state.set_client_size(second_client, Size { rows: 60, cols: 80 });
+    assert_eq!(
+        session_state.min_client_terminal_size(),
+        Some(Size { rows: 50, cols: 80 }),
+        "rows and columns are each clamped to the smallest client"
+    );
+}
+
+#[test]
+fn detaching_a_client_leaves_the_others_attached() {
+    let mut session_state = SessionState::new();
+    let first_client = session_state.new_client();
+    let second_client = session_state.new_client();
+    session_state.set_client_size(
+        first_client,
+        Size {
+            rows: 50,
+            cols: 100,
+        },
+    );
+    session_state.set_client_size(second_client, Size { rows: 20, cols: 40 });
+    session_state.remove_client(second_client);
+    assert_eq!(session_state.client_ids(), vec![first_client]);
+    assert_eq!(
+        session_state.min_client_terminal_size(),
+        Some(Size {
+            rows: 50,
+            cols: 100
+        }),
+        "session grows back to the size of the remaining client"
+    );
+    let third_client = session_state.new_client();
+    assert_eq!(
+        third_client, second_client,
+        "ids of detached clients are reused"
+    );
+}
+
+#[test]
+fn latest_size_policy_follows_the_client_that_resized_last() {
+    let mut session_state = SessionState::new();
+    session_state.set_size_policy(ClientSizePolicy::Latest);
+    let first_client = session_state.new_client();
+    let second_client = session_state.new_client();
+    let large = Size {
+        rows: 50,
+        cols: 100,
+    };
+    let small = Size { rows: 20, cols: 40 };
+    session_state.set_client_size(first_client, small);
+    session_state.set_client_size(second_client, large);
+    assert_eq!(session_state.session_size(), Some(large));
+    // the first client resizes mid-session
+    let resized = Size { rows: 30, cols: 60 };
+    session_state.set_client_size(first_client, resized);
+    assert_eq!(session_state.session_size(), Some(resized));
+    session_state.set_size_policy(ClientSizePolicy::Smallest);
+    assert_eq!(
+        session_state.session_size(),
+        Some(resized),
+        "switching back to the smallest client"
+    );
+    session_state.set_size_policy(ClientSizePolicy::Latest);
+    session_state.remove_client(first_client);
+    assert_eq!(
+        session_state.session_size(),
+        Some(large),
+        "falls back to the remaining clients once the latest one detaches"
+    );
+}
+
+#[test]
+fn attached_clients_are_detached_once_no_input_came_in_for_the_idle_timeout() {
+    let session_state = Arc::new(RwLock::new(SessionState::new()));
+    let client_id = session_state.write().unwrap().new_client();
+    let (to_server, server_receiver): ChannelWithContext<ServerInstruction> = channels::unbounded();
+    let to_server = SenderWithContext::new(to_server);
+    thread::spawn({
+        let session_state = session_state.clone();
+        move || {
+            watch_idle_timeout(
+                session_state,
+                to_server,
+                Duration::from_millis(50),
+                OnForceClose::Detach,
+            )
+        }
+    });
+    let (instruction, _err_ctx) = server_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("idle timeout passed");
+    assert!(
+        matches!(instruction, ServerInstruction::DetachSession(ref client_ids) if *client_ids == vec![client_id]),
+        "attached clients detached"
+    );
+}
+
+#[test]
+fn latest_size_policy_sizes_the_session_past_smaller_clients() {
+    let mut session_state = SessionState::new();
+    session_state.set_size_policy(ClientSizePolicy::Latest);
+    let small_client = session_state.new_client();
+    let large_client = session_state.new_client();
+    let small = Size { rows: 20, cols: 40 };
+    let large = Size {
+        rows: 50,
+        cols: 100,
+    };
+    session_state.set_client_size(small_client, small);
+    session_state.set_client_size(large_client, large);
+    // the screen clips the output of the small client to its own size
+    assert_eq!(
+        session_state.session_size(),
+        Some(large),
+        "sized to the latest client even though another one is smaller"
+    );
+    assert_eq!(
+        session_state.min_client_terminal_size(),
+        Some(small),
+        "the smaller client is still attached"
+    );
+}
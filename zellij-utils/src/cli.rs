@@ -1,21 +1,28 @@
 use crate::data::InputMode;
 use crate::setup::Setup;
 use crate::{
-    consts::{ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV},
-    input::actions::{Direction, ResizeDirection},
-    input::options::CliOptions,
+    consts::{
+        BUILD_VERSION, ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV, ZELLIJ_LOG_FILE_ENV,
+        ZELLIJ_LOG_LEVEL_ENV,
+    },
+    input::actions::{Direction, Fraction, ResizeDirection},
+    input::options::{CliOptions, ClientSizePolicy},
 };
 use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Parser, Default, Debug, Clone, Serialize, Deserialize)]
-#[clap(version, name = "zellij")]
+#[clap(version = BUILD_VERSION.as_str(), name = "zellij")]
 pub struct CliArgs {
-    /// Maximum panes on screen, caution: opening more panes will close old ones
+    /// Maximum panes on screen, opening more panes will be refused
     #[clap(long, value_parser)]
     pub max_panes: Option<usize>,
 
+    /// Close old panes instead of refusing to open new ones when reaching --max-panes
+    #[clap(long, value_parser, requires = "max-panes")]
+    pub max_panes_evict: bool,
+
     /// Change where zellij looks for plugins
     #[clap(long, value_parser, overrides_with = "data_dir")]
     pub data_dir: Option<PathBuf>,
@@ -28,10 +35,20 @@ pub struct CliArgs {
     #[clap(long, short, overrides_with = "session", value_parser)]
     pub session: Option<String>,
 
-    /// Name of a predefined layout inside the layout directory or the path to a layout file
+    /// Name of a predefined layout inside the layout directory or the path to a layout file,
+    /// "-" reads the layout from stdin
     #[clap(short, long, value_parser, overrides_with = "layout")]
     pub layout: Option<PathBuf>,
 
+    /// Change the working directory of the panes the layout opens
+    #[clap(long, value_parser, overrides_with = "cwd")]
+    pub cwd: Option<PathBuf>,
+
+    /// Set a variable used as ${KEY} in the cwd, command and args of the layout's panes, taking
+    /// precedence over the environment variable of the same name (can be given several times)
+    #[clap(long = "layout-var", value_name = "KEY=VALUE", value_parser = parse_layout_var)]
+    pub layout_vars: Vec<(String, String)>,
+
     /// Change where zellij looks for the configuration file
     #[clap(short, long, overrides_with = "config", env = ZELLIJ_CONFIG_FILE_ENV, value_parser)]
     pub config: Option<PathBuf>,
@@ -40,12 +57,59 @@ pub struct CliArgs {
     #[clap(long, overrides_with = "config_dir", env = ZELLIJ_CONFIG_DIR_ENV, value_parser)]
     pub config_dir: Option<PathBuf>,
 
+    /// Don't load the configuration file at the default location, use the defaults zellij ships
+    /// with instead
+    #[clap(long, value_parser)]
+    pub clean: bool,
+
+    /// Render inline instead of switching to the terminal's alternate screen, leaving the last
+    /// drawn screen in the terminal's scrollback on exit
+    #[clap(long, value_parser)]
+    pub no_altscreen: bool,
+
+    /// Don't print the exit message (eg. "Bye from Zellij!") when the client exits or detaches
+    /// normally, errors are still printed and the terminal is still restored
+    #[clap(short, long, value_parser)]
+    pub quiet: bool,
+
+    /// Record everything zellij draws to the terminal into an asciicast file at this path, to be
+    /// played back with asciinema
+    #[clap(long, value_parser)]
+    pub record: Option<PathBuf>,
+
     #[clap(subcommand)]
     pub command: Option<Command>,
 
     /// Specify emitting additional debug information
     #[clap(short, long, value_parser)]
     pub debug: bool,
+
+    /// Set the most verbose level of messages written to the log (defaults to info)
+    #[clap(
+        long,
+        env = ZELLIJ_LOG_LEVEL_ENV,
+        value_parser = ["off", "error", "warn", "info", "debug", "trace"]
+    )]
+    pub log_level: Option<String>,
+
+    /// Write the log to this file instead of zellij.log in the zellij temporary directory
+    #[clap(long, env = ZELLIJ_LOG_FILE_ENV, value_parser)]
+    pub log_file: Option<PathBuf>,
+}
+
+fn parse_layout_var(layout_var: &str) -> Result<(String, String), String> {
+    match layout_var.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected KEY=VALUE, found '{}'", layout_var)),
+    }
+}
+
+impl CliArgs {
+    /// Whether the defaults zellij ships with were asked for instead of the configuration at the
+    /// default location, with either `zellij --clean` or `zellij setup --clean`
+    pub fn is_clean(&self) -> bool {
+        self.clean || matches!(self.command, Some(Command::Setup(ref setup)) if setup.clean)
+    }
 }
 
 #[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
@@ -74,7 +138,12 @@ pub enum SessionCommand {
 pub enum Sessions {
     /// List active sessions
     #[clap(visible_alias = "ls")]
-    ListSessions,
+    ListSessions {
+        /// Also print the number of clients connected to each session and its number of tabs
+        /// (tab separated)
+        #[clap(short, long, value_parser)]
+        long: bool,
+    },
 
     /// Attach to a session
     #[clap(visible_alias = "a")]
@@ -91,6 +160,10 @@ pub enum Sessions {
         #[clap(long, value_parser)]
         index: Option<usize>,
 
+        /// Watch the session without being able to type into its panes or run actions.
+        #[clap(long, value_parser)]
+        read_only: bool,
+
         /// Change the behaviour of zellij
         #[clap(subcommand, name = "options")]
         options: Option<Box<SessionCommand>>,
@@ -104,6 +177,14 @@ pub enum Sessions {
         target_session: Option<String>,
     },
 
+    /// Detach all clients from the specific session, leaving it running in the background
+    #[clap(visible_alias = "d")]
+    DetachSession {
+        /// Name of target session, defaults to the current session
+        #[clap(value_parser)]
+        target_session: Option<String>,
+    },
+
     /// Kill all sessions
     #[clap(visible_alias = "ka")]
     KillAllSessions {
@@ -113,8 +194,20 @@ pub enum Sessions {
     },
     /// Send actions to a specific session
     #[clap(visible_alias = "ac")]
-    #[clap(subcommand)]
-    Action(CliAction),
+    Action {
+        /// Focus the pane with this id (as shown by list-panes, eg. terminal_3) before running
+        /// the action, so that the action applies to it rather than to the focused pane
+        #[clap(long, value_parser)]
+        pane_id: Option<String>,
+
+        /// Switch to the tab with this id (as shown by list-panes) before running the action, so
+        /// that the action applies to it rather than to the active tab
+        #[clap(long, value_parser)]
+        tab_id: Option<u32>,
+
+        #[clap(subcommand)]
+        action: CliAction,
+    },
     /// Run a command in a new pane
     #[clap(visible_alias = "r")]
     Run {
@@ -178,6 +271,15 @@ pub enum CliAction {
     WriteChars { chars: String },
     /// Resize the focused pane in the specified direction. [right|left|up|down|+|-]
     Resize { resize_direction: ResizeDirection },
+    /// Resize the focused pane by a single cell in the specified direction. [right|left|up|down|+|-]
+    ResizeFine { resize_direction: ResizeDirection },
+    /// Give all panes in the current tab an equal share of their split
+    EqualizePanes,
+    /// Arrange the panes of the current tab with the configured auto-layout (or in an even grid),
+    /// going back to arranging them automatically after they were resized by hand
+    ApplyAutoLayout,
+    /// Resize the focused pane to a fraction of the split it is in, eg. 1/2, 1/3 or 2/3
+    ResizeToFraction { fraction: Fraction },
     /// Change focus to the next pane
     FocusNextPane,
     /// Change focus to the previous pane
@@ -198,6 +300,26 @@ pub enum CliAction {
         #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
         full: bool,
     },
+    /// Write the focused pane to the stdin of a command, eg. to search or save it:
+    /// zellij action pipe-pane -- grep foo
+    PipePane {
+        /// Write the pane with full scrollback
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        full: bool,
+
+        /// Command to write the pane to
+        #[clap(last(true), required(true))]
+        command: Vec<String>,
+    },
+    /// Save the arrangement of the session's tabs and panes to a layout file which can be loaded
+    /// again with --layout
+    DumpLayout { path: PathBuf },
+    /// Change how the session is sized when clients of different sizes are attached to it
+    /// [smallest|latest]
+    SetClientSizePolicy {
+        #[clap(arg_enum, value_parser)]
+        policy: ClientSizePolicy,
+    },
     /// Open the pane scrollback in your default editor
     EditScrollback,
     /// Scroll up in the focused pane
@@ -206,6 +328,8 @@ pub enum CliAction {
     ScrollDown,
     /// Scroll down to bottom in focus pane.
     ScrollToBottom,
+    /// Toggle whether the focused pane follows new output while it is scrolled
+    ToggleFollowOutput,
     /// Scroll up one page in focus pane.
     PageScrollUp,
     /// Scroll down one page in focus pane.
@@ -214,6 +338,14 @@ pub enum CliAction {
     HalfPageScrollUp,
     /// Scroll down half page in focus pane.
     HalfPageScrollDown,
+    /// Scroll left half a page in focus pane, if its lines are wider than it
+    /// (only while the program running in it disabled linewrap)
+    ScrollLeft,
+    /// Scroll right half a page in focus pane, if its lines are wider than it
+    /// (only while the program running in it disabled linewrap)
+    ScrollRight,
+    /// Toggle whether lines longer than the focused pane wrap
+    ToggleLineWrap,
     /// Toggle between fullscreen focus pane and normal layout.
     ToggleFullscreen,
     /// Toggle frames around panes in the UI
@@ -273,10 +405,16 @@ pub enum CliAction {
     SwitchMode { input_mode: InputMode },
     /// Embed focused pane if floating or float focused pane if embedded
     TogglePaneEmbedOrFloating,
+    /// Pin the focused floating pane so that it stays on screen when switching tabs, or unpin it
+    TogglePanePinned,
     /// Toggle the visibility of all fdirectionloating panes in the current Tab, open one if none exist
     ToggleFloatingPanes,
     /// Close the focused pane.
     ClosePane,
+    /// Reopen the most recently closed pane of the current tab, running its command again
+    UndoClosePane,
+    /// Send a signal to the foreground process of the focused pane. [SIGINT|SIGTERM|SIGKILL|...]
+    SignalFocusedPane { signal: String },
     /// Renames the focused pane
     RenamePane { name: String },
     /// Remove a previously set pane name
@@ -285,17 +423,27 @@ pub enum CliAction {
     GoToNextTab,
     /// Go to the previous tab.
     GoToPreviousTab,
+    /// Move the current tab one position to the left.
+    MoveTabLeft,
+    /// Move the current tab one position to the right.
+    MoveTabRight,
+    /// Move the focused pane out of the current tab and into a new tab
+    MovePaneToNewTab,
+    /// Move the focused pane to the previous or next tab [right|left]
+    MovePaneToTab { direction: Direction },
     /// Close the current tab.
     CloseTab,
     /// Go to tab with index [index]
     GoToTab { index: u32 },
+    /// List the id, tab id, position, size and title of every pane, one pane per line
+    ListPanes,
     /// Renames the focused pane
     RenameTab { name: String },
     /// Remove a previously set tab name
     UndoRenameTab,
     /// Create a new tab, optionally with a specified tab layout and name
     NewTab {
-        /// Layout to use for the new tab
+        /// Layout to use for the new tab, "-" reads the layout from stdin
         #[clap(short, long, value_parser)]
         layout: Option<PathBuf>,
 
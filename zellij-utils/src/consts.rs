@@ -8,9 +8,16 @@ use std::path::PathBuf;
 pub const ZELLIJ_CONFIG_FILE_ENV: &str = "ZELLIJ_CONFIG_FILE";
 pub const ZELLIJ_CONFIG_DIR_ENV: &str = "ZELLIJ_CONFIG_DIR";
 pub const ZELLIJ_LAYOUT_DIR_ENV: &str = "ZELLIJ_LAYOUT_DIR";
+pub const ZELLIJ_LOG_LEVEL_ENV: &str = "ZELLIJ_LOG_LEVEL";
+pub const ZELLIJ_LOG_FILE_ENV: &str = "ZELLIJ_LOG_FILE";
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const DEFAULT_SCROLL_BUFFER_SIZE: usize = 10_000;
 pub static SCROLL_BUFFER_SIZE: OnceCell<usize> = OnceCell::new();
+pub const DEFAULT_PTY_READ_BUFFER_SIZE: usize = 65_536;
+pub const MAX_PTY_READ_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+pub static PTY_READ_BUFFER_SIZE: OnceCell<usize> = OnceCell::new();
+pub const DEFAULT_PENDING_OUTPUT_LIMIT: usize = 10 * 1024 * 1024;
+pub static PENDING_OUTPUT_LIMIT: OnceCell<usize> = OnceCell::new();
 pub static DEBUG_MODE: OnceCell<bool> = OnceCell::new();
 
 pub const SYSTEM_DEFAULT_CONFIG_DIR: &str = "/etc/zellij";
@@ -25,6 +32,13 @@ const fn system_default_data_dir() -> &'static str {
 }
 
 lazy_static! {
+    /// The version of this build, including the git commit it was built from if the
+    /// `ZELLIJ_GIT_HASH` environment variable was set at compile time.
+    /// Clients and servers only talk to each other if they are of the same build version.
+    pub static ref BUILD_VERSION: String = match std::option_env!("ZELLIJ_GIT_HASH") {
+        Some(git_hash) => format!("{} ({})", VERSION, git_hash),
+        None => VERSION.to_owned(),
+    };
     pub static ref ZELLIJ_PROJ_DIR: ProjectDirs =
         ProjectDirs::from("org", "Zellij Contributors", "Zellij").unwrap();
     pub static ref ZELLIJ_CACHE_DIR: PathBuf = ZELLIJ_PROJ_DIR.cache_dir().to_path_buf();
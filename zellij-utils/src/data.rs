@@ -239,6 +239,8 @@ pub enum Event {
     Timer(f64),
     CopyToClipboard(CopyDestination),
     SystemClipboardFailure,
+    /// A message to show the user, eg. explaining why an action was refused
+    StatusMessage(String),
     InputReceived,
     Visible(bool),
 }
@@ -303,6 +305,12 @@ pub enum InputMode {
     /// `Tmux` mode allows for basic tmux keybindings functionality
     #[serde(alias = "tmux")]
     Tmux,
+    /// `Signal` mode allows sending a signal to the process running in the focused pane
+    #[serde(alias = "signal")]
+    Signal,
+    /// `Help` mode shows the keybindings of every mode on top of the panes
+    #[serde(alias = "help")]
+    Help,
 }
 
 // impl TryFrom<&str> for InputMode {
@@ -372,6 +380,8 @@ impl FromStr for InputMode {
             "move" | "Move" => Ok(InputMode::Move),
             "prompt" | "Prompt" => Ok(InputMode::Prompt),
             "tmux" | "Tmux" => Ok(InputMode::Tmux),
+            "signal" | "Signal" => Ok(InputMode::Signal),
+            "help" | "Help" => Ok(InputMode::Help),
             "entersearch" | "Entersearch" | "EnterSearch" => Ok(InputMode::EnterSearch),
             e => Err(ConversionError::UnknownInputMode(e.into())),
         }
@@ -415,6 +425,36 @@ pub struct Palette {
 pub struct Style {
     pub colors: Palette,
     pub rounded_corners: bool,
+    pub frame_style: FrameStyle,
+}
+
+/// The set of characters pane frames and boundaries are drawn with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FrameStyle {
+    /// Thin box-drawing lines (these can have rounded corners)
+    Light,
+    /// Thick box-drawing lines
+    Heavy,
+    /// Plain `+`, `-` and `|`, for fonts or terminals without box-drawing characters
+    Ascii,
+}
+
+impl Default for FrameStyle {
+    fn default() -> Self {
+        FrameStyle::Light
+    }
+}
+
+impl FromStr for FrameStyle {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "light" | "Light" => Ok(FrameStyle::Light),
+            "heavy" | "Heavy" => Ok(FrameStyle::Heavy),
+            "ascii" | "Ascii" => Ok(FrameStyle::Ascii),
+            e => Err(format!("Unknown frame style: {}", e)),
+        }
+    }
 }
 
 // FIXME: Poor devs hashtable since HashTable can't derive `Default`...
@@ -458,6 +498,7 @@ pub struct TabInfo {
     pub is_sync_panes_active: bool,
     pub are_floating_panes_visible: bool,
     pub other_focused_clients: Vec<ClientId>,
+    pub has_unseen_bell: bool,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -3,7 +3,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
-    env::{set_var, var},
+    env::{remove_var, set_var, var},
 };
 
 use std::fmt;
@@ -26,6 +26,11 @@ pub fn set_session_name(v: String) {
     set_var(SESSION_NAME_ENV_KEY, v);
 }
 
+pub const COLOR_DEPTH_ENV_KEY: &str = "ZELLIJ_COLOR_DEPTH";
+pub fn get_color_depth() -> Result<String> {
+    Ok(var(COLOR_DEPTH_ENV_KEY)?)
+}
+
 pub fn set_initial_environment_vars() {
     set_var("COLORTERM", "24bit");
 }
@@ -36,6 +41,10 @@ pub fn get_socket_dir() -> Result<String> {
 }
 
 /// Manage ENVIRONMENT VARIABLES from the configuration and the layout files
+///
+/// Variables defined in the layout take precedence over those defined in the configuration,
+/// which in turn take precedence over the ones inherited from the environment zellij was
+/// started in. An empty value removes the inherited variable instead.
 #[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EnvironmentVariables {
     env: HashMap<String, String>,
@@ -62,10 +71,15 @@ impl EnvironmentVariables {
         EnvironmentVariables { env: data }
     }
     /// Set all the ENVIRONMENT VARIABLES, that are configured
-    /// in the configuration and layout files
+    /// in the configuration and layout files, so that they are inherited by spawned panes.
+    /// Variables with an empty value are unset
     pub fn set_vars(&self) {
         for (k, v) in &self.env {
-            set_var(k, v);
+            if v.is_empty() {
+                remove_var(k);
+            } else {
+                set_var(k, v);
+            }
         }
     }
 }
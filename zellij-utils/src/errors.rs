@@ -93,9 +93,10 @@ pub trait LoggableError<T>: Sized {
     /// ```
     fn print_error<F: Fn(&str)>(self, fun: F) -> Self;
 
-    /// Convenienve function, calls `print_error` with the closure `|msg| log::error!("{}", msg)`.
+    /// Convenienve function, calls `print_error` with a closure that logs the message with
+    /// `log::error!`, along with the call stack of the current thread.
     fn to_log(self) -> Self {
-        self.print_error(|msg| log::error!("{}", msg))
+        self.print_error(|msg| log::error!("{} call_stack=\"{}\"", msg, current_call_stack()))
     }
 
     /// Convenienve function, calls `print_error` with the closure `|msg| eprintln!("{}", msg)`.
@@ -118,6 +119,16 @@ impl<T> LoggableError<T> for anyhow::Result<T> {
     }
 }
 
+#[cfg(not(target_family = "wasm"))]
+fn current_call_stack() -> String {
+    get_current_ctx().call_stack()
+}
+
+#[cfg(target_family = "wasm")]
+fn current_call_stack() -> String {
+    String::new()
+}
+
 /// Special trait to mark fatal/non-fatal errors.
 ///
 /// This works in tandem with `LoggableError` above and is meant to make reading code easier with
@@ -224,6 +235,7 @@ pub enum ScreenContext {
     ShowFloatingPanes,
     HideFloatingPanes,
     TogglePaneEmbedOrFloating,
+    TogglePanePinned,
     HorizontalSplit,
     VerticalSplit,
     WriteCharacter,
@@ -233,6 +245,10 @@ pub enum ScreenContext {
     ResizeUp,
     ResizeIncrease,
     ResizeDecrease,
+    ResizeFine,
+    EqualizePanes,
+    ApplyAutoLayout,
+    ResizeToFraction,
     SwitchFocus,
     FocusNextPane,
     FocusPreviousPane,
@@ -250,18 +266,27 @@ pub enum ScreenContext {
     MovePaneLeft,
     Exit,
     DumpScreen,
+    PipePane,
+    DumpLayout,
     EditScrollback,
     ScrollUp,
     ScrollUpAt,
     ScrollDown,
     ScrollDownAt,
     ScrollToBottom,
+    ToggleFollowOutput,
     PageScrollUp,
     PageScrollDown,
     HalfPageScrollUp,
     HalfPageScrollDown,
+    ScrollLeft,
+    ScrollRight,
+    ToggleLineWrap,
     ClearScroll,
     CloseFocusedPane,
+    UndoClosePane,
+    ReopenClosedPane,
+    SignalFocusedPane,
     ToggleActiveSyncTab,
     ToggleActiveTerminalFullscreen,
     TogglePaneFrames,
@@ -271,16 +296,28 @@ pub enum ScreenContext {
     SetFixedWidth,
     ClosePane,
     HoldPane,
+    RestartCommandInPane,
+    PersistScrollback,
     UpdatePaneName,
     UndoRenamePane,
     NewTab,
     SwitchTabNext,
     SwitchTabPrev,
+    MoveTabLeft,
+    MoveTabRight,
+    MovePaneToNewTab,
+    MovePaneToTab,
     CloseTab,
     GoToTab,
+    GoToTabWithId,
+    FocusPaneWithId,
+    RestoreFocus,
+    ListPanes,
+    ReportTabCount,
     UpdateTabName,
     UndoRenameTab,
     TerminalResize,
+    ClientResize,
     TerminalPixelDimensions,
     TerminalBackgroundColor,
     TerminalForegroundColor,
@@ -296,6 +333,8 @@ pub enum ScreenContext {
     MouseHoldLeft,
     MouseHoldRight,
     MouseHoldMiddle,
+    MouseMove,
+    FocusFollowsMouse,
     Copy,
     ToggleTab,
     AddClient,
@@ -303,6 +342,7 @@ pub enum ScreenContext {
     AddOverlay,
     RemoveOverlay,
     ConfirmPrompt,
+    ConfirmQuit,
     DenyPrompt,
     UpdateSearch,
     SearchDown,
@@ -322,9 +362,12 @@ pub enum PtyContext {
     UpdateActivePane,
     GoToTab,
     NewTab,
+    NewTabWithLayout,
     ClosePane,
     CloseTab,
     ReRunCommandInPane,
+    CommandExited,
+    ReopenClosedPane,
     Exit,
 }
 
@@ -352,6 +395,7 @@ pub enum ClientContext {
     Connected,
     ActiveClients,
     OwnClientId,
+    Log,
 }
 
 /// Stack call representations corresponding to the different types of [`ServerInstruction`]s.
@@ -368,6 +412,8 @@ pub enum ServerContext {
     AttachClient,
     ConnStatus,
     ActiveClients,
+    SetClientSizePolicy,
+    Log,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -435,8 +481,12 @@ mod not_wasm {
                 "Panic occured:
              thread: {}
              location: {}
-             message: {}",
-                thread, location_string, msg
+             message: {}
+             call_stack: {}",
+                thread,
+                location_string,
+                msg,
+                err_ctx.call_stack()
             )
         );
 
@@ -485,8 +535,19 @@ mod not_wasm {
             self.calls.iter().all(|c| c == &ContextType::Empty)
         }
 
+        /// The calls of this [`ErrorContext`], oldest first, in a form fit for the log.
+        pub fn call_stack(&self) -> String {
+            self.calls
+                .iter()
+                .take_while(|ctx| **ctx != ContextType::Empty)
+                .map(|ctx| format!("{:?}", ctx))
+                .collect::<Vec<_>>()
+                .join(" > ")
+        }
+
         /// Adds a call to this [`ErrorContext`]'s call stack representation.
         pub fn add_call(&mut self, call: ContextType) {
+            log::debug!("event=dispatch call={:?}", call);
             for ctx in &mut self.calls {
                 if let ContextType::Empty = ctx {
                     *ctx = call;
@@ -575,3 +636,7 @@ mod not_wasm {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/errors_test.rs"]
+mod errors_test;
@@ -5,7 +5,7 @@ use super::layout::{Layout, PaneLayout};
 use crate::cli::CliAction;
 use crate::data::InputMode;
 use crate::input::config::{ConfigError, KdlError};
-use crate::input::options::OnForceClose;
+use crate::input::options::{ClientSizePolicy, OnForceClose};
 use miette::{NamedSource, Report};
 use serde::{Deserialize, Serialize};
 
@@ -65,6 +65,40 @@ impl FromStr for ResizeDirection {
     }
 }
 
+/// A fraction of the split a pane is in, eg. "1/3"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Fraction {
+    pub numerator: u8,
+    pub denominator: u8,
+}
+impl Fraction {
+    pub fn as_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+impl FromStr for Fraction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fraction = s.split_once('/').and_then(|(numerator, denominator)| {
+            let numerator = numerator.trim().parse::<u8>().ok()?;
+            let denominator = denominator.trim().parse::<u8>().ok()?;
+            Some(Fraction {
+                numerator,
+                denominator,
+            })
+        });
+        match fraction {
+            Some(fraction) if fraction.numerator > 0 && fraction.numerator < fraction.denominator => {
+                Ok(fraction)
+            },
+            _ => Err(format!(
+                "Failed to parse Fraction. Expected a fraction between 0 and 1 such as 1/3, got: {}",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum SearchDirection {
     Down,
@@ -128,6 +162,15 @@ pub enum Action {
     SwitchModeForAllClients(InputMode),
     /// Resize focus pane in specified direction.
     Resize(ResizeDirection),
+    /// Resize focus pane in specified direction by a single cell.
+    ResizeFine(ResizeDirection),
+    /// Give all panes in the current tab an equal share of their split.
+    EqualizePanes,
+    /// Arrange the panes of the current tab with the configured auto-layout and keep doing so
+    /// as panes are added or closed.
+    ApplyAutoLayout,
+    /// Resize the focused pane to a fraction of the split it is in.
+    ResizeToFraction(Fraction),
     /// Switch focus to next pane in specified direction.
     FocusNextPane,
     FocusPreviousPane,
@@ -140,6 +183,12 @@ pub enum Action {
     MovePane(Option<Direction>),
     /// Dumps the screen to a file
     DumpScreen(String, bool),
+    /// Write the focused pane to the stdin of a command, with its full scrollback if true
+    PipePane(Vec<String>, bool),
+    /// Dumps the layout of the session's tabs and panes to a file
+    DumpLayout(String),
+    /// Change how the session is sized when clients of different sizes are attached to it
+    SetClientSizePolicy(ClientSizePolicy),
     /// Scroll up in focus pane.
     EditScrollback,
     ScrollUp,
@@ -151,6 +200,8 @@ pub enum Action {
     ScrollDownAt(Position),
     /// Scroll down to bottom in focus pane.
     ScrollToBottom,
+    /// Toggle whether the focused pane follows new output while it is scrolled.
+    ToggleFollowOutput,
     /// Scroll up one page in focus pane.
     PageScrollUp,
     /// Scroll down one page in focus pane.
@@ -159,6 +210,12 @@ pub enum Action {
     HalfPageScrollUp,
     /// Scroll down half page in focus pane.
     HalfPageScrollDown,
+    /// Scroll left half a page in focus pane, if its lines are wider than it.
+    ScrollLeft,
+    /// Scroll right half a page in focus pane, if its lines are wider than it.
+    ScrollRight,
+    /// Toggle whether lines longer than the focused pane wrap.
+    ToggleLineWrap,
     /// Toggle between fullscreen focus pane and normal layout.
     ToggleFocusFullscreen,
     /// Toggle frames around panes in the UI
@@ -178,23 +235,50 @@ pub enum Action {
     // name
     /// Embed focused pane in tab if floating or float focused pane if embedded
     TogglePaneEmbedOrFloating,
+    /// Pin the focused floating pane so that it follows the active tab, or unpin it
+    TogglePanePinned,
     /// Toggle the visibility of all floating panes (if any) in the current Tab
     ToggleFloatingPanes,
     /// Close the focus pane.
     CloseFocus,
+    /// Reopen the most recently closed pane of the current tab.
+    UndoClosePane,
+    /// Send the named signal (eg. "SIGTERM") to the foreground process of the focused pane.
+    SignalFocusedPane(String),
     PaneNameInput(Vec<u8>),
     UndoRenamePane,
     /// Create a new tab, optionally with a specified tab layout.
     NewTab(Option<PaneLayout>, Option<String>), // the String is the tab name
+    /// Create a new tab with a layout, given by path or by name from the layout dir.
+    NewTabWithLayout(PathBuf),
     /// Do nothing.
     NoOp,
     /// Go to the next tab.
     GoToNextTab,
     /// Go to the previous tab.
     GoToPreviousTab,
+    /// Move the current tab one position to the left.
+    MoveTabLeft,
+    /// Move the current tab one position to the right.
+    MoveTabRight,
+    /// Move the focused pane out of the current tab and into a new tab of its own.
+    MovePaneToNewTab,
+    /// Move the focused pane to the previous (left/up) or next (right/down) tab.
+    MovePaneToTab(Direction),
     /// Close the current tab.
     CloseTab,
     GoToTab(u32),
+    /// Go to the tab with the given id, which does not change as tabs are moved or closed
+    GoToTabWithId(u32),
+    /// Focus the terminal pane with the given id, switching to its tab
+    FocusTerminalPaneWithId(u32),
+    /// Focus the plugin pane with the given id, switching to its tab
+    FocusPluginPaneWithId(u32),
+    /// Give the focus back to the tab and pane that had it before a cli action was targeted at
+    /// another tab or pane
+    RestoreFocus,
+    /// List the id, tab, geometry and title of every pane
+    ListPanes,
     ToggleTab,
     TabNameInput(Vec<u8>),
     UndoRenameTab,
@@ -211,6 +295,8 @@ pub enum Action {
     MouseHoldLeft(Position),
     MouseHoldRight(Position),
     MouseHoldMiddle(Position),
+    /// The mouse moved over the given position without a button held
+    MouseMove(Position),
     Copy,
     /// Confirm a prompt
     Confirm,
@@ -226,12 +312,32 @@ pub enum Action {
     SearchToggleOption(SearchOption),
 }
 
+/// Whether `signal` names a signal that can be sent to the foreground process of a pane (eg.
+/// "SIGTERM")
+#[cfg(unix)]
+pub fn is_signal_name(signal: &str) -> bool {
+    nix::sys::signal::Signal::from_str(signal).is_ok()
+}
+
+#[cfg(not(unix))]
+pub fn is_signal_name(_signal: &str) -> bool {
+    true
+}
+
 impl Action {
     pub fn actions_from_cli(cli_action: CliAction) -> Result<Vec<Action>, String> {
         match cli_action {
             CliAction::Write { bytes } => Ok(vec![Action::Write(bytes)]),
             CliAction::WriteChars { chars } => Ok(vec![Action::WriteChars(chars)]),
             CliAction::Resize { resize_direction } => Ok(vec![Action::Resize(resize_direction)]),
+            CliAction::ResizeFine { resize_direction } => {
+                Ok(vec![Action::ResizeFine(resize_direction)])
+            },
+            CliAction::EqualizePanes => Ok(vec![Action::EqualizePanes]),
+            CliAction::ApplyAutoLayout => Ok(vec![Action::ApplyAutoLayout]),
+            CliAction::ResizeToFraction { fraction } => {
+                Ok(vec![Action::ResizeToFraction(fraction)])
+            },
             CliAction::FocusNextPane => Ok(vec![Action::FocusNextPane]),
             CliAction::FocusPreviousPane => Ok(vec![Action::FocusPreviousPane]),
             CliAction::MoveFocus { direction } => Ok(vec![Action::MoveFocus(direction)]),
@@ -241,14 +347,25 @@ impl Action {
                 path.as_os_str().to_string_lossy().into(),
                 full,
             )]),
+            CliAction::PipePane { full, command } => Ok(vec![Action::PipePane(command, full)]),
+            CliAction::DumpLayout { path } => Ok(vec![Action::DumpLayout(
+                path.as_os_str().to_string_lossy().into(),
+            )]),
+            CliAction::SetClientSizePolicy { policy } => {
+                Ok(vec![Action::SetClientSizePolicy(policy)])
+            },
             CliAction::EditScrollback => Ok(vec![Action::EditScrollback]),
             CliAction::ScrollUp => Ok(vec![Action::ScrollUp]),
             CliAction::ScrollDown => Ok(vec![Action::ScrollDown]),
             CliAction::ScrollToBottom => Ok(vec![Action::ScrollToBottom]),
+            CliAction::ToggleFollowOutput => Ok(vec![Action::ToggleFollowOutput]),
             CliAction::PageScrollUp => Ok(vec![Action::PageScrollUp]),
             CliAction::PageScrollDown => Ok(vec![Action::PageScrollDown]),
             CliAction::HalfPageScrollUp => Ok(vec![Action::HalfPageScrollUp]),
             CliAction::HalfPageScrollDown => Ok(vec![Action::HalfPageScrollDown]),
+            CliAction::ScrollLeft => Ok(vec![Action::ScrollLeft]),
+            CliAction::ScrollRight => Ok(vec![Action::ScrollRight]),
+            CliAction::ToggleLineWrap => Ok(vec![Action::ToggleLineWrap]),
             CliAction::ToggleFullscreen => Ok(vec![Action::ToggleFocusFullscreen]),
             CliAction::TogglePaneFrames => Ok(vec![Action::TogglePaneFrames]),
             CliAction::ToggleActiveSyncTab => Ok(vec![Action::ToggleActiveSyncTab]),
@@ -315,8 +432,16 @@ impl Action {
                 Ok(vec![Action::SwitchModeForAllClients(input_mode)])
             },
             CliAction::TogglePaneEmbedOrFloating => Ok(vec![Action::TogglePaneEmbedOrFloating]),
+            CliAction::TogglePanePinned => Ok(vec![Action::TogglePanePinned]),
             CliAction::ToggleFloatingPanes => Ok(vec![Action::ToggleFloatingPanes]),
             CliAction::ClosePane => Ok(vec![Action::CloseFocus]),
+            CliAction::UndoClosePane => Ok(vec![Action::UndoClosePane]),
+            CliAction::SignalFocusedPane { signal } => {
+                if !is_signal_name(&signal) {
+                    return Err(format!("Unknown signal: '{}'", signal));
+                }
+                Ok(vec![Action::SignalFocusedPane(signal)])
+            },
             CliAction::RenamePane { name } => Ok(vec![
                 Action::UndoRenamePane,
                 Action::PaneNameInput(name.as_bytes().to_vec()),
@@ -324,8 +449,13 @@ impl Action {
             CliAction::UndoRenamePane => Ok(vec![Action::UndoRenamePane]),
             CliAction::GoToNextTab => Ok(vec![Action::GoToNextTab]),
             CliAction::GoToPreviousTab => Ok(vec![Action::GoToPreviousTab]),
+            CliAction::MoveTabLeft => Ok(vec![Action::MoveTabLeft]),
+            CliAction::MoveTabRight => Ok(vec![Action::MoveTabRight]),
+            CliAction::MovePaneToNewTab => Ok(vec![Action::MovePaneToNewTab]),
+            CliAction::MovePaneToTab { direction } => Ok(vec![Action::MovePaneToTab(direction)]),
             CliAction::CloseTab => Ok(vec![Action::CloseTab]),
             CliAction::GoToTab { index } => Ok(vec![Action::GoToTab(index)]),
+            CliAction::ListPanes => Ok(vec![Action::ListPanes]),
             CliAction::RenameTab { name } => Ok(vec![
                 Action::TabNameInput(vec![0]),
                 Action::TabNameInput(name.as_bytes().to_vec()),
@@ -385,6 +515,29 @@ impl Action {
             },
         }
     }
+    /// The actions to run ahead of a cli action for it to apply to the tab and pane with the
+    /// given ids, rather than to the active ones. Pane ids are either `terminal_<id>` or
+    /// `plugin_<id>`, a bare id being that of a terminal.
+    pub fn focus_actions_from_cli(
+        pane_id: Option<String>,
+        tab_id: Option<u32>,
+    ) -> Result<Vec<Action>, String> {
+        let mut actions = vec![];
+        if let Some(tab_id) = tab_id {
+            actions.push(Action::GoToTabWithId(tab_id));
+        }
+        if let Some(pane_id) = pane_id {
+            let invalid_pane_id = || format!("Invalid pane id: '{}'", pane_id);
+            let action = if let Some(id) = pane_id.strip_prefix("plugin_") {
+                Action::FocusPluginPaneWithId(id.parse().map_err(|_| invalid_pane_id())?)
+            } else {
+                let id = pane_id.strip_prefix("terminal_").unwrap_or(&pane_id);
+                Action::FocusTerminalPaneWithId(id.parse().map_err(|_| invalid_pane_id())?)
+            };
+            actions.push(action);
+        }
+        Ok(actions)
+    }
 }
 
 impl From<OnForceClose> for Action {
@@ -19,6 +19,11 @@ pub struct RunCommand {
     pub cwd: Option<PathBuf>,
     #[serde(default)]
     pub hold_on_close: bool,
+    #[serde(default)]
+    pub restart_on_exit: bool,
+    /// Wait for the user to press Enter in the pane before running the command
+    #[serde(default)]
+    pub start_suspended: bool,
 }
 
 impl std::fmt::Display for RunCommand {
@@ -59,6 +64,8 @@ impl From<RunCommandAction> for RunCommand {
             args: action.args,
             cwd: action.cwd,
             hold_on_close: action.hold_on_close,
+            restart_on_exit: false,
+            start_suspended: false,
         }
     }
 }
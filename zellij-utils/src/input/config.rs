@@ -1,5 +1,5 @@
-use crate::data::Palette;
-use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode};
+use crate::data::{InputMode, Key, Palette};
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode, SourceSpan};
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::PathBuf;
@@ -44,11 +44,22 @@ impl KdlError {
         self.src = Some(NamedSource::new(src_name, src_input));
         self
     }
+    /// The 1-based line and column the error points at, known once the source has been added
+    pub fn line_and_column(&self) -> Option<(usize, usize)> {
+        let src = self.src.as_ref()?;
+        let span: SourceSpan = (self.offset?, self.len.unwrap_or(0)).into();
+        let contents = src.read_span(&span, 0, 0).ok()?;
+        Some((contents.line() + 1, contents.column() + 1))
+    }
 }
 
 impl std::fmt::Display for KdlError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "Failed to parse Zellij configuration")
+        write!(f, "Failed to parse Zellij configuration")?;
+        if let Some((line, column)) = self.line_and_column() {
+            write!(f, " at {}:{}", line, column)?;
+        }
+        Ok(())
     }
 }
 use std::fmt::Display;
@@ -99,6 +110,21 @@ pub enum ConfigError {
     PluginsError(#[from] PluginsConfigError),
     #[error("{0}")]
     ConversionError(#[from] ConversionError),
+    // Error in a config file other than a KDL syntax error, with the path of the file
+    #[error("{0}, File: {1}")]
+    InFile(Box<ConfigError>, PathBuf),
+    // Command line arguments that can't be used together
+    #[error("Conflicting arguments: {0}")]
+    ConflictingArguments(String),
+    // A prefix key that would replace a keybinding for something else
+    #[error("Prefix key {0} is already bound in {1:?} mode")]
+    PrefixKeyAlreadyBound(Key, InputMode),
+    // A prefix key without any keybinding to replace
+    #[error("Prefix key {0} would do nothing, no mode has a key bound to enter tmux mode")]
+    PrefixKeyWithoutTmuxMode(Key),
+    // A layout checked with `setup --check-layout` that is not valid, with the reasons why
+    #[error("{0}")]
+    InvalidLayout(String),
 }
 
 impl ConfigError {
@@ -132,32 +158,41 @@ impl TryFrom<&CliArgs> for Config {
     type Error = ConfigError;
 
     fn try_from(opts: &CliArgs) -> ConfigResult {
+        // `zellij setup --clean` is still accepted alongside `zellij --clean`
+        let setup_is_clean = matches!(opts.command, Some(Command::Setup(ref setup)) if setup.clean);
+        if opts.clean && setup_is_clean {
+            return Err(ConfigError::ConflictingArguments(
+                "--clean was given to both zellij and its setup subcommand, give it only once"
+                    .into(),
+            ));
+        }
+
+        if opts.config.is_some() && opts.is_clean() {
+            return Err(ConfigError::ConflictingArguments(
+                "--config can't be used with --clean, which ignores all configuration".into(),
+            ));
+        }
+
         if let Some(ref path) = opts.config {
             let default_config = Config::from_default_assets()?;
             return Config::from_path(path, Some(default_config));
         }
 
-        if let Some(Command::Setup(ref setup)) = opts.command {
-            if setup.clean {
-                return Config::from_default_assets();
-            }
+        if opts.is_clean() {
+            return Config::from_default_assets();
         }
 
-        let config_dir = opts
-            .config_dir
-            .clone()
-            .or_else(setup::find_default_config_dir);
+        let config_file = match opts.config_dir {
+            Some(ref config_dir) => Some(config_dir.join(DEFAULT_CONFIG_FILE_NAME)),
+            None => setup::find_default_config_file(),
+        };
 
-        if let Some(ref config) = config_dir {
-            let path = config.join(DEFAULT_CONFIG_FILE_NAME);
-            if path.exists() {
+        match config_file {
+            Some(path) if path.exists() => {
                 let default_config = Config::from_default_assets()?;
                 Config::from_path(&path, Some(default_config))
-            } else {
-                Config::from_default_assets()
-            }
-        } else {
-            Config::from_default_assets()
+            },
+            _ => Config::from_default_assets(),
         }
     }
 }
@@ -165,7 +200,14 @@ impl TryFrom<&CliArgs> for Config {
 impl Config {
     pub fn theme_config(&self, opts: &Options) -> Option<Palette> {
         match &opts.theme {
-            Some(theme_name) => self.themes.get_theme(theme_name).map(|theme| theme.palette),
+            Some(theme_name) => self
+                .themes
+                .get_theme(theme_name)
+                .or_else(|| {
+                    log::error!("Theme '{}' not found, using the default theme", theme_name);
+                    self.themes.get_theme("default")
+                })
+                .map(|theme| theme.palette),
             None => self.themes.get_theme("default").map(|theme| theme.palette),
         }
     }
@@ -219,7 +261,7 @@ impl Config {
                             kdl_config,
                         )))
                     },
-                    Err(e) => Err(e),
+                    Err(e) => Err(ConfigError::InFile(Box::new(e), path.to_path_buf())),
                 }
             },
             Err(e) => Err(ConfigError::IoPath(e, path.into())),
@@ -230,11 +272,14 @@ impl Config {
 #[cfg(test)]
 mod config_test {
     use super::*;
-    use crate::data::{InputMode, Palette, PaletteColor, PluginTag};
+    use crate::data::{FrameStyle, InputMode, Palette, PaletteColor, PluginTag};
     use crate::input::layout::RunPluginLocation;
-    use crate::input::options::{Clipboard, OnForceClose};
+    use crate::input::options::{
+        AutoLayout, BellStyle, ClientSizePolicy, Clipboard, OnForceClose, OnNestedSession,
+    };
     use crate::input::plugins::{PluginConfig, PluginType, PluginsConfig};
     use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
+    use crate::shared::ColorDepth;
     use std::collections::HashMap;
     use std::io::Write;
     use tempfile::tempdir;
@@ -267,6 +312,114 @@ mod config_test {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn try_from_cli_args_with_clean_flag() {
+        let tmp = tempdir().unwrap();
+        File::create(tmp.path().join(DEFAULT_CONFIG_FILE_NAME))
+            .unwrap()
+            .write_all(b"simplified_ui true\npane_frames false\n")
+            .unwrap();
+        let opts = CliArgs {
+            clean: true,
+            config_dir: Some(tmp.path().to_path_buf()),
+            ..Default::default()
+        };
+        let result = Config::try_from(&opts);
+        assert_eq!(result.unwrap(), Config::from_default_assets().unwrap());
+    }
+
+    #[test]
+    fn config_file_that_fails_to_parse_is_named_in_the_error() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join(DEFAULT_CONFIG_FILE_NAME);
+        File::create(&path)
+            .unwrap()
+            .write_all(b"undo_close_depth -1\n")
+            .unwrap();
+        match Config::from_path(&path, None) {
+            Err(ConfigError::KdlError(kdl_error)) => {
+                // printed the same way as when starting the client
+                let report: miette::Report = kdl_error.into();
+                assert!(
+                    format!("{:?}", report).contains(&*path.to_string_lossy()),
+                    "printed error names the config file: {:?}",
+                    report
+                );
+            },
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn config_file_that_cannot_be_read_is_named_in_the_error() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join(DEFAULT_CONFIG_FILE_NAME);
+        let error = Config::from_path(&path, None).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .ends_with(&format!("File: {}", path.display())),
+            "error names the config file: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn kdl_error_reports_the_line_and_column_it_points_at() {
+        let raw_config = "keybinds {\n    normal {\n        bind \"a\" { Foo; }\n    }\n}\n";
+        let offset = raw_config.find("Foo").unwrap();
+        let error = KdlError {
+            error_message: "Unknown action".into(),
+            src: None,
+            offset: Some(offset),
+            len: Some(3),
+            help_message: None,
+        };
+        assert_eq!(
+            error.line_and_column(),
+            None,
+            "no position without a source"
+        );
+        let error = error.add_src("config.kdl".into(), raw_config.into());
+        assert_eq!(error.line_and_column(), Some((3, 20)));
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse Zellij configuration at 3:20"
+        );
+    }
+
+    #[test]
+    fn try_from_cli_args_with_config_and_clean_flag() {
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join(DEFAULT_CONFIG_FILE_NAME);
+        File::create(&config_path)
+            .unwrap()
+            .write_all(b"simplified_ui true\n")
+            .unwrap();
+        let opts = CliArgs {
+            clean: true,
+            config: Some(config_path),
+            ..Default::default()
+        };
+        let result = Config::try_from(&opts);
+        assert!(matches!(result, Err(ConfigError::ConflictingArguments(_))));
+    }
+
+    #[test]
+    fn try_from_cli_args_with_clean_flag_and_clean_setup() {
+        use crate::setup::Setup;
+        let opts = CliArgs {
+            clean: true,
+            command: Some(Command::Setup(Setup {
+                clean: true,
+                ..Setup::default()
+            })),
+            ..Default::default()
+        };
+        let result = Config::try_from(&opts);
+        assert!(matches!(result, Err(ConfigError::ConflictingArguments(_))));
+    }
+
     #[test]
     fn try_from_cli_args_with_config_dir() {
         let mut opts = CliArgs::default();
@@ -317,6 +470,38 @@ mod config_test {
             scrollback_editor "/path/to/my/scrollback-editor"
             session_name "my awesome session"
             attach_to_session true
+            clipboard_from_panes false
+            full_redraw true
+            move_tab_wraparound true
+            confirm_pane_close false
+            resize_percent 10
+            hold_shell_on_exit true
+            bracketed_paste false
+            status_in_title true
+            confirm_quit true
+            default_shell_args "-l --no-rcs"
+            on_nested_session "new-tab"
+            middle_click_paste true
+            client_size_policy "latest"
+            undo_close_depth 5
+            auto_layout "main_and_stack"
+            pty_read_buffer_size 4096
+            pending_output_limit 1048576
+            focus_follows_mouse true
+            max_command_restarts 3
+            command_restart_window 30
+            idle_timeout 600
+            on_idle_timeout "quit"
+            bell_style "visual"
+            persist_scrollback true
+            persist_scrollback_max_size 10
+            go_to_tab_creates_tab true
+            scroll_wheel_lines 5
+            min_pane_width 10
+            min_pane_height 4
+            new_tab_layout "/tmp/my-tab-layout.kdl"
+            prefix_key "Ctrl a"
+            color_depth "ansi256"
         "#;
         let config = Config::from_kdl(config_contents, None).unwrap();
         assert_eq!(
@@ -409,6 +594,219 @@ mod config_test {
             Some(true),
             "Option set in config"
         );
+        assert_eq!(
+            config.options.clipboard_from_panes,
+            Some(false),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.full_redraw,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.move_tab_wraparound,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.confirm_pane_close,
+            Some(false),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.resize_percent,
+            Some(10),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.hold_shell_on_exit,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.bracketed_paste,
+            Some(false),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.status_in_title,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.confirm_quit,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.default_shell_args,
+            Some("-l --no-rcs".to_string()),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.on_nested_session,
+            Some(OnNestedSession::NewTab),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.middle_click_paste,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.client_size_policy,
+            Some(ClientSizePolicy::Latest),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.undo_close_depth,
+            Some(5),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.auto_layout,
+            Some(AutoLayout::MainAndStack),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.pty_read_buffer_size,
+            Some(4096),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.pending_output_limit,
+            Some(1048576),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.focus_follows_mouse,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.max_command_restarts,
+            Some(3),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.command_restart_window,
+            Some(30),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.idle_timeout,
+            Some(600),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.on_idle_timeout,
+            Some(OnForceClose::Quit),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.bell_style,
+            Some(BellStyle::Visual),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.persist_scrollback,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.persist_scrollback_max_size,
+            Some(10),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.go_to_tab_creates_tab,
+            Some(true),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.scroll_wheel_lines,
+            Some(5),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.min_pane_width,
+            Some(10),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.min_pane_height,
+            Some(4),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.new_tab_layout,
+            Some(PathBuf::from("/tmp/my-tab-layout.kdl")),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.prefix_key,
+            Some("Ctrl a".to_string()),
+            "Option set in config"
+        );
+        assert_eq!(
+            config.options.color_depth,
+            Some(ColorDepth::Ansi256),
+            "Option set in config"
+        );
+    }
+
+    #[test]
+    fn negative_undo_close_depth_is_an_error() {
+        let config_contents = r#"
+            undo_close_depth -1
+        "#;
+        let config = Config::from_kdl(config_contents, None);
+        assert!(config.is_err(), "negative undo_close_depth rejected");
+    }
+
+    #[test]
+    fn out_of_range_pty_read_buffer_size_is_an_error() {
+        for pty_read_buffer_size in ["-1", "0", "16777217"] {
+            let config_contents = format!("pty_read_buffer_size {}", pty_read_buffer_size);
+            let config = Config::from_kdl(&config_contents, None);
+            assert!(
+                config.is_err(),
+                "pty_read_buffer_size {} rejected",
+                pty_read_buffer_size
+            );
+        }
+        let config = Config::from_kdl("pty_read_buffer_size 16777216", None).unwrap();
+        assert_eq!(
+            config.options.pty_read_buffer_size,
+            Some(16777216),
+            "largest pty_read_buffer_size accepted"
+        );
+    }
+
+    #[test]
+    fn out_of_range_resize_percent_is_an_error() {
+        for resize_percent in ["-5", "0", "101"] {
+            let config_contents = format!("resize_percent {}", resize_percent);
+            let config = Config::from_kdl(&config_contents, None);
+            assert!(
+                config.is_err(),
+                "resize_percent {} rejected",
+                resize_percent
+            );
+        }
+        let config = Config::from_kdl("resize_percent 100", None).unwrap();
+        assert_eq!(
+            config.options.resize_percent,
+            Some(100),
+            "largest resize_percent accepted"
+        );
+    }
+
+    #[test]
+    fn negative_pending_output_limit_is_an_error() {
+        let config = Config::from_kdl("pending_output_limit -1", None);
+        assert!(config.is_err(), "negative pending_output_limit rejected");
     }
 
     #[test]
@@ -455,6 +853,49 @@ mod config_test {
         assert_eq!(config.themes, expected_themes, "Theme defined in config");
     }
 
+    #[test]
+    fn nested_sessions_are_started_by_default() {
+        let config = Config::from_kdl("", None).unwrap();
+        assert_eq!(
+            config.options.on_nested_session.unwrap_or_default(),
+            OnNestedSession::Nest,
+            "Nested as before the option existed"
+        );
+    }
+
+    #[test]
+    fn missing_theme_falls_back_to_default_theme() {
+        let config_contents = r#"
+            theme "i_do_not_exist"
+            themes {
+                default {
+                    fg 248 248 242
+                    bg 40 42 54
+                    red 255 85 85
+                    green 80 250 123
+                    yellow 241 250 140
+                    blue 98 114 164
+                    magenta 255 121 198
+                    orange 255 184 108
+                    cyan 139 233 253
+                    black 0 0 0
+                    white 255 255 255
+                }
+            }
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        let default_palette = config
+            .themes
+            .get_theme("default")
+            .map(|theme| theme.palette);
+        assert!(default_palette.is_some(), "Default theme defined in config");
+        assert_eq!(
+            config.theme_config(&config.options),
+            default_palette,
+            "Default theme used in place of the missing one"
+        );
+    }
+
     #[test]
     fn can_define_multiple_themes_including_hex_themes_in_configfile() {
         let config_contents = r##"
@@ -639,6 +1080,7 @@ mod config_test {
             ui {
                 pane_frames {
                     rounded_corners true
+                    style "heavy"
                 }
             }
         "#;
@@ -646,6 +1088,7 @@ mod config_test {
         let expected_ui_config = UiConfig {
             pane_frames: FrameConfig {
                 rounded_corners: true,
+                style: FrameStyle::Heavy,
             },
         };
         assert_eq!(config.ui, expected_ui_config, "Ui config defined in config");
@@ -669,4 +1112,20 @@ mod config_test {
             "Env variables defined in config"
         );
     }
+
+    #[test]
+    fn empty_env_variable_in_config_file_unsets_inherited_variable() {
+        let config_contents = r#"
+            env {
+                ZELLIJ_TEST_UNSET_ME ""
+            }
+        "#;
+        std::env::set_var("ZELLIJ_TEST_UNSET_ME", "inherited");
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        config.env.set_vars();
+        assert!(
+            std::env::var("ZELLIJ_TEST_UNSET_ME").is_err(),
+            "Env variable with an empty value is unset"
+        );
+    }
 }
@@ -1,7 +1,8 @@
 use std::collections::{BTreeMap, HashMap};
 
 use super::actions::Action;
-use crate::data::{InputMode, Key, KeybindsVec};
+use super::config::ConfigError;
+use crate::data::{CharOrArrow, InputMode, Key, KeybindsVec};
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -51,9 +52,61 @@ impl Keybinds {
             InputMode::RenameTab => Action::TabNameInput(raw_bytes),
             InputMode::RenamePane => Action::PaneNameInput(raw_bytes),
             InputMode::EnterSearch => Action::SearchInput(raw_bytes),
+            // any key that is not bound closes the help
+            InputMode::Help => Action::SwitchToMode(InputMode::Normal),
             _ => Action::NoOp,
         }
     }
+    /// Makes `prefix_key` arm `Tmux` mode in every mode that had a key bound to enter it, in place
+    /// of those keys. Pressing the prefix again once armed sends it to the focused pane.
+    ///
+    /// Fails without changing any keybinding if `prefix_key` is already bound to something else
+    /// in one of those modes, or if there are no such modes.
+    pub fn bind_prefix_key(&mut self, prefix_key: Key) -> Result<(), ConfigError> {
+        let arm = vec![Action::SwitchToMode(InputMode::Tmux)];
+        let send_prefix = Keybinds::send_prefix_actions(&prefix_key);
+        let tmux_mode_is_entered = self.0.iter().any(|(mode, mode_keybinds)| {
+            *mode != InputMode::Tmux && mode_keybinds.values().any(|actions| *actions == arm)
+        });
+        if !tmux_mode_is_entered {
+            return Err(ConfigError::PrefixKeyWithoutTmuxMode(prefix_key));
+        }
+        for (mode, mode_keybinds) in self.0.iter() {
+            let arms_tmux_mode = mode_keybinds.values().any(|actions| *actions == arm);
+            let bound_as_prefix = match mode {
+                InputMode::Tmux => &send_prefix,
+                _ => &arm,
+            };
+            match mode_keybinds.get(&prefix_key) {
+                Some(actions) if actions != bound_as_prefix => {
+                    if *mode == InputMode::Tmux || arms_tmux_mode {
+                        return Err(ConfigError::PrefixKeyAlreadyBound(prefix_key, *mode));
+                    }
+                },
+                _ => {},
+            }
+        }
+        for (mode, mode_keybinds) in self.0.iter_mut() {
+            if *mode == InputMode::Tmux {
+                continue;
+            }
+            let arming_keys = mode_keybinds.len();
+            mode_keybinds.retain(|_key, actions| *actions != arm);
+            if mode_keybinds.len() < arming_keys {
+                mode_keybinds.insert(prefix_key, arm.clone());
+            }
+        }
+        self.get_input_mode_mut(&InputMode::Tmux)
+            .insert(prefix_key, send_prefix);
+        Ok(())
+    }
+    fn send_prefix_actions(prefix_key: &Key) -> Vec<Action> {
+        let mut send_prefix = vec![Action::SwitchToMode(InputMode::Normal)];
+        if let Some(bytes) = key_bytes(prefix_key) {
+            send_prefix.insert(0, Action::Write(bytes));
+        }
+        send_prefix
+    }
     pub fn to_keybinds_vec(&self) -> KeybindsVec {
         let mut ret = vec![];
         for (mode, mode_binds) in &self.0 {
@@ -67,6 +120,17 @@ impl Keybinds {
     }
 }
 
+/// The bytes a terminal sends for `key`, for the keys that can be told apart from others by them
+fn key_bytes(key: &Key) -> Option<Vec<u8>> {
+    match key {
+        Key::Char(c) => Some(c.to_string().into_bytes()),
+        Key::Ctrl(c) if c.is_ascii() => Some(vec![c.to_ascii_uppercase() as u8 & 0x1f]),
+        Key::Alt(CharOrArrow::Char(c)) => Some(format!("\u{1b}{}", c).into_bytes()),
+        Key::Esc => Some(vec![0x1b]),
+        _ => None,
+    }
+}
+
 // The unit test location.
 #[cfg(test)]
 #[path = "./unit/keybinds_test.rs"]
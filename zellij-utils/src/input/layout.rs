@@ -21,6 +21,7 @@ use std::str::FromStr;
 
 use super::plugins::{PluginTag, PluginsConfigError};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::vec::Vec;
 use std::{
@@ -150,6 +151,22 @@ impl Run {
             }
         }
     }
+    pub fn add_restart_on_exit(&mut self, restart_on_exit: Option<bool>) {
+        // overrides the restart policy of a Run::Command if it is Some
+        if let Some(restart_on_exit) = restart_on_exit {
+            if let Run::Command(run_command) = self {
+                run_command.restart_on_exit = restart_on_exit;
+            }
+        }
+    }
+    pub fn add_start_suspended(&mut self, start_suspended: Option<bool>) {
+        // overrides whether a Run::Command waits to be started if it is Some
+        if let Some(start_suspended) = start_suspended {
+            if let Run::Command(run_command) = self {
+                run_command.start_suspended = start_suspended;
+            }
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -319,6 +336,9 @@ impl Default for LayoutParts {
     }
 }
 
+/// The layouts shipped with zellij, loaded by name when no layout file of that name is found
+pub const BUILT_IN_LAYOUTS: &[&str] = &["default", "strider", "disable-status-bar", "compact"];
+
 impl Layout {
     pub fn stringified_from_path_or_default(
         layout_path: Option<&PathBuf>,
@@ -326,11 +346,16 @@ impl Layout {
     ) -> Result<(String, String), ConfigError> {
         // (path_to_layout as String, stringified_layout)
         match layout_path {
+            Some(layout_path) if layout_path.as_os_str() == "-" => Layout::stringified_from_stdin(),
             Some(layout_path) => {
                 // The way we determine where to look for the layout is similar to
                 // how a path would look for an executable.
                 // See the gh issue for more: https://github.com/zellij-org/zellij/issues/1412#issuecomment-1131559720
-                if layout_path.extension().is_some() || layout_path.components().count() > 1 {
+                // An existing file is always taken over a layout of the same name
+                if layout_path.extension().is_some()
+                    || layout_path.components().count() > 1
+                    || layout_path.is_file()
+                {
                     // We look localy!
                     Layout::stringified_from_path(layout_path)
                 } else {
@@ -347,11 +372,14 @@ impl Layout {
     pub fn from_path_or_default(
         layout_path: Option<&PathBuf>,
         layout_dir: Option<PathBuf>,
+        cwd: Option<PathBuf>,
+        layout_vars: HashMap<String, String>,
         config: Config,
     ) -> Result<(Layout, Config), ConfigError> {
         let (path_to_raw_layout, raw_layout) =
             Layout::stringified_from_path_or_default(layout_path, layout_dir)?;
-        let layout = Layout::from_kdl(&raw_layout, path_to_raw_layout, None)?;
+        let layout =
+            Layout::from_kdl_with_layout_vars(&raw_layout, path_to_raw_layout, cwd, layout_vars)?;
         let config = Config::from_kdl(&raw_layout, Some(config))?; // this merges the two config, with
         Ok((layout, config))
     }
@@ -389,6 +417,31 @@ impl Layout {
         layout_file.read_to_string(&mut kdl_layout)?;
         Ok((layout_path.as_os_str().to_string_lossy().into(), kdl_layout))
     }
+    pub fn stringified_from_stdin() -> Result<(String, String), ConfigError> {
+        // (path_to_layout as String, stringified_layout)
+        let mut kdl_layout = String::new();
+        std::io::stdin().read_to_string(&mut kdl_layout)?;
+        Layout::reattach_stdin_to_terminal();
+        Ok(("stdin".into(), kdl_layout))
+    }
+    /// Once a piped layout has been read, stdin is pointed back at the controlling terminal so
+    /// that the client can still read the keyboard from it
+    fn reattach_stdin_to_terminal() {
+        use nix::fcntl::{open, OFlag};
+        use nix::sys::stat::Mode;
+        use nix::unistd::{close, dup2, isatty};
+        if isatty(0).unwrap_or(false) {
+            return;
+        }
+        // without a controlling terminal (eg. a script running `zellij action`) there is
+        // nothing to read the keyboard from anyway
+        if let Ok(terminal) = open("/dev/tty", OFlag::O_RDWR, Mode::empty()) {
+            if let Err(e) = dup2(terminal, 0) {
+                log::error!("Failed to reattach stdin to the terminal: {}", e);
+            }
+            let _ = close(terminal);
+        }
+    }
     pub fn stringified_from_default_assets(path: &Path) -> Result<(String, String), ConfigError> {
         // (path_to_layout as String, stringified_layout)
         // TODO: ideally these should not be hard-coded
@@ -412,7 +465,13 @@ impl Layout {
                 Self::stringified_compact_from_assets()?,
             )),
             None | Some(_) => Err(ConfigError::IoPath(
-                std::io::Error::new(std::io::ErrorKind::Other, "The layout was not found"),
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!(
+                        "The layout was not found, the built-in layouts are: {}",
+                        BUILT_IN_LAYOUTS.join(", ")
+                    ),
+                ),
                 path.into(),
             )),
         }
@@ -1,11 +1,44 @@
 //! Handles cli and configuration options
 use crate::cli::Command;
 use crate::data::InputMode;
+use crate::shared::ColorDepth;
 use clap::{ArgEnum, Args};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
 
+/// How a bell rung by the program running in a pane is shown
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
+pub enum BellStyle {
+    /// Ring the bell of the terminal zellij runs in
+    #[serde(alias = "audible")]
+    Audible,
+    /// Flash the frame of the pane
+    #[serde(alias = "visual")]
+    Visual,
+    #[serde(alias = "none")]
+    None,
+}
+
+impl Default for BellStyle {
+    fn default() -> Self {
+        Self::Audible
+    }
+}
+
+impl FromStr for BellStyle {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "audible" => Ok(Self::Audible),
+            "visual" => Ok(Self::Visual),
+            "none" => Ok(Self::None),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
 pub enum OnForceClose {
     #[serde(alias = "quit")]
@@ -32,6 +65,100 @@ impl FromStr for OnForceClose {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
+pub enum OnNestedSession {
+    /// Exit with an error instead of starting the new session
+    #[serde(alias = "refuse")]
+    Refuse,
+    /// Start the new session inside the pane anyway
+    #[serde(alias = "nest")]
+    Nest,
+    /// Open a new tab in the session the command was run from instead
+    #[serde(alias = "new-tab")]
+    NewTab,
+}
+
+impl Default for OnNestedSession {
+    fn default() -> Self {
+        Self::Nest
+    }
+}
+
+impl FromStr for OnNestedSession {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "refuse" => Ok(Self::Refuse),
+            "nest" => Ok(Self::Nest),
+            "new-tab" => Ok(Self::NewTab),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
+pub enum ClientSizePolicy {
+    /// Size the session to fit the smallest attached client
+    #[serde(alias = "smallest")]
+    Smallest,
+    /// Size the session to the client that attached or resized last
+    ///
+    /// Output is clipped to the terminal of smaller clients, which only see its top left part.
+    #[serde(alias = "latest")]
+    Latest,
+}
+
+impl Default for ClientSizePolicy {
+    fn default() -> Self {
+        Self::Smallest
+    }
+}
+
+impl FromStr for ClientSizePolicy {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "smallest" => Ok(Self::Smallest),
+            "latest" => Ok(Self::Latest),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
+pub enum AutoLayout {
+    /// Leave the panes where they are split
+    #[serde(alias = "manual")]
+    Manual,
+    /// Arrange the panes in rows and columns of even size
+    #[serde(alias = "even_grid")]
+    EvenGrid,
+    /// Give the first pane the left half of the tab and stack the others on the right
+    #[serde(alias = "main_and_stack")]
+    MainAndStack,
+}
+
+impl Default for AutoLayout {
+    fn default() -> Self {
+        Self::Manual
+    }
+}
+
+impl FromStr for AutoLayout {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "manual" => Ok(Self::Manual),
+            "even_grid" => Ok(Self::EvenGrid),
+            "main_and_stack" => Ok(Self::MainAndStack),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Deserialize, Serialize, Args)]
 /// Options that can be set either through the config file,
 /// or cli flags - cli flags should take precedence over the config file
@@ -79,6 +206,7 @@ pub struct Options {
     /// Set behaviour on force close (quit or detach)
     #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
     pub on_force_close: Option<OnForceClose>,
+    /// Set the number of lines of scrollback each pane retains (defaults to 10000)
     #[clap(long, value_parser)]
     pub scroll_buffer_size: Option<usize>,
 
@@ -116,6 +244,190 @@ pub struct Options {
     #[clap(long, value_parser)]
     #[serde(default)]
     pub attach_to_session: Option<bool>,
+
+    /// Allow programs running in panes to set the clipboard through OSC52 (true or false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub clipboard_from_panes: Option<bool>,
+
+    /// Redraw the whole screen on every render instead of only the changed parts (true or false)
+    /// Useful for terminals that get out of sync with partial updates
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub full_redraw: Option<bool>,
+
+    /// Whether moving a tab past the start or end of the tab list wraps it around to the other end
+    /// (true or false, defaults to false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub move_tab_wraparound: Option<bool>,
+
+    /// Ask for confirmation before closing a pane that is running a program other than its shell
+    /// (true or false, defaults to true)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub confirm_pane_close: Option<bool>,
+
+    /// The percentage of the screen a pane grows or shrinks by on each resize step, between 1 and
+    /// 100 (defaults to 5)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub resize_percent: Option<usize>,
+
+    /// Keep a pane open showing its exit status when its shell exits, instead of closing it
+    /// (true or false, defaults to false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub hold_shell_on_exit: Option<bool>,
+
+    /// Ask the terminal to mark pasted text, so that a paste is sent to the focused pane as a whole
+    /// and never interpreted as keybindings (default: true)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub bracketed_paste: Option<bool>,
+
+    /// Show a status line with the session name, active tab and input mode in the terminal's title
+    /// (default: false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub status_in_title: Option<bool>,
+
+    /// Ask for confirmation before quitting while more than one tab is open
+    /// (true or false, defaults to false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub confirm_quit: Option<bool>,
+
+    /// Arguments to start the default shell with, separated by spaces and quoted like in a shell
+    /// (eg. "-l" for a login shell)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub default_shell_args: Option<String>,
+
+    /// Set behaviour when zellij is started from inside a zellij session (refuse, nest or new-tab)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub on_nested_session: Option<OnNestedSession>,
+
+    /// Paste the most recently copied text into a pane when middle-clicking it, unless the pane handles mouse events itself (true or false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub middle_click_paste: Option<bool>,
+
+    /// Size the session to the smallest attached client or to the one that attached or resized last (smallest or latest)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub client_size_policy: Option<ClientSizePolicy>,
+
+    /// The number of recently closed panes of each tab that can be reopened (defaults to 10)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub undo_close_depth: Option<usize>,
+
+    /// Arrange the panes of a tab automatically when panes are added or closed (manual, even_grid or main_and_stack)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub auto_layout: Option<AutoLayout>,
+
+    /// The number of bytes read from the pty of a pane at a time, between 1 and 16777216 (defaults
+    /// to 65536)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub pty_read_buffer_size: Option<usize>,
+
+    /// The number of bytes of output held back for a pane while it is scrolled back (defaults to 10485760)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub pending_output_limit: Option<usize>,
+
+    /// Focus the pane under the mouse pointer without clicking it (true or false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub focus_follows_mouse: Option<bool>,
+
+    /// How many times a layout pane with restart_on_exit restarts its command within
+    /// command_restart_window before it gives up (default: 5)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub max_command_restarts: Option<usize>,
+
+    /// The number of seconds in which the restarts of a command count towards max_command_restarts
+    /// (default: 60)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub command_restart_window: Option<usize>,
+
+    /// Detach or quit (see on_idle_timeout) after this many seconds without input from any client
+    /// (disabled by default)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub idle_timeout: Option<usize>,
+
+    /// What to do once the idle_timeout passes (detach or quit, defaults to detach)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub on_idle_timeout: Option<OnForceClose>,
+
+    /// How to show a bell rung in a pane (audible, visual or none, defaults to audible)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub bell_style: Option<BellStyle>,
+
+    /// Periodically save the scrollback of each pane to disk, to get it back when a session
+    /// with the same name is started after a crash (default: false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub persist_scrollback: Option<bool>,
+
+    /// The most disk space in megabytes the persisted scrollback of all sessions takes
+    /// (default: 50)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub persist_scrollback_max_size: Option<usize>,
+
+    /// Whether going to a tab past the last one opens a new tab rather than doing nothing
+    /// (default: false)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub go_to_tab_creates_tab: Option<bool>,
+
+    /// The number of lines the mouse wheel scrolls a pane by per tick
+    /// Default: 3
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub scroll_wheel_lines: Option<usize>,
+
+    /// The fewest columns a tiled pane may have: splits that would leave fewer are refused, and
+    /// while the terminal is too narrow to fit every pane a message is shown instead of the panes
+    /// Default: splits are refused under 5 columns, resizing the terminal shrinks panes down to 1
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub min_pane_width: Option<usize>,
+
+    /// The fewest rows a tiled pane may have: splits that would leave fewer are refused, and
+    /// while the terminal is too short to fit every pane a message is shown instead of the panes
+    /// Default: splits are refused under 5 rows, resizing the terminal shrinks panes down to 1
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub min_pane_height: Option<usize>,
+
+    /// A layout new tabs open with, either a path or the name of a layout in the layout_dir
+    /// (defaults to the default tab of the session's layout)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub new_tab_layout: Option<PathBuf>,
+
+    /// A key (eg. "Ctrl a") that arms tmux mode for the next keystroke instead of the keys bound to
+    /// enter it, pressing it twice sends it to the focused pane
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub prefix_key: Option<String>,
+
+    /// The colors the terminal can display (truecolor, ansi256 or ansi16), detected from $COLORTERM
+    /// and $TERM if not set
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub color_depth: Option<ColorDepth>,
 }
 
 #[derive(ArgEnum, Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -177,6 +489,42 @@ impl Options {
         let attach_to_session = other
             .attach_to_session
             .or_else(|| self.attach_to_session.clone());
+        let clipboard_from_panes = other.clipboard_from_panes.or(self.clipboard_from_panes);
+        let full_redraw = other.full_redraw.or(self.full_redraw);
+        let move_tab_wraparound = other.move_tab_wraparound.or(self.move_tab_wraparound);
+        let confirm_pane_close = other.confirm_pane_close.or(self.confirm_pane_close);
+        let resize_percent = other.resize_percent.or(self.resize_percent);
+        let hold_shell_on_exit = other.hold_shell_on_exit.or(self.hold_shell_on_exit);
+        let bracketed_paste = other.bracketed_paste.or(self.bracketed_paste);
+        let status_in_title = other.status_in_title.or(self.status_in_title);
+        let confirm_quit = other.confirm_quit.or(self.confirm_quit);
+        let default_shell_args = other
+            .default_shell_args
+            .or_else(|| self.default_shell_args.clone());
+        let on_nested_session = other.on_nested_session.or(self.on_nested_session);
+        let middle_click_paste = other.middle_click_paste.or(self.middle_click_paste);
+        let client_size_policy = other.client_size_policy.or(self.client_size_policy);
+        let undo_close_depth = other.undo_close_depth.or(self.undo_close_depth);
+        let auto_layout = other.auto_layout.or(self.auto_layout);
+        let pty_read_buffer_size = other.pty_read_buffer_size.or(self.pty_read_buffer_size);
+        let pending_output_limit = other.pending_output_limit.or(self.pending_output_limit);
+        let focus_follows_mouse = other.focus_follows_mouse.or(self.focus_follows_mouse);
+        let max_command_restarts = other.max_command_restarts.or(self.max_command_restarts);
+        let command_restart_window = other.command_restart_window.or(self.command_restart_window);
+        let idle_timeout = other.idle_timeout.or(self.idle_timeout);
+        let on_idle_timeout = other.on_idle_timeout.or(self.on_idle_timeout);
+        let bell_style = other.bell_style.or(self.bell_style);
+        let persist_scrollback = other.persist_scrollback.or(self.persist_scrollback);
+        let persist_scrollback_max_size = other
+            .persist_scrollback_max_size
+            .or(self.persist_scrollback_max_size);
+        let go_to_tab_creates_tab = other.go_to_tab_creates_tab.or(self.go_to_tab_creates_tab);
+        let scroll_wheel_lines = other.scroll_wheel_lines.or(self.scroll_wheel_lines);
+        let min_pane_width = other.min_pane_width.or(self.min_pane_width);
+        let min_pane_height = other.min_pane_height.or(self.min_pane_height);
+        let new_tab_layout = other.new_tab_layout.or_else(|| self.new_tab_layout.clone());
+        let prefix_key = other.prefix_key.or_else(|| self.prefix_key.clone());
+        let color_depth = other.color_depth.or(self.color_depth);
 
         Options {
             simplified_ui,
@@ -197,6 +545,38 @@ impl Options {
             scrollback_editor,
             session_name,
             attach_to_session,
+            clipboard_from_panes,
+            full_redraw,
+            move_tab_wraparound,
+            confirm_pane_close,
+            resize_percent,
+            hold_shell_on_exit,
+            bracketed_paste,
+            status_in_title,
+            confirm_quit,
+            default_shell_args,
+            on_nested_session,
+            middle_click_paste,
+            client_size_policy,
+            undo_close_depth,
+            auto_layout,
+            pty_read_buffer_size,
+            pending_output_limit,
+            focus_follows_mouse,
+            max_command_restarts,
+            command_restart_window,
+            idle_timeout,
+            on_idle_timeout,
+            bell_style,
+            persist_scrollback,
+            persist_scrollback_max_size,
+            go_to_tab_creates_tab,
+            scroll_wheel_lines,
+            min_pane_width,
+            min_pane_height,
+            new_tab_layout,
+            prefix_key,
+            color_depth,
         }
     }
 
@@ -238,6 +618,42 @@ impl Options {
         let attach_to_session = other
             .attach_to_session
             .or_else(|| self.attach_to_session.clone());
+        let clipboard_from_panes = other.clipboard_from_panes.or(self.clipboard_from_panes);
+        let full_redraw = other.full_redraw.or(self.full_redraw);
+        let move_tab_wraparound = other.move_tab_wraparound.or(self.move_tab_wraparound);
+        let confirm_pane_close = other.confirm_pane_close.or(self.confirm_pane_close);
+        let resize_percent = other.resize_percent.or(self.resize_percent);
+        let hold_shell_on_exit = other.hold_shell_on_exit.or(self.hold_shell_on_exit);
+        let bracketed_paste = other.bracketed_paste.or(self.bracketed_paste);
+        let status_in_title = other.status_in_title.or(self.status_in_title);
+        let confirm_quit = other.confirm_quit.or(self.confirm_quit);
+        let default_shell_args = other
+            .default_shell_args
+            .or_else(|| self.default_shell_args.clone());
+        let on_nested_session = other.on_nested_session.or(self.on_nested_session);
+        let middle_click_paste = other.middle_click_paste.or(self.middle_click_paste);
+        let client_size_policy = other.client_size_policy.or(self.client_size_policy);
+        let undo_close_depth = other.undo_close_depth.or(self.undo_close_depth);
+        let auto_layout = other.auto_layout.or(self.auto_layout);
+        let pty_read_buffer_size = other.pty_read_buffer_size.or(self.pty_read_buffer_size);
+        let pending_output_limit = other.pending_output_limit.or(self.pending_output_limit);
+        let focus_follows_mouse = other.focus_follows_mouse.or(self.focus_follows_mouse);
+        let max_command_restarts = other.max_command_restarts.or(self.max_command_restarts);
+        let command_restart_window = other.command_restart_window.or(self.command_restart_window);
+        let idle_timeout = other.idle_timeout.or(self.idle_timeout);
+        let on_idle_timeout = other.on_idle_timeout.or(self.on_idle_timeout);
+        let bell_style = other.bell_style.or(self.bell_style);
+        let persist_scrollback = other.persist_scrollback.or(self.persist_scrollback);
+        let persist_scrollback_max_size = other
+            .persist_scrollback_max_size
+            .or(self.persist_scrollback_max_size);
+        let go_to_tab_creates_tab = other.go_to_tab_creates_tab.or(self.go_to_tab_creates_tab);
+        let scroll_wheel_lines = other.scroll_wheel_lines.or(self.scroll_wheel_lines);
+        let min_pane_width = other.min_pane_width.or(self.min_pane_width);
+        let min_pane_height = other.min_pane_height.or(self.min_pane_height);
+        let new_tab_layout = other.new_tab_layout.or_else(|| self.new_tab_layout.clone());
+        let prefix_key = other.prefix_key.or_else(|| self.prefix_key.clone());
+        let color_depth = other.color_depth.or(self.color_depth);
 
         Options {
             simplified_ui,
@@ -258,6 +674,38 @@ impl Options {
             scrollback_editor,
             session_name,
             attach_to_session,
+            clipboard_from_panes,
+            full_redraw,
+            move_tab_wraparound,
+            confirm_pane_close,
+            resize_percent,
+            hold_shell_on_exit,
+            bracketed_paste,
+            status_in_title,
+            confirm_quit,
+            default_shell_args,
+            on_nested_session,
+            middle_click_paste,
+            client_size_policy,
+            undo_close_depth,
+            auto_layout,
+            pty_read_buffer_size,
+            pending_output_limit,
+            focus_follows_mouse,
+            max_command_restarts,
+            command_restart_window,
+            idle_timeout,
+            on_idle_timeout,
+            bell_style,
+            persist_scrollback,
+            persist_scrollback_max_size,
+            go_to_tab_creates_tab,
+            scroll_wheel_lines,
+            min_pane_width,
+            min_pane_height,
+            new_tab_layout,
+            prefix_key,
+            color_depth,
         }
     }
 
@@ -314,6 +762,38 @@ impl From<CliOptions> for Options {
             scrollback_editor: opts.scrollback_editor,
             session_name: opts.session_name,
             attach_to_session: opts.attach_to_session,
+            clipboard_from_panes: opts.clipboard_from_panes,
+            full_redraw: opts.full_redraw,
+            move_tab_wraparound: opts.move_tab_wraparound,
+            confirm_pane_close: opts.confirm_pane_close,
+            resize_percent: opts.resize_percent,
+            hold_shell_on_exit: opts.hold_shell_on_exit,
+            bracketed_paste: opts.bracketed_paste,
+            status_in_title: opts.status_in_title,
+            confirm_quit: opts.confirm_quit,
+            default_shell_args: opts.default_shell_args,
+            on_nested_session: opts.on_nested_session,
+            middle_click_paste: opts.middle_click_paste,
+            client_size_policy: opts.client_size_policy,
+            undo_close_depth: opts.undo_close_depth,
+            auto_layout: opts.auto_layout,
+            pty_read_buffer_size: opts.pty_read_buffer_size,
+            pending_output_limit: opts.pending_output_limit,
+            focus_follows_mouse: opts.focus_follows_mouse,
+            max_command_restarts: opts.max_command_restarts,
+            command_restart_window: opts.command_restart_window,
+            idle_timeout: opts.idle_timeout,
+            on_idle_timeout: opts.on_idle_timeout,
+            bell_style: opts.bell_style,
+            persist_scrollback: opts.persist_scrollback,
+            persist_scrollback_max_size: opts.persist_scrollback_max_size,
+            go_to_tab_creates_tab: opts.go_to_tab_creates_tab,
+            scroll_wheel_lines: opts.scroll_wheel_lines,
+            min_pane_width: opts.min_pane_width,
+            min_pane_height: opts.min_pane_height,
+            new_tab_layout: opts.new_tab_layout,
+            prefix_key: opts.prefix_key,
+            color_depth: opts.color_depth,
             ..Default::default()
         }
     }
@@ -7,7 +7,7 @@ use std::{
     fmt,
 };
 
-use crate::data::Palette;
+use crate::data::{FrameStyle, Palette};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct UiConfig {
@@ -25,12 +25,14 @@ impl UiConfig {
 #[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct FrameConfig {
     pub rounded_corners: bool,
+    pub style: FrameStyle,
 }
 
 impl FrameConfig {
     pub fn merge(&self, other: FrameConfig) -> Self {
         let mut merged = self.clone();
         merged.rounded_corners = other.rounded_corners;
+        merged.style = other.style;
         merged
     }
 }
@@ -1,7 +1,7 @@
 use super::super::actions::*;
 use super::super::keybinds::*;
 use crate::data::{self, CharOrArrow, Key};
-use crate::input::config::Config;
+use crate::input::config::{Config, ConfigError};
 use insta::assert_snapshot;
 use strum::IntoEnumIterator;
 
@@ -516,6 +516,23 @@ fn keybindings_unbinds_happen_after_binds() {
     assert_eq!(z_in_pane_mode, None, "Key was ultimately unbound");
 }
 
+#[test]
+fn unbound_keys_close_the_help() {
+    let config = Config::from_kdl("keybinds {}", None).unwrap();
+    let actions = config
+        .keybinds
+        .get_actions_for_key_in_mode_or_default_action(
+            &InputMode::Help,
+            &Key::Char('x'),
+            vec![b'x'],
+        );
+    assert_eq!(
+        actions,
+        vec![Action::SwitchToMode(InputMode::Normal)],
+        "Unbound key switches back to the normal mode"
+    );
+}
+
 #[test]
 fn error_received_on_unknown_input_mode() {
     let config_contents = r#"
@@ -542,3 +559,215 @@ fn error_received_on_unknown_key_instruction() {
     let config_error = Config::from_kdl(config_contents, None).unwrap_err();
     assert_snapshot!(format!("{:?}", config_error));
 }
+
+#[test]
+fn error_received_on_unknown_input_mode_in_shared_among() {
+    let config_contents = r#"
+        keybinds {
+            shared_among "normal" "i_do_not_exist" {
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    match config_error {
+        ConfigError::KdlError(kdl_error) => {
+            assert_eq!(
+                kdl_error.error_message, "Invalid mode: 'i_do_not_exist'",
+                "Error names the unknown mode"
+            );
+            let offset = kdl_error.offset.unwrap();
+            let len = kdl_error.len.unwrap();
+            assert_eq!(
+                config_contents[offset..offset + len].trim(),
+                "\"i_do_not_exist\"",
+                "Error points at the unknown mode"
+            );
+        },
+        e => panic!("Unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn error_received_on_unknown_signal() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Alt k" { SignalFocusedPane "SIGNOPE"; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    match config_error {
+        ConfigError::KdlError(kdl_error) => assert_eq!(
+            kdl_error.error_message, "Invalid signal: 'SIGNOPE'",
+            "Error names the unknown signal"
+        ),
+        e => panic!("Unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn can_bind_a_signal_to_send_to_the_focused_pane() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Alt k" { SignalFocusedPane "SIGTERM"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let alt_k_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Alt(CharOrArrow::Char('k')));
+    assert_eq!(
+        alt_k_action,
+        Some(&vec![Action::SignalFocusedPane("SIGTERM".into())]),
+        "SignalFocusedPane bound with the name of its signal"
+    );
+}
+
+#[test]
+fn unknown_signal_is_rejected_from_the_cli() {
+    let actions = Action::actions_from_cli(crate::cli::CliAction::SignalFocusedPane {
+        signal: "SIGNOPE".into(),
+    });
+    assert_eq!(
+        actions,
+        Err("Unknown signal: 'SIGNOPE'".to_string()),
+        "CLI action with an unknown signal rejected"
+    );
+}
+
+#[test]
+fn can_bind_new_tab_with_layout() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "l" { NewTabWithLayout "compact"; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let l_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('l'));
+    assert_eq!(
+        l_tab_mode_action,
+        Some(&vec![
+            Action::NewTabWithLayout("compact".into()),
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "NewTabWithLayout bound with the name of its layout"
+    );
+}
+
+#[test]
+fn prefix_key_replaces_the_keys_that_enter_tmux_mode() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl b" { SwitchToMode "Tmux"; }
+                bind "Ctrl p" { SwitchToMode "Pane"; }
+            }
+            tmux {
+                bind "c" { NewTab; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let mut config = Config::from_kdl(config_contents, None).unwrap();
+    config.keybinds.bind_prefix_key(Key::Ctrl('a')).unwrap();
+    let keybinds = &config.keybinds;
+    assert_eq!(
+        keybinds.get_actions_for_key_in_mode(&InputMode::Normal, &Key::Ctrl('a')),
+        Some(&vec![Action::SwitchToMode(InputMode::Tmux)]),
+        "prefix arms tmux mode"
+    );
+    assert_eq!(
+        keybinds.get_actions_for_key_in_mode(&InputMode::Normal, &Key::Ctrl('b')),
+        None,
+        "previous key to enter tmux mode unbound"
+    );
+    assert!(
+        keybinds
+            .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Ctrl('p'))
+            .is_some(),
+        "other keybindings left as they were"
+    );
+    assert_eq!(
+        keybinds.get_actions_for_key_in_mode(&InputMode::Tmux, &Key::Ctrl('a')),
+        Some(&vec![
+            Action::Write(vec![1]),
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "prefix pressed twice sent to the pane"
+    );
+}
+
+#[test]
+fn prefix_key_already_bound_to_something_else_is_rejected() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl b" { SwitchToMode "Tmux"; }
+                bind "Ctrl a" { SwitchToMode "Pane"; }
+            }
+        }
+    "#;
+    let mut config = Config::from_kdl(config_contents, None).unwrap();
+    let keybinds_before = config.keybinds.clone();
+    assert!(
+        matches!(
+            config.keybinds.bind_prefix_key(Key::Ctrl('a')),
+            Err(ConfigError::PrefixKeyAlreadyBound(
+                Key::Ctrl('a'),
+                InputMode::Normal
+            ))
+        ),
+        "prefix key rejected"
+    );
+    assert_eq!(
+        config.keybinds, keybinds_before,
+        "keybindings left as they were"
+    );
+}
+
+#[test]
+fn prefix_key_is_rejected_when_no_mode_enters_tmux_mode() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl p" { SwitchToMode "Pane"; }
+            }
+            tmux {
+                bind "c" { NewTab; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let mut config = Config::from_kdl(config_contents, None).unwrap();
+    let keybinds_before = config.keybinds.clone();
+    assert!(
+        matches!(
+            config.keybinds.bind_prefix_key(Key::Ctrl('a')),
+            Err(ConfigError::PrefixKeyWithoutTmuxMode(Key::Ctrl('a')))
+        ),
+        "prefix key rejected"
+    );
+    assert_eq!(
+        config.keybinds, keybinds_before,
+        "keybindings left as they were"
+    );
+}
+
+#[test]
+fn unbound_keys_in_tmux_mode_do_nothing_without_a_prefix_key() {
+    let config = Config::from_kdl("keybinds {}", None).unwrap();
+    let actions = config
+        .keybinds
+        .get_actions_for_key_in_mode_or_default_action(
+            &InputMode::Tmux,
+            &Key::Char('x'),
+            vec![b'x'],
+        );
+    assert_eq!(actions, vec![Action::NoOp], "Unbound key does nothing");
+}
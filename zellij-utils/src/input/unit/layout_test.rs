@@ -1,5 +1,7 @@
 use super::super::layout::*;
 use insta::assert_snapshot;
+use std::fs;
+use tempfile::tempdir;
 
 #[test]
 fn empty_layout() {
@@ -188,6 +190,34 @@ fn layout_with_panes_in_different_mixed_split_sizes() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn error_on_child_pane_sizes_over_100_percent() {
+    let kdl_layout = r#"
+        layout {
+            pane split_direction="vertical" {
+                pane size="60%"
+                pane size="50%"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None);
+    assert!(layout.is_err(), "child pane sizes exceed 100%");
+}
+
+#[test]
+fn error_on_tab_pane_sizes_over_100_percent() {
+    let kdl_layout = r#"
+        layout {
+            tab {
+                pane size="70%"
+                pane size="40%"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None);
+    assert!(layout.is_err(), "tab pane sizes exceed 100%");
+}
+
 #[test]
 fn layout_with_command_panes() {
     let kdl_layout = r#"
@@ -268,6 +298,93 @@ fn layout_with_command_panes_and_cwd_and_args() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_vars_are_resolved_in_cwd_command_and_args() {
+    let kdl_layout = r#"
+        layout {
+            pane command="${LAYOUT_TEST_EDITOR}" cwd="${LAYOUT_TEST_PROJECT_DIR}/src" {
+                args "--config" "${LAYOUT_TEST_PROJECT_DIR}/editor.toml"
+            }
+        }
+    "#;
+    let layout_vars = std::collections::HashMap::from([
+        ("LAYOUT_TEST_EDITOR".to_owned(), "vim".to_owned()),
+        (
+            "LAYOUT_TEST_PROJECT_DIR".to_owned(),
+            "/path/to/project".to_owned(),
+        ),
+    ]);
+    let layout =
+        Layout::from_kdl_with_layout_vars(kdl_layout, "layout_file_name".into(), None, layout_vars)
+            .unwrap();
+    let expected_layout = Layout {
+        template: Some(PaneLayout {
+            children: vec![PaneLayout {
+                run: Some(Run::Command(RunCommand {
+                    command: PathBuf::from("vim"),
+                    cwd: Some(PathBuf::from("/path/to/project/src")),
+                    args: vec![
+                        String::from("--config"),
+                        String::from("/path/to/project/editor.toml"),
+                    ],
+                    hold_on_close: true,
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn escaped_layout_vars_are_left_alone() {
+    let kdl_layout = r#"
+        layout {
+            pane command="bash" {
+                args "-c" "echo $${LAYOUT_TEST_UNSET_VAR}"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None).unwrap();
+    let expected_layout = Layout {
+        template: Some(PaneLayout {
+            children: vec![PaneLayout {
+                run: Some(Run::Command(RunCommand {
+                    command: PathBuf::from("bash"),
+                    args: vec![
+                        String::from("-c"),
+                        String::from("echo ${LAYOUT_TEST_UNSET_VAR}"),
+                    ],
+                    hold_on_close: true,
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn error_on_unresolved_layout_var() {
+    let kdl_layout = r#"
+        layout {
+            pane cwd="${LAYOUT_TEST_UNSET_VAR}"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None);
+    let error = format!("{:?}", layout.unwrap_err());
+    assert!(
+        error.contains("LAYOUT_TEST_UNSET_VAR"),
+        "error names the missing variable"
+    );
+}
+
 #[test]
 fn layout_with_command_panes_and_close_on_exit() {
     let kdl_layout = r#"
@@ -281,6 +398,93 @@ fn layout_with_command_panes_and_close_on_exit() {
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn layout_with_command_panes_and_restart_on_exit() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="tail" {
+                command "tail"
+                restart_on_exit true
+            }
+            pane command="htop" restart_on_exit=true
+            tail
+            tail {
+                restart_on_exit false
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None).unwrap();
+    let restart_on_exit: Vec<bool> = layout
+        .template
+        .unwrap()
+        .children
+        .iter()
+        .map(|pane| match &pane.run {
+            Some(Run::Command(run_command)) => run_command.restart_on_exit,
+            _ => panic!("expected a command pane"),
+        })
+        .collect();
+    assert_eq!(restart_on_exit, vec![true, true, false]);
+}
+
+#[test]
+fn error_on_restart_on_exit_without_command() {
+    let kdl_layout = r#"
+        layout {
+            pane restart_on_exit=true
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None);
+    assert!(
+        layout.is_err(),
+        "restart_on_exit without a command is an error"
+    );
+}
+
+#[test]
+fn layout_with_start_suspended_command_panes() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="tail" {
+                command "tail"
+                start_suspended true
+            }
+            pane command="htop" start_suspended=true
+            pane command="htop"
+            tail
+            tail {
+                start_suspended false
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None).unwrap();
+    let start_suspended: Vec<bool> = layout
+        .template
+        .unwrap()
+        .children
+        .iter()
+        .map(|pane| match &pane.run {
+            Some(Run::Command(run_command)) => run_command.start_suspended,
+            _ => panic!("expected a command pane"),
+        })
+        .collect();
+    assert_eq!(start_suspended, vec![true, false, true, false]);
+}
+
+#[test]
+fn error_on_start_suspended_without_command() {
+    let kdl_layout = r#"
+        layout {
+            pane start_suspended=true
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None);
+    assert!(
+        layout.is_err(),
+        "start_suspended without a command is an error"
+    );
+}
+
 #[test]
 fn layout_with_plugin_panes() {
     let kdl_layout = r#"
@@ -1555,3 +1759,115 @@ fn global_cwd_and_tab_cwd_prepended_to_panes_with_and_without_cwd_in_tab_templat
     let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None).unwrap();
     assert_snapshot!(format!("{:#?}", layout));
 }
+
+#[test]
+fn layout_serialized_to_kdl_is_loaded_back_unchanged() {
+    let kdl_layout = r#"
+        layout {
+            tab name="editing" focus=true split_direction="vertical" {
+                pane size="30%" command="htop" cwd="/tmp" close_on_exit=true {
+                    args "-d" "10"
+                }
+                pane {
+                    pane size=1 borderless=true {
+                        plugin location="zellij:tab-bar"
+                    }
+                    pane edit="/tmp/foo \"bar\"" line_number=12
+                    pane name="logs" cwd="/var/log" focus=true
+                }
+            }
+            tab name="second tab"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None).unwrap();
+    let serialized_layout = layout.to_kdl();
+    let reloaded_layout =
+        Layout::from_kdl(&serialized_layout, "layout_file_name".into(), None).unwrap();
+    assert_eq!(layout, reloaded_layout, "{}", serialized_layout);
+}
+
+#[test]
+fn built_in_layouts_are_found_by_name() {
+    for name in BUILT_IN_LAYOUTS {
+        let (path_to_raw_layout, raw_layout) =
+            Layout::stringified_from_path_or_default(Some(&PathBuf::from(name)), None)
+                .unwrap_or_else(|e| panic!("built-in layout {} not found: {}", name, e));
+        let layout = Layout::from_kdl(&raw_layout, path_to_raw_layout, None);
+        assert!(layout.is_ok(), "built-in layout {} parses", name);
+    }
+}
+
+#[test]
+fn unknown_layout_name_lists_the_built_in_layouts() {
+    let error =
+        Layout::stringified_from_path_or_default(Some(&PathBuf::from("i_do_not_exist")), None)
+            .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("the built-in layouts are: default, strider, disable-status-bar, compact"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn layout_file_is_preferred_over_built_in_layout_of_the_same_name() {
+    let layout_dir = tempdir().unwrap();
+    let raw_layout = "layout {\n    pane name=\"mine\"\n}\n";
+    fs::write(layout_dir.path().join("compact.kdl"), raw_layout).unwrap();
+    fs::write(layout_dir.path().join("strider"), raw_layout).unwrap();
+
+    let (_, from_layout_dir) = Layout::stringified_from_path_or_default(
+        Some(&PathBuf::from("compact")),
+        Some(layout_dir.path().to_path_buf()),
+    )
+    .unwrap();
+    assert_eq!(from_layout_dir, raw_layout, "layout in the layout dir wins");
+
+    let (_, from_file) =
+        Layout::stringified_from_path_or_default(Some(&layout_dir.path().join("strider")), None)
+            .unwrap();
+    assert_eq!(from_file, raw_layout, "explicit file path wins");
+}
+
+#[test]
+fn layout_parse_error_reports_its_line_and_column() {
+    let kdl_layout = "layout {\n    pane size=0\n}\n";
+    match Layout::from_kdl(kdl_layout, "layout_file_name".into(), None) {
+        Err(ConfigError::KdlError(kdl_error)) => {
+            let (line, _column) = kdl_error
+                .line_and_column()
+                .expect("error has a position in the layout");
+            assert!(
+                line <= 2,
+                "error points at or before the pane, got line {}",
+                line
+            );
+            assert!(kdl_error
+                .to_string()
+                .starts_with("Failed to parse Zellij configuration at "));
+        },
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn layout_with_edit_pane_and_line_number() {
+    let kdl_layout = r#"
+        layout {
+            pane edit="/tmp/foo" line_number=12
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None).unwrap();
+    let tab_layout = layout.new_tab();
+    assert_eq!(
+        tab_layout.children[0].run,
+        Some(Run::EditFile(PathBuf::from("/tmp/foo"), Some(12))),
+        "line number is parsed"
+    );
+    assert!(
+        layout.to_kdl().contains("line_number=12"),
+        "line number is serialized"
+    );
+}
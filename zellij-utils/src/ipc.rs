@@ -1,6 +1,7 @@
 //! IPC stuff for starting to split things into a client and server model.
 use crate::{
     cli::CliArgs,
+    consts::BUILD_VERSION,
     data::{ClientId, InputMode, Style},
     errors::{get_current_ctx, ErrorContext},
     input::keybinds::Keybinds,
@@ -42,6 +43,8 @@ pub struct ClientAttributes {
     pub size: Size,
     pub style: Style,
     pub keybinds: Keybinds,
+    /// A read-only client sees the session but its input is dropped by the server
+    pub read_only: bool,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -108,7 +111,8 @@ pub enum ServerToClientMsg {
     Exit(ExitReason),
     SwitchToMode(InputMode),
     Connected,
-    ActiveClients(Vec<ClientId>),
+    ActiveClients(Vec<ClientId>, usize), // the clients connected to the session and its tab count
+    Log(Vec<String>), // lines the client shows to the user, eg. the output of a cli action
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -166,6 +170,19 @@ impl<T: Serialize> IpcSenderWithContext<T> {
         }
     }
 
+    /// Sends the [`BUILD_VERSION`] of this build on its own. Clients send it first thing on a new
+    /// connection and the server replies with its own - unlike the messages that follow, it is
+    /// laid out the same way by every build, so that builds that can't understand each other's
+    /// messages can tell before they try to.
+    pub fn send_version(&mut self) -> Result<(), &'static str> {
+        if rmp_serde::encode::write(&mut self.sender, BUILD_VERSION.as_str()).is_err() {
+            Err("Failed to send version")
+        } else {
+            let _ = self.sender.flush();
+            Ok(())
+        }
+    }
+
     /// Returns an [`IpcReceiverWithContext`] with the same socket as this sender.
     pub fn get_receiver<F>(&self) -> IpcReceiverWithContext<F>
     where
@@ -207,6 +224,18 @@ where
         }
     }
 
+    /// Receives the version sent with [`IpcSenderWithContext::send_version`], `None` if the other
+    /// side hung up or sent something else.
+    pub fn recv_version(&mut self) -> Option<String> {
+        match rmp_serde::decode::from_read(&mut self.receiver) {
+            Ok(version) => Some(version),
+            Err(e) => {
+                warn!("Error in IpcReceiver.recv_version(): {:?}", e);
+                None
+            },
+        }
+    }
+
     /// Returns an [`IpcSenderWithContext`] with the same socket as this receiver.
     pub fn get_sender<F: Serialize>(&self) -> IpcSenderWithContext<F> {
         let sock_fd = self.receiver.get_ref().as_raw_fd();
@@ -215,3 +244,7 @@ where
         IpcSenderWithContext::new(socket)
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/ipc_test.rs"]
+mod ipc_test;
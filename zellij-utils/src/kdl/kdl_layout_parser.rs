@@ -25,6 +25,7 @@ use url::Url;
 
 pub struct KdlLayoutParser<'a> {
     global_cwd: Option<PathBuf>,
+    layout_vars: HashMap<String, String>,
     raw_layout: &'a str,
     tab_templates: HashMap<String, (PaneLayout, KdlNode)>,
     pane_templates: HashMap<String, (PaneLayout, KdlNode)>,
@@ -32,13 +33,18 @@ pub struct KdlLayoutParser<'a> {
 }
 
 impl<'a> KdlLayoutParser<'a> {
-    pub fn new(raw_layout: &'a str, global_cwd: Option<PathBuf>) -> Self {
+    pub fn new(
+        raw_layout: &'a str,
+        global_cwd: Option<PathBuf>,
+        layout_vars: HashMap<String, String>,
+    ) -> Self {
         KdlLayoutParser {
             raw_layout,
             tab_templates: HashMap::new(),
             pane_templates: HashMap::new(),
             default_tab_template: None,
             global_cwd,
+            layout_vars,
         }
     }
     fn is_a_reserved_word(&self, word: &str) -> bool {
@@ -49,11 +55,14 @@ impl<'a> KdlLayoutParser<'a> {
             || word == "default_tab_template"
             || word == "command"
             || word == "edit"
+            || word == "line_number"
             || word == "plugin"
             || word == "children"
             || word == "tab"
             || word == "args"
             || word == "close_on_exit"
+            || word == "restart_on_exit"
+            || word == "start_suspended"
             || word == "borderless"
             || word == "focus"
             || word == "name"
@@ -69,9 +78,12 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "plugin"
             || property_name == "command"
             || property_name == "edit"
+            || property_name == "line_number"
             || property_name == "cwd"
             || property_name == "args"
             || property_name == "close_on_exit"
+            || property_name == "restart_on_exit"
+            || property_name == "start_suspended"
             || property_name == "split_direction"
             || property_name == "pane"
             || property_name == "children"
@@ -198,6 +210,16 @@ impl<'a> KdlLayoutParser<'a> {
             location,
         })))
     }
+    fn parse_line_number(&self, pane_node: &KdlNode) -> Result<Option<usize>, ConfigError> {
+        match kdl_get_int_property_or_child_value!(pane_node, "line_number") {
+            Some(line_number) if line_number > 0 => Ok(Some(line_number as usize)),
+            Some(_) => Err(kdl_parsing_error!(
+                format!("line_number should be greater than 0"),
+                pane_node
+            )),
+            None => Ok(None),
+        }
+    }
     fn parse_args(&self, pane_node: &KdlNode) -> Result<Option<Vec<String>>, ConfigError> {
         match kdl_get_child!(pane_node, "args") {
             Some(kdl_args) => {
@@ -207,8 +229,8 @@ impl<'a> KdlLayoutParser<'a> {
                 Ok(Some(
                     kdl_string_arguments!(kdl_args)
                         .iter()
-                        .map(|s| String::from(*s))
-                        .collect(),
+                        .map(|s| self.resolve_layout_vars(s, kdl_args))
+                        .collect::<Result<Vec<String>, ConfigError>>()?,
                 ))
             },
             None => Ok(None),
@@ -223,10 +245,46 @@ impl<'a> KdlLayoutParser<'a> {
         })
     }
     fn parse_cwd(&self, kdl_node: &KdlNode) -> Result<Option<PathBuf>, ConfigError> {
-        Ok(
-            kdl_get_string_property_or_child_value_with_error!(kdl_node, "cwd")
-                .map(|cwd| PathBuf::from(cwd)),
-        )
+        kdl_get_string_property_or_child_value_with_error!(kdl_node, "cwd")
+            .map(|cwd| self.resolve_layout_vars(cwd, kdl_node).map(PathBuf::from))
+            .transpose()
+    }
+    /// Replaces every `${NAME}` in `value` with the layout variable of that name, or the
+    /// environment variable of that name if no such layout variable was given. `$${NAME}` is
+    /// left as a literal `${NAME}`, eg. for the shell running a command to expand.
+    fn resolve_layout_vars(&self, value: &str, kdl_node: &KdlNode) -> Result<String, ConfigError> {
+        let mut resolved = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+            if rest[..start].ends_with('$') {
+                resolved.push_str(&rest[..start - 1]);
+                resolved.push_str(&rest[start..=end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+            let name = &rest[start + 2..end];
+            let var = match self.layout_vars.get(name) {
+                Some(var) => var.clone(),
+                None => std::env::var(name).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!(
+                            "Layout variable '{}' is not set, set it with --layout-var {}=<value> or in the environment",
+                            name, name
+                        ),
+                        kdl_node
+                    )
+                })?,
+            };
+            resolved.push_str(&rest[..start]);
+            resolved.push_str(&var);
+            rest = &rest[end + 1..];
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
     }
     fn parse_pane_command(
         &self,
@@ -234,22 +292,32 @@ impl<'a> KdlLayoutParser<'a> {
         is_template: bool,
     ) -> Result<Option<Run>, ConfigError> {
         let command = kdl_get_string_property_or_child_value_with_error!(pane_node, "command")
-            .map(|c| PathBuf::from(c));
+            .map(|c| self.resolve_layout_vars(c, pane_node).map(PathBuf::from))
+            .transpose()?;
         let edit = kdl_get_string_property_or_child_value_with_error!(pane_node, "edit")
             .map(|c| PathBuf::from(c));
+        let line_number = self.parse_line_number(pane_node)?;
         let cwd = self.parse_cwd(pane_node)?;
         let args = self.parse_args(pane_node)?;
         let close_on_exit =
             kdl_get_bool_property_or_child_value_with_error!(pane_node, "close_on_exit");
+        let restart_on_exit =
+            kdl_get_bool_property_or_child_value_with_error!(pane_node, "restart_on_exit");
+        let start_suspended =
+            kdl_get_bool_property_or_child_value_with_error!(pane_node, "start_suspended");
         if !is_template {
             self.assert_no_bare_attributes_in_pane_node(
                 &command,
                 &args,
                 &close_on_exit,
+                &restart_on_exit,
+                &start_suspended,
                 pane_node,
             )?;
         }
         let hold_on_close = close_on_exit.map(|c| !c).unwrap_or(true);
+        let restart_on_exit = restart_on_exit.unwrap_or(false);
+        let start_suspended = start_suspended.unwrap_or(false);
         match (command, edit, cwd) {
             (None, None, Some(cwd)) => Ok(Some(Run::Cwd(cwd))),
             (Some(command), None, cwd) => Ok(Some(Run::Command(RunCommand {
@@ -257,9 +325,11 @@ impl<'a> KdlLayoutParser<'a> {
                 args: args.unwrap_or_else(|| vec![]),
                 cwd,
                 hold_on_close,
+                restart_on_exit,
+                start_suspended,
             }))),
-            (None, Some(edit), Some(cwd)) => Ok(Some(Run::EditFile(cwd.join(edit), None))),
-            (None, Some(edit), None) => Ok(Some(Run::EditFile(edit, None))),
+            (None, Some(edit), Some(cwd)) => Ok(Some(Run::EditFile(cwd.join(edit), line_number))),
+            (None, Some(edit), None) => Ok(Some(Run::EditFile(edit, line_number))),
             (Some(_command), Some(_edit), _) => Err(ConfigError::new_layout_kdl_error(
                 "cannot have both a command and an edit instruction for the same pane".into(),
                 pane_node.span().offset(),
@@ -327,6 +397,7 @@ impl<'a> KdlLayoutParser<'a> {
             Some(children) => self.parse_child_pane_nodes_for_pane(&children)?,
             None => (None, vec![]),
         };
+        self.assert_valid_split_sizes(&children, kdl_node)?;
         self.assert_no_mixed_children_and_properties(kdl_node)?;
         Ok(PaneLayout {
             borderless: borderless.unwrap_or_default(),
@@ -380,6 +451,10 @@ impl<'a> KdlLayoutParser<'a> {
         let args = self.parse_args(kdl_node)?;
         let close_on_exit =
             kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
+        let restart_on_exit =
+            kdl_get_bool_property_or_child_value_with_error!(kdl_node, "restart_on_exit");
+        let start_suspended =
+            kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
         let split_size = self.parse_split_size(kdl_node)?;
         let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
         self.assert_no_bare_attributes_in_pane_node_with_template(
@@ -387,6 +462,8 @@ impl<'a> KdlLayoutParser<'a> {
             &pane_template.run,
             &args,
             &close_on_exit,
+            &restart_on_exit,
+            &start_suspended,
             kdl_node,
         )?;
         self.insert_children_to_pane_template(
@@ -400,6 +477,8 @@ impl<'a> KdlLayoutParser<'a> {
             // can have bare args without a command
             pane_template_run_command.add_args(args);
             pane_template_run_command.add_close_on_exit(close_on_exit);
+            pane_template_run_command.add_restart_on_exit(restart_on_exit);
+            pane_template_run_command.add_start_suspended(start_suspended);
         };
         if let Some(borderless) = borderless {
             pane_template.borderless = borderless;
@@ -483,14 +562,16 @@ impl<'a> KdlLayoutParser<'a> {
         self.assert_valid_tab_properties(kdl_node)?;
         let tab_name =
             kdl_get_string_property_or_child_value!(kdl_node, "name").map(|s| s.to_string());
-        let tab_cwd =
-            kdl_get_string_property_or_child_value!(kdl_node, "cwd").map(|c| PathBuf::from(c));
+        let tab_cwd = kdl_get_string_property_or_child_value!(kdl_node, "cwd")
+            .map(|c| self.resolve_layout_vars(c, kdl_node).map(PathBuf::from))
+            .transpose()?;
         let is_focused = kdl_get_bool_property_or_child_value!(kdl_node, "focus").unwrap_or(false);
         let children_split_direction = self.parse_split_direction(kdl_node)?;
         let children = match kdl_children_nodes!(kdl_node) {
             Some(children) => self.parse_child_pane_nodes_for_tab(children)?,
             None => vec![],
         };
+        self.assert_valid_split_sizes(&children, kdl_node)?;
         let mut pane_layout = PaneLayout {
             children_split_direction,
             children,
@@ -600,6 +681,8 @@ impl<'a> KdlLayoutParser<'a> {
         pane_template_run: &Option<Run>,
         args: &Option<Vec<String>>,
         close_on_exit: &Option<bool>,
+        restart_on_exit: &Option<bool>,
+        start_suspended: &Option<bool>,
         pane_node: &KdlNode,
     ) -> Result<(), ConfigError> {
         if let (None, None, true) = (pane_run, pane_template_run, args.is_some()) {
@@ -614,6 +697,18 @@ impl<'a> KdlLayoutParser<'a> {
                 pane_node
             ));
         }
+        if let (None, None, true) = (pane_run, pane_template_run, restart_on_exit.is_some()) {
+            return Err(kdl_parsing_error!(
+                format!("restart_on_exit can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
+        if let (None, None, true) = (pane_run, pane_template_run, start_suspended.is_some()) {
+            return Err(kdl_parsing_error!(
+                format!("start_suspended can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
         Ok(())
     }
     fn assert_no_bare_attributes_in_pane_node(
@@ -621,6 +716,8 @@ impl<'a> KdlLayoutParser<'a> {
         command: &Option<PathBuf>,
         args: &Option<Vec<String>>,
         close_on_exit: &Option<bool>,
+        restart_on_exit: &Option<bool>,
+        start_suspended: &Option<bool>,
         pane_node: &KdlNode,
     ) -> Result<(), ConfigError> {
         if command.is_none() {
@@ -631,6 +728,20 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_node.span().len(),
                 ));
             }
+            if restart_on_exit.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "restart_on_exit can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
+            if start_suspended.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "start_suspended can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
             if args.is_some() {
                 return Err(ConfigError::new_layout_kdl_error(
                     "args can only be set if a command was specified".into(),
@@ -641,6 +752,30 @@ impl<'a> KdlLayoutParser<'a> {
         }
         Ok(())
     }
+    fn assert_valid_split_sizes(
+        &self,
+        children: &[PaneLayout],
+        kdl_node: &KdlNode,
+    ) -> Result<(), ConfigError> {
+        let total_percent: usize = children
+            .iter()
+            .filter_map(|child| match child.split_size {
+                Some(SplitSize::Percent(percent)) => Some(percent),
+                _ => None,
+            })
+            .sum();
+        if total_percent > 100 {
+            return Err(ConfigError::new_layout_kdl_error(
+                format!(
+                    "The sizes of the child panes add up to {}%, they must not exceed 100%",
+                    total_percent
+                ),
+                kdl_node.span().offset(),
+                kdl_node.span().len(),
+            ));
+        }
+        Ok(())
+    }
     fn assert_one_children_block(
         &self,
         layout: &PaneLayout,
@@ -764,8 +899,9 @@ impl<'a> KdlLayoutParser<'a> {
         // (is_focused, Option<tab_name>, PaneLayout)
         let tab_name =
             kdl_get_string_property_or_child_value!(kdl_node, "name").map(|s| s.to_string());
-        let tab_cwd =
-            kdl_get_string_property_or_child_value!(kdl_node, "cwd").map(|c| PathBuf::from(c));
+        let tab_cwd = kdl_get_string_property_or_child_value!(kdl_node, "cwd")
+            .map(|c| self.resolve_layout_vars(c, kdl_node).map(PathBuf::from))
+            .transpose()?;
         let is_focused = kdl_get_bool_property_or_child_value!(kdl_node, "focus").unwrap_or(false);
         let children_split_direction = self.parse_split_direction(kdl_node)?;
         match kdl_children_nodes!(kdl_node) {
@@ -976,7 +1112,9 @@ impl<'a> KdlLayoutParser<'a> {
             if let Some(global_cwd) =
                 kdl_get_string_property_or_child_value_with_error!(layout_node, "cwd")
             {
-                self.global_cwd = Some(PathBuf::from(global_cwd));
+                self.global_cwd = Some(PathBuf::from(
+                    self.resolve_layout_vars(global_cwd, layout_node)?,
+                ));
             }
         }
         Ok(())
@@ -1,13 +1,19 @@
 mod kdl_layout_parser;
-use crate::data::{InputMode, Key, Palette, PaletteColor};
+use crate::consts::MAX_PTY_READ_BUFFER_SIZE;
+use crate::data::{FrameStyle, InputMode, Key, Palette, PaletteColor};
 use crate::envs::EnvironmentVariables;
 use crate::input::command::RunCommand;
 use crate::input::config::{Config, ConfigError, KdlError};
 use crate::input::keybinds::Keybinds;
-use crate::input::layout::{Layout, RunPlugin, RunPluginLocation};
-use crate::input::options::{Clipboard, OnForceClose, Options};
+use crate::input::layout::{
+    Layout, PaneLayout, Run, RunPlugin, RunPluginLocation, SplitDirection, SplitSize,
+};
+use crate::input::options::{
+    AutoLayout, BellStyle, ClientSizePolicy, Clipboard, OnForceClose, OnNestedSession, Options,
+};
 use crate::input::plugins::{PluginConfig, PluginTag, PluginType, PluginsConfig};
 use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
+use crate::shared::ColorDepth;
 use kdl_layout_parser::KdlLayoutParser;
 use std::collections::HashMap;
 use std::fs::File;
@@ -22,7 +28,9 @@ use kdl::{KdlDocument, KdlEntry, KdlNode};
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use crate::input::actions::{Action, Direction, ResizeDirection, SearchDirection, SearchOption};
+use crate::input::actions::{
+    is_signal_name, Action, Direction, Fraction, ResizeDirection, SearchDirection, SearchOption,
+};
 use crate::input::command::RunCommandAction;
 
 #[macro_export]
@@ -44,20 +52,31 @@ macro_rules! parse_kdl_action_arguments {
                 "ScrollUp" => Ok(Action::ScrollUp),
                 "ScrollDown" => Ok(Action::ScrollDown),
                 "ScrollToBottom" => Ok(Action::ScrollToBottom),
+                "ToggleFollowOutput" => Ok(Action::ToggleFollowOutput),
                 "PageScrollUp" => Ok(Action::PageScrollUp),
                 "PageScrollDown" => Ok(Action::PageScrollDown),
                 "HalfPageScrollUp" => Ok(Action::HalfPageScrollUp),
                 "HalfPageScrollDown" => Ok(Action::HalfPageScrollDown),
+                "ScrollLeft" => Ok(Action::ScrollLeft),
+                "ScrollRight" => Ok(Action::ScrollRight),
+                "ToggleLineWrap" => Ok(Action::ToggleLineWrap),
                 "ToggleFocusFullscreen" => Ok(Action::ToggleFocusFullscreen),
                 "TogglePaneFrames" => Ok(Action::TogglePaneFrames),
+                "EqualizePanes" => Ok(Action::EqualizePanes),
+                "ApplyAutoLayout" => Ok(Action::ApplyAutoLayout),
                 "ToggleActiveSyncTab" => Ok(Action::ToggleActiveSyncTab),
                 "TogglePaneEmbedOrFloating" => Ok(Action::TogglePaneEmbedOrFloating),
+                "TogglePanePinned" => Ok(Action::TogglePanePinned),
                 "ToggleFloatingPanes" => Ok(Action::ToggleFloatingPanes),
                 "CloseFocus" => Ok(Action::CloseFocus),
+                "UndoClosePane" => Ok(Action::UndoClosePane),
                 "UndoRenamePane" => Ok(Action::UndoRenamePane),
                 "NoOp" => Ok(Action::NoOp),
                 "GoToNextTab" => Ok(Action::GoToNextTab),
                 "GoToPreviousTab" => Ok(Action::GoToPreviousTab),
+                "MoveTabLeft" => Ok(Action::MoveTabLeft),
+                "MoveTabRight" => Ok(Action::MoveTabRight),
+                "MovePaneToNewTab" => Ok(Action::MovePaneToNewTab),
                 "CloseTab" => Ok(Action::CloseTab),
                 "ToggleTab" => Ok(Action::ToggleTab),
                 "UndoRenameTab" => Ok(Action::UndoRenameTab),
@@ -383,6 +402,16 @@ impl Action {
                     ))
                 },
             },
+            "ResizeToFraction" => {
+                let fraction = Fraction::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid fraction: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::ResizeToFraction(fraction))
+            },
             "Resize" => {
                 let direction = ResizeDirection::from_str(string.as_str()).map_err(|_| {
                     ConfigError::new_kdl_error(
@@ -393,6 +422,16 @@ impl Action {
                 })?;
                 Ok(Action::Resize(direction))
             },
+            "ResizeFine" => {
+                let direction = ResizeDirection::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid direction: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::ResizeFine(direction))
+            },
             "MoveFocus" => {
                 let direction = Direction::from_str(string.as_str()).map_err(|_| {
                     ConfigError::new_kdl_error(
@@ -413,6 +452,16 @@ impl Action {
                 })?;
                 Ok(Action::MoveFocusOrTab(direction))
             },
+            "MovePaneToTab" => {
+                let direction = Direction::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid direction: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::MovePaneToTab(direction))
+            },
             "MovePane" => {
                 if string.is_empty() {
                     return Ok(Action::MovePane(None));
@@ -428,6 +477,28 @@ impl Action {
                 }
             },
             "DumpScreen" => Ok(Action::DumpScreen(string, false)),
+            "DumpLayout" => Ok(Action::DumpLayout(string)),
+            "NewTabWithLayout" => Ok(Action::NewTabWithLayout(PathBuf::from(string))),
+            "SetClientSizePolicy" => {
+                let policy = ClientSizePolicy::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid client size policy: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::SetClientSizePolicy(policy))
+            },
+            "SignalFocusedPane" => {
+                if !is_signal_name(&string) {
+                    return Err(ConfigError::new_kdl_error(
+                        format!("Invalid signal: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    ));
+                }
+                Ok(Action::SignalFocusedPane(string))
+            },
             "NewPane" => {
                 if string.is_empty() {
                     return Ok(Action::NewPane(None, None));
@@ -612,6 +683,9 @@ impl TryFrom<&KdlNode> for Action {
             "ScrollToBottom" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "ToggleFollowOutput" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "PageScrollUp" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
@@ -624,9 +698,24 @@ impl TryFrom<&KdlNode> for Action {
             "HalfPageScrollDown" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "ScrollLeft" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ScrollRight" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ToggleLineWrap" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "ToggleFocusFullscreen" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "EqualizePanes" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ApplyAutoLayout" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "TogglePaneFrames" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
@@ -636,10 +725,16 @@ impl TryFrom<&KdlNode> for Action {
             "TogglePaneEmbedOrFloating" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "TogglePanePinned" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "ToggleFloatingPanes" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
             "CloseFocus" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
+            "UndoClosePane" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "UndoRenamePane" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
@@ -648,6 +743,13 @@ impl TryFrom<&KdlNode> for Action {
             "GoToPreviousTab" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "MoveTabLeft" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
+            "MoveTabRight" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "MovePaneToNewTab" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "CloseTab" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
             "ToggleTab" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
             "UndoRenameTab" => {
@@ -678,6 +780,16 @@ impl TryFrom<&KdlNode> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "ResizeFine" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "ResizeToFraction" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             "MoveFocus" => parse_kdl_action_char_or_string_arguments!(
                 action_name,
                 action_arguments,
@@ -688,6 +800,11 @@ impl TryFrom<&KdlNode> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "MovePaneToTab" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             "MovePane" => parse_kdl_action_char_or_string_arguments!(
                 action_name,
                 action_arguments,
@@ -698,6 +815,26 @@ impl TryFrom<&KdlNode> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "DumpLayout" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "NewTabWithLayout" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "SetClientSizePolicy" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "SignalFocusedPane" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             "NewPane" => parse_kdl_action_char_or_string_arguments!(
                 action_name,
                 action_arguments,
@@ -1217,6 +1354,198 @@ impl Options {
         let attach_to_session =
             kdl_property_first_arg_as_bool_or_error!(kdl_options, "attach_to_session")
                 .map(|(v, _)| v);
+        let clipboard_from_panes =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "clipboard_from_panes")
+                .map(|(v, _)| v);
+        let full_redraw =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "full_redraw").map(|(v, _)| v);
+        let move_tab_wraparound =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "move_tab_wraparound")
+                .map(|(v, _)| v);
+        let confirm_pane_close =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "confirm_pane_close")
+                .map(|(v, _)| v);
+        let resize_percent =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "resize_percent") {
+                Some((v, entry)) => Some(
+                    usize::try_from(v)
+                        .ok()
+                        .filter(|v| (1..=100).contains(v))
+                        .ok_or_else(|| {
+                            kdl_parsing_error!(
+                                format!(
+                                    "Invalid value for resize_percent: '{}', it must be between 1 and 100",
+                                    v
+                                ),
+                                entry
+                            )
+                        })?,
+                ),
+                None => None,
+            };
+        let hold_shell_on_exit =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "hold_shell_on_exit")
+                .map(|(v, _)| v);
+        let bracketed_paste =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "bracketed_paste")
+                .map(|(v, _)| v);
+        let status_in_title =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "status_in_title")
+                .map(|(v, _)| v);
+        let confirm_quit =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "confirm_quit").map(|(v, _)| v);
+        let default_shell_args =
+            kdl_property_first_arg_as_string_or_error!(kdl_options, "default_shell_args")
+                .map(|(string, _entry)| string.to_string());
+        let on_nested_session =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "on_nested_session") {
+                Some((string, entry)) => Some(OnNestedSession::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for on_nested_session: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let middle_click_paste =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "middle_click_paste")
+                .map(|(v, _)| v);
+        let client_size_policy =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "client_size_policy") {
+                Some((string, entry)) => {
+                    Some(ClientSizePolicy::from_str(string).map_err(|_| {
+                        kdl_parsing_error!(
+                            format!("Invalid value for client_size_policy: '{}'", string),
+                            entry
+                        )
+                    })?)
+                },
+                None => None,
+            };
+        let undo_close_depth =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "undo_close_depth") {
+                Some((v, entry)) => Some(usize::try_from(v).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!(
+                            "Invalid value for undo_close_depth: '{}', it cannot be negative",
+                            v
+                        ),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let auto_layout =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "auto_layout") {
+                Some((string, entry)) => Some(AutoLayout::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for auto_layout: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let pty_read_buffer_size =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "pty_read_buffer_size") {
+                Some((v, entry)) => Some(
+                    usize::try_from(v)
+                        .ok()
+                        .filter(|v| (1..=MAX_PTY_READ_BUFFER_SIZE).contains(v))
+                        .ok_or_else(|| {
+                            kdl_parsing_error!(
+                                format!(
+                                    "Invalid value for pty_read_buffer_size: '{}', it must be between 1 and {}",
+                                    v, MAX_PTY_READ_BUFFER_SIZE
+                                ),
+                                entry
+                            )
+                        })?,
+                ),
+                None => None,
+            };
+        let pending_output_limit =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "pending_output_limit") {
+                Some((v, entry)) => Some(usize::try_from(v).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!(
+                            "Invalid value for pending_output_limit: '{}', it cannot be negative",
+                            v
+                        ),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let focus_follows_mouse =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "focus_follows_mouse")
+                .map(|(v, _)| v);
+        let max_command_restarts =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "max_command_restarts")
+                .map(|(v, _entry)| v as usize);
+        let command_restart_window =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "command_restart_window") {
+                Some((v, entry)) => Some(usize::try_from(v).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!(
+                            "Invalid value for command_restart_window: '{}', it cannot be negative",
+                            v
+                        ),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let idle_timeout = kdl_property_first_arg_as_i64_or_error!(kdl_options, "idle_timeout")
+            .map(|(v, _entry)| v as usize);
+        let on_idle_timeout =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "on_idle_timeout") {
+                Some((string, entry)) => Some(OnForceClose::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for on_idle_timeout: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let bell_style = match kdl_property_first_arg_as_string_or_error!(kdl_options, "bell_style")
+        {
+            Some((string, entry)) => Some(BellStyle::from_str(string).map_err(|_| {
+                kdl_parsing_error!(format!("Invalid value for bell_style: '{}'", string), entry)
+            })?),
+            None => None,
+        };
+        let persist_scrollback =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "persist_scrollback")
+                .map(|(v, _)| v);
+        let persist_scrollback_max_size =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "persist_scrollback_max_size")
+                .map(|(v, _entry)| v as usize);
+        let go_to_tab_creates_tab =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "go_to_tab_creates_tab")
+                .map(|(v, _)| v);
+        let scroll_wheel_lines =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "scroll_wheel_lines")
+                .map(|(v, _entry)| v as usize);
+        let min_pane_width = kdl_property_first_arg_as_i64_or_error!(kdl_options, "min_pane_width")
+            .map(|(v, _entry)| v as usize);
+        let min_pane_height =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "min_pane_height")
+                .map(|(v, _entry)| v as usize);
+        let new_tab_layout =
+            kdl_property_first_arg_as_string_or_error!(kdl_options, "new_tab_layout")
+                .map(|(string, _entry)| PathBuf::from(string));
+        let prefix_key = kdl_property_first_arg_as_string_or_error!(kdl_options, "prefix_key")
+            .map(|(string, _entry)| string.to_string());
+        let color_depth =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "color_depth") {
+                Some((string, entry)) => Some(ColorDepth::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for color_depth: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
         Ok(Options {
             simplified_ui,
             theme,
@@ -1236,6 +1565,38 @@ impl Options {
             scrollback_editor,
             session_name,
             attach_to_session,
+            clipboard_from_panes,
+            full_redraw,
+            move_tab_wraparound,
+            confirm_pane_close,
+            resize_percent,
+            hold_shell_on_exit,
+            bracketed_paste,
+            status_in_title,
+            confirm_quit,
+            default_shell_args,
+            on_nested_session,
+            middle_click_paste,
+            client_size_policy,
+            undo_close_depth,
+            auto_layout,
+            pty_read_buffer_size,
+            pending_output_limit,
+            focus_follows_mouse,
+            max_command_restarts,
+            command_restart_window,
+            idle_timeout,
+            on_idle_timeout,
+            bell_style,
+            persist_scrollback,
+            persist_scrollback_max_size,
+            go_to_tab_creates_tab,
+            scroll_wheel_lines,
+            min_pane_width,
+            min_pane_height,
+            new_tab_layout,
+            prefix_key,
+            color_depth,
         })
     }
 }
@@ -1271,7 +1632,17 @@ impl Layout {
         file_name: String,
         cwd: Option<PathBuf>,
     ) -> Result<Self, ConfigError> {
-        KdlLayoutParser::new(raw_layout, cwd).parse().map_err(|e| {
+        Layout::from_kdl_with_layout_vars(raw_layout, file_name, cwd, HashMap::new())
+    }
+    /// Parses the layout, resolving the `${NAME}` variables in its `cwd`, `command` and `args`
+    /// from `layout_vars` or the environment
+    pub fn from_kdl_with_layout_vars(
+        raw_layout: &str,
+        file_name: String,
+        cwd: Option<PathBuf>,
+        layout_vars: HashMap<String, String>,
+    ) -> Result<Self, ConfigError> {
+        KdlLayoutParser::new(raw_layout, cwd, layout_vars).parse().map_err(|e| {
             match e {
                 ConfigError::KdlError(kdl_error) => ConfigError::KdlError(kdl_error.add_src(file_name, String::from(raw_layout))),
                 ConfigError::KdlDeserializationError(kdl_error) => {
@@ -1298,6 +1669,128 @@ impl Layout {
             }
         })
     }
+    /// Serializes the tabs of the layout into the KDL read by `Layout::from_kdl`, so that it can
+    /// be loaded again to recreate the same tabs and panes
+    pub fn to_kdl(&self) -> String {
+        let mut kdl = String::from("layout {\n");
+        for (tab_index, (tab_name, tab_layout)) in self.tabs.iter().enumerate() {
+            let mut properties = vec![];
+            if let Some(tab_name) = tab_name {
+                properties.push(format!("name={}", kdl_string(tab_name)));
+            }
+            if self.focused_tab_index == Some(tab_index) {
+                properties.push(String::from("focus=true"));
+            }
+            if tab_layout.children_split_direction == SplitDirection::Vertical
+                && !tab_layout.children.is_empty()
+            {
+                properties.push(String::from("split_direction=\"vertical\""));
+            }
+            let mut children = String::new();
+            for child in &tab_layout.children {
+                pane_layout_to_kdl(child, 2, &mut children);
+            }
+            push_kdl_node(&mut kdl, 1, "tab", &properties, &children);
+        }
+        kdl.push_str("}\n");
+        kdl
+    }
+}
+fn kdl_string(value: &str) -> String {
+    KdlEntry::new(value).to_string().trim().to_owned()
+}
+fn push_kdl_node(
+    kdl: &mut String,
+    indent: usize,
+    name: &str,
+    properties: &[String],
+    children: &str,
+) {
+    let indentation = "    ".repeat(indent);
+    kdl.push_str(&indentation);
+    kdl.push_str(name);
+    for property in properties {
+        kdl.push(' ');
+        kdl.push_str(property);
+    }
+    if children.is_empty() {
+        kdl.push('\n');
+    } else {
+        kdl.push_str(" {\n");
+        kdl.push_str(children);
+        kdl.push_str(&indentation);
+        kdl.push_str("}\n");
+    }
+}
+fn pane_layout_to_kdl(pane_layout: &PaneLayout, indent: usize, kdl: &mut String) {
+    let mut properties = vec![];
+    let mut children = String::new();
+    let child_indentation = "    ".repeat(indent + 1);
+    if let Some(name) = &pane_layout.name {
+        properties.push(format!("name={}", kdl_string(name)));
+    }
+    match pane_layout.split_size {
+        Some(SplitSize::Percent(percent)) => properties.push(format!("size=\"{}%\"", percent)),
+        Some(SplitSize::Fixed(size)) => properties.push(format!("size={}", size)),
+        None => {},
+    }
+    if pane_layout.borderless {
+        properties.push(String::from("borderless=true"));
+    }
+    if pane_layout.focus == Some(true) {
+        properties.push(String::from("focus=true"));
+    }
+    if pane_layout.children_split_direction == SplitDirection::Vertical
+        && !pane_layout.children.is_empty()
+    {
+        properties.push(String::from("split_direction=\"vertical\""));
+    }
+    match &pane_layout.run {
+        Some(Run::Command(run_command)) => {
+            properties.push(format!(
+                "command={}",
+                kdl_string(&run_command.command.to_string_lossy())
+            ));
+            if let Some(cwd) = &run_command.cwd {
+                properties.push(format!("cwd={}", kdl_string(&cwd.to_string_lossy())));
+            }
+            if !run_command.hold_on_close {
+                properties.push(String::from("close_on_exit=true"));
+            }
+            if run_command.restart_on_exit {
+                properties.push(String::from("restart_on_exit=true"));
+            }
+            if run_command.start_suspended {
+                properties.push(String::from("start_suspended=true"));
+            }
+            if !run_command.args.is_empty() {
+                let args: Vec<String> = run_command.args.iter().map(|a| kdl_string(a)).collect();
+                children.push_str(&format!("{}args {}\n", child_indentation, args.join(" ")));
+            }
+        },
+        Some(Run::EditFile(file, line_number)) => {
+            properties.push(format!("edit={}", kdl_string(&file.to_string_lossy())));
+            if let Some(line_number) = line_number {
+                properties.push(format!("line_number={}", line_number));
+            }
+        },
+        Some(Run::Cwd(cwd)) => {
+            properties.push(format!("cwd={}", kdl_string(&cwd.to_string_lossy())));
+        },
+        Some(Run::Plugin(run_plugin)) => {
+            let location = Url::from(&run_plugin.location).to_string();
+            children.push_str(&format!(
+                "{}plugin location={}\n",
+                child_indentation,
+                kdl_string(&location)
+            ));
+        },
+        None => {},
+    }
+    for child in &pane_layout.children {
+        pane_layout_to_kdl(child, indent + 1, &mut children);
+    }
+    push_kdl_node(kdl, indent, "pane", &properties, &children);
 }
 impl EnvironmentVariables {
     pub fn from_kdl(kdl_env_variables: &KdlNode) -> Result<Self, ConfigError> {
@@ -1377,8 +1870,14 @@ impl Keybinds {
             }
             if kdl_name!(block) == "shared_among" {
                 let mut modes_to_include = vec![];
-                for mode_name in kdl_string_arguments!(block) {
-                    modes_to_include.push(InputMode::from_str(mode_name)?);
+                for (mode_name, entry) in kdl_string_arguments!(block).iter().zip(block.entries()) {
+                    modes_to_include.push(InputMode::from_str(mode_name).map_err(|_| {
+                        ConfigError::new_kdl_error(
+                            format!("Invalid mode: '{}'", mode_name),
+                            entry.span().offset(),
+                            entry.span().len(),
+                        )
+                    })?);
                 }
                 for mode in InputMode::iter() {
                     if !modes_to_include.contains(&mode) {
@@ -1482,6 +1981,8 @@ impl RunCommand {
             args,
             cwd,
             hold_on_close: true,
+            restart_on_exit: false,
+            start_suspended: false,
         })
     }
 }
@@ -1553,7 +2054,28 @@ impl UiConfig {
             let rounded_corners =
                 kdl_children_property_first_arg_as_bool!(pane_frames, "rounded_corners")
                     .unwrap_or(false);
-            let frame_config = FrameConfig { rounded_corners };
+            let style = match pane_frames.children().and_then(|c| c.get("style")) {
+                Some(style_node) => {
+                    let style_name = style_node
+                        .entries()
+                        .iter()
+                        .next()
+                        .and_then(|e| e.value().as_string())
+                        .unwrap_or_default();
+                    FrameStyle::from_str(style_name).map_err(|_| {
+                        ConfigError::new_kdl_error(
+                            format!("Invalid pane frame style: '{}'", style_name),
+                            style_node.span().offset(),
+                            style_node.span().len(),
+                        )
+                    })?
+                },
+                None => FrameStyle::default(),
+            };
+            let frame_config = FrameConfig {
+                rounded_corners,
+                style,
+            };
             ui_config.pane_frames = frame_config;
         }
         Ok(ui_config)
@@ -23,20 +23,15 @@ use crate::shared::set_permissions;
 
 const LOG_MAX_BYTES: u64 = 1024 * 1024 * 16; // 16 MiB per log
 
-pub fn configure_logger() {
+pub fn configure_logger(log_level: Option<&str>, log_file_path: Option<&Path>) {
     atomic_create_dir(&*ZELLIJ_TMP_DIR).unwrap();
     atomic_create_dir(&*ZELLIJ_TMP_LOG_DIR).unwrap();
-    atomic_create_file(&*ZELLIJ_TMP_LOG_FILE).unwrap();
+    let log_file_path = log_file_path.unwrap_or_else(|| ZELLIJ_TMP_LOG_FILE.as_path());
+    atomic_create_file(log_file_path).unwrap();
 
     let trigger = SizeTrigger::new(LOG_MAX_BYTES);
     let roller = FixedWindowRoller::builder()
-        .build(
-            ZELLIJ_TMP_LOG_DIR
-                .join("zellij.log.old.{}")
-                .to_str()
-                .unwrap(),
-            1,
-        )
+        .build(&format!("{}.old.{{}}", log_file_path.display()), 1)
         .unwrap();
 
     // {n} means platform dependent newline
@@ -47,7 +42,7 @@ pub fn configure_logger() {
     let log_file = RollingFileAppender::builder()
         .encoder(Box::new(PatternEncoder::new(file_pattern)))
         .build(
-            &*ZELLIJ_TMP_LOG_FILE,
+            log_file_path,
             Box::new(CompoundPolicy::new(
                 Box::new(trigger),
                 Box::new(roller.clone()),
@@ -62,12 +57,16 @@ pub fn configure_logger() {
             "{highlight({level:<6})} {message} {n}",
         )))
         .build(
-            &*ZELLIJ_TMP_LOG_FILE,
+            log_file_path,
             Box::new(CompoundPolicy::new(Box::new(trigger), Box::new(roller))),
         )
         .unwrap();
 
-    // Set the default logging level to "info" and log it to zellij.log file
+    let log_level = log_level
+        .and_then(|log_level| log_level.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+
+    // Set the logging level (by default "info") and log it to zellij.log file
     // Decrease verbosity for `wasmer_compiler_cranelift` module because it has a lot of useless info logs
     // For `zellij_server::logging_pipe`, we use custom format as we use logging macros to forward stderr output from plugins
     let config = Config::builder()
@@ -84,7 +83,7 @@ pub fn configure_logger() {
                 .additive(false)
                 .build("zellij_server::logging_pipe", LevelFilter::Trace),
         )
-        .build(Root::builder().appender("logFile").build(LevelFilter::Info))
+        .build(Root::builder().appender("logFile").build(log_level))
         .unwrap();
 
     let _ = log4rs::init_config(config).unwrap();
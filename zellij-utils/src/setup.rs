@@ -7,16 +7,18 @@ use crate::{
     },
     input::{
         config::{Config, ConfigError},
-        layout::Layout,
+        layout::{Layout, PaneLayout, Run},
         options::Options,
     },
 };
 use clap::{Args, IntoApp};
 use clap_complete::Shell;
 use directories_next::BaseDirs;
+use miette::Report;
 use serde::{Deserialize, Serialize};
 use std::{
-    convert::TryFrom, fmt::Write as FmtWrite, io::Write, path::Path, path::PathBuf, process,
+    collections::HashMap, convert::TryFrom, fmt::Write as FmtWrite, io::Write, path::Path,
+    path::PathBuf,
 };
 
 const CONFIG_LOCATION: &str = ".config/zellij";
@@ -39,6 +41,46 @@ pub fn find_default_config_dir() -> Option<PathBuf> {
     None
 }
 
+/// Returns the first existing entry with this name in any of the config directories, so that eg.
+/// a config file in the XDG config directory is found even if `~/.config/zellij` exists without
+/// one
+fn find_in_config_dirs(config_dirs: Vec<Option<PathBuf>>, name: &str) -> Option<PathBuf> {
+    config_dirs
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(name))
+        .find(|path| path.exists())
+}
+
+#[cfg(not(test))]
+/// Goes through the same list as [`find_default_config_dir`]
+fn find_in_default_config_dirs(name: &str) -> Option<PathBuf> {
+    find_in_config_dirs(default_config_dirs(), name)
+}
+
+#[cfg(test)]
+fn find_in_default_config_dirs(_name: &str) -> Option<PathBuf> {
+    None
+}
+
+pub fn find_default_config_file() -> Option<PathBuf> {
+    find_in_default_config_dirs(CONFIG_NAME)
+}
+
+pub fn find_default_layout_dir() -> Option<PathBuf> {
+    find_in_default_config_dirs("layouts")
+}
+
+/// The layout directory of the config directory given on the command line, or else the first
+/// one in the default config directories unless the defaults were asked for with `--clean`
+pub fn get_layout_dir_from_cli(cli_args: &CliArgs) -> Option<PathBuf> {
+    match cli_args.config_dir {
+        Some(ref config_dir) => get_layout_dir(Some(config_dir.clone())),
+        None if cli_args.is_clean() => None,
+        None => find_default_layout_dir(),
+    }
+}
+
 /// Order in which config directories are checked
 fn default_config_dirs() -> Vec<Option<PathBuf>> {
     vec![
@@ -159,6 +201,30 @@ pub fn dump_default_config() -> std::io::Result<()> {
     dump_asset(DEFAULT_CONFIG)
 }
 
+/// Checks whether the command is an existing executable file, either as given or, if it's a bare
+/// name, in one of the directories listed in `$PATH`
+fn command_exists(command: &Path) -> bool {
+    if command.components().count() > 1 {
+        return is_executable(command);
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| is_executable(&dir.join(command))))
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(target_family = "wasm")]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
 pub fn dump_specified_layout(layout: &str) -> std::io::Result<()> {
     match layout {
         "strider" => dump_asset(STRIDER_LAYOUT),
@@ -192,6 +258,11 @@ pub struct Setup {
     #[clap(long, value_parser)]
     pub dump_layout: Option<String>,
 
+    /// Checks that the specified layout can be loaded
+    /// and that the commands it runs can be found
+    #[clap(long, value_parser)]
+    pub check_layout: Option<PathBuf>,
+
     /// Generates completion for the specified shell
     #[clap(long, value_name = "SHELL", value_parser)]
     pub generate_completion: Option<String>,
@@ -212,7 +283,7 @@ impl Setup {
     /// 3. config options (`config.yaml`)
     pub fn from_cli_args(cli_args: &CliArgs) -> Result<(Config, Layout, Options), ConfigError> {
         // note that this can potentially exit the process
-        Setup::handle_setup_commands(cli_args);
+        Setup::handle_setup_commands(cli_args)?;
         let config = Config::try_from(cli_args)?;
         let cli_config_options: Option<Options> =
             if let Some(Command::Options(options)) = cli_args.command.clone() {
@@ -226,6 +297,9 @@ impl Setup {
             Some(cli_config_options) => config.options.merge(cli_config_options),
             None => config.options.clone(),
         };
+        if let Some(prefix_key) = &config_options.prefix_key {
+            config.keybinds.bind_prefix_key(prefix_key.parse()?)?;
+        }
 
         if let Some(theme_dir) = config_options
             .theme_dir
@@ -249,15 +323,7 @@ impl Setup {
         }
 
         if let Some(Command::Setup(ref setup)) = &cli_args.command {
-            setup
-                .from_cli_with_options(cli_args, &config_options)
-                .map_or_else(
-                    |e| {
-                        eprintln!("{:?}", e);
-                        process::exit(1);
-                    },
-                    |_| {},
-                );
+            setup.from_cli_with_options(cli_args, &config_options)?;
         };
         Ok((config, layout, config_options))
     }
@@ -296,14 +362,98 @@ impl Setup {
         &self,
         opts: &CliArgs,
         config_options: &Options,
-    ) -> std::io::Result<()> {
+    ) -> Result<(), ConfigError> {
         if self.check {
             Setup::check_defaults_config(opts, config_options)?;
             std::process::exit(0);
         }
+
+        if let Some(layout) = &self.check_layout {
+            let layout_dir = config_options
+                .layout_dir
+                .clone()
+                .or_else(|| get_layout_dir_from_cli(opts));
+            let layout_vars = opts.layout_vars.iter().cloned().collect();
+            match Setup::check_layout(layout, layout_dir, layout_vars) {
+                Ok(message) => {
+                    println!("{}", message);
+                    std::process::exit(0);
+                },
+                Err(message) => return Err(ConfigError::InvalidLayout(message)),
+            }
+        }
         Ok(())
     }
 
+    /// Loads the layout the same way `zellij --layout` does and checks that every command it
+    /// runs can be found, returning a summary of the layout or the first problem found
+    pub fn check_layout(
+        layout: &Path,
+        layout_dir: Option<PathBuf>,
+        layout_vars: HashMap<String, String>,
+    ) -> Result<String, String> {
+        let layout_path = layout.to_path_buf();
+        let (layout, _config) = Layout::from_path_or_default(
+            Some(&layout_path),
+            layout_dir,
+            None,
+            layout_vars,
+            Config::default(),
+        )
+        .map_err(|e| match e {
+            ConfigError::KdlError(error) => {
+                let report: Report = error.into();
+                format!("{:?}", report)
+            },
+            e => format!("{}", e),
+        })?;
+        // a layout without tabs is made up of its template alone
+        let pane_layouts: Vec<&PaneLayout> = if layout.tabs.is_empty() {
+            layout.template.iter().collect()
+        } else {
+            layout
+                .tabs
+                .iter()
+                .map(|(_tab_name, pane_layout)| pane_layout)
+                .collect()
+        };
+        let mut pane_count = 0;
+        for pane_layout in &pane_layouts {
+            pane_count += Setup::check_layout_commands(pane_layout, "Layout")?;
+        }
+        // new tabs are opened with the template, so the commands it runs have to be found too
+        if let (false, Some(template)) = (layout.tabs.is_empty(), &layout.template) {
+            Setup::check_layout_commands(template, "Layout template")?;
+        }
+        Ok(format!(
+            "Layout is valid: {} tab(s), {} pane(s)",
+            pane_layouts.len(),
+            pane_count
+        ))
+    }
+
+    /// Returns how many panes the layout opens, or which of their commands could not be found
+    fn check_layout_commands(pane_layout: &PaneLayout, part: &str) -> Result<usize, String> {
+        let run_instructions = pane_layout.extract_run_instructions();
+        for run in &run_instructions {
+            if let Some(Run::Command(run_command)) = run {
+                let command = match &run_command.cwd {
+                    Some(cwd) if run_command.command.components().count() > 1 => {
+                        cwd.join(&run_command.command)
+                    },
+                    _ => run_command.command.clone(),
+                };
+                if !command_exists(&command) {
+                    return Err(format!(
+                        "{} runs the command {:?}, which could not be found",
+                        part, run_command.command
+                    ));
+                }
+            }
+        }
+        Ok(run_instructions.len())
+    }
+
     pub fn check_defaults_config(opts: &CliArgs, config_options: &Options) -> std::io::Result<()> {
         let data_dir = opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
         let config_dir = opts.config_dir.clone().or_else(find_default_config_dir);
@@ -311,12 +461,12 @@ impl Setup {
         let layout_dir = config_options
             .layout_dir
             .clone()
-            .or_else(|| get_layout_dir(config_dir.clone()));
+            .or_else(|| get_layout_dir_from_cli(opts));
         let system_data_dir = PathBuf::from(SYSTEM_DEFAULT_DATA_DIR_PREFIX).join("share/zellij");
-        let config_file = opts
-            .config
-            .clone()
-            .or_else(|| config_dir.clone().map(|p| p.join(CONFIG_NAME)));
+        let config_file = opts.config.clone().or_else(|| match opts.config_dir {
+            Some(ref config_dir) => Some(config_dir.join(CONFIG_NAME)),
+            None => find_default_config_file(),
+        });
 
         // according to
         // https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
@@ -465,9 +615,7 @@ impl Setup {
             .as_ref()
             .and_then(|cli_options| cli_options.layout_dir.clone())
             .or_else(|| config.options.layout_dir.clone())
-            .or_else(|| {
-                get_layout_dir(cli_args.config_dir.clone().or_else(find_default_config_dir))
-            });
+            .or_else(|| get_layout_dir_from_cli(cli_args));
         // the chosen layout can either be a path relative to the layout_dir or a name of one
         // of our assets, this distinction is made when parsing the layout - TODO: ideally, this
         // logic should not be split up and all the decisions should happen here
@@ -482,28 +630,98 @@ impl Setup {
             .or_else(|| config.options.default_layout.clone());
         // we merge-override the config here because the layout might contain configuration
         // that needs to take precedence
-        Layout::from_path_or_default(chosen_layout.as_ref(), layout_dir.clone(), config)
+        // a relative --cwd is relative to where zellij was started, not to where the server runs
+        let cwd = cli_args
+            .cwd
+            .as_ref()
+            .map(|cwd| match std::env::current_dir() {
+                Ok(current_dir) => current_dir.join(cwd),
+                Err(_) => cwd.clone(),
+            });
+        let layout_vars = cli_args.layout_vars.iter().cloned().collect();
+        Layout::from_path_or_default(
+            chosen_layout.as_ref(),
+            layout_dir.clone(),
+            cwd,
+            layout_vars,
+            config,
+        )
     }
-    fn handle_setup_commands(cli_args: &CliArgs) {
+    fn handle_setup_commands(cli_args: &CliArgs) -> Result<(), ConfigError> {
         if let Some(Command::Setup(ref setup)) = &cli_args.command {
-            setup.from_cli().map_or_else(
-                |e| {
-                    eprintln!("{:?}", e);
-                    process::exit(1);
-                },
-                |_| {},
-            );
+            setup.from_cli()?;
         };
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod setup_test {
-    use super::Setup;
+    use super::{command_exists, find_in_config_dirs, get_layout_dir_from_cli, Setup};
     use crate::cli::{CliArgs, Command};
+    use crate::input::config::ConfigError;
     use crate::input::options::{CliOptions, Options};
     use insta::assert_snapshot;
+    use std::collections::HashMap;
+    use std::fs;
     use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn entry_is_found_in_the_first_config_dir_that_has_it() {
+        let home_config_dir = tempdir().unwrap();
+        let xdg_config_dir = tempdir().unwrap();
+        let system_config_dir = tempdir().unwrap();
+        fs::write(xdg_config_dir.path().join("config.kdl"), "").unwrap();
+        fs::write(system_config_dir.path().join("config.kdl"), "").unwrap();
+        let config_dirs = vec![
+            None,
+            Some(home_config_dir.path().to_path_buf()),
+            Some(xdg_config_dir.path().to_path_buf()),
+            Some(system_config_dir.path().to_path_buf()),
+        ];
+        assert_eq!(
+            find_in_config_dirs(config_dirs.clone(), "config.kdl"),
+            Some(xdg_config_dir.path().join("config.kdl")),
+            "config file found past a config dir without one"
+        );
+        assert_eq!(
+            find_in_config_dirs(config_dirs, "layouts"),
+            None,
+            "no config dir has layouts"
+        );
+    }
+    #[test]
+    fn layout_dir_is_taken_from_the_config_dir_on_the_cli() {
+        let config_dir = tempdir().unwrap();
+        let cli_args = CliArgs {
+            config_dir: Some(config_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_layout_dir_from_cli(&cli_args),
+            Some(config_dir.path().join("layouts")),
+            "layout dir of the cli config dir"
+        );
+        let cli_args = CliArgs {
+            config_dir: Some(config_dir.path().to_path_buf()),
+            clean: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            get_layout_dir_from_cli(&cli_args),
+            Some(config_dir.path().join("layouts")),
+            "cli config dir used even with --clean"
+        );
+    }
+    #[test]
+    fn layout_dir_is_not_looked_for_with_clean() {
+        let cli_args = CliArgs {
+            clean: true,
+            ..Default::default()
+        };
+        assert_eq!(get_layout_dir_from_cli(&cli_args), None, "no layout dir");
+    }
 
     #[test]
     fn default_config_with_no_cli_arguments() {
@@ -527,6 +745,64 @@ mod setup_test {
         assert_snapshot!(format!("{:#?}", options));
     }
     #[test]
+    fn check_valid_layout() {
+        let layout = PathBuf::from(format!(
+            "{}/src/test-fixtures/layout-with-options.kdl",
+            env!("CARGO_MANIFEST_DIR")
+        ));
+        assert!(Setup::check_layout(&layout, None, HashMap::new()).is_ok());
+    }
+    #[test]
+    fn check_missing_layout() {
+        let layout = PathBuf::from(format!(
+            "{}/src/test-fixtures/no-such-layout.kdl",
+            env!("CARGO_MANIFEST_DIR")
+        ));
+        assert!(Setup::check_layout(&layout, None, HashMap::new()).is_err());
+    }
+    #[test]
+    fn command_that_is_not_executable_does_not_exist() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempdir().unwrap();
+        let command = dir.path().join("command");
+        fs::write(&command, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&command, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(!command_exists(&command), "file without executable bits");
+        fs::set_permissions(&command, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(command_exists(&command), "executable file");
+        assert!(!command_exists(dir.path()), "directory");
+    }
+    #[test]
+    fn check_layout_with_layout_vars() {
+        let layout = PathBuf::from(format!(
+            "{}/src/test-fixtures/layout-with-layout-vars.kdl",
+            env!("CARGO_MANIFEST_DIR")
+        ));
+        assert!(
+            Setup::check_layout(&layout, None, HashMap::new()).is_err(),
+            "layout var is not set"
+        );
+        let layout_vars = HashMap::from([("CHECK_LAYOUT_TEST_SHELL".to_owned(), "sh".to_owned())]);
+        assert!(
+            Setup::check_layout(&layout, None, layout_vars).is_ok(),
+            "layout var is given"
+        );
+    }
+    #[test]
+    fn check_layout_with_a_template_that_runs_a_missing_command() {
+        let layout = PathBuf::from(format!(
+            "{}/src/test-fixtures/layout-with-template-command.kdl",
+            env!("CARGO_MANIFEST_DIR")
+        ));
+        assert_eq!(
+            Setup::check_layout(&layout, None, HashMap::new()),
+            Err(
+                "Layout template runs the command \"i_do_not_exist\", which could not be found"
+                    .to_owned()
+            ),
+        );
+    }
+    #[test]
     fn layout_options_override_config_options() {
         let mut cli_args = CliArgs::default();
         cli_args.layout = Some(PathBuf::from(format!(
@@ -556,6 +832,15 @@ mod setup_test {
         assert_snapshot!(format!("{:#?}", layout));
     }
     #[test]
+    fn cli_cwd_is_applied_to_layout_panes() {
+        use crate::input::layout::Run;
+        let mut cli_args = CliArgs::default();
+        cli_args.cwd = Some(PathBuf::from("/tmp"));
+        let (_config, layout, _options) = Setup::from_cli_args(&cli_args).unwrap();
+        let run_instructions = layout.template.unwrap().extract_run_instructions();
+        assert!(run_instructions.contains(&Some(Run::Cwd(PathBuf::from("/tmp")))));
+    }
+    #[test]
     fn layout_env_vars_override_config_env_vars() {
         let mut cli_args = CliArgs::default();
         cli_args.config = Some(PathBuf::from(format!(
@@ -625,4 +910,59 @@ mod setup_test {
         let (config, _layout, _options) = Setup::from_cli_args(&cli_args).unwrap();
         assert_snapshot!(format!("{:#?}", config));
     }
+    #[test]
+    fn dumped_default_config_documents_every_option() {
+        let default_config = String::from_utf8(super::DEFAULT_CONFIG.to_vec()).unwrap();
+        let options = serde_json::to_value(Options::default()).unwrap();
+        for option in options.as_object().unwrap().keys() {
+            assert!(
+                default_config.lines().any(|line| line
+                    .trim_start_matches("// ")
+                    .starts_with(&format!("{} ", option))),
+                "option {} documented in the default config",
+                option
+            );
+        }
+    }
+    #[test]
+    fn config_file_that_fails_to_parse_is_returned_as_an_error() {
+        let config_dir = tempdir().unwrap();
+        let config_file = config_dir.path().join("config.kdl");
+        fs::write(
+            &config_file,
+            "keybinds {\n    normal {\n        bind \"a\" {",
+        )
+        .unwrap();
+        let cli_args = CliArgs {
+            config: Some(config_file),
+            ..Default::default()
+        };
+        assert!(
+            matches!(
+                Setup::from_cli_args(&cli_args),
+                Err(ConfigError::KdlError(_))
+            ),
+            "error returned rather than exiting"
+        );
+    }
+    #[test]
+    fn invalid_layout_checked_by_setup_is_returned_as_an_error() {
+        let cli_args = CliArgs {
+            command: Some(Command::Setup(Setup {
+                check_layout: Some(PathBuf::from(format!(
+                    "{}/src/test-fixtures/no-such-layout.kdl",
+                    env!("CARGO_MANIFEST_DIR")
+                ))),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        assert!(
+            matches!(
+                Setup::from_cli_args(&cli_args),
+                Err(ConfigError::InvalidLayout(_))
+            ),
+            "error returned rather than exiting"
+        );
+    }
 }
@@ -1,10 +1,12 @@
 //! Some general utility functions.
 
-use std::{iter, str::from_utf8};
+use std::{iter, str::from_utf8, str::FromStr};
 
 use crate::data::{Palette, PaletteColor, PaletteSource, ThemeHue};
 use crate::envs::get_session_name;
+use clap::ArgEnum;
 use colorsys::Rgb;
+use serde::{Deserialize, Serialize};
 use strip_ansi_escapes::strip;
 use unicode_width::UnicodeWidthStr;
 
@@ -82,6 +84,182 @@ pub fn _hex_to_rgb(hex: &str) -> (u8, u8, u8) {
         .into()
 }
 
+/// How many colors the terminal a client runs in can display
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ArgEnum)]
+pub enum ColorDepth {
+    #[serde(alias = "truecolor")]
+    #[clap(name = "truecolor")]
+    TrueColor,
+    #[serde(alias = "ansi256")]
+    #[clap(name = "ansi256")]
+    Ansi256,
+    #[serde(alias = "ansi16")]
+    #[clap(name = "ansi16")]
+    Ansi16,
+}
+
+impl FromStr for ColorDepth {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truecolor" => Ok(Self::TrueColor),
+            "ansi256" => Ok(Self::Ansi256),
+            "ansi16" => Ok(Self::Ansi16),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
+/// The color depth to render with: the one chosen through `$ZELLIJ_COLOR_DEPTH` or the config if
+/// any, or else the one detected from `$COLORTERM` and `$TERM`
+pub fn color_depth(
+    env_color_depth: Option<&str>,
+    config_color_depth: Option<ColorDepth>,
+    term: Option<&str>,
+    colorterm: Option<&str>,
+) -> ColorDepth {
+    env_color_depth
+        .and_then(|color_depth| {
+            color_depth
+                .parse()
+                .map_err(|e| log::warn!("Invalid color depth in the environment: {}", e))
+                .ok()
+        })
+        .or(config_color_depth)
+        .unwrap_or_else(|| detect_color_depth(term, colorterm))
+}
+
+/// Guess the color depth of a terminal from its `$COLORTERM`, falling back to its `$TERM` and
+/// assuming the best when neither is set
+pub fn detect_color_depth(term: Option<&str>, colorterm: Option<&str>) -> ColorDepth {
+    if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+        return ColorDepth::TrueColor;
+    }
+    match term {
+        Some(term) if term.ends_with("-direct") => ColorDepth::TrueColor,
+        Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+        Some(term) if !term.is_empty() => ColorDepth::Ansi16,
+        _ => ColorDepth::TrueColor,
+    }
+}
+
+// the xterm defaults for the 16 basic colors
+const ANSI16_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+const ANSI256_CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel_distance = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel_distance(a.0, b.0) + channel_distance(a.1, b.1) + channel_distance(a.2, b.2)
+}
+
+/// The RGB value of one of the 256 indexed colors
+pub fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16_COLORS[index as usize],
+        16..=231 => {
+            let index = index - 16;
+            (
+                ANSI256_CUBE_LEVELS[(index / 36) as usize],
+                ANSI256_CUBE_LEVELS[(index / 6 % 6) as usize],
+                ANSI256_CUBE_LEVELS[(index % 6) as usize],
+            )
+        },
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        },
+    }
+}
+
+/// The index of the closest color in the 6x6x6 cube or the grayscale ramp of the 256 colors
+pub fn rgb_to_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let cube_index = |channel: u8| {
+        ANSI256_CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - channel as i32).abs())
+            .map(|(index, _)| index as u8)
+            .unwrap_or(0)
+    };
+    let cube_color = 16 + 36 * cube_index(rgb.0) + 6 * cube_index(rgb.1) + cube_index(rgb.2);
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_color = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+    if color_distance(ansi256_to_rgb(gray_color), rgb)
+        < color_distance(ansi256_to_rgb(cube_color), rgb)
+    {
+        gray_color
+    } else {
+        cube_color
+    }
+}
+
+/// The index of the closest of the 16 basic colors
+pub fn rgb_to_ansi16(rgb: (u8, u8, u8)) -> u8 {
+    ANSI16_COLORS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, color)| color_distance(**color, rgb))
+        .map(|(index, _)| index as u8)
+        .unwrap_or(0)
+}
+
+/// Map `color` to the closest one the terminal can display
+pub fn quantize_color(color: PaletteColor, color_depth: ColorDepth) -> PaletteColor {
+    match (color, color_depth) {
+        (PaletteColor::Rgb(rgb), ColorDepth::Ansi256) => {
+            PaletteColor::EightBit(rgb_to_ansi256(rgb))
+        },
+        (PaletteColor::Rgb(rgb), ColorDepth::Ansi16) => PaletteColor::EightBit(rgb_to_ansi16(rgb)),
+        (PaletteColor::EightBit(index), ColorDepth::Ansi16) if index > 15 => {
+            PaletteColor::EightBit(rgb_to_ansi16(ansi256_to_rgb(index)))
+        },
+        _ => color,
+    }
+}
+
+/// Map all the colors of `palette` to the closest ones the terminal can display
+pub fn quantize_palette(palette: Palette, color_depth: ColorDepth) -> Palette {
+    let quantize = |color| quantize_color(color, color_depth);
+    Palette {
+        fg: quantize(palette.fg),
+        bg: quantize(palette.bg),
+        black: quantize(palette.black),
+        red: quantize(palette.red),
+        green: quantize(palette.green),
+        yellow: quantize(palette.yellow),
+        blue: quantize(palette.blue),
+        magenta: quantize(palette.magenta),
+        cyan: quantize(palette.cyan),
+        white: quantize(palette.white),
+        orange: quantize(palette.orange),
+        gray: quantize(palette.gray),
+        purple: quantize(palette.purple),
+        gold: quantize(palette.gold),
+        silver: quantize(palette.silver),
+        pink: quantize(palette.pink),
+        brown: quantize(palette.brown),
+        ..palette
+    }
+}
+
 pub fn default_palette() -> Palette {
     Palette {
         source: PaletteSource::Default,
@@ -144,3 +322,7 @@ pub fn version_number(mut version: &str) -> usize {
 
     version_number
 }
+
+#[cfg(test)]
+#[path = "./unit/shared_test.rs"]
+mod shared_test;
@@ -0,0 +1,61 @@
+//! A one line summary of a session's state, for showing it to the user outside of the status bar
+//! plugin (eg. in the terminal's title).
+
+use crate::data::InputMode;
+use serde::{Deserialize, Serialize};
+
+/// The state of a session as seen by one of its clients.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionStatus {
+    pub session_name: Option<String>,
+    /// The names of all tabs, in the order they are shown in
+    pub tab_names: Vec<String>,
+    /// The position of the client's active tab in `tab_names`
+    pub active_tab: usize,
+    pub mode: InputMode,
+    /// The number of terminal panes in the client's active tab
+    pub pane_count: usize,
+}
+
+/// Renders a [`SessionStatus`] into a status line.
+pub trait StatusLine {
+    /// Returns the status line for `status`, which should be no wider than `max_width` characters.
+    fn render(&self, status: &SessionStatus, max_width: usize) -> String;
+}
+
+/// The default status line, eg. `my-session | editor (1/3) | NORMAL | 2 panes`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultStatusLine;
+
+impl StatusLine for DefaultStatusLine {
+    fn render(&self, status: &SessionStatus, max_width: usize) -> String {
+        let mut parts = vec![];
+        if let Some(session_name) = &status.session_name {
+            parts.push(session_name.clone());
+        }
+        if let Some(tab_name) = status.tab_names.get(status.active_tab) {
+            parts.push(format!(
+                "{} ({}/{})",
+                tab_name,
+                status.active_tab + 1,
+                status.tab_names.len()
+            ));
+        }
+        parts.push(format!("{:?}", status.mode).to_uppercase());
+        parts.push(match status.pane_count {
+            1 => String::from("1 pane"),
+            pane_count => format!("{} panes", pane_count),
+        });
+        parts.join(" | ").chars().take(max_width).collect()
+    }
+}
+
+/// Returns the [`StatusLine`] the server renders the status line with.
+/// To customize the status line, implement [`StatusLine`] and return it here instead.
+pub fn status_line() -> Box<dyn StatusLine> {
+    Box::new(DefaultStatusLine)
+}
+
+#[cfg(test)]
+#[path = "./unit/status_test.rs"]
+mod status_test;
@@ -0,0 +1,10 @@
+use super::*;
+
+#[test]
+fn call_stack_lists_the_calls_oldest_first() {
+    let mut err_ctx = ErrorContext::new();
+    assert_eq!(err_ctx.call_stack(), "");
+    err_ctx.add_call(ContextType::Screen(ScreenContext::NewTab));
+    err_ctx.add_call(ContextType::Pty(PtyContext::SpawnTerminal));
+    assert_eq!(err_ctx.call_stack(), "Screen(NewTab) > Pty(SpawnTerminal)");
+}
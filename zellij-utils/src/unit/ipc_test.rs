@@ -0,0 +1,45 @@
+use super::*;
+use std::os::unix::{io::IntoRawFd, net::UnixStream};
+
+fn connected_sockets() -> (LocalSocketStream, LocalSocketStream) {
+    let (client_end, server_end) = UnixStream::pair().unwrap();
+    unsafe {
+        (
+            LocalSocketStream::from_raw_fd(client_end.into_raw_fd()),
+            LocalSocketStream::from_raw_fd(server_end.into_raw_fd()),
+        )
+    }
+}
+
+#[test]
+fn version_is_received_ahead_of_the_messages() {
+    let (client_end, server_end) = connected_sockets();
+    let mut sender: IpcSenderWithContext<ClientToServerMsg> = IpcSenderWithContext::new(client_end);
+    let mut receiver: IpcReceiverWithContext<ClientToServerMsg> =
+        IpcReceiverWithContext::new(server_end);
+    sender.send_version().unwrap();
+    sender.send(ClientToServerMsg::ConnStatus).unwrap();
+    assert_eq!(
+        receiver.recv_version(),
+        Some(BUILD_VERSION.clone()),
+        "version received first"
+    );
+    assert!(
+        matches!(receiver.recv(), Some((ClientToServerMsg::ConnStatus, _))),
+        "message received after the version"
+    );
+}
+
+#[test]
+fn message_is_not_taken_for_a_version() {
+    let (client_end, server_end) = connected_sockets();
+    let mut sender: IpcSenderWithContext<ClientToServerMsg> = IpcSenderWithContext::new(client_end);
+    let mut receiver: IpcReceiverWithContext<ClientToServerMsg> =
+        IpcReceiverWithContext::new(server_end);
+    sender.send(ClientToServerMsg::ConnStatus).unwrap();
+    assert_eq!(
+        receiver.recv_version(),
+        None,
+        "a client that does not send its version is told apart"
+    );
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+#[test]
+fn color_depth_is_detected_from_term_and_colorterm() {
+    assert_eq!(detect_color_depth(Some("xterm"), None), ColorDepth::Ansi16);
+    assert_eq!(detect_color_depth(Some("linux"), None), ColorDepth::Ansi16);
+    assert_eq!(
+        detect_color_depth(Some("xterm-256color"), None),
+        ColorDepth::Ansi256
+    );
+    assert_eq!(
+        detect_color_depth(Some("xterm-256color"), Some("truecolor")),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(
+        detect_color_depth(Some("xterm-direct"), None),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(detect_color_depth(None, None), ColorDepth::TrueColor);
+}
+
+#[test]
+fn colorterm_takes_precedence_over_term() {
+    assert_eq!(
+        detect_color_depth(Some("xterm"), Some("24bit")),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(
+        detect_color_depth(Some("screen"), Some("truecolor")),
+        ColorDepth::TrueColor
+    );
+}
+
+#[test]
+fn color_depth_can_be_chosen_instead_of_detected() {
+    assert_eq!(
+        color_depth(None, Some(ColorDepth::Ansi16), None, Some("truecolor")),
+        ColorDepth::Ansi16,
+        "config takes precedence over the detected depth"
+    );
+    assert_eq!(
+        color_depth(
+            Some("ansi256"),
+            Some(ColorDepth::Ansi16),
+            None,
+            Some("truecolor")
+        ),
+        ColorDepth::Ansi256,
+        "environment takes precedence over the config"
+    );
+    assert_eq!(
+        color_depth(Some("lots"), None, Some("xterm"), None),
+        ColorDepth::Ansi16,
+        "invalid depth in the environment ignored"
+    );
+    assert_eq!(
+        color_depth(None, None, Some("xterm-256color"), None),
+        ColorDepth::Ansi256,
+        "depth detected when not chosen"
+    );
+}
+
+#[test]
+fn rgb_is_mapped_to_the_closest_of_the_256_colors() {
+    assert_eq!(rgb_to_ansi256((255, 0, 0)), 196);
+    assert_eq!(rgb_to_ansi256((0, 0, 0)), 16);
+    assert_eq!(rgb_to_ansi256((255, 255, 255)), 231);
+    assert_eq!(
+        rgb_to_ansi256((128, 128, 128)),
+        244,
+        "grays use the grayscale ramp"
+    );
+    assert_eq!(
+        rgb_to_ansi256((95, 135, 175)),
+        67,
+        "exact cube colors are kept"
+    );
+}
+
+#[test]
+fn rgb_is_mapped_to_the_closest_of_the_16_colors() {
+    assert_eq!(rgb_to_ansi16((250, 10, 10)), 9);
+    assert_eq!(rgb_to_ansi16((0, 0, 0)), 0);
+    assert_eq!(rgb_to_ansi16((200, 200, 200)), 7);
+    assert_eq!(rgb_to_ansi16((0, 0, 200)), 4);
+}
+
+#[test]
+fn indexed_colors_have_their_xterm_rgb_values() {
+    assert_eq!(ansi256_to_rgb(1), (205, 0, 0));
+    assert_eq!(ansi256_to_rgb(196), (255, 0, 0));
+    assert_eq!(ansi256_to_rgb(67), (95, 135, 175));
+    assert_eq!(ansi256_to_rgb(232), (8, 8, 8));
+    assert_eq!(ansi256_to_rgb(255), (238, 238, 238));
+}
+
+#[test]
+fn colors_are_only_quantized_when_the_terminal_needs_it() {
+    let truecolor = PaletteColor::Rgb((10, 20, 30));
+    assert_eq!(quantize_color(truecolor, ColorDepth::TrueColor), truecolor);
+    assert_eq!(
+        quantize_color(PaletteColor::Rgb((255, 0, 0)), ColorDepth::Ansi256),
+        PaletteColor::EightBit(196)
+    );
+    assert_eq!(
+        quantize_color(PaletteColor::EightBit(196), ColorDepth::Ansi256),
+        PaletteColor::EightBit(196)
+    );
+    assert_eq!(
+        quantize_color(PaletteColor::EightBit(238), ColorDepth::Ansi16),
+        PaletteColor::EightBit(8)
+    );
+    assert_eq!(
+        quantize_color(PaletteColor::EightBit(3), ColorDepth::Ansi16),
+        PaletteColor::EightBit(3)
+    );
+}
+
+#[test]
+fn quantizing_a_palette_maps_all_of_its_colors() {
+    let palette = Palette {
+        fg: PaletteColor::Rgb((255, 255, 255)),
+        red: PaletteColor::Rgb((255, 0, 0)),
+        ..Default::default()
+    };
+    let quantized = quantize_palette(palette, ColorDepth::Ansi16);
+    assert_eq!(quantized.fg, PaletteColor::EightBit(15));
+    assert_eq!(quantized.red, PaletteColor::EightBit(9));
+    assert_eq!(quantized.source, palette.source);
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+fn session_status() -> SessionStatus {
+    SessionStatus {
+        session_name: Some(String::from("my-session")),
+        tab_names: vec![String::from("Tab #1"), String::from("editor")],
+        active_tab: 1,
+        mode: InputMode::Pane,
+        pane_count: 2,
+    }
+}
+
+#[test]
+fn default_status_line() {
+    let status_line = DefaultStatusLine.render(&session_status(), 100);
+    assert_eq!(status_line, "my-session | editor (2/2) | PANE | 2 panes");
+}
+
+#[test]
+fn default_status_line_is_cut_to_max_width() {
+    let status_line = DefaultStatusLine.render(&session_status(), 10);
+    assert_eq!(status_line, "my-session");
+}